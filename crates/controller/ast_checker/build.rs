@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+// Embed the function specs in `funcs`, which are the only source of the
+// registry. The build fails if the directory is missing or a function is
+// described twice.
+fn main() {
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("funcs");
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let mut files = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("failed to read the specs in {:?}: {}", dir, e))
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        panic!("no function spec is found in {:?}", dir);
+    }
+    files.sort();
+    let mut names = HashSet::new();
+    files.iter().for_each(|p| {
+        let spec: Value = serde_json::from_str(&fs::read_to_string(p).unwrap())
+            .unwrap_or_else(|e| panic!("invalid spec {:?}: {}", p, e));
+        let name = spec["name"]
+            .as_str()
+            .unwrap_or_else(|| panic!("no name in {:?}", p));
+        if !names.insert(name.to_string()) {
+            panic!("{} is defined twice in {:?}", name, dir);
+        }
+    });
+    let specs = files.iter().fold(String::from("&[\n"), |mut prev, p| {
+        let path = fs::canonicalize(p).unwrap();
+        println!("cargo:rerun-if-changed={}", path.display());
+        prev.push_str(&format!(
+            "    include_str!({:?}),\n",
+            path.display().to_string()
+        ));
+        prev
+    });
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("func_specs.rs");
+    fs::write(out, specs + "]\n").unwrap();
+}
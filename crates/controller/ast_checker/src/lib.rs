@@ -1,28 +1,56 @@
-use std::collections::HashMap;
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate ts_rs;
+
+mod registry;
 
 use logisheets_base::FuncId;
 use logisheets_parser::ast::{self, Node};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+pub use registry::{get_all_func_signatures, get_func_signature};
 
-pub struct AstChecker {
-    registry: HashMap<FuncId, FuncSignature>,
+pub struct AstChecker<F>
+where
+    F: Fn(&FuncId) -> Option<String>,
+{
+    func_name_fetcher: F,
 }
 
-impl AstChecker {
+impl<F> AstChecker<F>
+where
+    F: Fn(&FuncId) -> Option<String>,
+{
+    pub fn new(func_name_fetcher: F) -> Self {
+        AstChecker { func_name_fetcher }
+    }
+
+    // The functions missing in the registry are not checked, because they
+    // may be async functions or functions from the plugins.
     pub fn func_check(&self, node: &ast::Node) -> Result<(), FuncCheckError> {
+        if let ast::PureNode::Func(f) = &node.pure {
+            f.args.iter().try_for_each(|arg| self.func_check(arg))?;
+        }
+        self.call_check(node)
+    }
+
+    // Check the function call at the top of the node only, leaving the ones
+    // in its arguments.
+    pub fn call_check(&self, node: &ast::Node) -> Result<(), FuncCheckError> {
         match &node.pure {
-            ast::PureNode::Func(f) => match &f.op {
-                ast::Operator::Function(id) => {
-                    if let Some(sign) = self.registry.get(id) {
-                        let args = &f.args;
-                        args_check(sign, args)
-                    } else {
-                        Ok(())
-                    }
+            ast::PureNode::Func(ast::Func {
+                op: ast::Operator::Function(id),
+                args,
+            }) => {
+                let sign = (self.func_name_fetcher)(id).and_then(|name| get_func_signature(&name));
+                match sign {
+                    Some(sign) => args_check(*id, sign, args),
+                    None => Ok(()),
                 }
-                _ => Ok(()),
-            },
-            ast::PureNode::Value(_) => Ok(()),
-            ast::PureNode::Reference(_) => Ok(()),
+            }
+            _ => Ok(()),
         }
     }
 }
@@ -65,14 +93,37 @@ impl<'a> Iterator for ArgDefIter<'a> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../src/bindings/func_signature.ts")]
+#[serde(rename_all = "camelCase")]
 pub struct FuncSignature {
-    pub id: FuncId,
+    pub name: String,
     pub arg_count: ArgCount,
     pub args: Vec<ArgDef>,
+    // Volatile functions are calculated in every calculation even if none
+    // of their arguments changes.
+    #[serde(default)]
+    pub volatile: bool,
+    #[serde(default)]
+    pub return_kind: ReturnKind,
+    #[serde(default)]
+    pub description: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../src/bindings/return_kind.ts")]
+pub enum ReturnKind {
+    Number,
+    Text,
+    Logical,
+    Reference,
+    Array,
+    #[default]
+    Any,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../src/bindings/arg_count.ts")]
 pub struct ArgCount {
     pub le: Option<u8>,
     pub ge: Option<u8>,
@@ -81,6 +132,12 @@ pub struct ArgCount {
     pub even: Option<bool>,
 }
 
+impl ArgCount {
+    pub fn check(&self, count: usize) -> bool {
+        u8::try_from(count).is_ok_and(|c| arg_count_check(self, c))
+    }
+}
+
 fn arg_count_check(count_rule: &ArgCount, count: u8) -> bool {
     if let Some(limit) = count_rule.le {
         if !(count <= limit) {
@@ -110,7 +167,9 @@ fn arg_count_check(count_rule: &ArgCount, count: u8) -> bool {
     true
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../src/bindings/arg_def.ts")]
+#[serde(rename_all = "camelCase")]
 pub struct ArgDef {
     pub arg_name: String,
     pub ref_only: Option<bool>,
@@ -134,12 +193,12 @@ pub struct FuncCheckError {
     pub break_args: Option<(usize, ArgDef)>,
 }
 
-fn args_check(sign: &FuncSignature, args: &Vec<Node>) -> Result<(), FuncCheckError> {
+fn args_check(id: FuncId, sign: &FuncSignature, args: &[Node]) -> Result<(), FuncCheckError> {
     let count_rule = &sign.arg_count;
     let count = args.len() as u8;
     if !arg_count_check(count_rule, count) {
         return Err(FuncCheckError {
-            id: sign.id,
+            id,
             break_args: None,
             break_arg_count: Some(count_rule.clone()),
         });
@@ -151,7 +210,7 @@ fn args_check(sign: &FuncSignature, args: &Vec<Node>) -> Result<(), FuncCheckErr
         let rule = arg_rule_iter.next();
         if rule.is_none() {
             return Err(FuncCheckError {
-                id: sign.id,
+                id,
                 break_arg_count: None,
                 break_args: Some((idx, ArgDef::unexpected_arg())),
             });
@@ -159,14 +218,14 @@ fn args_check(sign: &FuncSignature, args: &Vec<Node>) -> Result<(), FuncCheckErr
         let arg_def = rule.unwrap();
         if !arg_def_check(arg_def, arg) {
             return Err(FuncCheckError {
-                id: sign.id,
+                id,
                 break_arg_count: None,
                 break_args: Some((idx, arg_def.clone())),
             });
         }
         idx += 1;
     }
-    todo!()
+    Ok(())
 }
 
 fn arg_def_check(arg_def: &ArgDef, arg: &Node) -> bool {
@@ -273,4 +332,51 @@ mod tests {
         assert_eq!(iter.next().unwrap().arg_name, "arg3");
         assert_eq!(iter.next().unwrap().arg_name, "arg4");
     }
+
+    #[test]
+    fn registry_test() {
+        let sumifs = get_func_signature("sumifs").unwrap();
+        assert_eq!(sumifs.name, "SUMIFS");
+        assert!(!sumifs.arg_count.check(2));
+        assert!(sumifs.arg_count.check(3));
+        assert!(!sumifs.arg_count.check(4));
+        assert!(sumifs.arg_count.check(5));
+        let mut iter = ArgDefIter::from(&sumifs.args);
+        assert_eq!(iter.next().unwrap().arg_name, "sum_range");
+        assert_eq!(iter.next().unwrap().arg_name, "criteria_range");
+        assert_eq!(iter.next().unwrap().arg_name, "criteria");
+        assert_eq!(iter.next().unwrap().arg_name, "criteria_range");
+
+        let switch = get_func_signature("SWITCH").unwrap();
+        assert!(switch.arg_count.check(3));
+        assert!(switch.arg_count.check(4));
+        assert!(!switch.arg_count.check(2));
+
+        let row = get_func_signature("ROW").unwrap();
+        assert!(row.arg_count.check(0));
+        assert!(row.arg_count.check(1));
+        assert!(!row.arg_count.check(2));
+        assert_eq!(row.args[0].ref_only, Some(true));
+
+        assert!(get_func_signature("OFFSET").unwrap().volatile);
+        assert!(!get_func_signature("SUM").unwrap().volatile);
+        assert_eq!(
+            get_func_signature("TEXT").unwrap().return_kind,
+            ReturnKind::Text
+        );
+        assert!(get_func_signature("NOT_A_FUNC").is_none());
+    }
+
+    #[test]
+    fn registry_arg_count_test() {
+        get_all_func_signatures().into_iter().for_each(|sign| {
+            let repeated = sign.args.iter().any(|a| a.start_repeated == Some(true));
+            let max = sign.arg_count.eq.or(sign.arg_count.le);
+            if repeated {
+                assert!(max.is_none(), "{}", sign.name);
+            } else {
+                assert_eq!(max, Some(sign.args.len() as u8), "{}", sign.name);
+            }
+        });
+    }
 }
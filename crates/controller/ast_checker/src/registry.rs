@@ -0,0 +1,24 @@
+use std::collections::HashMap;
+
+use super::FuncSignature;
+
+// The specs in `funcs`, embedded by the build script.
+const FUNC_SPECS: &[&str] = include!(concat!(env!("OUT_DIR"), "/func_specs.rs"));
+
+lazy_static! {
+    static ref REGISTRY: HashMap<String, FuncSignature> = FUNC_SPECS
+        .iter()
+        .map(|spec| serde_json::from_str(spec).unwrap())
+        .map(|sign: FuncSignature| (sign.name.clone(), sign))
+        .collect();
+}
+
+pub fn get_func_signature(name: &str) -> Option<&'static FuncSignature> {
+    REGISTRY.get(&name.to_uppercase())
+}
+
+pub fn get_all_func_signatures() -> Vec<&'static FuncSignature> {
+    let mut result = REGISTRY.values().collect::<Vec<_>>();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
@@ -50,7 +50,43 @@ impl Time {
     }
 }
 
-pub fn get_serial_num_by_date_1900(year: u32, month: u32, day: u32) -> Option<u32> {
+// The 1900 date system counts the days from 1900-01-00 and keeps the date
+// 1900-02-29 which does not exist. The 1904 date system counts the days from
+// 1904-01-01.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateSystem {
+    #[default]
+    Date1900,
+    Date1904,
+}
+
+// The serial number of a date in the 1900 date system minus the one in the
+// 1904 date system.
+pub const DATE_1904_OFFSET: u32 = 1462;
+
+pub fn get_serial_num_by_date(year: u32, month: u32, day: u32, system: DateSystem) -> Option<u32> {
+    match system {
+        DateSystem::Date1900 => get_serial_num_by_date_1900(year, month, day),
+        DateSystem::Date1904 => get_serial_num_by_date_1904(year, month, day),
+    }
+}
+
+// The time of the day is the fraction part.
+pub fn get_serial_num_by_datetime(d: &DateTime<FixedOffset>, system: DateSystem) -> Option<f64> {
+    let days = get_serial_num_by_date(d.year() as u32, d.month(), d.day(), system)?;
+    Some(days as f64 + d.num_seconds_from_midnight() as f64 / 86400.)
+}
+
+pub fn get_date_by_serial_num(n: u32, system: DateSystem) -> Date {
+    match system {
+        DateSystem::Date1900 => get_date_by_serial_num_1900(n),
+        DateSystem::Date1904 => get_date_by_serial_num_1904(n),
+    }
+}
+
+// Like Excel, the years less than 1900 are regarded as the years after 1900,
+// and the months and days out of range roll over.
+fn normalize_date(year: u32, month: u32, day: u32) -> NaiveDate {
     let year = if year < 1900 { year + 1900 } else { year };
     let (year, month) = {
         let (y, m) = (year + month / 12, month % 12);
@@ -61,8 +97,11 @@ pub fn get_serial_num_by_date_1900(year: u32, month: u32, day: u32) -> Option<u3
         }
     };
     let init_date = NaiveDate::from_ymd(year as i32, month, 1);
-    let duration = Duration::days((day - 1) as i64);
-    let new_date = init_date + duration;
+    init_date + Duration::days(day as i64 - 1)
+}
+
+pub fn get_serial_num_by_date_1900(year: u32, month: u32, day: u32) -> Option<u32> {
+    let new_date = normalize_date(year, month, day);
     let duration = new_date.signed_duration_since(NaiveDate::from_ymd(1899, 12, 31));
     let r = duration.num_days();
     if r < 1 {
@@ -77,6 +116,17 @@ pub fn get_serial_num_by_date_1900(year: u32, month: u32, day: u32) -> Option<u3
     }
 }
 
+pub fn get_serial_num_by_date_1904(year: u32, month: u32, day: u32) -> Option<u32> {
+    let new_date = normalize_date(year, month, day);
+    let duration = new_date.signed_duration_since(NaiveDate::from_ymd_opt(1904, 1, 1).unwrap());
+    let r = duration.num_days();
+    if r < 0 {
+        None
+    } else {
+        Some(r as u32)
+    }
+}
+
 pub fn get_date_by_serial_num_1900(n: u32) -> Date {
     if n == 60 {
         // This is a date which does not exist. This is why we do not use
@@ -98,6 +148,15 @@ pub fn get_date_by_serial_num_1900(n: u32) -> Date {
     }
 }
 
+pub fn get_date_by_serial_num_1904(n: u32) -> Date {
+    let target = NaiveDate::from_ymd_opt(1904, 1, 1).unwrap() + Duration::days(n as i64);
+    Date {
+        year: target.year() as u32,
+        month: target.month(),
+        day: target.day(),
+    }
+}
+
 pub fn get_time_by_decimal_num(t: f64) -> Time {
     let time = t.fract();
     let seconds = (time * 86400.).round() as u32;
@@ -123,8 +182,10 @@ pub fn get_decimal_num_by_time(hour: u32, minute: u32, second: u32) -> Option<f6
 #[cfg(test)]
 mod tests {
     use super::get_date_by_serial_num_1900;
+    use super::get_date_by_serial_num_1904;
     use super::get_decimal_num_by_time;
     use super::get_serial_num_by_date_1900;
+    use super::get_serial_num_by_date_1904;
     use super::get_time_by_decimal_num;
     use super::Date;
     #[test]
@@ -145,6 +206,18 @@ mod tests {
         assert_eq!(r, Some(41233));
     }
 
+    #[test]
+    fn date_1904_test() {
+        assert_eq!(get_serial_num_by_date_1904(1904, 1, 1), Some(0));
+        assert_eq!(get_serial_num_by_date_1904(1903, 12, 31), None);
+        let r = get_serial_num_by_date_1904(2012, 11, 10);
+        assert_eq!(r, Some(41223 - super::DATE_1904_OFFSET));
+        assert_eq!(get_date_by_serial_num_1904(0).to_triple(), (1904, 1, 1));
+        let r = get_date_by_serial_num_1904(41223 - super::DATE_1904_OFFSET);
+        assert_eq!(r.to_triple(), (2012, 11, 10));
+        assert_eq!(get_serial_num_by_date_1900(2020, 3, 0), Some(43890));
+    }
+
     #[test]
     fn get_decimal_num_by_time_test() {
         let r = get_decimal_num_by_time(12, 0, 0).unwrap();
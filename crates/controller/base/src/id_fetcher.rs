@@ -16,8 +16,16 @@ pub trait IdFetcherTrait {
 
     fn fetch_sheet_id(&mut self, sheet_name: &str) -> SheetId;
 
+    // Unlike `fetch_sheet_id`, no id is registered for an unknown sheet.
+    fn find_sheet_id(&mut self, sheet_name: &str) -> Option<SheetId>;
+
     fn fetch_name_id(&mut self, workbook: &Option<&str>, name: &str) -> NameId;
 
+    // Return the id of the name defined in the scope of this sheet. Return
+    // None if there is no such name, and then the name should be looked up in
+    // the workbook scope.
+    fn fetch_local_name_id(&mut self, sheet_id: SheetId, name: &str) -> Option<NameId>;
+
     fn fetch_ext_book_id(&mut self, book: &str) -> ExtBookId;
 
     fn fetch_text_id(&mut self, text: &str) -> TextId;
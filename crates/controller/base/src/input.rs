@@ -0,0 +1,496 @@
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::datetime::{get_decimal_num_by_time, get_serial_num_by_date, DateSystem};
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// The order of the day, the month and the year in the dates typed by users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../src/bindings/date_order.ts")]
+pub enum DateOrder {
+    Mdy,
+    Dmy,
+    Ymd,
+}
+
+/// The conventions used to recognize the user inputs.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../src/bindings/locale.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct Locale {
+    #[ts(type = "string")]
+    pub decimal_separator: char,
+    #[ts(type = "string")]
+    pub group_separator: char,
+    pub date_order: DateOrder,
+    pub currency_symbols: Vec<String>,
+}
+
+impl Default for Locale {
+    // en-US
+    fn default() -> Self {
+        Locale {
+            decimal_separator: '.',
+            group_separator: ',',
+            date_order: DateOrder::Mdy,
+            currency_symbols: vec![String::from("$")],
+        }
+    }
+}
+
+/// A number recognized from the input and the number format it implies. The
+/// format is None for the plain numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecognizedInput {
+    pub value: f64,
+    pub num_fmt: Option<String>,
+}
+
+/// Recognize the numbers, percents, currencies, fractions, dates and times
+/// typed into a cell, as Excel does. Return None if the input should be
+/// stored as a text.
+pub fn recognize_input(text: &str, locale: &Locale, system: DateSystem) -> Option<RecognizedInput> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    recognize_number(text, locale)
+        .or_else(|| recognize_fraction(text))
+        .or_else(|| recognize_datetime(text, locale, system))
+}
+
+fn recognize_number(text: &str, locale: &Locale) -> Option<RecognizedInput> {
+    let (negative, text) = match text.strip_prefix('(') {
+        Some(t) => (true, t.strip_suffix(')')?),
+        None => (false, text),
+    };
+    let (negative, text) = strip_sign(text, negative);
+    let (currency, prefix, text) = strip_currency(text, locale);
+    let (negative, text) = match prefix {
+        // Both `-$5` and `$-5` are accepted.
+        true => strip_sign(text, negative),
+        false => (negative, text),
+    };
+    let (percent, text) = match text.strip_suffix('%') {
+        Some(t) => (true, t.trim_end()),
+        None => (false, text),
+    };
+    if percent && currency.is_some() {
+        return None;
+    }
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    let (int_part, frac_part) = match mantissa.split_once(locale.decimal_separator) {
+        Some((i, f)) => (i, Some(f)),
+        None => (mantissa, None),
+    };
+    let groups = int_part.split(locale.group_separator).collect::<Vec<_>>();
+    let grouped = groups.len() > 1;
+    if grouped && (groups[0].is_empty() || groups[1..].iter().any(|g| g.len() != 3)) {
+        return None;
+    }
+    let int_digits = groups.concat();
+    let frac_digits = frac_part.unwrap_or("");
+    if int_digits.is_empty() && frac_digits.is_empty() {
+        return None;
+    }
+    if !int_digits
+        .chars()
+        .chain(frac_digits.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let mut normalized = format!("{}.{}", int_digits, frac_digits);
+    if let Some(e) = exponent {
+        let digits = e.strip_prefix(|c| c == '+' || c == '-').unwrap_or(e);
+        if grouped || digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        normalized = format!("{}e{}", normalized, e);
+    }
+    // Numbers too large for a double, like `1e400`, are kept as texts.
+    let mut value = normalized.parse::<f64>().ok().filter(|v| v.is_finite())?;
+    if negative {
+        value = -value;
+    }
+    let decimals = match frac_digits.len() {
+        0 => "",
+        _ => ".00",
+    };
+    let num_fmt = if percent {
+        value /= 100.;
+        Some(format!("0{}%", decimals))
+    } else if let Some(symbol) = currency {
+        let symbol = quote_symbol(symbol);
+        match prefix {
+            true => Some(format!("{}#,##0{}", symbol, decimals)),
+            false => Some(format!("#,##0{} {}", decimals, symbol)),
+        }
+    } else if exponent.is_some() {
+        Some(String::from("0.00E+00"))
+    } else if grouped {
+        Some(format!("#,##0{}", decimals))
+    } else {
+        None
+    };
+    Some(RecognizedInput { value, num_fmt })
+}
+
+fn strip_sign(text: &str, negative: bool) -> (bool, &str) {
+    if let Some(t) = text.strip_prefix('-') {
+        (!negative, t.trim_start())
+    } else if let Some(t) = text.strip_prefix('+') {
+        (negative, t.trim_start())
+    } else {
+        (negative, text)
+    }
+}
+
+// Return the currency symbol, whether it is a prefix and the rest text.
+fn strip_currency<'a, 'b>(text: &'a str, locale: &'b Locale) -> (Option<&'b str>, bool, &'a str) {
+    for symbol in locale.currency_symbols.iter() {
+        if let Some(t) = text.strip_prefix(symbol.as_str()) {
+            return (Some(symbol), true, t.trim_start());
+        }
+        if let Some(t) = text.strip_suffix(symbol.as_str()) {
+            return (Some(symbol), false, t.trim_end());
+        }
+    }
+    (None, false, text)
+}
+
+// The letters in number formats need quotes.
+fn quote_symbol(symbol: &str) -> String {
+    if symbol.chars().any(|c| c.is_alphanumeric()) {
+        format!("\"{}\"", symbol)
+    } else {
+        symbol.to_string()
+    }
+}
+
+// A fraction must have its integer part like `0 1/2` or `-1 3/4`. Otherwise
+// it is regarded as a date.
+fn recognize_fraction(text: &str) -> Option<RecognizedInput> {
+    let (negative, text) = strip_sign(text, false);
+    let (int_part, frac_part) = text.split_once(' ')?;
+    let (num, den) = frac_part.trim_start().split_once('/')?;
+    let int_part = int_part.parse::<u32>().ok()?;
+    if !num.chars().chain(den.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let num = num.parse::<u32>().ok()?;
+    let den = den.parse::<u32>().ok()?;
+    if den == 0 {
+        return None;
+    }
+    let value = int_part as f64 + num as f64 / den as f64;
+    let digits = "?".repeat(den.to_string().len().min(3));
+    Some(RecognizedInput {
+        value: if negative { -value } else { value },
+        num_fmt: Some(format!("# {}/{}", digits, digits)),
+    })
+}
+
+fn recognize_datetime(text: &str, locale: &Locale, system: DateSystem) -> Option<RecognizedInput> {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let last = *words.last()?;
+    // The index of the first word of the time.
+    let time_start = if is_am_pm(last) && words.len() >= 2 {
+        words.len() - 2
+    } else if last.contains(':') {
+        words.len() - 1
+    } else if let Some(i) = last.find(|c: char| matches!(c.to_ascii_uppercase(), 'A' | 'P')) {
+        // Like `3:45PM`.
+        match i > 0 && is_am_pm(&last[i..]) {
+            true => words.len() - 1,
+            false => words.len(),
+        }
+    } else {
+        words.len()
+    };
+    let date_text = words[..time_start].join(" ");
+    let time_text = words[time_start..].join(" ");
+    let time = match time_text.as_str() {
+        "" => None,
+        t => Some(parse_time(t)?),
+    };
+    let date = match date_text.as_str() {
+        "" => None,
+        t => Some(parse_date(t, locale)?),
+    };
+    match (date, time) {
+        (Some((y, m, d, date_fmt)), time) => {
+            let serial = get_serial_num_by_date(y, m, d, system)? as f64;
+            match time {
+                Some((t, time_fmt)) => Some(RecognizedInput {
+                    value: serial + t,
+                    num_fmt: Some(format!("{} {}", date_fmt, time_fmt)),
+                }),
+                None => Some(RecognizedInput {
+                    value: serial,
+                    num_fmt: Some(date_fmt.to_string()),
+                }),
+            }
+        }
+        (None, Some((t, time_fmt))) => Some(RecognizedInput {
+            value: t,
+            num_fmt: Some(time_fmt.to_string()),
+        }),
+        (None, None) => None,
+    }
+}
+
+fn is_am_pm(text: &str) -> bool {
+    matches!(text.to_uppercase().as_str(), "AM" | "PM" | "A" | "P")
+}
+
+// Parse the texts like `13:45`, `3:45:10 PM` or `3 PM`.
+fn parse_time(text: &str) -> Option<(f64, &'static str)> {
+    let upper = text.to_uppercase();
+    let (text, pm) = if let Some(t) = upper.strip_suffix("AM").or(upper.strip_suffix('A')) {
+        (t.trim_end(), Some(false))
+    } else if let Some(t) = upper.strip_suffix("PM").or(upper.strip_suffix('P')) {
+        (t.trim_end(), Some(true))
+    } else {
+        (upper.as_str(), None)
+    };
+    let parts = text
+        .split(':')
+        .map(
+            |p| match !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()) {
+                true => p.parse::<u32>().ok(),
+                false => None,
+            },
+        )
+        .collect::<Option<Vec<_>>>()?;
+    let (hour, minute, second) = match parts.as_slice() {
+        [h] if pm.is_some() => (*h, 0, 0),
+        [h, m] => (*h, *m, 0),
+        [h, m, s] => (*h, *m, *s),
+        _ => return None,
+    };
+    if minute >= 60 || second >= 60 {
+        return None;
+    }
+    let hour = match pm {
+        Some(pm) => {
+            if hour == 0 || hour > 12 {
+                return None;
+            }
+            hour % 12 + if pm { 12 } else { 0 }
+        }
+        None if hour >= 24 => return None,
+        None => hour,
+    };
+    let value = get_decimal_num_by_time(hour, minute, second)?;
+    let fmt = match (parts.len() == 3, pm.is_some()) {
+        (false, false) => "h:mm",
+        (true, false) => "h:mm:ss",
+        (false, true) => "h:mm AM/PM",
+        (true, true) => "h:mm:ss AM/PM",
+    };
+    Some((value, fmt))
+}
+
+enum DatePart {
+    Number(u32, usize),
+    Month(u32),
+}
+
+// Parse the texts like `2024-03-01`, `3/1/2024`, `1-Mar-24` or `Mar 1, 2024`
+// and return the year, the month, the day and the number format.
+fn parse_date(text: &str, locale: &Locale) -> Option<(u32, u32, u32, &'static str)> {
+    let parts = text
+        .split(['/', '-', '.', ' ', ','])
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            if p.chars().all(|c| c.is_ascii_digit()) {
+                Some(DatePart::Number(p.parse().ok()?, p.len()))
+            } else {
+                parse_month_name(p).map(DatePart::Month)
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    // The spaces and commas only appear in the dates with month names.
+    let has_month = parts.iter().any(|p| matches!(p, DatePart::Month(_)));
+    if !has_month && text.contains([' ', ',']) {
+        return None;
+    }
+    let this_year = Local::now().year() as u32;
+    let (y, m, d, fmt) = match parts.as_slice() {
+        [DatePart::Number(y, 4), DatePart::Number(m, _), DatePart::Number(d, _)] => {
+            let fmt = match text.contains('-') {
+                true => "yyyy-mm-dd",
+                false => "yyyy/m/d",
+            };
+            (*y, *m, *d, fmt)
+        }
+        [DatePart::Number(a, a_len), DatePart::Number(b, _), DatePart::Number(c, c_len)] => {
+            match locale.date_order {
+                DateOrder::Mdy => (to_year(*c, *c_len), *a, *b, "m/d/yyyy"),
+                DateOrder::Dmy => (to_year(*c, *c_len), *b, *a, "d/m/yyyy"),
+                DateOrder::Ymd => (to_year(*a, *a_len), *b, *c, "yyyy/m/d"),
+            }
+        }
+        [DatePart::Number(y, 4), DatePart::Number(m, _)] => (*y, *m, 1, "mmm-yy"),
+        [DatePart::Number(a, _), DatePart::Number(b, len)] => {
+            let (m, d) = match locale.date_order {
+                DateOrder::Dmy => (*b, *a),
+                _ => (*a, *b),
+            };
+            match valid_date(this_year, m, d) {
+                true => (this_year, m, d, "d-mmm"),
+                // Like `3/2024` or `3/99`.
+                false => (to_year(*b, *len), *a, 1, "mmm-yy"),
+            }
+        }
+        [DatePart::Number(d, _), DatePart::Month(m), DatePart::Number(y, len)]
+        | [DatePart::Month(m), DatePart::Number(d, _), DatePart::Number(y, len)] => {
+            (to_year(*y, *len), *m, *d, "d-mmm-yy")
+        }
+        [DatePart::Number(d, _), DatePart::Month(m)] => (this_year, *m, *d, "d-mmm"),
+        [DatePart::Month(m), DatePart::Number(n, len)] => match valid_date(this_year, *m, *n) {
+            true if *len <= 2 => (this_year, *m, *n, "d-mmm"),
+            _ => (to_year(*n, *len), *m, 1, "mmm-yy"),
+        },
+        _ => return None,
+    };
+    match valid_date(y, m, d) && y >= 1900 {
+        true => Some((y, m, d, fmt)),
+        false => None,
+    }
+}
+
+fn parse_month_name(text: &str) -> Option<u32> {
+    let lower = text.to_lowercase();
+    if lower.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|m| m.starts_with(&lower))
+        .map(|i| i as u32 + 1)
+}
+
+// Like Excel, the 2-digit years from 00 to 29 are in the 21st century.
+fn to_year(n: u32, len: usize) -> u32 {
+    match (len, n) {
+        (l, n) if l <= 2 && n < 30 => 2000 + n,
+        (l, n) if l <= 2 => 1900 + n,
+        _ => n,
+    }
+}
+
+fn valid_date(y: u32, m: u32, d: u32) -> bool {
+    // 1900-02-29 is a valid date in Excel.
+    (y, m, d) == (1900, 2, 29) || NaiveDate::from_ymd_opt(y as i32, m, d).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recognize_input, DateOrder, Locale, RecognizedInput};
+    use crate::datetime::DateSystem;
+    use chrono::{Datelike, Local};
+
+    fn recognize(text: &str, locale: &Locale) -> Option<(f64, Option<String>)> {
+        recognize_input(text, locale, DateSystem::Date1900)
+            .map(|RecognizedInput { value, num_fmt }| (value, num_fmt))
+    }
+
+    fn some(value: f64, num_fmt: &str) -> Option<(f64, Option<String>)> {
+        Some((value, Some(num_fmt.to_string())))
+    }
+
+    #[test]
+    fn number_test() {
+        let en = Locale::default();
+        assert_eq!(recognize("12.5", &en), Some((12.5, None)));
+        assert_eq!(recognize("-.5", &en), Some((-0.5, None)));
+        assert_eq!(recognize("12%", &en), some(0.12, "0%"));
+        assert_eq!(recognize("12.5%", &en), some(0.125, "0.00%"));
+        assert_eq!(recognize("$1,200.50", &en), some(1200.5, "$#,##0.00"));
+        assert_eq!(recognize("-$5", &en), some(-5., "$#,##0"));
+        assert_eq!(recognize("($1,000)", &en), some(-1000., "$#,##0"));
+        assert_eq!(recognize("1,234,567", &en), some(1234567., "#,##0"));
+        assert_eq!(recognize("1.5E3", &en), some(1500., "0.00E+00"));
+        assert_eq!(recognize("1,23", &en), None);
+        assert_eq!(recognize("$12%", &en), None);
+        assert_eq!(recognize("inf", &en), None);
+        assert_eq!(recognize("1e400", &en), None);
+        assert_eq!(recognize("-1E+400", &en), None);
+        assert_eq!(recognize(&format!("1{}", "0".repeat(400)), &en), None);
+        assert_eq!(recognize("abc", &en), None);
+
+        let de = Locale {
+            decimal_separator: ',',
+            group_separator: '.',
+            date_order: DateOrder::Dmy,
+            currency_symbols: vec![String::from("€"), String::from("EUR")],
+        };
+        assert_eq!(recognize("1.200,5", &de), some(1200.5, "#,##0.00"));
+        assert_eq!(recognize("3,5 €", &de), some(3.5, "#,##0.00 €"));
+        assert_eq!(recognize("3 EUR", &de), some(3., "#,##0 \"EUR\""));
+    }
+
+    #[test]
+    fn fraction_test() {
+        let en = Locale::default();
+        assert_eq!(recognize("0 1/2", &en), some(0.5, "# ?/?"));
+        assert_eq!(recognize("-1 3/4", &en), some(-1.75, "# ?/?"));
+        assert_eq!(recognize("2 5/16", &en), some(2.3125, "# ??/??"));
+        assert_eq!(recognize("1 1/0", &en), None);
+    }
+
+    #[test]
+    fn datetime_test() {
+        let en = Locale::default();
+        let this_year = Local::now().year();
+        assert_eq!(recognize("2024-03-01", &en), some(45352., "yyyy-mm-dd"));
+        assert_eq!(recognize("3/1/2024", &en), some(45352., "m/d/yyyy"));
+        assert_eq!(recognize("3/1/24", &en), some(45352., "m/d/yyyy"));
+        assert_eq!(recognize("1-Mar-2024", &en), some(45352., "d-mmm-yy"));
+        assert_eq!(recognize("March 1, 2024", &en), some(45352., "d-mmm-yy"));
+        assert_eq!(recognize("Mar-2024", &en), some(45352., "mmm-yy"));
+        assert_eq!(recognize("3/2024", &en), some(45352., "mmm-yy"));
+        let jan_2 = recognize(&format!("{}-01-02", this_year), &en).unwrap().0;
+        assert_eq!(recognize("1/2", &en), some(jan_2, "d-mmm"));
+        assert_eq!(recognize("2/30/2024", &en), None);
+        assert_eq!(recognize("1 2", &en), None);
+        assert_eq!(recognize("3:45 PM", &en), some(0.65625, "h:mm AM/PM"));
+        assert_eq!(recognize("3:45pm", &en), some(0.65625, "h:mm AM/PM"));
+        assert_eq!(recognize("12 AM", &en), some(0., "h:mm AM/PM"));
+        assert_eq!(recognize("13:30:36", &en), some(48636. / 86400., "h:mm:ss"));
+        assert_eq!(recognize("25:00", &en), None);
+        assert_eq!(
+            recognize("2024-03-01 18:00", &en),
+            some(45352.75, "yyyy-mm-dd h:mm")
+        );
+
+        let uk = Locale {
+            date_order: DateOrder::Dmy,
+            ..Locale::default()
+        };
+        assert_eq!(recognize("1/3/2024", &uk), some(45352., "d/m/yyyy"));
+        assert_eq!(
+            recognize_input("2024-03-01", &en, DateSystem::Date1904).map(|r| r.value),
+            Some(43890.)
+        );
+    }
+}
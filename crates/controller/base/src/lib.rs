@@ -17,6 +17,7 @@ pub mod get_norm_cell_id;
 pub mod get_norm_cells_in_line;
 pub mod id_fetcher;
 pub mod index_fetcher;
+pub mod input;
 pub mod matrix_value;
 pub mod name_fetcher;
 pub mod set_curr_cell;
@@ -31,7 +32,7 @@ pub type RowId = u32;
 pub type ColId = u32;
 pub type SheetId = u16;
 pub type TextId = u32;
-pub type NameId = u8;
+pub type NameId = u16;
 pub type FuncId = u8;
 pub type BlockId = u16;
 pub const CURR_BOOK: ExtBookId = 0;
@@ -106,6 +107,7 @@ pub enum Error {
     Ref,         // #REF!
     Value,       // #VALUE!
     GettingData, // #GETTING_DATA
+    Spill,       // #SPILL!
 }
 
 impl Error {
@@ -119,6 +121,7 @@ impl Error {
             Error::Ref => "#REF!",
             Error::Value => "#VALUE!",
             Error::GettingData => "#GETTING_DATA",
+            Error::Spill => "#SPILL!",
             Error::Unspecified => "#UNKNOWN!",
         };
         String::from(s)
@@ -161,7 +164,8 @@ impl CellValue {
             chars.next();
             let text_id = text_id_fetcher(chars.as_str());
             CellValue::String(text_id)
-        } else if let Ok(n) = text.parse::<f64>() {
+        } else if let Some(n) = text.parse::<f64>().ok().filter(|n| n.is_finite()) {
+            // Texts like `inf`, `NaN` or `1e400` are kept as texts.
             CellValue::Number(n)
         } else {
             let tid = text_id_fetcher(&text);
@@ -178,6 +182,12 @@ impl CellValue {
     where
         F: FnMut(usize) -> TextId,
     {
+        if let StCellType::InlineStr = t {
+            return match is {
+                Some(is) => CellValue::InlineStr(is.clone()),
+                None => CellValue::Blank,
+            };
+        }
         if let Some(text) = value {
             match t {
                 StCellType::N => {
@@ -203,13 +213,7 @@ impl CellValue {
                     let id = f(idx);
                     CellValue::String(id)
                 }
-                StCellType::InlineStr => {
-                    if let Some(is) = is {
-                        CellValue::InlineStr(is.clone())
-                    } else {
-                        CellValue::Blank
-                    }
-                }
+                StCellType::InlineStr => unreachable!(),
                 StCellType::Str => CellValue::FormulaStr(text.value.clone()),
                 StCellType::D => {
                     let dt = DateTime::parse_from_rfc3339(&text.value);
@@ -230,10 +234,14 @@ impl CellValue {
                             Error::Null
                         } else if &text.value == "#NUM!" {
                             Error::Num
+                        } else if &text.value == "#REF!" {
+                            Error::Ref
                         } else if &text.value == "#VALUE!" {
                             Error::Value
                         } else if &text.value == "#GETTING_DATA" {
                             Error::GettingData
+                        } else if &text.value == "#SPILL!" {
+                            Error::Spill
                         } else {
                             Error::Value
                         }
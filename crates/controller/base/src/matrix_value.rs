@@ -1,5 +1,8 @@
 use im::HashMap;
 
+// Calculate two elements into one.
+type BinaryFn<T> = Box<dyn Fn(&T, &T) -> T>;
+
 pub struct MatrixValue<T>
 where
     T: 'static + Default + Clone,
@@ -105,7 +108,7 @@ where
         }
     }
 
-    pub fn calc_scalar(self, scalar: T, func: Box<dyn Fn(&T, &T) -> T>, scalar_lhs: bool) -> Self {
+    pub fn calc_scalar(self, scalar: T, func: BinaryFn<T>, scalar_lhs: bool) -> Self {
         let f = func;
         let data: HashMap<(usize, usize), T> = self
             .data
@@ -135,58 +138,62 @@ where
         }
     }
 
-    pub fn calc_range(
-        self,
-        range: MatrixValue<T>,
-        func: Box<dyn Fn(&T, &T) -> T>,
-        range_lhs: bool,
-    ) -> Self {
-        let this_avail_size = self.get_avail_size();
-        let rival_size = range.get_avail_size();
-        let (min_row, max_row) = {
-            if this_avail_size.0 > rival_size.0 {
-                (rival_size.0, this_avail_size.0)
-            } else {
-                (this_avail_size.0, rival_size.0)
-            }
+    // Calculate with another matrix element by element. Like Excel, a matrix
+    // of a single row or column is repeated along the other one, and the
+    // positions out of either matrix are filled with `pad`.
+    pub fn calc_range(self, range: MatrixValue<T>, func: BinaryFn<T>, pad: T) -> Self {
+        // The size and the available size of the result in a dimension.
+        let dim = |this: usize, rival: usize, this_avail: usize, rival_avail: usize| match (
+            this, rival,
+        ) {
+            _ if this == rival => (this, this_avail.max(rival_avail)),
+            (1, _) => (rival, rival),
+            (_, 1) => (this, this),
+            _ => (this.max(rival), this.max(rival)),
         };
-        let (min_col, max_col) = {
-            if this_avail_size.1 > rival_size.1 {
-                (rival_size.1, this_avail_size.1)
-            } else {
-                (this_avail_size.1, rival_size.1)
+        let (row, avail_row) = dim(
+            self.size.0,
+            range.size.0,
+            self.avail_size.0,
+            range.avail_size.0,
+        );
+        let (col, avail_col) = dim(
+            self.size.1,
+            range.size.1,
+            self.avail_size.1,
+            range.avail_size.1,
+        );
+        let get = |m: &MatrixValue<T>, i: usize, j: usize| {
+            let i = if m.size.0 == 1 { 0 } else { i };
+            let j = if m.size.1 == 1 { 0 } else { j };
+            if i >= m.size.0 || j >= m.size.1 {
+                return None;
+            }
+            match m.visit(i, j) {
+                Ok(t) => Some(t.clone()),
+                Err(e) => Some(e),
             }
         };
-        let new_size = (max_row, max_col);
-        let new_avail_size = (min_row, min_col);
-        let new_data = {
-            cross_product_usize(0, new_avail_size.0 - 1, 0, new_avail_size.1 - 1)
+        let data = match (avail_row, avail_col) {
+            (0, _) | (_, 0) => HashMap::new(),
+            _ => cross_product_usize(0, avail_row - 1, 0, avail_col - 1)
                 .into_iter()
-                .fold(HashMap::<(usize, usize), T>::new(), |mut prev, (i, j)| {
-                    let v1 = match self.visit(i, j) {
-                        Ok(t) => t.clone(),
-                        Err(e) => e,
+                .map(|(i, j)| {
+                    let v = match (get(&self, i, j), get(&range, i, j)) {
+                        (Some(l), Some(r)) => func(&l, &r),
+                        _ => pad.clone(),
                     };
-                    let v2 = match range.visit(i, j) {
-                        Ok(t) => t.clone(),
-                        Err(e) => e,
-                    };
-                    let v = {
-                        if range_lhs {
-                            func(&v2, &v1)
-                        } else {
-                            func(&v1, &v2)
-                        }
-                    };
-                    prev.insert((i, j), v);
-                    prev
+                    ((i, j), v)
                 })
+                .collect(),
         };
+        // Out of the available size, both matrices hold their default values.
+        let default = func(&self.get_default(), &range.get_default());
         MatrixValue {
-            size: new_size,
-            avail_size: new_avail_size,
-            data: new_data,
-            default_value: vec![],
+            size: (row, col),
+            avail_size: (avail_row, avail_col),
+            data,
+            default_value: vec![Box::new(move |_| default.clone())],
         }
     }
 
@@ -198,27 +205,44 @@ where
         self.avail_size
     }
 
+    // The positions whose values are stored. The others hold the default value,
+    // so that a sparse matrix can be visited without going through every cell.
+    pub fn get_stored_positions(&self) -> Vec<(usize, usize)> {
+        let (row, col) = self.avail_size;
+        if row == 0 || col == 0 {
+            return vec![];
+        }
+        if !self.default_value.is_empty() {
+            return cross_product_usize(0, row - 1, 0, col - 1);
+        }
+        self.data
+            .keys()
+            .filter(|(i, j)| *i < row && *j < col)
+            .cloned()
+            .collect()
+    }
+
     pub fn visit(&self, i: usize, j: usize) -> Result<&T, T> {
         match self.raw_visit(i, j) {
             Some(r) => Ok(r),
-            None => Err(self
-                .default_value
-                .iter()
-                .fold(T::default(), |prev, f| f(&prev))),
+            None => Err(self.get_default()),
         }
     }
 
     pub fn visit_and_insert(&mut self, i: usize, j: usize) -> Option<&T> {
         if self.raw_visit(i, j).is_none() {
-            let v = self
-                .default_value
-                .iter()
-                .fold(T::default(), |prev, f| f(&prev));
+            let v = self.get_default();
             self.insert(i, j, v);
         }
         self.raw_visit(i, j)
     }
 
+    fn get_default(&self) -> T {
+        self.default_value
+            .iter()
+            .fold(T::default(), |prev, f| f(&prev))
+    }
+
     pub fn insert(&mut self, i: usize, j: usize, v: T) {
         let (new_i, new_j) = self.get_avail_index(i, j);
         self.data.insert((new_i, new_j), v);
@@ -295,6 +319,34 @@ pub fn cross_product_usize(
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn calc_range_test() {
+        use super::MatrixValue;
+        let values = |m: MatrixValue<i32>| {
+            let (row, col) = m.get_size();
+            (0..row)
+                .map(|i| (0..col).map(|j| *m.visit(i, j).unwrap()).collect())
+                .collect::<Vec<Vec<_>>>()
+        };
+        let add = || Box::new(|a: &i32, b: &i32| a + b);
+        let row = MatrixValue::from(vec![vec![1, 2, 3]]);
+        let col = MatrixValue::from(vec![vec![10], vec![20]]);
+        let r = row.clone().calc_range(col.clone(), add(), -1);
+        assert_eq!(values(r), vec![vec![11, 12, 13], vec![21, 22, 23]]);
+
+        let r = col.calc_range(MatrixValue::from(vec![vec![1, 2]]), add(), -1);
+        assert_eq!(values(r), vec![vec![11, 12], vec![21, 22]]);
+
+        let square = MatrixValue::from(vec![vec![1, 1], vec![1, 1]]);
+        let r = square.calc_range(row, add(), -1);
+        assert_eq!(values(r), vec![vec![2, 3, -1], vec![2, 3, -1]]);
+
+        let a = MatrixValue::from(vec![vec![1], vec![2], vec![3]]);
+        let b = MatrixValue::from(vec![vec![1], vec![2]]);
+        let r = a.calc_range(b, add(), -1);
+        assert_eq!(values(r), vec![vec![2], vec![4], vec![-1]]);
+    }
+
     #[test]
     fn cross_product_usize_test() {
         use super::cross_product_usize;
@@ -6,6 +6,8 @@ pub trait NameFetcherTrait {
     fn fetch_sheet_name(&self, sheet_id: &SheetId) -> String;
     fn fetch_book_name(&self, book_id: &ExtBookId) -> String;
     fn fetch_defined_name(&self, nid: &NameId) -> String;
+    // Return the sheet if this name is defined in a sheet scope.
+    fn fetch_name_scope(&self, nid: &NameId) -> Option<SheetId>;
     fn fetch_cell_idx(&mut self, sheet_id: &SheetId, cell_id: &CellId) -> (usize, usize);
     fn fetch_row_idx(&mut self, sheet_id: &SheetId, row_id: &RowId) -> usize;
     fn fetch_col_idx(&mut self, sheet_id: &SheetId, col_id: &ColId) -> usize;
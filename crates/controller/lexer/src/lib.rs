@@ -2,6 +2,7 @@ extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
+use pest::error::{ErrorVariant, InputLocation};
 use pest::Parser;
 
 #[derive(Parser)]
@@ -9,19 +10,84 @@ use pest::Parser;
 pub struct FormulaParser;
 
 pub fn lex(s: &str) -> Option<pest::iterators::Pair<Rule>> {
+    try_lex(s).ok()
+}
+
+// The byte span where the lexer fails and the rules it expects there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub start: usize,
+    pub end: usize,
+    pub expected: Vec<Rule>,
+}
+
+pub fn try_lex(s: &str) -> Result<pest::iterators::Pair<'_, Rule>, LexError> {
     let result = FormulaParser::parse(Rule::start, s);
     match result {
         Ok(mut r) => {
             let tokens = r.next().unwrap();
-            Some(tokens)
+            Ok(tokens)
+        }
+        Err(e) => {
+            let (start, end) = match e.location {
+                InputLocation::Pos(p) => (p, p),
+                InputLocation::Span(span) => span,
+            };
+            let expected = match e.variant {
+                ErrorVariant::ParsingError { positives, .. } => positives,
+                ErrorVariant::CustomError { .. } => vec![],
+            };
+            Err(LexError {
+                start,
+                end,
+                expected,
+            })
         }
-        Err(_e) => None,
+    }
+}
+
+// A defined name is valid if formulas read it back as a name, rather than
+// a reference, a constant or an intersection. Like Excel, `R` and `C` are
+// kept for the R1C1 references.
+pub fn is_valid_name(s: &str) -> bool {
+    if s.eq_ignore_ascii_case("R") || s.eq_ignore_ascii_case("C") {
+        return false;
+    }
+    match lex(s) {
+        Some(formula) => formula
+            .into_inner()
+            .flatten()
+            .any(|p| p.as_rule() == Rule::name_characters && p.as_str() == s),
+        None => false,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::lex;
+    use super::{is_valid_name, lex, try_lex, Rule};
+
+    #[test]
+    fn valid_name() {
+        ["Rate", "_tax", "\\path", "tax.rate", "Rate_2", "SUM"]
+            .iter()
+            .for_each(|n| assert!(is_valid_name(n), "{}", n));
+        [
+            "",
+            "A1",
+            "Rate2",
+            "R2D2",
+            "R1C1",
+            "r",
+            "C",
+            "has space",
+            "2x",
+            "TRUE",
+            "a-b",
+            "Book!x",
+        ]
+        .iter()
+        .for_each(|n| assert!(!is_valid_name(n), "{}", n));
+    }
 
     #[test]
     fn constant() {
@@ -71,10 +137,30 @@ mod tests {
         println!("{:?}", r);
         let r = lex("5%").unwrap();
         println!("{:?}", r);
+        // The sheet name in a string is not a reference.
+        let r = lex("INDIRECT(\"Sheet1!A1\")").unwrap();
+        let rules = r
+            .into_inner()
+            .flatten()
+            .map(|p| p.as_rule())
+            .collect::<Vec<_>>();
+        assert!(rules.contains(&Rule::string_constant));
+        assert!(!rules.contains(&Rule::work_sheet_prefix));
+        assert!(!rules.contains(&Rule::cell_reference));
         let r = lex("SUM(1:2)").unwrap();
         println!("{:?}", r);
         let r = lex("_xll.VDATA(1:2)").unwrap();
         println!("{:?}", r);
+        let r = lex("SUM(A1#)").unwrap();
+        let pairs = r.into_inner().flatten().collect::<Vec<_>>();
+        let spill = pairs
+            .iter()
+            .position(|p| p.as_rule() == Rule::spill_op)
+            .unwrap();
+        assert_eq!(pairs[spill].as_str(), "#");
+        assert!(pairs[..spill]
+            .iter()
+            .any(|p| p.as_rule() == Rule::cell_reference && p.as_str() == "A1"));
     }
 
     #[test]
@@ -106,4 +192,15 @@ mod tests {
         let r = lex("1/-2");
         println!("{:?}", r);
     }
+
+    #[test]
+    fn lex_error() {
+        let e = try_lex("SUM(A1:,B2)").unwrap_err();
+        assert_eq!(e.start, 7);
+        assert!(e.expected.contains(&Rule::a1_reference));
+        let e = try_lex("1+").unwrap_err();
+        assert_eq!(e.start, 2);
+        assert!(e.expected.contains(&Rule::function_name));
+        assert!(try_lex("SUM(A1:B2)").is_ok());
+    }
 }
@@ -13,6 +13,7 @@ pub enum Error {
     Ref,
     Value,
     GettingData,
+    Spill,
 }
 
 impl Error {
@@ -27,6 +28,7 @@ impl Error {
             Error::Ref => "#REF!",
             Error::Value => "#VALUE!",
             Error::GettingData => "#GETTING_DATA",
+            Error::Spill => "#SPILL!",
         }
     }
 
@@ -41,6 +43,7 @@ impl Error {
             "#REF!" => Error::Ref,
             "#VALUE!" => Error::Value,
             "#GETTING_DATA" => Error::GettingData,
+            "#SPILL!" => Error::Spill,
             _ => Error::Unspecified,
         }
     }
@@ -73,6 +76,8 @@ pub enum PrefixOperator {
 #[derive(Debug, Clone)]
 pub enum PostfixOperator {
     Percent,
+    // The `#` operator, referring to the spill range of the anchor cell.
+    Spill,
 }
 
 #[derive(Debug, Clone)]
@@ -83,6 +88,8 @@ pub enum Value {
     Boolean(bool),
     Error(Error),
     Date(DateTime<FixedOffset>),
+    // Array constants like {1,2;3,4}, stored row by row.
+    Array(Vec<Vec<Value>>),
 }
 
 #[derive(Debug, Clone)]
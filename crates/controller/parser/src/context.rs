@@ -67,10 +67,22 @@ where
         self.id_fetcher.fetch_sheet_id(sheet_name)
     }
 
+    fn find_sheet_id(&mut self, sheet_name: &str) -> Option<SheetId> {
+        self.id_fetcher.find_sheet_id(sheet_name)
+    }
+
     fn fetch_name_id(&mut self, workbook: &Option<&str>, name: &str) -> logisheets_base::NameId {
         self.id_fetcher.fetch_name_id(workbook, name)
     }
 
+    fn fetch_local_name_id(
+        &mut self,
+        sheet_id: SheetId,
+        name: &str,
+    ) -> Option<logisheets_base::NameId> {
+        self.id_fetcher.fetch_local_name_id(sheet_id, name)
+    }
+
     fn fetch_ext_book_id(&mut self, book: &str) -> logisheets_base::ExtBookId {
         self.id_fetcher.fetch_ext_book_id(book)
     }
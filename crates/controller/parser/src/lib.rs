@@ -12,7 +12,6 @@ pub mod walker;
 extern crate lazy_static;
 use crate::climber::{Assoc, Climber, ClimberBuilder, Operator};
 use context::ContextTrait;
-use logisheets_base::id_fetcher::IdFetcherTrait;
 use logisheets_lexer::*;
 use pest::iterators::Pair;
 use reference::build_cell_reference;
@@ -37,6 +36,7 @@ lazy_static! {
         .op(Operator::new(Rule::prefix_op, Assoc::Prefix))
         .op(Operator::new(Rule::space_op, Assoc::Left))
         .op(Operator::new(Rule::colon_op, Assoc::Left))
+        .op(Operator::new(Rule::spill_op, Assoc::Postfix))
         .build();
     static ref NUM_REGEX: Regex =
         Regex::new(r#"([0-9]+)?(\.?([0-9]+))?([Ee]([+-]?[0-9]+))?"#).unwrap();
@@ -113,6 +113,13 @@ impl Parser {
                         let pure = ast::PureNode::Func(func);
                         ast::Node { pure, bracket }
                     }
+                    Rule::spill_op => {
+                        let op = ast::Operator::Postfix(ast::PostfixOperator::Spill);
+                        let args = vec![lhs];
+                        let func = ast::Func { op, args };
+                        let pure = ast::PureNode::Func(func);
+                        ast::Node { pure, bracket }
+                    }
                     _ => unreachable!(),
                 }
             },
@@ -154,6 +161,13 @@ impl Parser {
                     bracket: false,
                 }
             }
+            Rule::array_constant => {
+                let pure = build_array_constant(pair);
+                ast::Node {
+                    pure,
+                    bracket: false,
+                }
+            }
             Rule::cell_reference => {
                 let pure = build_cell_reference(pair, context)
                     .unwrap_or(ast::PureNode::Value(ast::Value::Error(ast::Error::Ref)));
@@ -211,6 +225,10 @@ impl Parser {
     {
         match pair.as_rule() {
             Rule::expression => self.parse_from_pair(pair, context, false),
+            Rule::empty_argument => ast::Node {
+                pure: ast::PureNode::Value(ast::Value::Blank),
+                bracket: false,
+            },
             Rule::comma_node => {
                 let mut args = Vec::<ast::Node>::new();
                 pair.into_inner().for_each(|p| match p.as_rule() {
@@ -252,22 +270,36 @@ fn parse_number(s: &str) -> Option<f64> {
     Some(result)
 }
 
-fn build_name_with_prefix<T>(pair: Pair<Rule>, id_fetcher: &mut T) -> Option<ast::CellReference>
+// A name without prefix is looked up in the scope of the active sheet first.
+// A name like `Sheet1!Name` refers to the name defined in `Sheet1`, or the
+// workbook scoped one if `Sheet1` has no such name. The prefix is regarded as
+// an external workbook only when no sheet is called so.
+fn build_name_with_prefix<T>(pair: Pair<Rule>, context: &mut T) -> Option<ast::CellReference>
 where
-    T: IdFetcherTrait,
+    T: ContextTrait,
 {
     let mut p_iter = pair.into_inner();
     let first = p_iter.next().unwrap();
     match first.as_rule() {
         Rule::workbook_name => {
-            let book_name = &Some(first.as_str());
+            let prefix = first.as_str();
             let second = p_iter.next().unwrap().as_str();
-            let name_id = id_fetcher.fetch_name_id(book_name, second);
+            let name_id = match context.find_sheet_id(prefix) {
+                Some(sheet_id) => match context.fetch_local_name_id(sheet_id, second) {
+                    Some(id) => id,
+                    None => context.fetch_name_id(&None, second),
+                },
+                None => context.fetch_name_id(&Some(prefix), second),
+            };
             Some(ast::CellReference::Name(name_id))
         }
         Rule::name_characters => {
             let n = first.as_str();
-            let name_id = id_fetcher.fetch_name_id(&None, n);
+            let sheet_id = context.get_active_sheet();
+            let name_id = match context.fetch_local_name_id(sheet_id, n) {
+                Some(id) => id,
+                None => context.fetch_name_id(&None, n),
+            };
             Some(ast::CellReference::Name(name_id))
         }
         _ => unreachable!(),
@@ -275,8 +307,7 @@ where
 }
 
 fn build_bool(pair: Pair<Rule>) -> ast::PureNode {
-    let v = pair.into_inner().as_str().parse::<String>().unwrap();
-    if v == "TRUE" {
+    if pair.as_str() == "TRUE" {
         let value = ast::Value::Boolean(true);
         ast::PureNode::Value(value)
     } else {
@@ -295,6 +326,7 @@ fn build_error(pair: Pair<Rule>) -> ast::PureNode {
         "#REF!" => ast::Error::Ref,
         "#VALUE!" => ast::Error::Value,
         "#GETTING_DATA" => ast::Error::GettingData,
+        "#SPILL!" => ast::Error::Spill,
         _ => unreachable!(),
     };
     ast::PureNode::Value(ast::Value::Error(error))
@@ -313,6 +345,49 @@ fn build_numerical_constant(pair: Pair<Rule>) -> ast::PureNode {
     )
 }
 
+// The rows of an array constant should have the same number of elements.
+fn build_array_constant(pair: Pair<Rule>) -> ast::PureNode {
+    let rows = pair
+        .into_inner()
+        .flat_map(|rows| rows.into_inner())
+        .filter(|row| row.as_rule() == Rule::constant_list_row)
+        .map(|row| {
+            row.into_inner()
+                .filter(|e| e.as_rule() != Rule::comma)
+                .map(build_array_element)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let cols = rows.first().map_or(0, |r| r.len());
+    if rows.iter().any(|r| r.len() != cols) {
+        return ast::PureNode::Value(ast::Value::Error(ast::Error::Value));
+    }
+    ast::PureNode::Value(ast::Value::Array(rows))
+}
+
+fn build_array_element(pair: Pair<Rule>) -> ast::Value {
+    let pure = match pair.as_rule() {
+        Rule::logical_constant => build_bool(pair),
+        Rule::error_constant => build_error(pair),
+        Rule::string_constant => build_string_constant(pair),
+        Rule::numerical_constant => build_numerical_constant(pair),
+        Rule::negative_numerical_constant => {
+            let num = pair.into_inner().next().unwrap();
+            match build_numerical_constant(num) {
+                ast::PureNode::Value(ast::Value::Number(n)) => {
+                    ast::PureNode::Value(ast::Value::Number(-n))
+                }
+                p => p,
+            }
+        }
+        _ => unreachable!(),
+    };
+    match pure {
+        ast::PureNode::Value(v) => v,
+        _ => unreachable!(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ast;
@@ -375,6 +450,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_empty_args_test() {
+        let parser = Parser {};
+        let mut id_fetcher = TestFetcher {};
+        let mut context = Context {
+            sheet_id: 1,
+            book_name: "book",
+            id_fetcher: &mut id_fetcher,
+        };
+        let blanks = |f: &str, context: &mut Context<TestFetcher>| match parser
+            .parse(f, context)
+            .unwrap()
+            .pure
+        {
+            ast::PureNode::Func(f) => f
+                .args
+                .iter()
+                .map(|a| matches!(a.pure, ast::PureNode::Value(ast::Value::Blank)))
+                .collect::<Vec<_>>(),
+            _ => panic!(),
+        };
+        assert_eq!(blanks("SUM()", &mut context), Vec::<bool>::new());
+        assert_eq!(blanks("SUM(1, ,2)", &mut context), vec![false, true, false]);
+        assert_eq!(blanks("SUM(,1)", &mut context), vec![true, false]);
+        assert_eq!(blanks("SUM(1,,)", &mut context), vec![false, true, true]);
+    }
+
     #[test]
     fn parse_prefix_op() {
         let parser = Parser {};
@@ -452,6 +554,28 @@ mod tests {
         let f1 = "3.14%";
         let r1 = parser.parse(f1, &mut context).unwrap().pure;
         assert!(matches!(r1, ast::PureNode::Func(_)));
+        let f2 = "SUM(A1#)*2";
+        let r2 = parser.parse(f2, &mut context).unwrap().pure;
+        match r2 {
+            ast::PureNode::Func(func) => {
+                let sum = func.args.into_iter().next().unwrap();
+                let spill = match sum.pure {
+                    ast::PureNode::Func(f) => f.args.into_iter().next().unwrap(),
+                    _ => panic!(),
+                };
+                match spill.pure {
+                    ast::PureNode::Func(f) => {
+                        assert!(matches!(
+                            f.op,
+                            ast::Operator::Postfix(ast::PostfixOperator::Spill)
+                        ));
+                        assert!(matches!(f.args[0].pure, ast::PureNode::Reference(_)));
+                    }
+                    _ => panic!(),
+                }
+            }
+            _ => panic!(),
+        }
     }
 
     #[test]
@@ -49,7 +49,7 @@ where
     let build_mut_ref = |pair, sheet_id, id_fetcher| {
         let a1_ref = build_mut_a1_reference_range(pair, sheet_id, id_fetcher)?;
         Some(ast::CellReference::Mut(MutRefWithPrefix {
-            sheet_id: curr_sheet,
+            sheet_id,
             reference: ast::MutRef::A1ReferenceRange(a1_ref),
         }))
     };
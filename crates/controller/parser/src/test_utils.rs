@@ -28,10 +28,18 @@ impl IdFetcherTrait for TestFetcher {
         sheet_name.len() as SheetId
     }
 
+    fn find_sheet_id(&mut self, sheet_name: &str) -> Option<SheetId> {
+        Some(sheet_name.len() as SheetId)
+    }
+
     fn fetch_name_id(&mut self, _: &Option<&str>, name: &str) -> NameId {
         name.len() as NameId
     }
 
+    fn fetch_local_name_id(&mut self, _: SheetId, _: &str) -> Option<NameId> {
+        None
+    }
+
     fn fetch_ext_book_id(&mut self, book: &str) -> ExtBookId {
         book.len() as ExtBookId
     }
@@ -66,6 +74,10 @@ impl NameFetcherTrait for TestFetcher {
         nid.to_string()
     }
 
+    fn fetch_name_scope(&self, _nid: &NameId) -> Option<SheetId> {
+        None
+    }
+
     fn fetch_cell_idx(&mut self, _sheet_id: &SheetId, cell_id: &CellId) -> (usize, usize) {
         if let CellId::NormalCell(NormalCellId {
             row,
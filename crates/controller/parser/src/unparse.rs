@@ -119,7 +119,15 @@ impl Stringify for CellReference {
         match self {
             CellReference::Mut(mutref) => mutref.unparse(fetcher, curr_sheet),
             CellReference::UnMut(unmut_ref) => unmut_ref.unparse(fetcher, curr_sheet),
-            CellReference::Name(nid) => fetcher.fetch_defined_name(nid),
+            CellReference::Name(nid) => {
+                let name = fetcher.fetch_defined_name(nid);
+                match fetcher.fetch_name_scope(nid) {
+                    Some(sheet) if sheet != curr_sheet => {
+                        format!("{}!{}", fetcher.fetch_sheet_name(&sheet), name)
+                    }
+                    _ => name,
+                }
+            }
         }
     }
 }
@@ -133,7 +141,7 @@ impl Stringify for MutRefWithPrefix {
         let prefix = if sheet == curr_sheet {
             "".to_owned()
         } else {
-            fetcher.fetch_sheet_name(&sheet)
+            quote_sheet_name(fetcher.fetch_sheet_name(&sheet))
         };
         let mut get_a1_ref_str = |r: &A1Reference| -> String {
             match r {
@@ -229,11 +237,14 @@ impl Stringify for UnMutRefPrefix {
         match self {
             UnMutRefPrefix::Local(local) => match local {
                 LocalUnMutRefPrefix::SheetToSheet(sts) => {
-                    format!(
-                        "{}:{}!",
-                        fetcher.fetch_sheet_name(&sts.from_sheet),
-                        fetcher.fetch_sheet_name(&sts.to_sheet)
-                    )
+                    let from = fetcher.fetch_sheet_name(&sts.from_sheet);
+                    let to = fetcher.fetch_sheet_name(&sts.to_sheet);
+                    let range = format!("{}:{}", from, to);
+                    if is_plain_sheet_name(&from) && is_plain_sheet_name(&to) {
+                        format!("{}!", range)
+                    } else {
+                        format!("'{}'!", range.replace('\'', "''"))
+                    }
                 }
             },
             UnMutRefPrefix::External(e) => e.unparse(fetcher, curr_sheet),
@@ -412,6 +423,18 @@ impl Stringify for Value {
             .to_string(),
             Value::Error(e) => e.unparse(fetcher, curr_sheet),
             Value::Date(_) => todo!(),
+            Value::Array(rows) => {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|v| v.unparse(fetcher, curr_sheet))
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", rows.join(";"))
+            }
         }
     }
 }
@@ -468,10 +491,47 @@ impl Stringify for PostfixOperator {
     {
         match self {
             PostfixOperator::Percent => String::from("%"),
+            PostfixOperator::Spill => String::from("#"),
         }
     }
 }
 
+// Sheet names are quoted unless they are made of letters, digits, underscores
+// and dots, and can not be read as a number or a cell reference like `A1` or
+// `R1C1`. Otherwise the formula can not be parsed again.
+fn quote_sheet_name(name: String) -> String {
+    if is_plain_sheet_name(&name) {
+        name
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+fn is_plain_sheet_name(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    // Up to 3 letters followed by digits, like `XFD1`.
+    let letters = upper.trim_end_matches(|c: char| c.is_ascii_digit());
+    let is_a1 = (1..=3).contains(&letters.len())
+        && letters.len() < upper.len()
+        && letters.chars().all(|c| c.is_ascii_uppercase());
+    // `R` and `C` each followed by optional digits, like `R1C1`, `RC` or `C3`.
+    let is_r1c1 = {
+        let rest = upper.strip_prefix('R').unwrap_or(&upper);
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        let rest = rest.strip_prefix('C').unwrap_or(rest);
+        rest.trim_start_matches(|c: char| c.is_ascii_digit())
+            .is_empty()
+            && (upper.starts_with('R') || upper.starts_with('C'))
+    };
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && !is_a1
+        && !is_r1c1
+}
+
 fn abs_str(abs: bool) -> String {
     if abs {
         String::from("$")
@@ -482,7 +542,7 @@ fn abs_str(abs: bool) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::unparse;
+    use super::{quote_sheet_name, unparse};
     use crate::context::Context;
     use crate::test_utils::TestFetcher;
     use crate::Parser;
@@ -531,4 +591,40 @@ mod tests {
         let a = unparse(&node, &mut id_fetcher, 0);
         assert_eq!(a, "1 * (3 - 2)")
     }
+
+    #[test]
+    fn array_constant_test() {
+        let parser = Parser {};
+        let f = "SUM(2,{1,\"a\";-2.5,\"b\";TRUE,#N/A},2)";
+        let mut id_fetcher = TestFetcher {};
+        let mut context = Context {
+            sheet_id: 1,
+            book_name: "book",
+            id_fetcher: &mut id_fetcher,
+        };
+        let node = parser.parse(f, &mut context).unwrap();
+        let a = unparse(&node, &mut TestFetcher {}, 0);
+        assert_eq!(a, "SUM(2, {1,\"a\";-2.5,\"b\";TRUE,#N/A}, 2)");
+        let node = parser.parse("{1,2;3}", &mut context).unwrap();
+        let a = unparse(&node, &mut TestFetcher {}, 0);
+        assert_eq!(a, "#VALUE!");
+    }
+
+    #[test]
+    fn quote_sheet_name_test() {
+        let quote = |name: &str| quote_sheet_name(name.to_string());
+        assert_eq!(quote("Sheet1"), "Sheet1");
+        assert_eq!(quote("data_2024.v2"), "data_2024.v2");
+        assert_eq!(quote("ABCD1"), "ABCD1");
+        assert_eq!(quote("Sheet 1"), "'Sheet 1'");
+        assert_eq!(quote("Bob's"), "'Bob''s'");
+        assert_eq!(quote("2024"), "'2024'");
+        assert_eq!(quote("1Q"), "'1Q'");
+        assert_eq!(quote("A1"), "'A1'");
+        assert_eq!(quote("xfd1048576"), "'xfd1048576'");
+        assert_eq!(quote("R1C1"), "'R1C1'");
+        assert_eq!(quote("R"), "'R'");
+        assert_eq!(quote("c3"), "'c3'");
+        assert_eq!(quote("a:b"), "'a:b'");
+    }
 }
@@ -82,7 +82,8 @@ where
                                 None => break,
                             }
                         }
-                        if curr_scc.len() > 1 {
+                        // A vertex depending on itself is a cycle, too.
+                        if curr_scc.len() > 1 || rdeps_fetcher(&u).contains(&u) {
                             curr_scc.iter().for_each(|s| {
                                 scc_map.insert(s.clone(), scc_id);
                             });
@@ -103,9 +104,11 @@ where
         }
         calc_order_desc
     });
+    // The vertices finished later should be calculated earlier, even if they
+    // are found in a later search.
     orders.fold(VecDeque::<CalcUnit<V>>::new(), |mut prev, c| {
-        c.into_iter().rev().for_each(|e| {
-            prev.push_back(e);
+        c.into_iter().for_each(|e| {
+            prev.push_front(e);
         });
         prev
     })
@@ -327,6 +330,30 @@ mod tests {
         assert_eq!(nodes, vec![4, 1, 2, 5]);
     }
 
+    #[test]
+    fn node_order_test3() {
+        // 1 -> 2 -> 3, and all of them are dirty.
+        let graph = Graph::<u32>::new().add_dep(2, 1).add_dep(3, 2);
+        let rdeps_fetcher = |r: &u32| -> Vec<u32> {
+            match graph.get_rdeps(r) {
+                Some(s) => s.clone().into_iter().collect_vec(),
+                None => vec![],
+            }
+        };
+        (0..10).for_each(|_| {
+            let dirty_nodes = HashSet::<u32>::from(vec![3, 1, 2]);
+            let order = calc_order(&rdeps_fetcher, dirty_nodes);
+            let nodes = order
+                .into_iter()
+                .map(|c| match c {
+                    CalcUnit::Cycle(_) => panic!("unexpected cycle"),
+                    CalcUnit::Node(n) => n,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(nodes, vec![1, 2, 3]);
+        });
+    }
+
     #[test]
     fn order_set() {
         let mut ordset = OrdSet::<u32>::new();
@@ -386,4 +413,31 @@ mod tests {
         assert!(get_nodes_order(4) < get_nodes_order(5));
         assert!(get_nodes_order(4) < get_cycles_order(&vec![2, 3, 1]));
     }
+
+    #[test]
+    fn self_cycle_test() {
+        //  1 -- 2
+        //  |
+        //  1
+        let graph = Graph::<u32>::new().add_dep(1, 1).add_dep(2, 1);
+        let rdeps_fetcher = |r: &u32| -> Vec<u32> {
+            match graph.get_rdeps(r) {
+                Some(s) => s.clone().into_iter().collect_vec(),
+                None => vec![],
+            }
+        };
+        let mut dirty_nodes = HashSet::<u32>::new();
+        dirty_nodes.insert(1);
+        let order = calc_order(&rdeps_fetcher, dirty_nodes);
+        assert_eq!(order.len(), 2);
+        let mut iter = order.into_iter();
+        match iter.next().unwrap() {
+            CalcUnit::Cycle(c) => assert_eq!(c, vec![1]),
+            CalcUnit::Node(_) => panic!(),
+        }
+        match iter.next().unwrap() {
+            CalcUnit::Cycle(_) => panic!(),
+            CalcUnit::Node(n) => assert_eq!(n, 2),
+        }
+    }
 }
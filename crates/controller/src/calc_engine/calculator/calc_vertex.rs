@@ -94,6 +94,8 @@ impl Value {
             ast::Value::Boolean(b) => Value::Boolean(b.clone()),
             ast::Value::Error(e) => Value::Error(e.clone()),
             ast::Value::Date(d) => Value::Date(d.clone()),
+            // Arrays are not scalars and they are converted to ranges.
+            ast::Value::Array(_) => Value::Error(ast::Error::Value),
         }
     }
 
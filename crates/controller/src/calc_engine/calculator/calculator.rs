@@ -1,6 +1,8 @@
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_base::Addr;
 use logisheets_parser::ast;
 
-use super::calc_vertex::{CalcValue, CalcVertex, Value};
+use super::calc_vertex::{CalcReference, CalcValue, CalcVertex, Range, Reference, Value};
 
 use super::super::connector::Connector;
 use super::funcs;
@@ -19,6 +21,13 @@ where
     C: Connector,
 {
     match &node.pure {
+        ast::PureNode::Value(ast::Value::Array(rows)) => {
+            let rows = rows
+                .iter()
+                .map(|row| row.iter().map(Value::from_ast_value).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            CalcVertex::Value(CalcValue::Range(MatrixValue::from(rows)))
+        }
         ast::PureNode::Value(v) => CalcVertex::Value(CalcValue::Scalar(Value::from_ast_value(v))),
         ast::PureNode::Func(f) => calc_func(f, fetcher),
         ast::PureNode::Reference(r) => fetcher.convert(r),
@@ -52,13 +61,17 @@ where
             };
             infix::calc_infix(lhs, &op, rhs, fetcher)
         }
-        ast::Operator::Postfix(_) => {
+        ast::Operator::Postfix(ast::PostfixOperator::Percent) => {
             let mut iter = args.into_iter();
             let lhs = iter.next().unwrap();
-            let rhs = CalcVertex::Value(CalcValue::Scalar(Value::Number(0_f64)));
+            let rhs = CalcVertex::Value(CalcValue::Scalar(Value::Number(100_f64)));
             let op = ast::InfixOperator::Divide;
             infix::calc_infix(lhs, &op, rhs, fetcher)
         }
+        ast::Operator::Postfix(ast::PostfixOperator::Spill) => {
+            let anchor = args.into_iter().next().unwrap();
+            calc_spill_ref(anchor, fetcher)
+        }
         ast::Operator::Function(fid) => {
             let name = fetcher.get_func_name(fid);
             match name {
@@ -78,3 +91,77 @@ where
         }
     }
 }
+
+// The `#` operator only applies to a cell which has spilled an array.
+fn calc_spill_ref<C>(anchor: CalcVertex, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    match anchor {
+        CalcVertex::Reference(CalcReference {
+            from_sheet: None,
+            sheet,
+            reference: Reference::Addr(start),
+        }) => match fetcher.get_spill_size(sheet, start.row, start.col) {
+            Some((rows, cols)) => CalcVertex::Reference(CalcReference {
+                from_sheet: None,
+                sheet,
+                reference: Reference::Range(Range {
+                    start,
+                    end: Addr {
+                        row: start.row + rows - 1,
+                        col: start.col + cols - 1,
+                    },
+                }),
+            }),
+            None => CalcVertex::from_error(ast::Error::Ref),
+        },
+        CalcVertex::Value(CalcValue::Scalar(Value::Error(e))) => CalcVertex::from_error(e),
+        _ => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    #[test]
+    fn array_constant_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 1, 0, "2"),
+                input(0, 2, 0, "3"),
+                input(0, 0, 1, "=SUM({1;2;3}*A1:A3)"),
+                input(0, 0, 2, "=SUM({1,2;3,4}, 10)"),
+                input(0, 0, 3, "={1,\"a\";TRUE,-2}"),
+                input(0, 5, 0, "=SUM({1,2,3}*{1;2;3})"),
+                input(0, 6, 0, "={1,2}+{1,2,3}"),
+                // The last row and the last column of an array constant.
+                input(0, 7, 0, "=INDEX({1,2;3,4},2,1,1)"),
+                input(0, 7, 1, "=INDEX({1,2;3,4},1,2,1)"),
+                input(0, 7, 2, "=INDEX({1,2;3,4},2,2,1)"),
+                input(0, 7, 3, "=INDEX({1,2;3,4},3,1,1)"),
+            ],
+        );
+        assert_eq!(value(&mut wb, 0, 0, 1), "14");
+        assert_eq!(value(&mut wb, 0, 0, 2), "20");
+        assert_eq!(value(&mut wb, 0, 0, 3), "1");
+        assert_eq!(value(&mut wb, 0, 0, 4), "a");
+        assert_eq!(value(&mut wb, 0, 1, 3), "TRUE");
+        assert_eq!(value(&mut wb, 0, 1, 4), "-2");
+        let formula = wb.get_sheet_by_idx(0).unwrap().get_formula(0, 3).unwrap();
+        assert_eq!(formula, "{1,\"a\";TRUE,-2}");
+        assert_eq!(value(&mut wb, 0, 5, 0), "36");
+        assert_eq!(value(&mut wb, 0, 6, 0), "2");
+        assert_eq!(value(&mut wb, 0, 6, 1), "4");
+        assert_eq!(value(&mut wb, 0, 6, 2), "#N/A");
+        assert_eq!(value(&mut wb, 0, 7, 0), "3");
+        assert_eq!(value(&mut wb, 0, 7, 1), "2");
+        assert_eq!(value(&mut wb, 0, 7, 2), "4");
+        assert_eq!(value(&mut wb, 0, 7, 3), "#REF!");
+    }
+}
@@ -0,0 +1,110 @@
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+// The characters of Windows-1252 from 0x80 to 0x9F. The undefined ones are
+// mapped to the C1 control characters like Excel does.
+const CP1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+pub fn calc_char<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    let arg = fetcher.get_calc_value(args.into_iter().next().unwrap());
+    assert_f64_from_calc_value!(n, arg);
+    match cp1252_to_char(n.trunc()) {
+        Some(c) => CalcVertex::from_string(c.to_string()),
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+pub fn calc_code<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    let arg = fetcher.get_calc_value(args.into_iter().next().unwrap());
+    assert_text_from_calc_value!(text, arg);
+    match text.chars().next() {
+        Some(c) => CalcVertex::from_number(char_to_cp1252(c) as f64),
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+pub fn calc_unichar<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    let arg = fetcher.get_calc_value(args.into_iter().next().unwrap());
+    assert_f64_from_calc_value!(n, arg);
+    let n = n.trunc();
+    assert_or_return!(n >= 1. && n <= 0x10FFFF as f64, ast::Error::Value);
+    match char::from_u32(n as u32) {
+        Some(c) => CalcVertex::from_string(c.to_string()),
+        // The surrogates.
+        None => CalcVertex::from_error(ast::Error::Na),
+    }
+}
+
+pub fn calc_unicode<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    let arg = fetcher.get_calc_value(args.into_iter().next().unwrap());
+    assert_text_from_calc_value!(text, arg);
+    match text.chars().next() {
+        Some(c) => CalcVertex::from_number(c as u32 as f64),
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+fn cp1252_to_char(n: f64) -> Option<char> {
+    if !(1. ..=255.).contains(&n) {
+        return None;
+    }
+    let n = n as u32;
+    if (0x80..0xA0).contains(&n) {
+        Some(CP1252_HIGH[(n - 0x80) as usize])
+    } else {
+        char::from_u32(n)
+    }
+}
+
+// The characters out of Windows-1252 are regarded as `?`.
+fn char_to_cp1252(c: char) -> u32 {
+    let n = c as u32;
+    match n {
+        0..=0x7F | 0xA0..=0xFF => n,
+        _ => match CP1252_HIGH.iter().position(|h| *h == c) {
+            Some(p) => 0x80 + p as u32,
+            None => '?' as u32,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{char_to_cp1252, cp1252_to_char};
+
+    #[test]
+    fn cp1252_test() {
+        assert_eq!(cp1252_to_char(65.), Some('A'));
+        assert_eq!(cp1252_to_char(33.), Some('!'));
+        assert_eq!(cp1252_to_char(128.), Some('€'));
+        assert_eq!(cp1252_to_char(233.), Some('é'));
+        assert_eq!(cp1252_to_char(0.), None);
+        assert_eq!(cp1252_to_char(256.), None);
+        assert_eq!(char_to_cp1252('A'), 65);
+        assert_eq!(char_to_cp1252('!'), 33);
+        assert_eq!(char_to_cp1252('€'), 128);
+        assert_eq!(char_to_cp1252('逻'), 63);
+    }
+}
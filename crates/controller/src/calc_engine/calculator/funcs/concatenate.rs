@@ -1,16 +1,162 @@
+use super::lookup::matching::get_value;
+use super::utils::{get_condition_result, ConditionResult};
 use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
 use crate::calc_engine::connector::Connector;
+use logisheets_base::datetime::{get_serial_num_by_datetime, DateSystem};
 use logisheets_parser::ast;
 
+// The maximum length of a cell text.
+const MAX_LEN: usize = 32767;
+
 pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
 where
     C: Connector,
 {
-    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    assert_or_return!(!args.is_empty(), ast::Error::Unspecified);
+    let mut res = String::new();
+    for arg in args.into_iter() {
+        let value = fetcher.get_calc_value(arg);
+        assert_text_from_calc_value!(s, value);
+        res.push_str(&s);
+    }
+    check_len(res)
+}
+
+pub fn calc_concat<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty(), ast::Error::Unspecified);
+    let mut res = String::new();
+    for arg in args.into_iter() {
+        let texts = match get_texts(fetcher.get_calc_value(arg), fetcher.get_date_system()) {
+            Ok(t) => t,
+            Err(e) => return CalcVertex::from_error(e),
+        };
+        texts.iter().for_each(|t| res.push_str(t));
+    }
+    check_len(res)
+}
+
+pub fn calc_textjoin<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 3, ast::Error::Unspecified);
     let mut args_iter = args.into_iter();
-    let first = fetcher.get_calc_value(args_iter.next().unwrap());
-    assert_text_from_calc_value!(first_str, first);
-    let second = fetcher.get_calc_value(args_iter.next().unwrap());
-    assert_text_from_calc_value!(second_str, second);
-    CalcVertex::from_string(first_str + &second_str)
+    let delimiters = match get_texts(
+        fetcher.get_calc_value(args_iter.next().unwrap()),
+        fetcher.get_date_system(),
+    ) {
+        Ok(d) => d,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let ignore_empty = match get_condition_result(fetcher.get_calc_value(args_iter.next().unwrap()))
+    {
+        ConditionResult::True => true,
+        ConditionResult::False => false,
+        ConditionResult::Error(e) => return CalcVertex::from_error(e),
+    };
+    let mut texts = Vec::<String>::new();
+    for arg in args_iter {
+        match get_texts(fetcher.get_calc_value(arg), fetcher.get_date_system()) {
+            Ok(t) => texts.extend(t),
+            Err(e) => return CalcVertex::from_error(e),
+        }
+    }
+    check_len(textjoin(&delimiters, ignore_empty, texts))
+}
+
+// The delimiters are used in turn when more than one is given.
+fn textjoin(delimiters: &[String], ignore_empty: bool, texts: Vec<String>) -> String {
+    let mut res = String::new();
+    texts
+        .into_iter()
+        .filter(|t| !(ignore_empty && t.is_empty()))
+        .enumerate()
+        .for_each(|(i, t)| {
+            if i > 0 && !delimiters.is_empty() {
+                res.push_str(&delimiters[(i - 1) % delimiters.len()]);
+            }
+            res.push_str(&t);
+        });
+    res
+}
+
+// The texts of a value, row by row if it is a range. Dates are written as
+// their serial numbers.
+pub fn get_texts(value: CalcValue, system: DateSystem) -> Result<Vec<String>, ast::Error> {
+    match value {
+        CalcValue::Scalar(v) => Ok(vec![to_text(v, system)?]),
+        CalcValue::Range(m) => {
+            let (rows, cols) = m.get_size();
+            (0..rows)
+                .flat_map(|i| (0..cols).map(move |j| (i, j)))
+                .map(|(i, j)| to_text(get_value(&m, i, j), system))
+                .collect()
+        }
+        CalcValue::Cube(_) => Err(ast::Error::Ref),
+        CalcValue::Union(u) => u.into_iter().try_fold(vec![], |mut prev, v| {
+            prev.extend(get_texts(*v, system)?);
+            Ok(prev)
+        }),
+    }
+}
+
+fn to_text(value: Value, system: DateSystem) -> Result<String, ast::Error> {
+    match value {
+        Value::Blank => Ok(String::new()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Text(t) => Ok(t),
+        Value::Boolean(true) => Ok(String::from("TRUE")),
+        Value::Boolean(false) => Ok(String::from("FALSE")),
+        Value::Error(e) => Err(e),
+        Value::Date(d) => get_serial_num_by_datetime(&d, system)
+            .map(|n| n.to_string())
+            .ok_or(ast::Error::Value),
+    }
+}
+
+fn check_len(s: String) -> CalcVertex {
+    if s.chars().count() > MAX_LEN {
+        CalcVertex::from_error(ast::Error::Value)
+    } else {
+        CalcVertex::from_string(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{textjoin, to_text};
+    use crate::calc_engine::calculator::calc_vertex::Value;
+    use logisheets_base::datetime::DateSystem;
+
+    #[test]
+    fn to_text_test() {
+        let date = chrono::DateTime::parse_from_rfc3339("2008-07-08T12:00:00+00:00").unwrap();
+        let text = to_text(Value::Date(date), DateSystem::Date1900);
+        assert_eq!(text, Ok(String::from("39637.5")));
+        let text = to_text(Value::Date(date), DateSystem::Date1904);
+        assert_eq!(text, Ok(String::from("38175.5")));
+    }
+
+    #[test]
+    fn textjoin_test() {
+        let texts = ["US Dollar", "Australian Dollar", "", "Euro"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        let comma = vec![String::from(", ")];
+        assert_eq!(
+            textjoin(&comma, true, texts.clone()),
+            "US Dollar, Australian Dollar, Euro"
+        );
+        assert_eq!(
+            textjoin(&comma, false, texts.clone()),
+            "US Dollar, Australian Dollar, , Euro"
+        );
+        let delimiters = vec![String::from(","), String::from(";")];
+        let texts = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(textjoin(&delimiters, true, texts), "a,b;c,d");
+    }
 }
@@ -1,4 +1,4 @@
-use regex::Regex;
+use std::cmp::Ordering;
 
 use crate::calc_engine::calculator::calc_vertex::Value;
 
@@ -28,7 +28,10 @@ pub enum ConditionValue {
 pub fn parse_condition(text: &str) -> Option<Condition> {
     let text = text.trim();
     let mut chars = text.chars().peekable();
-    let first = chars.next()?;
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return Some(Condition::TextPattern(String::new())),
+    };
     let op: Option<Op> = match first {
         '>' => {
             if let Some('=') = chars.peek() {
@@ -67,7 +70,7 @@ pub fn parse_condition(text: &str) -> Option<Condition> {
             })),
         }
     } else {
-        Some(Condition::TextPattern(chars.collect::<String>()))
+        Some(Condition::TextPattern(text.to_string()))
     }
 }
 
@@ -89,46 +92,104 @@ pub fn get_condition_value(value: &Value) -> ConditionValue {
     }
 }
 
+// Texts are matched case-insensitively, and the `=` and `<>` criteria support
+// wildcards. A criterion without operators matches the numbers it reads as.
 pub fn match_condition(cond: &Condition, value: &Value) -> bool {
-    let v = get_condition_value(value);
-    match (cond, v) {
-        (Condition::Logical(l), ConditionValue::Number(lhs)) => match &l.value {
-            ConditionValue::Number(rhs) => match &l.op {
-                Op::Eq => lhs == *rhs,
-                Op::Ge => lhs >= *rhs,
-                Op::Gt => lhs > *rhs,
-                Op::Le => lhs <= *rhs,
-                Op::Lt => lhs < *rhs,
-                Op::Neq => lhs != *rhs,
-            },
-            ConditionValue::Text(_) => false,
+    match cond {
+        Condition::TextPattern(p) => {
+            if let Ok(n) = p.parse::<f64>() {
+                if let Value::Number(v) = value {
+                    return *v == n;
+                }
+            }
+            match (p.as_str(), value) {
+                ("", Value::Blank) => true,
+                (_, Value::Blank) | (_, Value::Number(_)) => false,
+                _ => match get_condition_value(value) {
+                    ConditionValue::Text(t) => match_text_pattern(p, &t),
+                    ConditionValue::Number(_) => false,
+                },
+            }
+        }
+        Condition::Logical(l) => match &l.op {
+            Op::Eq => is_equal(&l.value, value),
+            Op::Neq => !is_equal(&l.value, value),
+            op => {
+                let ord = match (&l.value, get_condition_value(value), value) {
+                    (_, _, Value::Blank) => None,
+                    (ConditionValue::Number(rhs), ConditionValue::Number(lhs), _) => {
+                        lhs.partial_cmp(rhs)
+                    }
+                    (ConditionValue::Text(rhs), ConditionValue::Text(lhs), _) => {
+                        Some(lhs.to_lowercase().cmp(&rhs.to_lowercase()))
+                    }
+                    _ => None,
+                };
+                match (op, ord) {
+                    (_, None) => false,
+                    (Op::Ge, Some(o)) => o != Ordering::Less,
+                    (Op::Gt, Some(o)) => o == Ordering::Greater,
+                    (Op::Le, Some(o)) => o != Ordering::Greater,
+                    (Op::Lt, Some(o)) => o == Ordering::Less,
+                    _ => unreachable!(),
+                }
+            }
         },
-        (Condition::Logical(l), ConditionValue::Text(lhs)) => match &l.value {
+    }
+}
+
+fn is_equal(cond_value: &ConditionValue, value: &Value) -> bool {
+    match (cond_value, value) {
+        (ConditionValue::Text(t), Value::Blank) => t.is_empty(),
+        (ConditionValue::Text(t), _) if t.is_empty() => false,
+        (ConditionValue::Number(n), Value::Number(v)) => v == n,
+        (ConditionValue::Number(_), _) => false,
+        (ConditionValue::Text(p), _) => match get_condition_value(value) {
+            ConditionValue::Text(t) => match_text_pattern(p, &t),
             ConditionValue::Number(_) => false,
-            ConditionValue::Text(rhs) => match &l.op {
-                Op::Eq => lhs == *rhs,
-                Op::Ge => lhs >= *rhs,
-                Op::Gt => lhs > *rhs,
-                Op::Le => lhs <= *rhs,
-                Op::Lt => lhs < *rhs,
-                Op::Neq => lhs != *rhs,
-            },
         },
-        (Condition::TextPattern(_), ConditionValue::Number(_)) => false,
-        (Condition::TextPattern(p), ConditionValue::Text(t)) => match_text_pattern(p, &t),
     }
 }
 
-fn match_text_pattern(pattern: &str, text: &str) -> bool {
-    let regex_str = format!("^{}$", pattern.replace("?", ".{1}").replace("*", ".*"));
-    let regex = Regex::new(&regex_str);
-    match regex {
-        Ok(r) => match r.captures_iter(text).next() {
-            Some(_) => true,
-            None => false,
-        },
-        Err(_) => false,
+// `*` matches any sequence of characters, `?` matches any single character
+// and `~` escapes the next one. Letters are matched case-insensitively.
+pub fn match_text_pattern(pattern: &str, text: &str) -> bool {
+    let mut tokens = Vec::<(char, bool)>::new();
+    let mut chars = pattern
+        .to_lowercase()
+        .chars()
+        .collect::<Vec<_>>()
+        .into_iter();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next() {
+                Some(n) => tokens.push((n, false)),
+                None => tokens.push(('~', false)),
+            },
+            '*' | '?' => tokens.push((c, true)),
+            _ => tokens.push((c, false)),
+        }
     }
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    // matched[j]: whether the tokens so far match the first j characters.
+    let mut matched = vec![false; text.len() + 1];
+    matched[0] = true;
+    tokens.iter().for_each(|(c, wild)| {
+        let mut next = vec![false; text.len() + 1];
+        match (c, wild) {
+            ('*', true) => {
+                let mut any = false;
+                (0..=text.len()).for_each(|j| {
+                    any = any || matched[j];
+                    next[j] = any;
+                });
+            }
+            ('?', true) => (1..=text.len()).for_each(|j| next[j] = matched[j - 1]),
+            _ => (1..=text.len()).for_each(|j| next[j] = matched[j - 1] && text[j - 1] == *c),
+        }
+        matched = next;
+    });
+    matched[text.len()]
 }
 
 #[cfg(test)]
@@ -252,4 +313,35 @@ mod tests {
         let condition = parse_condition(cond).unwrap();
         assert!(match_condition(&condition, &Value::Number(8_f64)));
     }
+
+    #[test]
+    fn match_text_pattern_test2() {
+        assert!(match_text_pattern("a*", "Apple"));
+        assert!(match_text_pattern("a~*", "a*"));
+        assert!(!match_text_pattern("a~*", "ab"));
+        assert!(!match_text_pattern("a?", "a"));
+        assert!(match_text_pattern("(1+1)", "(1+1)"));
+    }
+
+    #[test]
+    fn match_condition_test2() {
+        let matches = |cond: &str, value: Value| {
+            let condition = parse_condition(cond).unwrap();
+            match_condition(&condition, &value)
+        };
+        let text = |t: &str| Value::Text(t.to_string());
+        assert!(matches("apple", text("APPLE")));
+        assert!(matches("5", Value::Number(5.)));
+        assert!(matches("=b*", text("Banana")));
+        assert!(!matches("<>b*", text("Banana")));
+        assert!(matches("<>b*", Value::Blank));
+        assert!(matches("", Value::Blank));
+        assert!(matches("=", Value::Blank));
+        assert!(!matches("=", text("x")));
+        assert!(matches("<>", text("x")));
+        assert!(!matches("<>", Value::Blank));
+        assert!(matches(">b", text("C")));
+        assert!(!matches(">5", text("9")));
+        assert!(!matches("<5", Value::Blank));
+    }
 }
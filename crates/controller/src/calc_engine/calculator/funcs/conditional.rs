@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+
+use super::condition::{
+    get_condition_value, match_condition, parse_condition, Condition, LogicalCondition, Op,
+};
+use super::lookup::matching::{get_matrix, get_value};
+use super::lookup::{from_bounds, get_bounds, MAX_COLS, MAX_ROWS};
+use super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+
+enum Aggregate {
+    Sum,
+    Average,
+    Max,
+    Min,
+}
+
+struct Criterion {
+    range: MatrixValue<Value>,
+    // The size of the range in the sheet. The matrices of whole columns or
+    // rows only cover a part of the sheet.
+    size: (usize, usize),
+    condition: Condition,
+}
+
+pub fn calc_countif<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    calc_countifs(args, fetcher)
+}
+
+pub fn calc_countifs<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(
+        args.len() >= 2 && args.len() % 2 == 0,
+        ast::Error::Unspecified
+    );
+    let criteria = match get_criteria(args, fetcher) {
+        Ok(c) => c,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let size = criteria[0].size;
+    assert_or_return!(criteria.iter().all(|c| c.size == size), ast::Error::Value);
+    // Only the populated cells are checked one by one. The blank ones are
+    // counted together.
+    let positions = criteria
+        .iter()
+        .flat_map(|c| c.range.get_stored_positions())
+        .collect::<HashSet<_>>();
+    let matched = positions
+        .iter()
+        .filter(|(i, j)| is_matched(&criteria, *i, *j))
+        .count();
+    let blanks = if criteria
+        .iter()
+        .all(|c| match_condition(&c.condition, &Value::Blank))
+    {
+        size.0 * size.1 - positions.len()
+    } else {
+        0
+    };
+    CalcVertex::from_number((matched + blanks) as f64)
+}
+
+pub fn calc_sumif<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_if(args, fetcher, Aggregate::Sum)
+}
+
+pub fn calc_averageif<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_if(args, fetcher, Aggregate::Average)
+}
+
+pub fn calc_sumifs<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_ifs(args, fetcher, Aggregate::Sum)
+}
+
+pub fn calc_averageifs<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_ifs(args, fetcher, Aggregate::Average)
+}
+
+pub fn calc_maxifs<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_ifs(args, fetcher, Aggregate::Max)
+}
+
+pub fn calc_minifs<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_ifs(args, fetcher, Aggregate::Min)
+}
+
+// SUMIF(range, criteria, [sum_range]). A sum range in a different shape is
+// resized to the shape of the range from its top-left cell.
+fn calc_if<C>(args: Vec<CalcVertex>, fetcher: &mut C, aggregate: Aggregate) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2 || args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let range = args_iter.next().unwrap();
+    let criteria = args_iter.next().unwrap();
+    let values = match (args_iter.next(), &range) {
+        (Some(CalcVertex::Reference(mut sum_ref)), CalcVertex::Reference(r))
+            if sum_ref.from_sheet.is_none() =>
+        {
+            let (_, _, height, width) = get_bounds(&r.reference);
+            let (top, left, _, _) = get_bounds(&sum_ref.reference);
+            if top + height <= MAX_ROWS && left + width <= MAX_COLS {
+                sum_ref.reference = from_bounds(top, left, height, width);
+            }
+            CalcVertex::Reference(sum_ref)
+        }
+        (Some(sum_range), _) => sum_range,
+        (None, _) => range.clone(),
+    };
+    let values = match get_matrix(fetcher.get_calc_value(values)) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    match get_criteria(vec![range, criteria], fetcher) {
+        Ok(criteria) => aggregate_if(values, &criteria, aggregate),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+// SUMIFS(sum_range, range1, criteria1, [range2, criteria2], ...)
+fn calc_ifs<C>(args: Vec<CalcVertex>, fetcher: &mut C, aggregate: Aggregate) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(
+        args.len() >= 3 && args.len() % 2 == 1,
+        ast::Error::Unspecified
+    );
+    let mut args_iter = args.into_iter();
+    let values = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    match get_criteria(args_iter.collect(), fetcher) {
+        Ok(criteria) => aggregate_if(values, &criteria, aggregate),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+fn aggregate_if(
+    values: MatrixValue<Value>,
+    criteria: &[Criterion],
+    aggregate: Aggregate,
+) -> CalcVertex {
+    let size = values.get_size();
+    assert_or_return!(
+        criteria.iter().all(|c| c.range.get_size() == size),
+        ast::Error::Value
+    );
+    // Blank cells in the sum range never count, so only the populated ones
+    // are checked.
+    let mut nums = Vec::<f64>::new();
+    for (i, j) in values.get_stored_positions() {
+        let n = match get_value(&values, i, j) {
+            Value::Number(n) => n,
+            Value::Error(e) => {
+                if is_matched(criteria, i, j) {
+                    return CalcVertex::from_error(e);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if is_matched(criteria, i, j) {
+            nums.push(n);
+        }
+    }
+    match aggregate {
+        // Summing from 0 rather than -0, which `Iterator::sum` starts from.
+        Aggregate::Sum => CalcVertex::from_number(nums.iter().fold(0., |acc, n| acc + n)),
+        Aggregate::Average => {
+            assert_or_return!(!nums.is_empty(), ast::Error::Div0);
+            CalcVertex::from_number(nums.iter().sum::<f64>() / nums.len() as f64)
+        }
+        Aggregate::Max => CalcVertex::from_number(nums.into_iter().reduce(f64::max).unwrap_or(0.)),
+        Aggregate::Min => CalcVertex::from_number(nums.into_iter().reduce(f64::min).unwrap_or(0.)),
+    }
+}
+
+fn is_matched(criteria: &[Criterion], i: usize, j: usize) -> bool {
+    criteria
+        .iter()
+        .all(|c| match_condition(&c.condition, &get_value(&c.range, i, j)))
+}
+
+// Read the pairs of ranges and criteria.
+fn get_criteria<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> Result<Vec<Criterion>, ast::Error>
+where
+    C: Connector,
+{
+    let mut result = Vec::with_capacity(args.len() / 2);
+    let mut args_iter = args.into_iter();
+    while let (Some(range), Some(criteria)) = (args_iter.next(), args_iter.next()) {
+        let bounds = match &range {
+            CalcVertex::Reference(r) => Some(get_bounds(&r.reference)),
+            _ => None,
+        };
+        let range = get_matrix(fetcher.get_calc_value(range))?;
+        let size = match bounds {
+            Some((_, _, height, width)) => (height, width),
+            None => range.get_size(),
+        };
+        let condition = match fetcher.get_calc_value(criteria) {
+            CalcValue::Scalar(Value::Text(t)) => parse_condition(&t).ok_or(ast::Error::Value)?,
+            CalcValue::Scalar(v) => Condition::Logical(LogicalCondition {
+                op: Op::Eq,
+                value: get_condition_value(&v),
+            }),
+            _ => return Err(ast::Error::Value),
+        };
+        result.push(Criterion {
+            range,
+            size,
+            condition,
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    #[test]
+    fn conditional_aggregate_test() {
+        let cells = [
+            (0, 0, "apple"),
+            (1, 0, "Banana"),
+            (2, 0, "apple pie"),
+            (3, 0, "cherry"),
+            (0, 1, "1"),
+            (1, 1, "2"),
+            (2, 1, "3"),
+            (3, 1, "4"),
+            (4, 1, "5"),
+            (0, 2, "10"),
+            (1, 2, "20"),
+            (2, 2, "30"),
+            (3, 2, "40"),
+        ];
+        let cases = [
+            ("=SUMIF(A:A, \"apple*\", B:B)", "4"),
+            ("=SUMIF(A1:A5, \"a*\", C1)", "40"),
+            ("=SUMIFS(B:B, A:A, \"<>apple\", B:B, \">1\")", "14"),
+            ("=COUNTIF(A:A, \"*a*\")", "3"),
+            ("=COUNTIFS(A1:A5, \"\", B1:B5, \">=5\")", "1"),
+            ("=COUNTIF(A1:A6, \"\")", "2"),
+            ("=COUNTIF(A1:A5, \"APPLE\")+COUNTIF(B1:B5, 3)*10", "11"),
+            ("=AVERAGEIF(A1:A4, \"apple*\", B1:B4)", "2"),
+            ("=AVERAGEIFS(B1:B5, B1:B5, \">10\")", "#DIV/0!"),
+            ("=MAXIFS(C1:C5, A1:A5, \"*e*\")", "40"),
+            ("=MINIFS(C:C, A:A, \"b*\")", "20"),
+            ("=SUMIFS(B1:B4, A1:A4, \"kiwi\")", "0"),
+            ("=COUNTIF(A:A, \"\")", "1048572"),
+            ("=COUNTIFS(A:A, \"\", B:B, \"\")", "1048571"),
+        ];
+        let payloads = cells
+            .iter()
+            .map(|(row, col, content)| input(0, *row, *col, content))
+            .chain(
+                cases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (f, _))| input(0, i, 4, f)),
+            )
+            .collect();
+        let mut wb = Workbook::default();
+        edit(&mut wb, payloads);
+        cases.iter().enumerate().for_each(|(i, (f, e))| {
+            assert_eq!(value(&mut wb, 0, i, 4), *e, "{}", f);
+        });
+    }
+}
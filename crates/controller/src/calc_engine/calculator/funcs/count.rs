@@ -1,5 +1,9 @@
+use logisheets_parser::ast;
+
 use crate::calc_engine::connector::Connector;
 
+use super::lookup::get_bounds;
+use super::lookup::matching::get_value;
 use super::{CalcValue, CalcVertex, Value};
 
 pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
@@ -13,6 +17,44 @@ where
     CalcVertex::from_number(cnt as f64)
 }
 
+// The empty texts returned by formulas are counted as blank cells.
+pub fn calc_countblank<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    let is_blank = |v: &Value| match v {
+        Value::Blank => true,
+        Value::Text(t) => t.is_empty(),
+        _ => false,
+    };
+    let arg = args.into_iter().next().unwrap();
+    // The matrices of whole columns or rows only cover a part of the sheet.
+    let bounds = match &arg {
+        CalcVertex::Reference(r) => Some(get_bounds(&r.reference)),
+        _ => None,
+    };
+    let cnt = match fetcher.get_calc_value(arg) {
+        CalcValue::Scalar(s) => is_blank(&s) as usize,
+        CalcValue::Range(r) => {
+            let size = match bounds {
+                Some((_, _, height, width)) => height * width,
+                None => r.get_size().0 * r.get_size().1,
+            };
+            // Only the stored cells can be non-blank.
+            let non_blanks = r
+                .get_stored_positions()
+                .into_iter()
+                .filter(|(i, j)| !is_blank(&get_value(&r, *i, *j)))
+                .count();
+            size - non_blanks
+        }
+        CalcValue::Cube(c) => c.into_iter().filter(is_blank).count(),
+        CalcValue::Union(_) => return CalcVertex::from_error(ast::Error::Value),
+    };
+    CalcVertex::from_number(cnt as f64)
+}
+
 fn count_calc_value(value: CalcValue) -> u16 {
     match value {
         CalcValue::Scalar(s) => match s {
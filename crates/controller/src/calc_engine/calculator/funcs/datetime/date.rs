@@ -1,4 +1,4 @@
-use logisheets_base::datetime::get_serial_num_by_date_1900;
+use logisheets_base::datetime::get_serial_num_by_date;
 use logisheets_parser::ast;
 
 use crate::calc_engine::connector::Connector;
@@ -20,7 +20,8 @@ where
     let third = fetcher.get_calc_value(args_iter.next().unwrap());
     assert_f64_from_calc_value!(day, third);
     assert_or_return!(day >= 0., ast::Error::Num);
-    let res = get_serial_num_by_date_1900(year as u32, month as u32, day as u32);
+    let system = fetcher.get_date_system();
+    let res = get_serial_num_by_date(year as u32, month as u32, day as u32, system);
     match res {
         Some(r) => CalcVertex::from_number(r as f64),
         None => CalcVertex::from_error(ast::Error::Num),
@@ -1,4 +1,4 @@
-use logisheets_base::datetime::{get_date_by_serial_num_1900, get_serial_num_by_date_1900};
+use logisheets_base::datetime::{get_date_by_serial_num, get_serial_num_by_date};
 use logisheets_parser::ast;
 
 use crate::calc_engine::connector::Connector;
@@ -16,9 +16,10 @@ where
     let second = fetcher.get_calc_value(args_iter.next().unwrap());
     assert_f64_from_calc_value!(delta_months, second);
     let delta_months = delta_months.floor() as i32;
-    let mut date = get_date_by_serial_num_1900(date_num.floor() as u32);
+    let system = fetcher.get_date_system();
+    let mut date = get_date_by_serial_num(date_num.floor() as u32, system);
     date.add_delta_months(delta_months);
-    let res = get_serial_num_by_date_1900(date.year, date.month, date.day);
+    let res = get_serial_num_by_date(date.year, date.month, date.day, system);
     if let Some(r) = res {
         CalcVertex::from_number(r as f64)
     } else {
@@ -1,4 +1,4 @@
-use logisheets_base::datetime::{get_date_by_serial_num_1900, get_serial_num_by_date_1900};
+use logisheets_base::datetime::{get_date_by_serial_num, get_serial_num_by_date};
 use logisheets_parser::ast;
 
 use super::super::{CalcValue, CalcVertex, Value};
@@ -15,7 +15,8 @@ where
     let second = fetcher.get_calc_value(args_iter.next().unwrap());
     assert_f64_from_calc_value!(delta_months, second);
     let delta_months = delta_months.floor() as i32;
-    let mut date = get_date_by_serial_num_1900(date_num.floor() as u32);
+    let system = fetcher.get_date_system();
+    let mut date = get_date_by_serial_num(date_num.floor() as u32, system);
     date.add_delta_months(delta_months);
     let year = date.year;
     let month = date.month;
@@ -40,7 +41,7 @@ where
         12 => 30,
         _ => 30,
     };
-    let res = get_serial_num_by_date_1900(year, month, day);
+    let res = get_serial_num_by_date(year, month, day, system);
     if let Some(r) = res {
         CalcVertex::from_number(r as f64)
     } else {
@@ -1,4 +1,3 @@
-/// TODO: Support 1904 datetime system.
 pub mod date;
 pub mod days;
 pub mod edate;
@@ -1,10 +1,14 @@
 use chrono::prelude::*;
-use logisheets_base::datetime::{get_decimal_num_by_time, get_serial_num_by_date_1900};
+use logisheets_base::datetime::{get_decimal_num_by_time, get_serial_num_by_date};
 use logisheets_parser::ast;
 
 use super::super::CalcVertex;
+use crate::calc_engine::connector::Connector;
 
-pub fn calc(args: Vec<CalcVertex>) -> CalcVertex {
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
     assert_or_return!(args.len() == 0, ast::Error::Unspecified);
     let datetime = Local::now();
     let date = datetime.date();
@@ -15,7 +19,7 @@ pub fn calc(args: Vec<CalcVertex>) -> CalcVertex {
     let hour = time.hour();
     let minute = time.minute();
     let second = time.second();
-    let d = get_serial_num_by_date_1900(year as u32, month, day);
+    let d = get_serial_num_by_date(year as u32, month, day, fetcher.get_date_system());
     let t = get_decimal_num_by_time(hour, minute, second);
     match (d, t) {
         (Some(d), Some(t)) => {
@@ -1,17 +1,21 @@
 use chrono::prelude::*;
-use logisheets_base::datetime::get_serial_num_by_date_1900;
+use logisheets_base::datetime::get_serial_num_by_date;
 use logisheets_parser::ast;
 
 use super::super::CalcVertex;
+use crate::calc_engine::connector::Connector;
 
-pub fn calc(args: Vec<CalcVertex>) -> CalcVertex {
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
     assert_or_return!(args.len() == 0, ast::Error::Unspecified);
     let datetime = Local::now();
     let date = datetime.date();
     let year = date.year();
     let month = date.month();
     let day = date.day();
-    let d = get_serial_num_by_date_1900(year as u32, month, day);
+    let d = get_serial_num_by_date(year as u32, month, day, fetcher.get_date_system());
     match d {
         Some(n) => CalcVertex::from_number(n as f64),
         None => CalcVertex::from_error(ast::Error::Num),
@@ -1,4 +1,4 @@
-use logisheets_base::datetime::{get_date_by_serial_num_1900, Date};
+use logisheets_base::datetime::{get_date_by_serial_num, Date};
 use logisheets_parser::ast;
 
 use crate::calc_engine::connector::Connector;
@@ -35,7 +35,7 @@ where
     let first = fetcher.get_calc_value(args.into_iter().next().unwrap());
     assert_f64_from_calc_value!(n, first);
     let n = n.trunc() as u32;
-    let date = get_date_by_serial_num_1900(n);
+    let date = get_date_by_serial_num(n, fetcher.get_date_system());
     let r = func(date) as f64;
     CalcVertex::from_number(r)
 }
@@ -0,0 +1,236 @@
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use statrs::function::gamma::ln_gamma;
+use std::f64::consts::PI;
+
+pub fn calc_binomdist<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(number, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(trials, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(prob, third);
+    let (number, trials) = (number.trunc(), trials.trunc());
+    assert_or_return!((0. ..=trials).contains(&number), ast::Error::Num);
+    assert_or_return!((0. ..=1.).contains(&prob), ast::Error::Num);
+    let (number, trials) = (number as u64, trials as u64);
+    let forth = fetcher.get_calc_value(args_iter.next().unwrap());
+    let v = get_condition_result(forth);
+    match v {
+        ConditionResult::True => CalcVertex::from_number(cdf(trials, prob, number)),
+        ConditionResult::False => CalcVertex::from_number(pmf(trials, prob, number)),
+        ConditionResult::Error(e) => CalcVertex::from_error(e),
+    }
+}
+
+pub fn calc_binomdist_range<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3 || args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(trials, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(prob, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(s1, third);
+    let trials = trials.trunc();
+    let s1 = s1.trunc();
+    let s2 = match args_iter.next() {
+        Some(arg) => {
+            let forth = fetcher.get_calc_value(arg);
+            assert_f64_from_calc_value!(s2, forth);
+            s2.trunc()
+        }
+        None => s1,
+    };
+    assert_or_return!(trials >= 0., ast::Error::Num);
+    assert_or_return!((0. ..=1.).contains(&prob), ast::Error::Num);
+    assert_or_return!(s1 >= 0. && s1 <= s2 && s2 <= trials, ast::Error::Num);
+    let (trials, s1, s2) = (trials as u64, s1 as u64, s2 as u64);
+    let r = match s1 {
+        _ if s1 == s2 => pmf(trials, prob, s1),
+        0 => cdf(trials, prob, s2),
+        _ => cdf(trials, prob, s2) - cdf(trials, prob, s1 - 1),
+    };
+    CalcVertex::from_number(r.max(0.))
+}
+
+// CRITBINOM is the same as BINOM.INV.
+pub fn calc_binom_inv<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(trials, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(prob, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(alpha, third);
+    let trials = trials.trunc();
+    assert_or_return!(trials >= 0., ast::Error::Num);
+    assert_or_return!((0. ..=1.).contains(&prob), ast::Error::Num);
+    assert_or_return!((0. ..=1.).contains(&alpha), ast::Error::Num);
+    // The smallest number whose cumulative probability reaches alpha.
+    let (mut low, mut high) = (0, trials as u64);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if cdf(trials as u64, prob, mid) >= alpha {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    CalcVertex::from_number(low as f64)
+}
+
+// The probability of `k` successes in `n` trials. It is computed from the
+// binomial coefficient while the coefficient is exact in f64. Beyond that,
+// the saddle point form by Catherine Loader keeps it accurate for large `n`,
+// where the difference of the log factorials loses the precision.
+fn pmf(n: u64, p: f64, k: u64) -> f64 {
+    if p == 0. || p == 1. {
+        let certain = if p == 0. { 0 } else { n };
+        return if k == certain { 1. } else { 0. };
+    }
+    let q = 1. - p;
+    if k == 0 {
+        return (n as f64 * q.ln()).exp();
+    }
+    if k == n {
+        return (n as f64 * p.ln()).exp();
+    }
+    if let Some(c) = binom_coef(n, k) {
+        return c * p.powf(k as f64) * q.powf((n - k) as f64);
+    }
+    let (n, k) = (n as f64, k as f64);
+    let lc = stirlerr(n) - stirlerr(k) - stirlerr(n - k) - bd0(k, n * p) - bd0(n - k, n * q);
+    let lf = (2. * PI).ln() + k.ln() + (-k / n).ln_1p();
+    (lc - 0.5 * lf).exp()
+}
+
+// The probability of at most `k` successes in `n` trials, which is the
+// regularized incomplete beta function I(1 - p; n - k, k + 1).
+fn cdf(n: u64, p: f64, k: u64) -> f64 {
+    if k >= n {
+        return 1.;
+    }
+    if p == 0. || p == 1. {
+        return if p == 0. { 1. } else { 0. };
+    }
+    let (a, b, x) = ((n - k) as f64, k as f64 + 1., 1. - p);
+    // x^a * (1 - x)^b / B(a, b) written with the probability mass.
+    let front = (n - k) as f64 * p * pmf(n, p, k);
+    if x < (a + 1.) / (a + b + 2.) {
+        front * beta_cf(a, b, x) / a
+    } else {
+        1. - front * beta_cf(b, a, 1. - x) / b
+    }
+}
+
+// The continued fraction of the incomplete beta function by the modified
+// Lentz's method. It takes about sqrt(max(a, b)) iterations to converge.
+fn beta_cf(a: f64, b: f64, x: f64) -> f64 {
+    let fpmin = f64::MIN_POSITIVE / f64::EPSILON;
+    let clamp = |v: f64| if v.abs() < fpmin { fpmin } else { v };
+    let (qab, qap, qam) = (a + b, a + 1., a - 1.);
+    let mut c = 1.;
+    let mut d = 1. / clamp(1. - qab * x / qap);
+    let mut h = d;
+    let max_iter = 100 + 10 * a.max(b).sqrt() as u64;
+    for m in 1..=max_iter {
+        let m = m as f64;
+        let m2 = m * 2.;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1. / clamp(1. + aa * d);
+        c = clamp(1. + aa / c);
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1. / clamp(1. + aa * d);
+        c = clamp(1. + aa / c);
+        let del = d * c;
+        h *= del;
+        if (del - 1.).abs() <= f64::EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+// The binomial coefficient, or None if it can not be exactly represented.
+fn binom_coef(n: u64, k: u64) -> Option<f64> {
+    let k = k.min(n - k);
+    let mut c = 1_u64;
+    for i in 1..=k {
+        // Every partial product is the coefficient C(n - k + i, i).
+        c = c.checked_mul(n - k + i)? / i;
+    }
+    (c <= 1 << f64::MANTISSA_DIGITS).then_some(c as f64)
+}
+
+// ln(n!) - ln(sqrt(2 * PI * n) * (n / e)^n)
+fn stirlerr(n: f64) -> f64 {
+    const S0: f64 = 1. / 12.;
+    const S1: f64 = 1. / 360.;
+    const S2: f64 = 1. / 1260.;
+    const S3: f64 = 1. / 1680.;
+    const S4: f64 = 1. / 1188.;
+    if n <= 15. {
+        return ln_gamma(n + 1.) - (n + 0.5) * n.ln() + n - (2. * PI).sqrt().ln();
+    }
+    let nn = n * n;
+    (S0 - (S1 - (S2 - (S3 - S4 / nn) / nn) / nn) / nn) / n
+}
+
+// x * ln(x / np) + np - x, computed without the cancellation when x is
+// close to np.
+fn bd0(x: f64, np: f64) -> f64 {
+    if (x - np).abs() >= 0.1 * (x + np) {
+        return x * (x / np).ln() + np - x;
+    }
+    let mut v = (x - np) / (x + np);
+    let mut s = (x - np) * v;
+    let mut ej = 2. * x * v;
+    v *= v;
+    let mut j = 1.;
+    loop {
+        ej *= v;
+        let s1 = s + ej / (2. * j + 1.);
+        if s1 == s {
+            return s1;
+        }
+        s = s1;
+        j += 1.;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cdf, pmf};
+
+    #[test]
+    fn binomial_test() {
+        assert!((pmf(10, 0.5, 6) - 0.205078125).abs() < 1e-12);
+        assert_eq!(pmf(10, 0.5, 3), 0.1171875);
+        assert!((cdf(10, 0.5, 6) - 0.828125).abs() < 1e-12);
+        assert!((cdf(10, 0.5, 1) - 0.0107421875).abs() < 1e-12);
+        assert_eq!(cdf(10, 0.5, 10), 1.);
+        assert_eq!(pmf(10, 0., 0), 1.);
+        // The center of a large distribution, where the mass is about
+        // sqrt(2 / (PI * n)).
+        let n = 1_000_000_000_000;
+        let expected = (2. / (std::f64::consts::PI * n as f64)).sqrt();
+        assert!((pmf(n, 0.5, n / 2) / expected - 1.).abs() < 1e-9);
+        assert!((cdf(n, 0.5, n / 2) - (0.5 + expected / 2.)).abs() < 1e-9);
+    }
+}
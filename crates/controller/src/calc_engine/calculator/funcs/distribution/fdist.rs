@@ -0,0 +1,136 @@
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use statrs::distribution::{Continuous, ContinuousCDF, FisherSnedecor};
+
+pub fn calc_fdist<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    assert_or_return!(x >= 0., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(d1, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(d2, third);
+    let dist = match get_dist(d1, d2) {
+        Some(d) => d,
+        None => return CalcVertex::from_error(ast::Error::Num),
+    };
+    let fourth = fetcher.get_calc_value(args_iter.next().unwrap());
+    let v = get_condition_result(fourth);
+    match v {
+        ConditionResult::True => CalcVertex::from_number(dist.cdf(x)),
+        ConditionResult::False => CalcVertex::from_number(dist.pdf(x)),
+        ConditionResult::Error(e) => CalcVertex::from_error(e),
+    }
+}
+
+// FDIST is the same as F.DIST.RT.
+pub fn calc_fdist_rt<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    assert_or_return!(x >= 0., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(d1, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(d2, third);
+    let dist = match get_dist(d1, d2) {
+        Some(d) => d,
+        None => return CalcVertex::from_error(ast::Error::Num),
+    };
+    CalcVertex::from_number(1. - dist.cdf(x))
+}
+
+pub fn calc_finv<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_inv(args, fetcher, false)
+}
+
+// FINV is the same as F.INV.RT.
+pub fn calc_finv_rt<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_inv(args, fetcher, true)
+}
+
+fn calc_inv<C>(args: Vec<CalcVertex>, fetcher: &mut C, right_tailed: bool) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(prob, first);
+    assert_or_return!((0. ..=1.).contains(&prob), ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(d1, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(d2, third);
+    let dist = match get_dist(d1, d2) {
+        Some(d) => d,
+        None => return CalcVertex::from_error(ast::Error::Num),
+    };
+    let p = if right_tailed { 1. - prob } else { prob };
+    assert_or_return!(p < 1., ast::Error::Num);
+    CalcVertex::from_number(inverse_cdf(&dist, p))
+}
+
+fn get_dist(d1: f64, d2: f64) -> Option<FisherSnedecor> {
+    let (d1, d2) = (d1.trunc(), d2.trunc());
+    if d1 < 1. || d2 < 1. || d1 >= 1e10 || d2 >= 1e10 {
+        return None;
+    }
+    FisherSnedecor::new(d1, d2).ok()
+}
+
+// The inverse provided by statrs stops too early to be accurate, so bisect
+// the cdf here.
+fn inverse_cdf(dist: &FisherSnedecor, p: f64) -> f64 {
+    if p <= 0. {
+        return 0.;
+    }
+    let mut high = 1.;
+    while dist.cdf(high) < p {
+        high *= 2.;
+    }
+    let mut low = 0.;
+    for _ in 0..200 {
+        let mid = (low + high) / 2.;
+        if dist.cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low <= f64::EPSILON * high {
+            break;
+        }
+    }
+    (low + high) / 2.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inverse_cdf;
+    use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+    #[test]
+    fn fisher_snedecor_test() {
+        let dist = FisherSnedecor::new(6., 4.).unwrap();
+        assert!((1. - dist.cdf(15.2068649) - 0.01).abs() < 1e-8);
+        assert!((inverse_cdf(&dist, 0.99) - 15.20686486).abs() < 1e-6);
+        assert!((inverse_cdf(&dist, 0.01) - 0.10930991).abs() < 1e-6);
+    }
+}
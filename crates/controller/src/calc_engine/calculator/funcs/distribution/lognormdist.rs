@@ -0,0 +1,80 @@
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use statrs::distribution::{Continuous, ContinuousCDF, LogNormal, Normal};
+
+pub fn calc_lognormdist<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    assert_or_return!(x > 0., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(mean, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(std_dev, third);
+    assert_or_return!(std_dev > 0., ast::Error::Num);
+    let forth = fetcher.get_calc_value(args_iter.next().unwrap());
+    let v = get_condition_result(forth);
+    let dist = LogNormal::new(mean, std_dev).unwrap();
+    match v {
+        ConditionResult::True => CalcVertex::from_number(dist.cdf(x)),
+        ConditionResult::False => CalcVertex::from_number(dist.pdf(x)),
+        ConditionResult::Error(e) => CalcVertex::from_error(e),
+    }
+}
+
+// LOGNORMDIST is always cumulative.
+pub fn calc_lognormdist_legacy<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    assert_or_return!(x > 0., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(mean, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(std_dev, third);
+    assert_or_return!(std_dev > 0., ast::Error::Num);
+    let dist = LogNormal::new(mean, std_dev).unwrap();
+    CalcVertex::from_number(dist.cdf(x))
+}
+
+// LOGINV is the same as LOGNORM.INV.
+pub fn calc_lognorm_inv<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(prob, first);
+    assert_or_return!(prob > 0. && prob < 1., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(mean, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(std_dev, third);
+    assert_or_return!(std_dev > 0., ast::Error::Num);
+    let dist = Normal::new(mean, std_dev).unwrap();
+    CalcVertex::from_number(dist.inverse_cdf(prob).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use statrs::distribution::{ContinuousCDF, LogNormal, Normal};
+
+    #[test]
+    fn lognormal_test() {
+        let dist = LogNormal::new(3.5, 1.2).unwrap();
+        assert!((dist.cdf(4.) - 0.0390836).abs() < 1e-7);
+        let dist = Normal::new(3.5, 1.2).unwrap();
+        assert!((dist.inverse_cdf(0.039084).exp() - 4.0000252).abs() < 1e-6);
+    }
+}
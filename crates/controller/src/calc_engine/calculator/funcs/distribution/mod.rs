@@ -1,5 +1,71 @@
+pub mod binomdist;
 pub mod chisqdist;
+pub mod expondist;
+pub mod fdist;
 pub mod gammadist;
+pub mod lognormdist;
 pub mod normdist;
 pub mod norminv;
+pub mod order;
+pub mod percentile;
+pub mod poissondist;
+pub mod regression;
 pub mod statistics;
+pub mod tdist;
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    #[test]
+    fn statistical_functions_test() {
+        let cells = [
+            (0, 0, "1"),
+            (1, 0, "3"),
+            (2, 0, "abc"),
+            (3, 0, "TRUE"),
+            (4, 0, "2"),
+            (5, 0, "3"),
+        ];
+        // Text and booleans in references are skipped unless the function
+        // ends with A, but they are counted when typed as arguments directly.
+        let cases = [
+            ("=MAX(A1:A6)", 3.),
+            ("=MAX(\"5\", TRUE)", 5.),
+            ("=MIN(A1:A6)", 1.),
+            ("=MINA(A1:A6)", 0.),
+            ("=MEDIAN(A1:A6)", 2.5),
+            ("=MODE.SNGL(A1:A6)", 3.),
+            ("=LARGE(A1:A6, 3)", 2.),
+            ("=SMALL(A1:A6, 2)", 2.),
+            ("=PERCENTILE.INC(A1:A6, 0.5)", 2.5),
+            ("=QUARTILE.INC(A1:A6, 1)", 1.75),
+            ("=VAR.P(A1:A6)", 0.6875),
+            ("=DEVSQ(A1:A6)", 2.75),
+            ("=AVEDEV(A1:A6)", 0.75),
+            ("=SLOPE({2,4,6}, {1,2,3})", 2.),
+            ("=FORECAST.LINEAR(4, {3,5,7}, {1,2,3})", 9.),
+            ("=BINOM.DIST(6, 10, 0.5, FALSE)", 0.205078125),
+            ("=POISSON.DIST(0, 0, FALSE)", 1.),
+            ("=T.DIST.2T(0, 5)", 1.),
+        ];
+        let payloads = cells
+            .iter()
+            .map(|(row, col, content)| input(0, *row, *col, content))
+            .chain(
+                cases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (f, _))| input(0, i, 2, f)),
+            )
+            .collect();
+        let mut wb = Workbook::default();
+        edit(&mut wb, payloads);
+        cases.iter().enumerate().for_each(|(i, (f, e))| {
+            let v = value(&mut wb, 0, i, 2);
+            let n = v.parse::<f64>().unwrap_or_else(|_| panic!("{}: {}", f, v));
+            assert!((n - e).abs() < 1e-9, "{}: {}", f, n);
+        });
+    }
+}
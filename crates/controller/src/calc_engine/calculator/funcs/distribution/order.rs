@@ -0,0 +1,193 @@
+use super::super::utils::get_nums_from_arg;
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+use std::collections::HashMap;
+
+pub fn calc_max<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |nums| {
+        Ok(fold_or_zero(nums, f64::max))
+    })
+}
+
+pub fn calc_maxa<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true, |nums| Ok(fold_or_zero(nums, f64::max)))
+}
+
+pub fn calc_min<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |nums| {
+        Ok(fold_or_zero(nums, f64::min))
+    })
+}
+
+pub fn calc_mina<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true, |nums| Ok(fold_or_zero(nums, f64::min)))
+}
+
+pub fn calc_median<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |mut nums| {
+        if nums.is_empty() {
+            return Err(ast::Error::Num);
+        }
+        sort(&mut nums);
+        let mid = nums.len() / 2;
+        if nums.len() % 2 == 0 {
+            Ok((nums[mid - 1] + nums[mid]) / 2.)
+        } else {
+            Ok(nums[mid])
+        }
+    })
+}
+
+pub fn calc_mode_sngl<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |nums| match modes(&nums).first() {
+        Some(m) => Ok(*m),
+        None => Err(ast::Error::Na),
+    })
+}
+
+pub fn calc_mode_mult<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty(), ast::Error::Unspecified);
+    let mut nums = vec![];
+    for arg in args.into_iter() {
+        match get_nums_from_arg(arg, fetcher, false) {
+            Ok(n) => nums.extend(n),
+            Err(e) => return CalcVertex::from_error(e),
+        }
+    }
+    let modes = modes(&nums);
+    match modes.len() {
+        0 => CalcVertex::from_error(ast::Error::Na),
+        1 => CalcVertex::from_number(modes[0]),
+        _ => {
+            let data = modes.into_iter().map(|m| vec![Value::Number(m)]).collect();
+            CalcVertex::Value(CalcValue::Range(MatrixValue::from(data)))
+        }
+    }
+}
+
+pub fn calc_large<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_kth(args, fetcher, true)
+}
+
+pub fn calc_small<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_kth(args, fetcher, false)
+}
+
+fn calc_kth<C>(args: Vec<CalcVertex>, fetcher: &mut C, largest: bool) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let mut nums = match get_nums_from_arg(args_iter.next().unwrap(), fetcher, false) {
+        Ok(n) => n,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(k, second);
+    let k = k.ceil();
+    assert_or_return!(k >= 1. && k <= nums.len() as f64, ast::Error::Num);
+    sort(&mut nums);
+    if largest {
+        nums.reverse();
+    }
+    CalcVertex::from_number(nums[k as usize - 1])
+}
+
+fn calc<C, F>(args: Vec<CalcVertex>, fetcher: &mut C, count_all: bool, func: F) -> CalcVertex
+where
+    C: Connector,
+    F: Fn(Vec<f64>) -> Result<f64, ast::Error>,
+{
+    assert_or_return!(!args.is_empty(), ast::Error::Unspecified);
+    let mut nums = vec![];
+    for arg in args.into_iter() {
+        match get_nums_from_arg(arg, fetcher, count_all) {
+            Ok(n) => nums.extend(n),
+            Err(e) => return CalcVertex::from_error(e),
+        }
+    }
+    match func(nums) {
+        Ok(r) => CalcVertex::from_number(r),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+// MIN and MAX return 0 if there is no number.
+fn fold_or_zero<F>(nums: Vec<f64>, f: F) -> f64
+where
+    F: Fn(f64, f64) -> f64,
+{
+    nums.into_iter().reduce(f).unwrap_or(0.)
+}
+
+pub fn sort(nums: &mut [f64]) {
+    nums.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+// The most frequent numbers in the order of their first appearances. Numbers
+// appearing only once are never modes.
+fn modes(nums: &[f64]) -> Vec<f64> {
+    let mut counts = HashMap::<u64, usize>::new();
+    let mut order = Vec::<f64>::new();
+    nums.iter().for_each(|n| {
+        // Regard 0 and -0 as the same number.
+        let c = counts.entry((n + 0.).to_bits()).or_insert(0);
+        if *c == 0 {
+            order.push(*n);
+        }
+        *c += 1;
+    });
+    let max = counts.values().cloned().max().unwrap_or(0);
+    if max < 2 {
+        return vec![];
+    }
+    order
+        .into_iter()
+        .filter(|n| counts[&(n + 0.).to_bits()] == max)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::modes;
+
+    #[test]
+    fn modes_test() {
+        assert_eq!(modes(&[5.6, 4., 4., 3., 2., 4.]), vec![4.]);
+        assert_eq!(
+            modes(&[1., 2., 3., 4., 3., 2., 1., 2., 3., 5., 6., 1.]),
+            vec![1., 2., 3.]
+        );
+        assert_eq!(modes(&[1., 2., 3.]), Vec::<f64>::new());
+    }
+}
@@ -0,0 +1,191 @@
+use super::super::utils::get_nums_from_arg;
+use super::super::{CalcValue, CalcVertex, Value};
+use super::order::sort;
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+pub fn calc_percentile_inc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, |nums, k| percentile(nums, k, true))
+}
+
+pub fn calc_percentile_exc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, |nums, k| percentile(nums, k, false))
+}
+
+pub fn calc_quartile_inc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, |nums, quart| {
+        let quart = quart.trunc();
+        if !(0. ..=4.).contains(&quart) {
+            return Err(ast::Error::Num);
+        }
+        percentile(nums, quart / 4., true)
+    })
+}
+
+pub fn calc_quartile_exc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, |nums, quart| {
+        let quart = quart.trunc();
+        if !(1. ..=3.).contains(&quart) {
+            return Err(ast::Error::Num);
+        }
+        percentile(nums, quart / 4., false)
+    })
+}
+
+pub fn calc_percentrank_inc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_percentrank(args, fetcher, true)
+}
+
+pub fn calc_percentrank_exc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_percentrank(args, fetcher, false)
+}
+
+fn calc<C, F>(args: Vec<CalcVertex>, fetcher: &mut C, func: F) -> CalcVertex
+where
+    C: Connector,
+    F: Fn(Vec<f64>, f64) -> Result<f64, ast::Error>,
+{
+    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let nums = match get_nums_from_arg(args_iter.next().unwrap(), fetcher, false) {
+        Ok(n) => n,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(k, second);
+    match func(nums, k) {
+        Ok(r) => CalcVertex::from_number(r),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+fn calc_percentrank<C>(args: Vec<CalcVertex>, fetcher: &mut C, inclusive: bool) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2 || args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let nums = match get_nums_from_arg(args_iter.next().unwrap(), fetcher, false) {
+        Ok(n) => n,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(3.)));
+    assert_f64_from_calc_value!(significance, third);
+    let significance = significance.trunc();
+    assert_or_return!(significance >= 1., ast::Error::Num);
+    match percentrank(nums, x, inclusive) {
+        Ok(r) => {
+            // The result is truncated rather than rounded.
+            let factor = 10_f64.powi(significance as i32);
+            let r = (r * factor + 1e-9).floor() / factor;
+            CalcVertex::from_number(r)
+        }
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+// The rank is interpolated linearly between the sorted numbers.
+fn percentile(mut nums: Vec<f64>, k: f64, inclusive: bool) -> Result<f64, ast::Error> {
+    let n = nums.len() as f64;
+    if nums.is_empty() {
+        return Err(ast::Error::Num);
+    }
+    let rank = if inclusive {
+        if !(0. ..=1.).contains(&k) {
+            return Err(ast::Error::Num);
+        }
+        k * (n - 1.)
+    } else {
+        let r = k * (n + 1.) - 1.;
+        if r < 0. || r > n - 1. {
+            return Err(ast::Error::Num);
+        }
+        r
+    };
+    sort(&mut nums);
+    let lower = rank.floor() as usize;
+    let frac = rank - rank.floor();
+    if frac == 0. {
+        Ok(nums[lower])
+    } else {
+        Ok(nums[lower] + frac * (nums[lower + 1] - nums[lower]))
+    }
+}
+
+fn percentrank(mut nums: Vec<f64>, x: f64, inclusive: bool) -> Result<f64, ast::Error> {
+    if nums.is_empty() {
+        return Err(ast::Error::Num);
+    }
+    sort(&mut nums);
+    let n = nums.len();
+    if x < nums[0] || x > nums[n - 1] {
+        return Err(ast::Error::Na);
+    }
+    let less = nums.iter().filter(|v| **v < x).count();
+    let rank = if nums[less] == x {
+        less as f64
+    } else {
+        let (lo, hi) = (nums[less - 1], nums[less]);
+        (less - 1) as f64 + (x - lo) / (hi - lo)
+    };
+    if inclusive {
+        if n == 1 {
+            Ok(1.)
+        } else {
+            Ok(rank / (n - 1) as f64)
+        }
+    } else {
+        Ok((rank + 1.) / (n + 1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{percentile, percentrank};
+
+    #[test]
+    fn percentile_test() {
+        assert_eq!(percentile(vec![1., 3., 2., 4.], 0.3, true), Ok(1.9));
+        let nums = vec![1., 2., 3., 6., 6., 6., 7., 8., 9.];
+        assert_eq!(percentile(nums.clone(), 0.25, false), Ok(2.5));
+        assert!(percentile(nums.clone(), 0., false).is_err());
+        assert!(percentile(nums, 0.01, false).is_err());
+        let nums = vec![1., 2., 4., 7., 8., 9., 10., 12.];
+        assert_eq!(percentile(nums.clone(), 0.25, true), Ok(3.5));
+        assert_eq!(percentile(nums, 0.25, false), Ok(2.5));
+    }
+
+    #[test]
+    fn percentrank_test() {
+        let nums = vec![13., 12., 11., 8., 4., 3., 2., 1., 1., 1.];
+        assert_eq!(percentrank(nums.clone(), 2., true), Ok(3. / 9.));
+        assert_eq!(percentrank(nums.clone(), 4., true), Ok(5. / 9.));
+        assert_eq!(percentrank(nums.clone(), 8., true), Ok(6. / 9.));
+        let r = percentrank(nums.clone(), 5., true).unwrap();
+        assert!((r - 0.583333).abs() < 1e-6);
+        assert!(percentrank(nums, 14., true).is_err());
+        let nums = vec![1., 2., 3., 6., 6., 6., 7., 8., 9.];
+        let r = percentrank(nums, 7., false).unwrap();
+        assert_eq!(r, 0.7);
+    }
+}
@@ -0,0 +1,50 @@
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use statrs::distribution::{Discrete, DiscreteCDF, Poisson};
+
+pub fn calc_poissondist<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    let x = x.trunc();
+    assert_or_return!(x >= 0., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(mean, second);
+    assert_or_return!(mean >= 0., ast::Error::Num);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    let v = get_condition_result(third);
+    let cumulative = match v {
+        ConditionResult::True => true,
+        ConditionResult::False => false,
+        ConditionResult::Error(e) => return CalcVertex::from_error(e),
+    };
+    // statrs does not accept a zero mean, in which case all the mass is at 0.
+    if mean == 0. {
+        let r = if cumulative || x == 0. { 1. } else { 0. };
+        return CalcVertex::from_number(r);
+    }
+    let dist = Poisson::new(mean).unwrap();
+    if cumulative {
+        CalcVertex::from_number(dist.cdf(x as u64))
+    } else {
+        CalcVertex::from_number(dist.pmf(x as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use statrs::distribution::{Discrete, DiscreteCDF, Poisson};
+
+    #[test]
+    fn poisson_test() {
+        let dist = Poisson::new(5.).unwrap();
+        assert!((dist.cdf(2) - 0.124652019).abs() < 1e-9);
+        assert!((dist.pmf(2) - 0.084224337).abs() < 1e-9);
+    }
+}
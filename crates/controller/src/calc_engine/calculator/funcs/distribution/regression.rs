@@ -0,0 +1,530 @@
+use super::super::lookup::matching::{get_matrix, get_value};
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+
+pub fn calc_correl<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_pairs(args, fetcher, pearson)
+}
+
+pub fn calc_rsq<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_pairs(args, fetcher, |pairs| pearson(pairs).map(|r| r * r))
+}
+
+pub fn calc_covariance_p<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_pairs(args, fetcher, |pairs| covariance(pairs, false))
+}
+
+pub fn calc_covariance_s<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_pairs(args, fetcher, |pairs| covariance(pairs, true))
+}
+
+// The arguments of SLOPE and INTERCEPT are the known y's followed by the
+// known x's.
+pub fn calc_slope<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_pairs(args, fetcher, |pairs| line(pairs).map(|(slope, _)| slope))
+}
+
+pub fn calc_intercept<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_pairs(args, fetcher, |pairs| line(pairs).map(|(_, b)| b))
+}
+
+pub fn calc_forecast_linear<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    calc_pairs(args_iter.collect(), fetcher, |pairs| {
+        line(pairs).map(|(slope, b)| b + slope * x)
+    })
+}
+
+pub fn calc_linest<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty() && args.len() <= 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let known_y = fetcher.get_calc_value(args_iter.next().unwrap());
+    let known_x = get_optional(args_iter.next(), fetcher);
+    let constant = match get_flag(args_iter.next(), fetcher, true) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let stats = match get_flag(args_iter.next(), fetcher, false) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let result = get_observations(known_y, known_x)
+        .and_then(|(xs, ys, _)| fit(&xs, &ys, constant))
+        .map(|f| linest_output(&f, stats));
+    match result {
+        Ok(data) => CalcVertex::Value(CalcValue::Range(MatrixValue::from(data))),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+pub fn calc_trend<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty() && args.len() <= 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let known_y = fetcher.get_calc_value(args_iter.next().unwrap());
+    let known_x = get_optional(args_iter.next(), fetcher);
+    let new_x = get_optional(args_iter.next(), fetcher);
+    let constant = match get_flag(args_iter.next(), fetcher, true) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let result = get_observations(known_y, known_x.clone()).and_then(|(xs, ys, layout)| {
+        let f = fit(&xs, &ys, constant)?;
+        let new_x = match new_x.or(known_x) {
+            Some(v) => get_numbers(v)?,
+            None => default_x(ys.len(), layout),
+        };
+        predict(&f, new_x, layout)
+    });
+    match result {
+        Ok(data) if data.len() == 1 && data[0].len() == 1 => CalcVertex::from_number(data[0][0]),
+        Ok(data) => {
+            let data = data
+                .into_iter()
+                .map(|r| r.into_iter().map(Value::Number).collect())
+                .collect();
+            CalcVertex::Value(CalcValue::Range(MatrixValue::from(data)))
+        }
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+fn calc_pairs<C, F>(args: Vec<CalcVertex>, fetcher: &mut C, func: F) -> CalcVertex
+where
+    C: Connector,
+    F: Fn(&[(f64, f64)]) -> Result<f64, ast::Error>,
+{
+    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    match get_pairs(first, second).and_then(|pairs| func(&pairs)) {
+        Ok(r) => CalcVertex::from_number(r),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+// The numbers at the same positions of two arrays of the same size. The
+// pairs containing texts, booleans or blanks are ignored.
+fn get_pairs(lhs: CalcValue, rhs: CalcValue) -> Result<Vec<(f64, f64)>, ast::Error> {
+    let lhs = get_matrix(lhs)?;
+    let rhs = get_matrix(rhs)?;
+    if lhs.get_size() != rhs.get_size() {
+        return Err(ast::Error::Na);
+    }
+    let mut positions = lhs.get_stored_positions();
+    positions.extend(rhs.get_stored_positions());
+    positions.sort();
+    positions.dedup();
+    positions.into_iter().try_fold(vec![], |mut prev, (i, j)| {
+        match (get_value(&lhs, i, j), get_value(&rhs, i, j)) {
+            (Value::Error(e), _) | (_, Value::Error(e)) => return Err(e),
+            (Value::Number(l), Value::Number(r)) => prev.push((l, r)),
+            _ => {}
+        }
+        Ok(prev)
+    })
+}
+
+// Return the sums of the squared deviations of both sides and the sum of the
+// products of the deviations.
+fn deviations(pairs: &[(f64, f64)]) -> (f64, f64, f64) {
+    let n = pairs.len() as f64;
+    let mean_l = pairs.iter().map(|(l, _)| l).sum::<f64>() / n;
+    let mean_r = pairs.iter().map(|(_, r)| r).sum::<f64>() / n;
+    pairs.iter().fold((0., 0., 0.), |(ll, rr, lr), (l, r)| {
+        let (dl, dr) = (l - mean_l, r - mean_r);
+        (ll + dl * dl, rr + dr * dr, lr + dl * dr)
+    })
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Result<f64, ast::Error> {
+    if pairs.len() < 2 {
+        return Err(ast::Error::Div0);
+    }
+    let (ll, rr, lr) = deviations(pairs);
+    if ll == 0. || rr == 0. {
+        return Err(ast::Error::Div0);
+    }
+    Ok(lr / (ll * rr).sqrt())
+}
+
+fn covariance(pairs: &[(f64, f64)], sample: bool) -> Result<f64, ast::Error> {
+    let n = if sample {
+        pairs.len() as f64 - 1.
+    } else {
+        pairs.len() as f64
+    };
+    if n < 1. {
+        return Err(ast::Error::Div0);
+    }
+    let (_, _, lr) = deviations(pairs);
+    Ok(lr / n)
+}
+
+// The slope and the intercept of the line fitting the (x, y) pairs, where the
+// pairs are given as (y, x).
+fn line(pairs: &[(f64, f64)]) -> Result<(f64, f64), ast::Error> {
+    if pairs.is_empty() {
+        return Err(ast::Error::Div0);
+    }
+    let (_, xx, yx) = deviations(pairs);
+    if xx == 0. {
+        return Err(ast::Error::Div0);
+    }
+    let n = pairs.len() as f64;
+    let mean_y = pairs.iter().map(|(y, _)| y).sum::<f64>() / n;
+    let mean_x = pairs.iter().map(|(_, x)| x).sum::<f64>() / n;
+    let slope = yx / xx;
+    Ok((slope, mean_y - slope * mean_x))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    // Each row is an observation.
+    Rows,
+    // Each column is an observation.
+    Cols,
+}
+
+// The x's, the y's and how the observations are laid out.
+type Observations = (Vec<Vec<f64>>, Vec<f64>, Layout);
+
+struct Fit {
+    // The coefficients of x1, x2, ...
+    coefs: Vec<f64>,
+    intercept: f64,
+    constant: bool,
+    // The diagonal of the inverse of XᵀX, in the order of the coefficients.
+    inv_diag: Vec<f64>,
+    n: usize,
+    ss_reg: f64,
+    ss_resid: f64,
+}
+
+fn get_optional<C>(arg: Option<CalcVertex>, fetcher: &mut C) -> Option<CalcValue>
+where
+    C: Connector,
+{
+    match arg.map(|a| fetcher.get_calc_value(a)) {
+        Some(CalcValue::Scalar(Value::Blank)) => None,
+        v => v,
+    }
+}
+
+fn get_flag<C>(arg: Option<CalcVertex>, fetcher: &mut C, default: bool) -> Result<bool, ast::Error>
+where
+    C: Connector,
+{
+    match get_optional(arg, fetcher) {
+        Some(v) => match get_condition_result(v) {
+            ConditionResult::True => Ok(true),
+            ConditionResult::False => Ok(false),
+            ConditionResult::Error(e) => Err(e),
+        },
+        None => Ok(default),
+    }
+}
+
+// Every value in the regression functions should be a number.
+fn get_numbers(value: CalcValue) -> Result<Vec<Vec<f64>>, ast::Error> {
+    let m = get_matrix(value)?;
+    let (rows, cols) = m.get_size();
+    (0..rows)
+        .map(|i| {
+            (0..cols)
+                .map(|j| match get_value(&m, i, j) {
+                    Value::Number(n) => Ok(n),
+                    Value::Error(e) => Err(e),
+                    _ => Err(ast::Error::Value),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// The known x's are 1, 2, 3, ... if they are omitted.
+fn default_x(n: usize, layout: Layout) -> Vec<Vec<f64>> {
+    let xs = (1..=n).map(|i| i as f64);
+    match layout {
+        Layout::Rows => xs.map(|x| vec![x]).collect(),
+        Layout::Cols => vec![xs.collect()],
+    }
+}
+
+// Split the known values into observations. A column of y's pairs with the
+// rows of x's, a row of y's pairs with the columns of x's and otherwise the
+// x's must have the same size as the y's.
+fn get_observations(
+    known_y: CalcValue,
+    known_x: Option<CalcValue>,
+) -> Result<Observations, ast::Error> {
+    let ys = get_numbers(known_y)?;
+    let (rows, cols) = (ys.len(), ys[0].len());
+    let layout = if cols == 1 || rows != 1 {
+        Layout::Rows
+    } else {
+        Layout::Cols
+    };
+    let xs = match known_x {
+        Some(v) => get_numbers(v)?,
+        None if rows > 1 && cols > 1 => return Err(ast::Error::Ref),
+        None => default_x(rows * cols, layout),
+    };
+    let (x_rows, x_cols) = (xs.len(), xs[0].len());
+    if rows > 1 && cols > 1 {
+        if (x_rows, x_cols) != (rows, cols) {
+            return Err(ast::Error::Ref);
+        }
+        let xs = xs.into_iter().flatten().map(|x| vec![x]).collect();
+        return Ok((xs, ys.into_iter().flatten().collect(), layout));
+    }
+    let ys = ys.into_iter().flatten().collect::<Vec<_>>();
+    let xs = match layout {
+        Layout::Rows if x_rows == ys.len() => xs,
+        Layout::Cols if x_cols == ys.len() => transpose(xs),
+        _ => return Err(ast::Error::Ref),
+    };
+    Ok((xs, ys, layout))
+}
+
+fn transpose(m: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let cols = m[0].len();
+    (0..cols)
+        .map(|j| m.iter().map(|r| r[j]).collect())
+        .collect()
+}
+
+// Ordinary least squares by solving the normal equations.
+fn fit(xs: &[Vec<f64>], ys: &[f64], constant: bool) -> Result<Fit, ast::Error> {
+    let n = ys.len();
+    let k = xs[0].len();
+    let design = xs
+        .iter()
+        .map(|r| {
+            let mut r = r.clone();
+            if constant {
+                r.push(1.);
+            }
+            r
+        })
+        .collect::<Vec<_>>();
+    let p = design[0].len();
+    let xtx = (0..p)
+        .map(|a| {
+            (0..p)
+                .map(|b| design.iter().map(|r| r[a] * r[b]).sum())
+                .collect::<Vec<f64>>()
+        })
+        .collect::<Vec<_>>();
+    let xty = (0..p)
+        .map(|a| design.iter().zip(ys).map(|(r, y)| r[a] * y).sum())
+        .collect::<Vec<f64>>();
+    let inv = invert(xtx).ok_or(ast::Error::Num)?;
+    let beta = inv
+        .iter()
+        .map(|r| r.iter().zip(&xty).map(|(a, b)| a * b).sum())
+        .collect::<Vec<f64>>();
+    let ss_resid = design
+        .iter()
+        .zip(ys)
+        .map(|(r, y)| {
+            let y_hat = r.iter().zip(&beta).map(|(a, b)| a * b).sum::<f64>();
+            (y - y_hat).powi(2)
+        })
+        .sum::<f64>();
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+    let ss_total = if constant {
+        ys.iter().map(|y| (y - mean_y).powi(2)).sum::<f64>()
+    } else {
+        ys.iter().map(|y| y * y).sum::<f64>()
+    };
+    Ok(Fit {
+        coefs: beta[..k].to_vec(),
+        intercept: if constant { beta[k] } else { 0. },
+        constant,
+        inv_diag: (0..p).map(|i| inv[i][i]).collect(),
+        n,
+        ss_reg: ss_total - ss_resid,
+        ss_resid,
+    })
+}
+
+// Gauss-Jordan elimination with partial pivoting.
+fn invert(mut m: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = m.len();
+    let mut inv = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1. } else { 0. }).collect())
+        .collect::<Vec<Vec<f64>>>();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|a, b| {
+            m[*a][col]
+                .abs()
+                .partial_cmp(&m[*b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        inv.swap(col, pivot);
+        let d = m[col][col];
+        m[col].iter_mut().for_each(|v| *v /= d);
+        inv[col].iter_mut().for_each(|v| *v /= d);
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = m[row][col];
+            if factor == 0. {
+                continue;
+            }
+            for j in 0..n {
+                m[row][j] -= factor * m[col][j];
+                inv[row][j] -= factor * inv[col][j];
+            }
+        }
+    }
+    Some(inv)
+}
+
+// The coefficients are listed from the last x to the first, followed by the
+// intercept. The statistics fill 4 more rows padded with #N/A.
+fn linest_output(f: &Fit, stats: bool) -> Vec<Vec<Value>> {
+    let width = f.coefs.len() + 1;
+    let num = |n: f64| {
+        if n.is_finite() {
+            Value::Number(n)
+        } else {
+            Value::Error(ast::Error::Num)
+        }
+    };
+    let mut first = f.coefs.iter().rev().map(|c| num(*c)).collect::<Vec<_>>();
+    first.push(num(f.intercept));
+    if !stats {
+        return vec![first];
+    }
+    let params = f.coefs.len() + f.constant as usize;
+    let df = f.n as f64 - params as f64;
+    let sey = (f.ss_resid / df).sqrt();
+    let se = |i: usize| num((f.inv_diag[i] * sey * sey).sqrt());
+    let mut second = (0..f.coefs.len()).rev().map(se).collect::<Vec<_>>();
+    if f.constant {
+        second.push(se(f.coefs.len()));
+    } else {
+        second.push(Value::Error(ast::Error::Na));
+    }
+    let f_stat = (f.ss_reg / f.coefs.len() as f64) / (f.ss_resid / df);
+    let pad = |mut row: Vec<Value>| {
+        row.resize(width, Value::Error(ast::Error::Na));
+        row
+    };
+    let r2 = f.ss_reg / (f.ss_reg + f.ss_resid);
+    vec![
+        first,
+        second,
+        pad(vec![num(r2), num(sey)]),
+        pad(vec![num(f_stat), num(df)]),
+        pad(vec![num(f.ss_reg), num(f.ss_resid)]),
+    ]
+}
+
+fn predict(f: &Fit, new_x: Vec<Vec<f64>>, layout: Layout) -> Result<Vec<Vec<f64>>, ast::Error> {
+    let y = |xs: &[f64]| xs.iter().zip(&f.coefs).map(|(x, c)| x * c).sum::<f64>() + f.intercept;
+    // With a single x, each new x gives a result at the same place.
+    if f.coefs.len() == 1 {
+        return Ok(new_x
+            .into_iter()
+            .map(|r| r.into_iter().map(|x| y(&[x])).collect())
+            .collect());
+    }
+    match layout {
+        Layout::Rows if new_x[0].len() == f.coefs.len() => {
+            Ok(new_x.iter().map(|r| vec![y(r)]).collect())
+        }
+        Layout::Cols if new_x.len() == f.coefs.len() => {
+            Ok(vec![transpose(new_x).iter().map(|r| y(r)).collect()])
+        }
+        _ => Err(ast::Error::Ref),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fit, line, linest_output, pearson, Value};
+
+    #[test]
+    fn pairs_test() {
+        let pairs = [(3., 9.), (2., 7.), (4., 12.), (5., 15.), (6., 17.)];
+        let r = pearson(&pairs).unwrap();
+        assert!((r - 0.997054486).abs() < 1e-9);
+        let pairs = [
+            (2., 6.),
+            (3., 5.),
+            (9., 11.),
+            (1., 7.),
+            (8., 5.),
+            (7., 4.),
+            (5., 4.),
+        ];
+        let (slope, intercept) = line(&pairs).unwrap();
+        assert!((slope - 0.305555556).abs() < 1e-9);
+        assert!((intercept - 3.166666667).abs() < 1e-9);
+    }
+
+    #[test]
+    fn linest_test() {
+        let xs = [1., 2., 3., 4.]
+            .iter()
+            .map(|x| vec![*x])
+            .collect::<Vec<_>>();
+        let ys = [1., 9., 5., 7.];
+        let f = fit(&xs, &ys, true).unwrap();
+        let output = linest_output(&f, true);
+        let get = |i: usize, j: usize| match output[i][j] {
+            Value::Number(n) => n,
+            _ => panic!(),
+        };
+        assert!((get(0, 0) - 1.4).abs() < 1e-9);
+        assert!((get(0, 1) - 2.).abs() < 1e-9);
+        assert!((get(2, 0) - 0.28).abs() < 1e-9);
+        assert!((get(3, 0) - 9.8 / 12.6).abs() < 1e-9);
+        assert!((get(3, 1) - 2.).abs() < 1e-9);
+        assert!((get(4, 1) - 25.2).abs() < 1e-9);
+        let f = fit(&xs, &ys, false).unwrap();
+        assert!((f.coefs[0] - 62. / 30.).abs() < 1e-9);
+        assert_eq!(f.intercept, 0.);
+    }
+}
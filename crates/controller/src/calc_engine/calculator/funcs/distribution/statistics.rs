@@ -1,5 +1,5 @@
 use super::super::CalcVertex;
-use crate::calc_engine::{calculator::funcs::utils::get_nums_from_value, connector::Connector};
+use crate::calc_engine::{calculator::funcs::utils::get_nums_from_arg, connector::Connector};
 use logisheets_parser::ast;
 use statrs::statistics::Statistics;
 
@@ -7,45 +7,139 @@ pub fn calc_var<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
 where
     C: Connector,
 {
-    let func = |nums: Vec<f64>| -> Option<f64> {
-        match nums.variance() {
-            f if f.is_nan() => None,
-            f => Some(f),
-        }
-    };
-    calc(args, fetcher, func)
+    calc(args, fetcher, false, sample_variance)
+}
+
+pub fn calc_vara<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true, sample_variance)
+}
+
+pub fn calc_varp<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, population_variance)
+}
+
+pub fn calc_varpa<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true, population_variance)
 }
 
 pub fn calc_stdev<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
 where
     C: Connector,
 {
-    let func = |nums: Vec<f64>| -> Option<f64> {
-        match nums.std_dev() {
-            f if f.is_nan() => None,
-            f => Some(f),
+    calc(args, fetcher, false, |nums| {
+        sample_variance(nums).map(f64::sqrt)
+    })
+}
+
+pub fn calc_stdeva<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true, |nums| {
+        sample_variance(nums).map(f64::sqrt)
+    })
+}
+
+pub fn calc_stdevp<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |nums| {
+        population_variance(nums).map(f64::sqrt)
+    })
+}
+
+pub fn calc_stdevpa<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true, |nums| {
+        population_variance(nums).map(f64::sqrt)
+    })
+}
+
+pub fn calc_avedev<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |nums| {
+        if nums.is_empty() {
+            return Err(ast::Error::Num);
+        }
+        let mean = nums.iter().mean();
+        Ok(nums.iter().map(|n| (n - mean).abs()).sum::<f64>() / nums.len() as f64)
+    })
+}
+
+pub fn calc_devsq<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false, |nums| {
+        if nums.is_empty() {
+            return Err(ast::Error::Num);
         }
-    };
-    calc(args, fetcher, func)
+        let mean = nums.iter().mean();
+        Ok(nums.iter().map(|n| (n - mean).powi(2)).sum())
+    })
 }
 
-fn calc<C, F>(args: Vec<CalcVertex>, fetcher: &mut C, func: F) -> CalcVertex
+fn calc<C, F>(args: Vec<CalcVertex>, fetcher: &mut C, count_all: bool, func: F) -> CalcVertex
 where
     C: Connector,
-    F: Fn(Vec<f64>) -> Option<f64>,
+    F: Fn(Vec<f64>) -> Result<f64, ast::Error>,
 {
     assert_or_return!(args.len() > 0, ast::Error::Unspecified);
     let mut iter = args.into_iter();
     let mut result: Vec<f64> = vec![];
     while let Some(vertex) = iter.next() {
-        let value = fetcher.get_calc_value(vertex);
-        match get_nums_from_value(value) {
+        match get_nums_from_arg(vertex, fetcher, count_all) {
             Ok(nums) => result.extend(nums),
             Err(e) => return CalcVertex::from_error(e),
         }
     }
     match func(result) {
-        Some(r) => CalcVertex::from_number(r),
-        None => CalcVertex::from_error(ast::Error::Na),
+        Ok(r) => CalcVertex::from_number(r),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+fn sample_variance(nums: Vec<f64>) -> Result<f64, ast::Error> {
+    match nums.variance() {
+        f if f.is_nan() => Err(ast::Error::Div0),
+        f => Ok(f),
+    }
+}
+
+fn population_variance(nums: Vec<f64>) -> Result<f64, ast::Error> {
+    match nums.population_variance() {
+        f if f.is_nan() => Err(ast::Error::Div0),
+        f => Ok(f),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{population_variance, sample_variance};
+
+    #[test]
+    fn variance_test() {
+        let nums = vec![
+            1345., 1301., 1368., 1322., 1310., 1370., 1318., 1350., 1303., 1299.,
+        ];
+        let stdev = sample_variance(nums.clone()).unwrap().sqrt();
+        assert!((stdev - 27.46391572).abs() < 1e-8);
+        let stdevp = population_variance(nums).unwrap().sqrt();
+        assert!((stdevp - 26.05455814).abs() < 1e-8);
+        assert!(sample_variance(vec![1.]).is_err());
     }
 }
@@ -0,0 +1,131 @@
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use statrs::distribution::{Continuous, ContinuousCDF, StudentsT};
+
+pub fn calc_tdist<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(freedom, second);
+    let freedom = freedom.trunc();
+    assert_or_return!(freedom >= 1., ast::Error::Num);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    let v = get_condition_result(third);
+    let dist = StudentsT::new(0., 1., freedom).unwrap();
+    match v {
+        ConditionResult::True => CalcVertex::from_number(dist.cdf(x)),
+        ConditionResult::False => CalcVertex::from_number(dist.pdf(x)),
+        ConditionResult::Error(e) => CalcVertex::from_error(e),
+    }
+}
+
+pub fn calc_tdist_2t<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_tail(args, fetcher, Some(2.))
+}
+
+pub fn calc_tdist_rt<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_tail(args, fetcher, Some(1.))
+}
+
+// TDIST(x, freedom, tails) is the legacy form of T.DIST.RT and T.DIST.2T.
+pub fn calc_tdist_legacy<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_tail(args, fetcher, None)
+}
+
+fn calc_tail<C>(args: Vec<CalcVertex>, fetcher: &mut C, tails: Option<f64>) -> CalcVertex
+where
+    C: Connector,
+{
+    let arg_cnt = if tails.is_some() { 2 } else { 3 };
+    assert_or_return!(args.len() == arg_cnt, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(x, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(freedom, second);
+    let freedom = freedom.trunc();
+    assert_or_return!(freedom >= 1., ast::Error::Num);
+    let tails = match tails {
+        Some(t) => t,
+        None => {
+            let third = fetcher.get_calc_value(args_iter.next().unwrap());
+            assert_f64_from_calc_value!(t, third);
+            let t = t.trunc();
+            assert_or_return!(t == 1. || t == 2., ast::Error::Num);
+            assert_or_return!(x >= 0., ast::Error::Num);
+            t
+        }
+    };
+    assert_or_return!(tails == 1. || x >= 0., ast::Error::Num);
+    let dist = StudentsT::new(0., 1., freedom).unwrap();
+    CalcVertex::from_number(tails * (1. - dist.cdf(x)))
+}
+
+pub fn calc_tinv<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_inv(args, fetcher, false)
+}
+
+// TINV is the same as T.INV.2T.
+pub fn calc_tinv_2t<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc_inv(args, fetcher, true)
+}
+
+fn calc_inv<C>(args: Vec<CalcVertex>, fetcher: &mut C, two_tailed: bool) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(prob, first);
+    assert_or_return!(prob > 0. && prob <= 1., ast::Error::Num);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(freedom, second);
+    let freedom = freedom.trunc();
+    assert_or_return!(freedom >= 1., ast::Error::Num);
+    let dist = StudentsT::new(0., 1., freedom).unwrap();
+    if two_tailed {
+        CalcVertex::from_number(dist.inverse_cdf(1. - prob / 2.))
+    } else {
+        assert_or_return!(prob < 1., ast::Error::Num);
+        CalcVertex::from_number(dist.inverse_cdf(prob))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use statrs::distribution::{ContinuousCDF, StudentsT};
+
+    #[test]
+    fn students_t_test() {
+        let dist = StudentsT::new(0., 1., 60.).unwrap();
+        let r = 2. * (1. - dist.cdf(1.959999998));
+        assert!((r - 0.054644930).abs() < 1e-8);
+        let dist = StudentsT::new(0., 1., 2.).unwrap();
+        assert!((dist.inverse_cdf(0.75) - 0.8164966).abs() < 1e-6);
+        let dist = StudentsT::new(0., 1., 60.).unwrap();
+        assert!((dist.inverse_cdf(1. - 0.05 / 2.) - 2.000297822).abs() < 1e-8);
+    }
+}
@@ -0,0 +1,115 @@
+use super::condition::match_text_pattern;
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn calc_find<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, find)
+}
+
+pub fn calc_findb<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, findb)
+}
+
+pub fn calc_search<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, search)
+}
+
+fn calc<C, F>(args: Vec<CalcVertex>, fetcher: &mut C, func: F) -> CalcVertex
+where
+    C: Connector,
+    F: Fn(&str, &str, usize) -> Option<usize>,
+{
+    assert_or_return!(args.len() == 2 || args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(find_text, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(within_text, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(1.)));
+    assert_f64_from_calc_value!(start, third);
+    assert_or_return!(start >= 1., ast::Error::Value);
+    match func(&find_text, &within_text, start as usize) {
+        Some(p) => CalcVertex::from_number(p as f64),
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+// Case-sensitive search without wildcards. Positions are counted in
+// graphemes and a combining mark never matches a part of a grapheme.
+fn find(find_text: &str, within_text: &str, start: usize) -> Option<usize> {
+    let pattern = find_text.graphemes(true).collect::<Vec<_>>();
+    let graphemes = within_text.graphemes(true).collect::<Vec<_>>();
+    if start > graphemes.len().max(1) {
+        return None;
+    }
+    (start - 1..=graphemes.len())
+        .find(|i| graphemes[*i..].starts_with(&pattern))
+        .map(|i| i + 1)
+}
+
+// Positions are counted in bytes, which is the same as LENB.
+fn findb(find_text: &str, within_text: &str, start: usize) -> Option<usize> {
+    if start > within_text.len().max(1) {
+        return None;
+    }
+    (start - 1..=within_text.len())
+        .filter(|i| within_text.is_char_boundary(*i))
+        .find(|i| within_text[*i..].starts_with(find_text))
+        .map(|i| i + 1)
+}
+
+// Case-insensitive search supporting the wildcards `?`, `*` and `~`.
+fn search(find_text: &str, within_text: &str, start: usize) -> Option<usize> {
+    let offsets = within_text
+        .grapheme_indices(true)
+        .map(|(i, _)| i)
+        .chain(std::iter::once(within_text.len()))
+        .collect::<Vec<_>>();
+    if start > (offsets.len() - 1).max(1) {
+        return None;
+    }
+    let pattern = format!("{}*", find_text);
+    offsets[start - 1..]
+        .iter()
+        .position(|i| match_text_pattern(&pattern, &within_text[*i..]))
+        .map(|p| p + start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, findb, search};
+
+    #[test]
+    fn find_test() {
+        let text = "Miriam McGovern";
+        assert_eq!(find("M", text, 1), Some(1));
+        assert_eq!(find("m", text, 1), Some(6));
+        assert_eq!(find("M", text, 3), Some(8));
+        assert_eq!(find("", text, 3), Some(3));
+        assert_eq!(find("x", text, 1), None);
+        assert_eq!(find("M", text, 16), None);
+        assert_eq!(find("e", "e\u{301}e", 1), Some(2));
+        assert_eq!(find("辑", "逻辑汇", 1), Some(2));
+        assert_eq!(findb("辑", "逻辑汇", 1), Some(4));
+    }
+
+    #[test]
+    fn search_test() {
+        assert_eq!(search("e", "Statements", 6), Some(7));
+        assert_eq!(search("margin", "Profit Margin", 1), Some(8));
+        assert_eq!(search("m?r", "Profit Margin", 1), Some(8));
+        assert_eq!(search("~?", "Why?", 1), Some(4));
+        assert_eq!(search("x", "Statements", 1), None);
+    }
+}
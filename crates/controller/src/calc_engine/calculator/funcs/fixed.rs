@@ -0,0 +1,103 @@
+use super::utils::{get_condition_result, ConditionResult};
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use crate::formatter::NumFormat;
+use logisheets_parser::ast;
+
+// More decimals are rejected, and fewer negative ones round any number to 0
+// like this limit does.
+const MAX_DECIMALS: f64 = 127.;
+
+pub fn calc_fixed<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty() && args.len() <= 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(n, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(2.)));
+    assert_f64_from_calc_value!(decimals, second);
+    let no_commas = match args_iter.next() {
+        Some(arg) => match get_condition_result(fetcher.get_calc_value(arg)) {
+            ConditionResult::True => true,
+            ConditionResult::False => false,
+            ConditionResult::Error(e) => return CalcVertex::from_error(e),
+        },
+        None => false,
+    };
+    let decimals = decimals.trunc();
+    assert_or_return!(decimals <= MAX_DECIMALS, ast::Error::Value);
+    CalcVertex::from_string(fixed(n, decimals.max(-MAX_DECIMALS) as i32, no_commas))
+}
+
+pub fn calc_dollar<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1 || args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(n, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(2.)));
+    assert_f64_from_calc_value!(decimals, second);
+    let decimals = decimals.trunc();
+    assert_or_return!(decimals <= MAX_DECIMALS, ast::Error::Value);
+    CalcVertex::from_string(dollar(n, decimals.max(-MAX_DECIMALS) as i32))
+}
+
+fn fixed(n: f64, decimals: i32, no_commas: bool) -> String {
+    let int_part = if no_commas { "0" } else { "#,##0" };
+    let code = format!("{}{}", int_part, decimal_part(decimals));
+    NumFormat::new(&code)
+        .format_number(round_left(n, decimals))
+        .text
+}
+
+fn dollar(n: f64, decimals: i32) -> String {
+    let section = format!("$#,##0{}", decimal_part(decimals));
+    let code = format!("{};({})", section, section);
+    NumFormat::new(&code)
+        .format_number(round_left(n, decimals))
+        .text
+}
+
+fn decimal_part(decimals: i32) -> String {
+    if decimals > 0 {
+        format!(".{}", "0".repeat(decimals as usize))
+    } else {
+        String::new()
+    }
+}
+
+// Negative decimals round the number to the left of the decimal point.
+fn round_left(n: f64, decimals: i32) -> f64 {
+    if decimals >= 0 {
+        return n;
+    }
+    let factor = 10_f64.powi(-decimals);
+    (n / factor).round() * factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dollar, fixed};
+
+    #[test]
+    fn fixed_test() {
+        assert_eq!(fixed(1234.567, 1, false), "1,234.6");
+        assert_eq!(fixed(1234.567, -1, false), "1,230");
+        assert_eq!(fixed(-1234.567, -1, true), "-1230");
+        assert_eq!(fixed(44.332, 2, false), "44.33");
+    }
+
+    #[test]
+    fn dollar_test() {
+        assert_eq!(dollar(1234.567, 2), "$1,234.57");
+        assert_eq!(dollar(1234.567, -2), "$1,200");
+        assert_eq!(dollar(-1234.567, -2), "($1,200)");
+        assert_eq!(dollar(-0.123, 4), "($0.1230)");
+        assert_eq!(dollar(99.888, 2), "$99.89");
+        assert_eq!(dollar(1., -127), "$0");
+    }
+}
@@ -91,6 +91,7 @@ fn get_num_from_calc_value(value: CalcValue) -> Result<Vec<u32>, ast::Error> {
 fn get_num_from_value(value: Value) -> Result<u32, ast::Error> {
     match value {
         Value::Blank => Ok(0),
+        Value::Number(f) if f < 0. => Err(ast::Error::Num),
         Value::Number(f) => Ok(f.floor() as u32),
         Value::Text(_) => Err(ast::Error::Value),
         Value::Boolean(b) => {
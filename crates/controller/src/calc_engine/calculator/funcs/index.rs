@@ -1,98 +1,79 @@
+use super::lookup::get_bounds;
+use super::lookup::matching::{get_col, get_matrix, get_row, get_value};
+use super::utils::is_omitted;
 use crate::calc_engine::{
     calculator::calc_vertex::{CalcValue, CalcVertex, Value},
     connector::Connector,
 };
+use logisheets_base::matrix_value::MatrixValue;
 use logisheets_parser::ast;
 
+// INDEX(array, row_num, [column_num], [area_num]). A row or column number of
+// 0 selects the whole column or row.
 pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
 where
     C: Connector,
 {
-    if args.len() != 3 && args.len() != 4 {
-        return CalcVertex::from_error(ast::Error::Unspecified);
-    }
+    assert_or_return!(args.len() >= 2 && args.len() <= 4, ast::Error::Unspecified);
     let mut args_iter = args.into_iter();
-    let first = args_iter.next().unwrap();
-    let second = fetcher.get_calc_value(args_iter.next().unwrap());
-    let third = fetcher.get_calc_value(args_iter.next().unwrap());
-    let idx = args_iter.next().map_or(None, |vertex| {
-        let value = fetcher.get_calc_value(vertex);
-        match get_offset_from_calc_value(value) {
-            Ok(n) => Some(n),
-            Err(_) => None,
+    let array = args_iter.next().unwrap();
+    let mut nums = Vec::with_capacity(3);
+    for arg in args_iter {
+        if is_omitted(&arg) {
+            nums.push(None);
+            continue;
         }
-    });
-    let row_offset = get_offset_from_calc_value(second);
-    let col_offset = get_offset_from_calc_value(third);
-    match (row_offset, col_offset) {
-        (Ok(r), Ok(c)) => get_index_result(first, r, c, idx),
-        (Ok(_), Err(e)) => CalcVertex::from_error(e),
-        (Err(e), Ok(_)) => CalcVertex::from_error(e),
-        (Err(e), Err(_)) => CalcVertex::from_error(e),
-    }
-}
-
-fn get_index_result(
-    cv: CalcVertex,
-    row_offset: usize,
-    col_offset: usize,
-    idx: Option<usize>,
-) -> CalcVertex {
-    match idx {
-        Some(i) => {
-            if i == 0 {
-                return CalcVertex::from_error(ast::Error::Value);
-            }
-            match cv {
-                CalcVertex::Union(u) => {
-                    let target_v = u.into_iter().skip(i - 1).next();
-                    match target_v {
-                        Some(v) => _index(*v, row_offset, col_offset),
-                        None => CalcVertex::from_error(ast::Error::Ref),
-                    }
-                }
-                _ => {
-                    if i == 1 {
-                        _index(cv, row_offset, col_offset)
-                    } else {
-                        CalcVertex::from_error(ast::Error::Ref)
-                    }
-                }
-            }
+        match get_offset_from_calc_value(fetcher.get_calc_value(arg)) {
+            Ok(n) => nums.push(Some(n)),
+            Err(e) => return CalcVertex::from_error(e),
         }
-        None => todo!(),
     }
-}
-
-fn _index(cv: CalcVertex, r: usize, c: usize) -> CalcVertex {
-    match &cv {
-        CalcVertex::Value(v) => match v {
-            CalcValue::Scalar(_) => {
-                if r <= 1 && c <= 1 {
-                    cv
-                } else {
-                    CalcVertex::from_error(ast::Error::Ref)
-                }
-            }
-            CalcValue::Range(mv) => {
-                if r == 0 || c == 0 {
-                    todo!()
-                }
-                let size = mv.get_size();
-                if r >= size.0 || c >= size.1 {
-                    CalcVertex::from_error(ast::Error::Ref)
-                } else {
-                    match mv.visit(r - 1, c - 1) {
-                        Ok(v) => CalcVertex::Value(CalcValue::Scalar(v.clone())),
-                        Err(v) => CalcVertex::Value(CalcValue::Scalar(v)),
-                    }
-                }
-            }
-            CalcValue::Cube(_) => CalcVertex::from_error(ast::Error::Value),
-            CalcValue::Union(_) => unreachable!(),
+    let area = nums.get(2).copied().flatten().unwrap_or(1);
+    assert_or_return!(area > 0, ast::Error::Value);
+    let array = match array {
+        CalcVertex::Union(u) => match u.into_iter().nth(area - 1) {
+            Some(v) => *v,
+            None => return CalcVertex::from_error(ast::Error::Ref),
         },
-        CalcVertex::Reference(_) => todo!(),
-        CalcVertex::Union(_) => unreachable!(),
+        _ if area > 1 => return CalcVertex::from_error(ast::Error::Ref),
+        v => v,
+    };
+    // The matrices of whole columns or rows only cover a part of the sheet.
+    let bounds = match &array {
+        CalcVertex::Reference(r) => Some(get_bounds(&r.reference)),
+        _ => None,
+    };
+    let matrix = match get_matrix(fetcher.get_calc_value(array)) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let (rows, cols) = match bounds {
+        Some((_, _, height, width)) => (height, width),
+        None => matrix.get_size(),
+    };
+    let row = nums[0].unwrap_or(0);
+    let (row, col) = match nums.get(1) {
+        Some(col) => (row, col.unwrap_or(0)),
+        // The only number is for the longer side of a vector.
+        None if rows == 1 => (1, row),
+        None if cols == 1 => (row, 1),
+        None => (row, 0),
+    };
+    assert_or_return!(row <= rows && col <= cols, ast::Error::Ref);
+    match (row, col) {
+        _ if rows == 1 && cols == 1 => {
+            CalcVertex::Value(CalcValue::Scalar(get_value(&matrix, 0, 0)))
+        }
+        (0, 0) => CalcVertex::Value(CalcValue::Range(matrix)),
+        (0, c) => {
+            let col = get_col(&matrix, c - 1).into_iter().map(|v| vec![v]);
+            CalcVertex::Value(CalcValue::Range(MatrixValue::from(col.collect::<Vec<_>>())))
+        }
+        (r, 0) => {
+            let row = get_row(&matrix, r - 1);
+            CalcVertex::Value(CalcValue::Range(MatrixValue::from(vec![row])))
+        }
+        (r, c) => CalcVertex::Value(CalcValue::Scalar(get_value(&matrix, r - 1, c - 1))),
     }
 }
 
@@ -100,6 +81,7 @@ fn get_offset_from_calc_value(value: CalcValue) -> Result<usize, ast::Error> {
     match value {
         CalcValue::Scalar(s) => match s {
             Value::Blank => Ok(0),
+            Value::Number(f) if f < 0. => Err(ast::Error::Value),
             Value::Number(f) => Ok(f.floor() as usize),
             Value::Text(_) => Err(ast::Error::Value),
             Value::Boolean(b) => {
@@ -110,7 +92,7 @@ fn get_offset_from_calc_value(value: CalcValue) -> Result<usize, ast::Error> {
                 }
             }
             Value::Error(e) => Err(e),
-            Value::Date(_) => todo!(),
+            Value::Date(_) => Err(ast::Error::Value),
         },
         // WPS does not support.
         _ => Err(ast::Error::Value),
@@ -0,0 +1,89 @@
+use super::super::utils::{get_condition_result, is_omitted, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use super::{MAX_COLS, MAX_ROWS};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::index_to_column_label;
+use logisheets_parser::ast;
+
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 2 && args.len() <= 5, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let row_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(row, row_arg);
+    let col_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(col, col_arg);
+    // Omitted arguments take their defaults instead of shifting the later ones.
+    let mut args_iter = args_iter.map(|arg| if is_omitted(&arg) { None } else { Some(arg) });
+    let abs_arg = args_iter
+        .next()
+        .flatten()
+        .unwrap_or(CalcVertex::from_number(1.));
+    assert_f64_from_calc_value!(abs_num, fetcher.get_calc_value(abs_arg));
+    let a1 = match args_iter.next().flatten() {
+        Some(arg) => match get_condition_result(fetcher.get_calc_value(arg)) {
+            ConditionResult::True => true,
+            ConditionResult::False => false,
+            ConditionResult::Error(e) => return CalcVertex::from_error(e),
+        },
+        None => true,
+    };
+    let sheet = match args_iter.next().flatten() {
+        Some(arg) => {
+            assert_text_from_calc_value!(sheet, fetcher.get_calc_value(arg));
+            Some(sheet)
+        }
+        None => None,
+    };
+    let (row, col) = (row.trunc(), col.trunc());
+    assert_or_return!(
+        row >= 1. && col >= 1. && row <= MAX_ROWS as f64 && col <= MAX_COLS as f64,
+        ast::Error::Value
+    );
+    let (row, col) = (row as usize, col as usize);
+    let (abs_row, abs_col) = match abs_num.trunc() as i64 {
+        1 => (true, true),
+        2 => (true, false),
+        3 => (false, true),
+        4 => (false, false),
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let addr = if a1 {
+        let dollar = |abs: bool| if abs { "$" } else { "" };
+        format!(
+            "{}{}{}{}",
+            dollar(abs_col),
+            index_to_column_label(col - 1),
+            dollar(abs_row),
+            row
+        )
+    } else {
+        let part = |abs: bool, n: usize| {
+            if abs {
+                n.to_string()
+            } else {
+                format!("[{}]", n)
+            }
+        };
+        format!("R{}C{}", part(abs_row, row), part(abs_col, col))
+    };
+    match sheet {
+        Some(s) if s.is_empty() => CalcVertex::from_text(format!("!{}", addr)),
+        Some(s) => CalcVertex::from_text(format!("{}!{}", quote_sheet_name(&s), addr)),
+        None => CalcVertex::from_text(addr),
+    }
+}
+
+fn quote_sheet_name(name: &str) -> String {
+    let plain = name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        && !name.starts_with(|c: char| c.is_ascii_digit());
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
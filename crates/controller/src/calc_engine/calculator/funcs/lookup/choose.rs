@@ -0,0 +1,20 @@
+use super::super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+// The chosen argument is returned as it is, so that a reference can be chosen.
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let idx_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(idx, idx_arg);
+    let idx = idx.trunc();
+    assert_or_return!(idx >= 1., ast::Error::Value);
+    match args_iter.nth(idx as usize - 1) {
+        Some(v) => v,
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
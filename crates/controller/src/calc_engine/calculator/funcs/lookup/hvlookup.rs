@@ -0,0 +1,67 @@
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use super::matching::{find, find_sorted, get_col, get_matrix, get_row, get_value};
+use super::matching::{MatchMode, SearchMode};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+pub fn calc_vlookup<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true)
+}
+
+pub fn calc_hlookup<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false)
+}
+
+fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C, vertical: bool) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3 || args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let lookup = match fetcher.get_calc_value(args_iter.next().unwrap()) {
+        CalcValue::Scalar(Value::Error(e)) => return CalcVertex::from_error(e),
+        CalcValue::Scalar(v) => v,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let table = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let idx_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(idx, idx_arg);
+    let approximate = match args_iter.next() {
+        Some(arg) => match get_condition_result(fetcher.get_calc_value(arg)) {
+            ConditionResult::True => true,
+            ConditionResult::False => false,
+            ConditionResult::Error(e) => return CalcVertex::from_error(e),
+        },
+        None => true,
+    };
+    let idx = idx.trunc();
+    assert_or_return!(idx >= 1., ast::Error::Value);
+    let idx = idx as usize - 1;
+    let (rows, cols) = table.get_size();
+    let (keys, len) = if vertical {
+        (get_col(&table, 0), cols)
+    } else {
+        (get_row(&table, 0), rows)
+    };
+    assert_or_return!(idx < len, ast::Error::Ref);
+    let pos = if approximate {
+        find_sorted(&lookup, &keys, false)
+    } else {
+        find(&lookup, &keys, MatchMode::Wildcard, SearchMode::FirstToLast)
+    };
+    match pos {
+        Some(p) if vertical => CalcVertex::Value(CalcValue::Scalar(get_value(&table, p, idx))),
+        Some(p) => CalcVertex::Value(CalcValue::Scalar(get_value(&table, idx, p))),
+        None => CalcVertex::from_error(ast::Error::Na),
+    }
+}
@@ -0,0 +1,181 @@
+use super::super::super::calc_vertex::CalcReference;
+use super::super::utils::{get_condition_result, ConditionResult};
+use super::super::{CalcValue, CalcVertex, Value};
+use super::{from_bounds, MAX_COLS, MAX_ROWS};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::{column_label_to_index, Addr};
+use logisheets_parser::ast;
+
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1 || args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    assert_text_from_calc_value!(text, fetcher.get_calc_value(args_iter.next().unwrap()));
+    let a1 = match args_iter.next() {
+        Some(arg) => match get_condition_result(fetcher.get_calc_value(arg)) {
+            ConditionResult::True => true,
+            ConditionResult::False => false,
+            ConditionResult::Error(e) => return CalcVertex::from_error(e),
+        },
+        None => true,
+    };
+    let (sheet, text) = match text.rsplit_once('!') {
+        Some((sheet, text)) => (Some(unquote_sheet_name(sheet)), text),
+        None => (None, text.as_str()),
+    };
+    let sheet_id = match sheet {
+        Some(name) => match fetcher.get_sheet_id_by_name(&name) {
+            Some(id) => id,
+            None => return CalcVertex::from_error(ast::Error::Ref),
+        },
+        None => fetcher.get_active_sheet(),
+    };
+    let curr = fetcher.get_curr_addr();
+    let parts = text
+        .trim()
+        .split(':')
+        .map(|p| {
+            if a1 {
+                parse_a1_part(p)
+            } else {
+                parse_r1c1_part(p, &curr)
+            }
+        })
+        .collect::<Option<Vec<_>>>();
+    let bounds = match parts.as_deref() {
+        Some([(Some(r), Some(c))]) => Some((*r, *c, 1, 1)),
+        Some([(r1, c1), (r2, c2)]) => match (r1, c1, r2, c2) {
+            (Some(r1), Some(c1), Some(r2), Some(c2)) => Some((
+                *r1.min(r2),
+                *c1.min(c2),
+                r1.max(r2) - r1.min(r2) + 1,
+                c1.max(c2) - c1.min(c2) + 1,
+            )),
+            (None, Some(c1), None, Some(c2)) => {
+                Some((0, *c1.min(c2), MAX_ROWS, c1.max(c2) - c1.min(c2) + 1))
+            }
+            (Some(r1), None, Some(r2), None) => {
+                Some((*r1.min(r2), 0, r1.max(r2) - r1.min(r2) + 1, MAX_COLS))
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    match bounds {
+        Some((top, left, height, width)) => {
+            let result = CalcReference {
+                from_sheet: None,
+                sheet: sheet_id,
+                reference: from_bounds(top, left, height, width),
+            };
+            fetcher.add_dynamic_dep(&result);
+            CalcVertex::Reference(result)
+        }
+        None => CalcVertex::from_error(ast::Error::Ref),
+    }
+}
+
+fn unquote_sheet_name(name: &str) -> String {
+    match name.strip_prefix('\'').and_then(|n| n.strip_suffix('\'')) {
+        Some(n) => n.replace("''", "'"),
+        None => name.to_string(),
+    }
+}
+
+// Parse a part like `$A$1`, `A` or `1` into the 0-based row and column.
+fn parse_a1_part(part: &str) -> Option<(Option<usize>, Option<usize>)> {
+    let part = part.replace('$', "").to_uppercase();
+    let split = part
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(part.len());
+    let (label, digits) = part.split_at(split);
+    if label.is_empty() && digits.is_empty() {
+        return None;
+    }
+    let col = match label {
+        "" => None,
+        l if l.len() <= 3 => Some(column_label_to_index(l)),
+        _ => return None,
+    };
+    let row = match digits {
+        "" => None,
+        d => match d.parse::<usize>() {
+            Ok(r) if r >= 1 => Some(r - 1),
+            _ => return None,
+        },
+    };
+    check_bounds(row, col)
+}
+
+// Parse a part like `R1C1`, `R[-1]C`, `R2` or `C3`, where the bracketed numbers
+// are relative to the current cell.
+fn parse_r1c1_part(part: &str, curr: &Addr) -> Option<(Option<usize>, Option<usize>)> {
+    let part = part.to_uppercase();
+    let (row_part, col_part) = match part.find('C') {
+        Some(i) => (&part[..i], Some(&part[i + 1..])),
+        None => (part.as_str(), None),
+    };
+    let row = match row_part.strip_prefix('R') {
+        Some(r) => Some(parse_r1c1_num(r, curr.row)?),
+        None if row_part.is_empty() => None,
+        None => return None,
+    };
+    let col = match col_part {
+        Some(c) => Some(parse_r1c1_num(c, curr.col)?),
+        None => None,
+    };
+    if row.is_none() && col.is_none() {
+        return None;
+    }
+    check_bounds(row, col)
+}
+
+fn parse_r1c1_num(s: &str, curr: usize) -> Option<usize> {
+    if s.is_empty() {
+        return Some(curr);
+    }
+    match s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        Some(offset) => {
+            let n = curr as i64 + offset.parse::<i64>().ok()?;
+            if n < 0 {
+                None
+            } else {
+                Some(n as usize)
+            }
+        }
+        None => match s.parse::<usize>() {
+            Ok(n) if n >= 1 => Some(n - 1),
+            _ => None,
+        },
+    }
+}
+
+fn check_bounds(row: Option<usize>, col: Option<usize>) -> Option<(Option<usize>, Option<usize>)> {
+    if row.is_some_and(|r| r >= MAX_ROWS) || col.is_some_and(|c| c >= MAX_COLS) {
+        None
+    } else {
+        Some((row, col))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_a1_part, parse_r1c1_part};
+    use logisheets_base::Addr;
+
+    #[test]
+    fn parse_part_test() {
+        assert_eq!(parse_a1_part("$B$3"), Some((Some(2), Some(1))));
+        assert_eq!(parse_a1_part("aa"), Some((None, Some(26))));
+        assert_eq!(parse_a1_part("12"), Some((Some(11), None)));
+        assert_eq!(parse_a1_part("A0"), None);
+        assert_eq!(parse_a1_part("1A"), None);
+        let curr = Addr { row: 4, col: 4 };
+        assert_eq!(parse_r1c1_part("R2C3", &curr), Some((Some(1), Some(2))));
+        assert_eq!(parse_r1c1_part("R[-1]C", &curr), Some((Some(3), Some(4))));
+        assert_eq!(parse_r1c1_part("C[2]", &curr), Some((None, Some(6))));
+        assert_eq!(parse_r1c1_part("R[-5]C1", &curr), None);
+    }
+}
@@ -0,0 +1,88 @@
+use super::super::{CalcValue, CalcVertex, Value};
+use super::matching::{find, find_sorted, get_col, get_matrix, get_row, get_vector};
+use super::matching::{MatchMode, SearchMode};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+pub fn calc_lookup<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2 || args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let lookup = match fetcher.get_calc_value(args_iter.next().unwrap()) {
+        CalcValue::Scalar(Value::Error(e)) => return CalcVertex::from_error(e),
+        CalcValue::Scalar(v) => v,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let lookup_array = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let (keys, results) = match args_iter.next() {
+        Some(arg) => {
+            let result_array = match get_matrix(fetcher.get_calc_value(arg)) {
+                Ok(m) => m,
+                Err(e) => return CalcVertex::from_error(e),
+            };
+            match (get_vector(&lookup_array), get_vector(&result_array)) {
+                (Some(keys), Some(results)) => (keys, results),
+                _ => return CalcVertex::from_error(ast::Error::Na),
+            }
+        }
+        // The array form searches in the longer side and returns from the
+        // last row or column.
+        None => {
+            let (rows, cols) = lookup_array.get_size();
+            if cols > rows {
+                (get_row(&lookup_array, 0), get_row(&lookup_array, rows - 1))
+            } else {
+                (get_col(&lookup_array, 0), get_col(&lookup_array, cols - 1))
+            }
+        }
+    };
+    match find_sorted(&lookup, &keys, false).and_then(|p| results.get(p)) {
+        Some(v) => CalcVertex::Value(CalcValue::Scalar(v.clone())),
+        None => CalcVertex::from_error(ast::Error::Na),
+    }
+}
+
+pub fn calc_match<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2 || args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let lookup = match fetcher.get_calc_value(args_iter.next().unwrap()) {
+        CalcValue::Scalar(Value::Error(e)) => return CalcVertex::from_error(e),
+        CalcValue::Scalar(v) => v,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let lookup_array = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let match_type =
+        fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(1.)));
+    assert_f64_from_calc_value!(match_type, match_type);
+    let values = match get_vector(&lookup_array) {
+        Some(v) => v,
+        None => return CalcVertex::from_error(ast::Error::Na),
+    };
+    let pos = if match_type > 0. {
+        find_sorted(&lookup, &values, false)
+    } else if match_type < 0. {
+        find_sorted(&lookup, &values, true)
+    } else {
+        find(
+            &lookup,
+            &values,
+            MatchMode::Wildcard,
+            SearchMode::FirstToLast,
+        )
+    };
+    match pos {
+        Some(p) => CalcVertex::from_number((p + 1) as f64),
+        None => CalcVertex::from_error(ast::Error::Na),
+    }
+}
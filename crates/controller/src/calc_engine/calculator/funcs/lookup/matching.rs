@@ -0,0 +1,170 @@
+use std::cmp::Ordering;
+
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+
+use super::super::condition::match_text_pattern;
+use super::super::{CalcValue, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchMode {
+    Exact,
+    ExactOrSmaller,
+    ExactOrLarger,
+    Wildcard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    FirstToLast,
+    LastToFirst,
+}
+
+pub fn get_matrix(value: CalcValue) -> Result<MatrixValue<Value>, ast::Error> {
+    match value {
+        CalcValue::Scalar(Value::Error(e)) => Err(e),
+        CalcValue::Scalar(v) => Ok(MatrixValue::from(vec![vec![v]])),
+        CalcValue::Range(m) => Ok(m),
+        CalcValue::Cube(_) => Err(ast::Error::Ref),
+        CalcValue::Union(_) => Err(ast::Error::Value),
+    }
+}
+
+pub fn get_value(matrix: &MatrixValue<Value>, i: usize, j: usize) -> Value {
+    match matrix.visit(i, j) {
+        Ok(v) => v.clone(),
+        Err(v) => v,
+    }
+}
+
+pub fn get_row(matrix: &MatrixValue<Value>, i: usize) -> Vec<Value> {
+    let (_, cols) = matrix.get_size();
+    (0..cols).map(|j| get_value(matrix, i, j)).collect()
+}
+
+pub fn get_col(matrix: &MatrixValue<Value>, j: usize) -> Vec<Value> {
+    let (rows, _) = matrix.get_size();
+    (0..rows).map(|i| get_value(matrix, i, j)).collect()
+}
+
+// The values of a single row or a single column.
+pub fn get_vector(matrix: &MatrixValue<Value>) -> Option<Vec<Value>> {
+    match matrix.get_size() {
+        (1, _) => Some(get_row(matrix, 0)),
+        (_, 1) => Some(get_col(matrix, 0)),
+        _ => None,
+    }
+}
+
+// Only values of the same type are comparable in lookups, and texts are
+// compared case-insensitively.
+pub fn compare_lookup(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Number(l), Value::Number(r)) => l.partial_cmp(r),
+        (Value::Text(l), Value::Text(r)) => Some(l.to_lowercase().cmp(&r.to_lowercase())),
+        (Value::Boolean(l), Value::Boolean(r)) => Some(l.cmp(r)),
+        (Value::Blank, Value::Blank) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+fn is_match(lookup: &Value, value: &Value, wildcard: bool) -> bool {
+    match (lookup, value, wildcard) {
+        (Value::Text(p), Value::Text(t), true) => match_text_pattern(p, t),
+        _ => compare_lookup(value, lookup) == Some(Ordering::Equal),
+    }
+}
+
+pub fn find(
+    lookup: &Value,
+    values: &[Value],
+    match_mode: MatchMode,
+    search_mode: SearchMode,
+) -> Option<usize> {
+    let indices: Box<dyn Iterator<Item = usize>> = match search_mode {
+        SearchMode::FirstToLast => Box::new(0..values.len()),
+        SearchMode::LastToFirst => Box::new((0..values.len()).rev()),
+    };
+    let wildcard = match_mode == MatchMode::Wildcard;
+    let mut best: Option<usize> = None;
+    for i in indices {
+        let value = &values[i];
+        if is_match(lookup, value, wildcard) {
+            return Some(i);
+        }
+        let better = match (match_mode, compare_lookup(value, lookup)) {
+            (MatchMode::ExactOrSmaller, Some(Ordering::Less)) => {
+                best.is_none_or(|b| compare_lookup(value, &values[b]) == Some(Ordering::Greater))
+            }
+            (MatchMode::ExactOrLarger, Some(Ordering::Greater)) => {
+                best.is_none_or(|b| compare_lookup(value, &values[b]) == Some(Ordering::Less))
+            }
+            _ => false,
+        };
+        if better {
+            best = Some(i);
+        }
+    }
+    best
+}
+
+// Approximate match in sorted values, returning the position of the last value
+// not passing the lookup value. Values of other types are skipped.
+pub fn find_sorted(lookup: &Value, values: &[Value], descending: bool) -> Option<usize> {
+    let mut result = None;
+    for (i, value) in values.iter().enumerate() {
+        match (compare_lookup(value, lookup), descending) {
+            (None, _) => continue,
+            (Some(Ordering::Equal), _) => result = Some(i),
+            (Some(Ordering::Less), false) | (Some(Ordering::Greater), true) => result = Some(i),
+            _ => break,
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, find_sorted, MatchMode, SearchMode};
+    use crate::calc_engine::calculator::calc_vertex::Value;
+
+    #[test]
+    fn find_test() {
+        let values = vec![
+            Value::Number(1.),
+            Value::Number(3.),
+            Value::Text(String::from("x")),
+            Value::Number(5.),
+            Value::Number(3.),
+        ];
+        let find_num = |n: f64, m: MatchMode, s: SearchMode| find(&Value::Number(n), &values, m, s);
+        assert_eq!(
+            find_num(3., MatchMode::Exact, SearchMode::FirstToLast),
+            Some(1)
+        );
+        assert_eq!(
+            find_num(3., MatchMode::Exact, SearchMode::LastToFirst),
+            Some(4)
+        );
+        assert_eq!(
+            find_num(4., MatchMode::Exact, SearchMode::FirstToLast),
+            None
+        );
+        let smaller = find_num(4., MatchMode::ExactOrSmaller, SearchMode::FirstToLast);
+        assert_eq!(smaller, Some(1));
+        let larger = find_num(4., MatchMode::ExactOrLarger, SearchMode::FirstToLast);
+        assert_eq!(larger, Some(3));
+        let text = find(
+            &Value::Text(String::from("X*")),
+            &values,
+            MatchMode::Wildcard,
+            SearchMode::FirstToLast,
+        );
+        assert_eq!(text, Some(2));
+        assert_eq!(
+            find_sorted(&Value::Number(4.), &values[..2], false),
+            Some(1)
+        );
+        assert_eq!(find_sorted(&Value::Number(0.), &values[..2], false), None);
+    }
+}
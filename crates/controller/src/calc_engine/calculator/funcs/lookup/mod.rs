@@ -0,0 +1,123 @@
+pub mod address;
+pub mod choose;
+pub mod hvlookup;
+pub mod indirect;
+pub mod lookupmatch;
+pub mod matching;
+pub mod offset;
+pub mod rowcol;
+pub mod xlookup;
+
+pub const MAX_ROWS: usize = 1_048_576;
+pub const MAX_COLS: usize = 16_384;
+
+use super::super::calc_vertex::{ColRange, Range, Reference, RowRange};
+use logisheets_base::Addr;
+
+// The top-left position and the size of a reference.
+pub fn get_bounds(reference: &Reference) -> (usize, usize, usize, usize) {
+    match reference {
+        Reference::Addr(a) => (a.row, a.col, 1, 1),
+        Reference::ColumnRange(cr) => (0, cr.start, MAX_ROWS, cr.end - cr.start + 1),
+        Reference::RowRange(rr) => (rr.start, 0, rr.end - rr.start + 1, MAX_COLS),
+        Reference::Range(r) => (
+            r.start.row,
+            r.start.col,
+            r.end.row - r.start.row + 1,
+            r.end.col - r.start.col + 1,
+        ),
+    }
+}
+
+pub fn from_bounds(top: usize, left: usize, height: usize, width: usize) -> Reference {
+    if height == 1 && width == 1 {
+        Reference::Addr(Addr {
+            row: top,
+            col: left,
+        })
+    } else if height == MAX_ROWS {
+        Reference::ColumnRange(ColRange {
+            start: left,
+            end: left + width - 1,
+        })
+    } else if width == MAX_COLS {
+        Reference::RowRange(RowRange {
+            start: top,
+            end: top + height - 1,
+        })
+    } else {
+        Reference::Range(Range {
+            start: Addr {
+                row: top,
+                col: left,
+            },
+            end: Addr {
+                row: top + height - 1,
+                col: left + width - 1,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    #[test]
+    fn lookup_functions_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "10"),
+                input(0, 1, 0, "20"),
+                input(0, 2, 0, "30"),
+                input(0, 0, 1, "a"),
+                input(0, 1, 1, "b"),
+                input(0, 2, 1, "c"),
+            ],
+        );
+        let cases = [
+            ("=MATCH(20, A1:A3, 0)", "2"),
+            ("=MATCH(25, A1:A3)", "2"),
+            ("=MATCH(\"B*\", B1:B3, 0)", "2"),
+            ("=VLOOKUP(25, A1:B3, 2)", "b"),
+            ("=VLOOKUP(30, A1:B3, 2, FALSE)", "c"),
+            ("=HLOOKUP(2, {1,2,3;\"x\",\"y\",\"z\"}, 2, FALSE)", "y"),
+            ("=LOOKUP(22, A1:A3, B1:B3)", "b"),
+            ("=XLOOKUP(\"C\", B1:B3, A1:A3)", "30"),
+            ("=XLOOKUP(25, A1:A3, B1:B3, \"none\", 1)", "c"),
+            ("=XLOOKUP(5, A1:A3, B1:B3, \"none\")", "none"),
+            ("=XMATCH(\"b*\", B1:B3, 2)", "2"),
+            ("=CHOOSE(2, \"x\", \"y\")", "y"),
+            ("=SUM(CHOOSE(1, A1:A3, B1:B3))", "60"),
+            ("=COLUMN(C5)+ROW(C5)*10", "53"),
+            ("=ROWS(A1:B3)*10+COLUMNS(A1:B3)", "32"),
+            ("=ADDRESS(2, 3)", "$C$2"),
+            ("=ADDRESS(2, 3, 4, TRUE, \"My Sheet\")", "'My Sheet'!C2"),
+            ("=ADDRESS(2, 3, 1, FALSE)", "R2C3"),
+            ("=SUM(OFFSET(A1, 1, 0, 2, 1))", "50"),
+            ("=INDIRECT(\"A2\")", "20"),
+            ("=SUM(INDIRECT(\"Sheet1!A1:A3\"))", "60"),
+            ("=INDIRECT(\"R3C1\", FALSE)", "30"),
+            ("=INDIRECT(\"Sheet9!A1\")", "#REF!"),
+        ];
+        let payloads = cases
+            .iter()
+            .enumerate()
+            .map(|(i, (f, _))| input(0, i, 4, f))
+            .collect();
+        edit(&mut wb, payloads);
+        cases.iter().enumerate().for_each(|(i, (f, e))| {
+            assert_eq!(value(&mut wb, 0, i, 4), *e, "{}", f);
+        });
+
+        // The cells found by OFFSET and INDIRECT are dependencies too.
+        edit(&mut wb, vec![input(0, 1, 0, "25"), input(0, 2, 0, "40")]);
+        assert_eq!(value(&mut wb, 0, 18, 4), "65");
+        assert_eq!(value(&mut wb, 0, 19, 4), "25");
+        assert_eq!(value(&mut wb, 0, 20, 4), "75");
+        assert_eq!(value(&mut wb, 0, 21, 4), "40");
+    }
+}
@@ -0,0 +1,60 @@
+use super::super::super::calc_vertex::CalcReference;
+use super::super::utils::is_omitted;
+use super::super::{CalcValue, CalcVertex, Value};
+use super::{from_bounds, get_bounds, MAX_COLS, MAX_ROWS};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 3 && args.len() <= 5, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let reference = match args_iter.next().unwrap() {
+        CalcVertex::Reference(r) if r.from_sheet.is_none() => r,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let (top, left, height, width) = get_bounds(&reference.reference);
+    let rows_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(rows, rows_arg);
+    let cols_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(cols, cols_arg);
+    let height_arg = match args_iter.next() {
+        Some(arg) if !is_omitted(&arg) => arg,
+        _ => CalcVertex::from_number(height as f64),
+    };
+    assert_f64_from_calc_value!(height, fetcher.get_calc_value(height_arg));
+    let width_arg = match args_iter.next() {
+        Some(arg) if !is_omitted(&arg) => arg,
+        _ => CalcVertex::from_number(width as f64),
+    };
+    assert_f64_from_calc_value!(width, fetcher.get_calc_value(width_arg));
+    let (height, width) = (height.trunc(), width.trunc());
+    assert_or_return!(height != 0. && width != 0., ast::Error::Ref);
+    let mut top = top as f64 + rows.trunc();
+    let mut left = left as f64 + cols.trunc();
+    // A negative height or width extends the area upwards or leftwards from
+    // the offset cell.
+    if height < 0. {
+        top += height + 1.;
+    }
+    if width < 0. {
+        left += width + 1.;
+    }
+    let (height, width) = (height.abs(), width.abs());
+    assert_or_return!(top >= 0. && left >= 0., ast::Error::Ref);
+    assert_or_return!(
+        top + height <= MAX_ROWS as f64 && left + width <= MAX_COLS as f64,
+        ast::Error::Ref
+    );
+    let (top, left) = (top as usize, left as usize);
+    let (height, width) = (height as usize, width as usize);
+    let result = CalcReference {
+        from_sheet: None,
+        sheet: reference.sheet,
+        reference: from_bounds(top, left, height, width),
+    };
+    fetcher.add_dynamic_dep(&result);
+    CalcVertex::Reference(result)
+}
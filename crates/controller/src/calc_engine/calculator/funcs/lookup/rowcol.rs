@@ -0,0 +1,94 @@
+use super::super::{CalcValue, CalcVertex, Value};
+use super::{get_bounds, MAX_COLS, MAX_ROWS};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+
+pub fn calc_row<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() <= 1, ast::Error::Unspecified);
+    match args.into_iter().next() {
+        Some(CalcVertex::Reference(r)) => {
+            let (row, _, height, _) = get_bounds(&r.reference);
+            // Whole columns only give the first row.
+            let height = if height == MAX_ROWS { 1 } else { height };
+            let rows = (row..row + height)
+                .map(|r| vec![Value::Number((r + 1) as f64)])
+                .collect::<Vec<_>>();
+            from_matrix(rows)
+        }
+        Some(_) => CalcVertex::from_error(ast::Error::Value),
+        None => CalcVertex::from_number(fetcher.get_curr_addr().row as f64 + 1_f64),
+    }
+}
+
+pub fn calc_column<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() <= 1, ast::Error::Unspecified);
+    match args.into_iter().next() {
+        Some(CalcVertex::Reference(r)) => {
+            let (_, col, _, width) = get_bounds(&r.reference);
+            let width = if width == MAX_COLS { 1 } else { width };
+            let cols = (col..col + width)
+                .map(|c| Value::Number((c + 1) as f64))
+                .collect::<Vec<_>>();
+            from_matrix(vec![cols])
+        }
+        Some(_) => CalcVertex::from_error(ast::Error::Value),
+        None => CalcVertex::from_number(fetcher.get_curr_addr().col as f64 + 1_f64),
+    }
+}
+
+pub fn calc_rows<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    match get_size(args, fetcher) {
+        Ok((rows, _)) => CalcVertex::from_number(rows as f64),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+pub fn calc_columns<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    match get_size(args, fetcher) {
+        Ok((_, cols)) => CalcVertex::from_number(cols as f64),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+fn get_size<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> Result<(usize, usize), ast::Error>
+where
+    C: Connector,
+{
+    if args.len() != 1 {
+        return Err(ast::Error::Unspecified);
+    }
+    match args.into_iter().next().unwrap() {
+        CalcVertex::Reference(r) => {
+            let (_, _, height, width) = get_bounds(&r.reference);
+            Ok((height, width))
+        }
+        v => match fetcher.get_calc_value(v) {
+            CalcValue::Scalar(Value::Error(e)) => Err(e),
+            CalcValue::Scalar(_) => Ok((1, 1)),
+            CalcValue::Range(m) => Ok(m.get_size()),
+            _ => Err(ast::Error::Value),
+        },
+    }
+}
+
+fn from_matrix(data: Vec<Vec<Value>>) -> CalcVertex {
+    if data.len() == 1 && data[0].len() == 1 {
+        let v = data.into_iter().next().unwrap().into_iter().next().unwrap();
+        CalcVertex::Value(CalcValue::Scalar(v))
+    } else {
+        CalcVertex::Value(CalcValue::Range(MatrixValue::from(data)))
+    }
+}
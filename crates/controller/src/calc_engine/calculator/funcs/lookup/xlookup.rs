@@ -0,0 +1,139 @@
+use logisheets_base::matrix_value::MatrixValue;
+
+use super::super::utils::is_omitted;
+use super::super::{CalcValue, CalcVertex, Value};
+use super::matching::{find, get_col, get_matrix, get_row, get_vector};
+use super::matching::{MatchMode, SearchMode};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+pub fn calc_xlookup<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 3 && args.len() <= 6, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let lookup = match fetcher.get_calc_value(args_iter.next().unwrap()) {
+        CalcValue::Scalar(Value::Error(e)) => return CalcVertex::from_error(e),
+        CalcValue::Scalar(v) => v,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let lookup_array = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let return_array = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let if_not_found = args_iter.next().filter(|arg| !is_omitted(arg));
+    let (match_mode, search_mode) = match get_modes(args_iter, fetcher) {
+        Ok(modes) => modes,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let values = match get_vector(&lookup_array) {
+        Some(v) => v,
+        None => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let (rows, cols) = return_array.get_size();
+    // A vertical lookup array picks a row from the return array, and a
+    // horizontal one picks a column.
+    let vertical = lookup_array.get_size().1 == 1;
+    if vertical {
+        assert_or_return!(rows == values.len(), ast::Error::Value);
+    } else {
+        assert_or_return!(cols == values.len(), ast::Error::Value);
+    }
+    match find(&lookup, &values, match_mode, search_mode) {
+        Some(p) => {
+            let result = if vertical {
+                vec![get_row(&return_array, p)]
+            } else {
+                get_col(&return_array, p)
+                    .into_iter()
+                    .map(|v| vec![v])
+                    .collect()
+            };
+            if result.len() == 1 && result[0].len() == 1 {
+                let v = result
+                    .into_iter()
+                    .next()
+                    .unwrap()
+                    .into_iter()
+                    .next()
+                    .unwrap();
+                CalcVertex::Value(CalcValue::Scalar(v))
+            } else {
+                CalcVertex::Value(CalcValue::Range(MatrixValue::from(result)))
+            }
+        }
+        None => match if_not_found {
+            Some(v) => CalcVertex::Value(fetcher.get_calc_value(v)),
+            None => CalcVertex::from_error(ast::Error::Na),
+        },
+    }
+}
+
+pub fn calc_xmatch<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 2 && args.len() <= 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let lookup = match fetcher.get_calc_value(args_iter.next().unwrap()) {
+        CalcValue::Scalar(Value::Error(e)) => return CalcVertex::from_error(e),
+        CalcValue::Scalar(v) => v,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let lookup_array = match get_matrix(fetcher.get_calc_value(args_iter.next().unwrap())) {
+        Ok(m) => m,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let (match_mode, search_mode) = match get_modes(args_iter, fetcher) {
+        Ok(modes) => modes,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let values = match get_vector(&lookup_array) {
+        Some(v) => v,
+        None => return CalcVertex::from_error(ast::Error::Value),
+    };
+    match find(&lookup, &values, match_mode, search_mode) {
+        Some(p) => CalcVertex::from_number((p + 1) as f64),
+        None => CalcVertex::from_error(ast::Error::Na),
+    }
+}
+
+// The binary search modes give the same results as the linear ones on sorted
+// data, so they are searched linearly too.
+fn get_modes<C, I>(args: I, fetcher: &mut C) -> Result<(MatchMode, SearchMode), ast::Error>
+where
+    C: Connector,
+    I: Iterator<Item = CalcVertex>,
+{
+    // An omitted mode is None and takes its default.
+    let mut nums = args.map(|arg| {
+        if is_omitted(&arg) {
+            return Ok(None);
+        }
+        match fetcher.get_calc_value(arg) {
+            CalcValue::Scalar(Value::Number(n)) => Ok(Some(n.trunc() as i64)),
+            CalcValue::Scalar(Value::Boolean(b)) => Ok(Some(b as i64)),
+            CalcValue::Scalar(Value::Blank) => Ok(Some(0)),
+            CalcValue::Scalar(Value::Error(e)) => Err(e),
+            _ => Err(ast::Error::Value),
+        }
+    });
+    let match_mode = match nums.next().unwrap_or(Ok(None))?.unwrap_or(0) {
+        0 => MatchMode::Exact,
+        -1 => MatchMode::ExactOrSmaller,
+        1 => MatchMode::ExactOrLarger,
+        2 => MatchMode::Wildcard,
+        _ => return Err(ast::Error::Value),
+    };
+    let search_mode = match nums.next().unwrap_or(Ok(None))?.unwrap_or(1) {
+        1 | 2 | -2 => SearchMode::FirstToLast,
+        -1 => SearchMode::LastToFirst,
+        _ => return Err(ast::Error::Value),
+    };
+    Ok((match_mode, search_mode))
+}
@@ -0,0 +1,37 @@
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(text, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(start, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(n, third);
+    assert_or_return!(start >= 1. && n >= 0., ast::Error::Value);
+    CalcVertex::from_string(mid(&text, start as usize, n as usize))
+}
+
+fn mid(text: &str, start: usize, n: usize) -> String {
+    text.graphemes(true).skip(start - 1).take(n).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mid;
+
+    #[test]
+    fn mid_test() {
+        assert_eq!(mid("Fluid Flow", 1, 5), "Fluid");
+        assert_eq!(mid("Fluid Flow", 7, 20), "Flow");
+        assert_eq!(mid("Fluid Flow", 20, 5), "");
+        assert_eq!(mid("e\u{301}te\u{301}", 2, 2), "te\u{301}");
+    }
+}
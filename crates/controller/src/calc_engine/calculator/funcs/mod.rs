@@ -5,16 +5,19 @@ mod asyncs;
 mod average;
 mod bits;
 mod boolean;
+mod charcode;
 mod complex;
 mod concatenate;
 mod condition;
+mod conditional;
 mod count;
-mod countif;
 mod datetime;
 mod delta;
 mod distribution;
 mod effect;
 mod exact;
+mod find;
+mod fixed;
 mod fvpv;
 mod gcdlcm;
 mod gestep;
@@ -27,6 +30,8 @@ mod irr;
 mod iserr;
 mod leftright;
 mod len;
+pub mod lookup;
+mod mid;
 mod na;
 mod npv;
 mod permutation;
@@ -35,20 +40,29 @@ mod pmt;
 mod quotient;
 mod rand;
 mod rank;
+mod replace;
 mod rept;
-mod row;
 mod scalar_number;
 mod scalar_text;
+mod sequence;
 mod sum;
 mod switch;
+mod text;
+mod textsplit;
+mod tn;
 mod utils;
-mod vlookup;
+mod value;
 
+use logisheets_astchecker::get_func_signature;
 use logisheets_parser::ast;
 
 use super::calc_vertex::{CalcValue, CalcVertex, Value};
 use crate::calc_engine::connector::Connector;
 
+pub fn is_volatile(name: &str) -> bool {
+    get_func_signature(name).is_some_and(|sign| sign.volatile)
+}
+
 pub fn function_calculate<C>(name: &str, args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
 where
     C: Connector,
@@ -56,34 +70,63 @@ where
     if fetcher.is_async_func(name) {
         return asyncs::calc(name, args, fetcher);
     }
-    match name.to_uppercase().as_str() {
+    let sign = match get_func_signature(name) {
+        Some(sign) => sign,
+        None => return CalcVertex::from_error(ast::Error::Name),
+    };
+    // The formulas from the inputs are checked against the signatures before
+    // they are added, but the loaded ones are not and they give #VALUE!.
+    assert_or_return!(sign.arg_count.check(args.len()), ast::Error::Value);
+    match sign.name.as_str() {
         "ABS" => scalar_number::calc_abs(args, fetcher),
         "ACOS" => scalar_number::calc_acos(args, fetcher),
         "ACOSH" => scalar_number::calc_acosh(args, fetcher),
+        "ADDRESS" => lookup::address::calc(args, fetcher),
         "AND" => and::calc(args, fetcher),
         "ASIN" => scalar_number::calc_asin(args, fetcher),
         "ASINH" => scalar_number::calc_asinh(args, fetcher),
         "ATAN" => scalar_number::calc_atan(args, fetcher),
         "ATANH" => scalar_number::calc_atanh(args, fetcher),
+        "AVEDEV" => distribution::statistics::calc_avedev(args, fetcher),
         "AVERAGE" => average::calc_average(args, fetcher),
+        "AVERAGEIF" => conditional::calc_averageif(args, fetcher),
+        "AVERAGEIFS" => conditional::calc_averageifs(args, fetcher),
         "BIN2DEC" => bits::hob2dec::calc_bin2dec(args, fetcher),
         "BIN2HEX" => bits::hob2hob::calc_bin2hex(args, fetcher),
         "BIN2OCT" => bits::hob2hob::calc_bin2oct(args, fetcher),
+        "BINOM.DIST" => distribution::binomdist::calc_binomdist(args, fetcher),
+        "BINOM.DIST.RANGE" => distribution::binomdist::calc_binomdist_range(args, fetcher),
+        "BINOM.INV" => distribution::binomdist::calc_binom_inv(args, fetcher),
+        "BINOMDIST" => distribution::binomdist::calc_binomdist(args, fetcher),
         "BITAND" => bits::bit::calc_bitand(args, fetcher),
         "BITLSHIFT" => bits::bit::calc_bitlshift(args, fetcher),
         "BITOR" => bits::bit::calc_bitor(args, fetcher),
         "BITRSHIFT" => bits::bit::calc_bitrshift(args, fetcher),
         "BITXOR" => bits::bit::calc_bitxor(args, fetcher),
+        "CHAR" => charcode::calc_char(args, fetcher),
         "CHISQ.DIST" => distribution::chisqdist::calc_chisqdist(args, fetcher),
         "CHISQ.DIST.RT" => distribution::chisqdist::calc_chisqdist_rt(args, fetcher),
+        "CHOOSE" => lookup::choose::calc(args, fetcher),
+        "CLEAN" => scalar_text::calc_clean(args, fetcher),
+        "CODE" => charcode::calc_code(args, fetcher),
+        "COLUMN" => lookup::rowcol::calc_column(args, fetcher),
+        "COLUMNS" => lookup::rowcol::calc_columns(args, fetcher),
         "COMBINE" => permutation::calc_combine(args, fetcher),
         "COMPLEX" => complex::calc(args, fetcher),
+        "CONCAT" => concatenate::calc_concat(args, fetcher),
         "CONCATENATE" => concatenate::calc(args, fetcher),
+        "CORREL" => distribution::regression::calc_correl(args, fetcher),
         "COS" => scalar_number::calc_cos(args, fetcher),
         "COT" => scalar_number::calc_cot(args, fetcher),
         "COTH" => scalar_number::calc_coth(args, fetcher),
         "COUNT" => count::calc(args, fetcher),
-        "COUNTIF" => countif::calc(args, fetcher),
+        "COUNTBLANK" => count::calc_countblank(args, fetcher),
+        "COUNTIF" => conditional::calc_countif(args, fetcher),
+        "COUNTIFS" => conditional::calc_countifs(args, fetcher),
+        "COVAR" => distribution::regression::calc_covariance_p(args, fetcher),
+        "COVARIANCE.P" => distribution::regression::calc_covariance_p(args, fetcher),
+        "COVARIANCE.S" => distribution::regression::calc_covariance_s(args, fetcher),
+        "CRITBINOM" => distribution::binomdist::calc_binom_inv(args, fetcher),
         "CSC" => scalar_number::calc_csc(args, fetcher),
         "DATE" => datetime::date::calc(args, fetcher),
         "DAY" => datetime::ymd::calc_day(args, fetcher),
@@ -93,15 +136,30 @@ where
         "DEC2OCT" => bits::dec2hob::calc_dec2oct(args, fetcher),
         "DEGREES" => scalar_number::calc_degrees(args, fetcher),
         "DELTA" => delta::calc(args, fetcher),
+        "DEVSQ" => distribution::statistics::calc_devsq(args, fetcher),
+        "DOLLAR" => fixed::calc_dollar(args, fetcher),
         "EDATE" => datetime::edate::calc(args, fetcher),
         "EFFECT" => effect::calc(args, fetcher),
         "EOMONTH" => datetime::eomonth::calc(args, fetcher),
         "EVEN" => scalar_number::calc_even(args, fetcher),
         "EXACT" => exact::calc(args, fetcher),
         "EXP" => scalar_number::calc_exp(args, fetcher),
+        "EXPON.DIST" => distribution::expondist::calc_expondist(args, fetcher),
+        "EXPONDIST" => distribution::expondist::calc_expondist(args, fetcher),
+        "F.DIST" => distribution::fdist::calc_fdist(args, fetcher),
+        "F.DIST.RT" => distribution::fdist::calc_fdist_rt(args, fetcher),
+        "F.INV" => distribution::fdist::calc_finv(args, fetcher),
+        "F.INV.RT" => distribution::fdist::calc_finv_rt(args, fetcher),
         "FACT" => scalar_number::calc_fact(args, fetcher),
         "FACTDOUBLE" => scalar_number::calc_factdouble(args, fetcher),
         "FALSE" => boolean::calc_false(args),
+        "FDIST" => distribution::fdist::calc_fdist_rt(args, fetcher),
+        "FIND" => find::calc_find(args, fetcher),
+        "FINDB" => find::calc_findb(args, fetcher),
+        "FINV" => distribution::fdist::calc_finv_rt(args, fetcher),
+        "FIXED" => fixed::calc_fixed(args, fetcher),
+        "FORECAST" => distribution::regression::calc_forecast_linear(args, fetcher),
+        "FORECAST.LINEAR" => distribution::regression::calc_forecast_linear(args, fetcher),
         "FV" => fvpv::fv(args, fetcher),
         "GAMMA" => scalar_number::calc_gamma(args, fetcher),
         "GAMMALN" => scalar_number::calc_gammaln(args, fetcher),
@@ -115,6 +173,7 @@ where
         "HEX2BIN" => bits::hob2hob::calc_hex2bin(args, fetcher),
         "HEX2DEC" => bits::hob2dec::calc_hex2dec(args, fetcher),
         "HEX2OCT" => bits::hob2hob::calc_hex2oct(args, fetcher),
+        "HLOOKUP" => lookup::hvlookup::calc_hlookup(args, fetcher),
         "HOUR" => datetime::hms::calc_hour(args, fetcher),
         "IF" => if_plugin::calc(args, fetcher),
         "IFERROR" => iferror::calc(args, fetcher),
@@ -137,20 +196,42 @@ where
         "IMTAN" => im::calc_imtan(args, fetcher),
         "IMTANH" => im::calc_imtanh(args, fetcher),
         "INDEX" => index::calc(args, fetcher),
+        "INDIRECT" => lookup::indirect::calc(args, fetcher),
+        "INTERCEPT" => distribution::regression::calc_intercept(args, fetcher),
         "IPMT" => pmt::ipmt(args, fetcher),
         "IRR" => irr::calc(args, fetcher),
         "ISERR" => iserr::calc(args, fetcher, iserr::IsErrType::ExceptNa),
         "ISERROR" => iserr::calc(args, fetcher, iserr::IsErrType::All),
         "ISNA" => iserr::calc(args, fetcher, iserr::IsErrType::Na),
+        "LARGE" => distribution::order::calc_large(args, fetcher),
         "LCM" => gcdlcm::calc_lcm(args, fetcher),
         "LEFT" => leftright::calc_left(args, fetcher),
         "LEN" => len::calc_len(args, fetcher),
         "LENB" => len::calc_lenb(args, fetcher),
+        "LINEST" => distribution::regression::calc_linest(args, fetcher),
         "LN" => scalar_number::calc_ln(args, fetcher),
         "LOG10" => scalar_number::calc_log10(args, fetcher),
+        "LOGINV" => distribution::lognormdist::calc_lognorm_inv(args, fetcher),
+        "LOGNORM.DIST" => distribution::lognormdist::calc_lognormdist(args, fetcher),
+        "LOGNORM.INV" => distribution::lognormdist::calc_lognorm_inv(args, fetcher),
+        "LOGNORMDIST" => distribution::lognormdist::calc_lognormdist_legacy(args, fetcher),
+        "LOOKUP" => lookup::lookupmatch::calc_lookup(args, fetcher),
         "LOWER" => scalar_text::calc_lower(args, fetcher),
+        "MATCH" => lookup::lookupmatch::calc_match(args, fetcher),
+        "MAX" => distribution::order::calc_max(args, fetcher),
+        "MAXA" => distribution::order::calc_maxa(args, fetcher),
+        "MAXIFS" => conditional::calc_maxifs(args, fetcher),
+        "MEDIAN" => distribution::order::calc_median(args, fetcher),
+        "MID" => mid::calc(args, fetcher),
+        "MIN" => distribution::order::calc_min(args, fetcher),
+        "MINA" => distribution::order::calc_mina(args, fetcher),
+        "MINIFS" => conditional::calc_minifs(args, fetcher),
         "MINUTE" => datetime::hms::calc_minute(args, fetcher),
+        "MODE" => distribution::order::calc_mode_sngl(args, fetcher),
+        "MODE.MULT" => distribution::order::calc_mode_mult(args, fetcher),
+        "MODE.SNGL" => distribution::order::calc_mode_sngl(args, fetcher),
         "MONTH" => datetime::ymd::calc_month(args, fetcher),
+        "N" => tn::calc_n(args, fetcher),
         "NA" => na::calc(args),
         "NORM.DIST" => distribution::normdist::calc_normdist(args, fetcher),
         "NORM.S.DIST" => scalar_number::calc_normsdist(args, fetcher),
@@ -159,44 +240,173 @@ where
         "NORMINV" => distribution::norminv::calc_norminv(args, fetcher),
         "NORMSDIST" => scalar_number::calc_normsdist(args, fetcher),
         "NORMSINV" => distribution::norminv::calc_normsinv(args, fetcher),
-        "NOW" => datetime::now::calc(args),
+        "NOW" => datetime::now::calc(args, fetcher),
         "NPV" => npv::calc(args, fetcher),
+        "NUMBERVALUE" => value::calc_numbervalue(args, fetcher),
         "OCT2BIN" => bits::hob2hob::calc_oct2bin(args, fetcher),
         "OCT2DEC" => bits::hob2dec::calc_oct2dec(args, fetcher),
         "OCT2HEX" => bits::hob2hob::calc_oct2hex(args, fetcher),
         "ODD" => scalar_number::calc_odd(args, fetcher),
+        "OFFSET" => lookup::offset::calc(args, fetcher),
+        "PEARSON" => distribution::regression::calc_correl(args, fetcher),
+        "PERCENTILE" => distribution::percentile::calc_percentile_inc(args, fetcher),
+        "PERCENTILE.EXC" => distribution::percentile::calc_percentile_exc(args, fetcher),
+        "PERCENTILE.INC" => distribution::percentile::calc_percentile_inc(args, fetcher),
+        "PERCENTRANK" => distribution::percentile::calc_percentrank_inc(args, fetcher),
+        "PERCENTRANK.EXC" => distribution::percentile::calc_percentrank_exc(args, fetcher),
+        "PERCENTRANK.INC" => distribution::percentile::calc_percentrank_inc(args, fetcher),
         "PERMUT" => permutation::calc_permut(args, fetcher),
         "PI" => pi::calc(args),
         "PMT" => pmt::pmt(args, fetcher),
+        "POISSON" => distribution::poissondist::calc_poissondist(args, fetcher),
+        "POISSON.DIST" => distribution::poissondist::calc_poissondist(args, fetcher),
         "PPMT" => pmt::ppmt(args, fetcher),
+        "PROPER" => scalar_text::calc_proper(args, fetcher),
         "PV" => fvpv::pv(args, fetcher),
+        "QUARTILE" => distribution::percentile::calc_quartile_inc(args, fetcher),
+        "QUARTILE.EXC" => distribution::percentile::calc_quartile_exc(args, fetcher),
+        "QUARTILE.INC" => distribution::percentile::calc_quartile_inc(args, fetcher),
         "QUOTIENT" => quotient::calc(args, fetcher),
         "RADIANS" => scalar_number::calc_radians(args, fetcher),
         "RAND" => rand::calc(args),
         "RANK" => rank::calc_rank(args, fetcher),
         "RANK.AVG" => rank::calc_rank_avg(args, fetcher),
         "RANK.EQ" => rank::calc_rank(args, fetcher),
+        "REPLACE" => replace::calc_replace(args, fetcher),
         "REPT" => rept::calc(args, fetcher),
         "RIGHT" => leftright::calc_right(args, fetcher),
-        "ROW" => row::calc(args, fetcher),
+        "ROW" => lookup::rowcol::calc_row(args, fetcher),
+        "ROWS" => lookup::rowcol::calc_rows(args, fetcher),
+        "RSQ" => distribution::regression::calc_rsq(args, fetcher),
+        "SEARCH" => find::calc_search(args, fetcher),
         "SECOND" => datetime::hms::calc_second(args, fetcher),
+        "SEQUENCE" => sequence::calc(args, fetcher),
         "SIGN" => scalar_number::calc_sign(args, fetcher),
         "SIN" => scalar_number::calc_sin(args, fetcher),
+        "SLOPE" => distribution::regression::calc_slope(args, fetcher),
+        "SMALL" => distribution::order::calc_small(args, fetcher),
         "SQRT" => scalar_number::calc_sqrt(args, fetcher),
         "SQRTPI" => scalar_number::calc_sqrtpi(args, fetcher),
         "STDEV" => distribution::statistics::calc_stdev(args, fetcher),
+        "STDEV.P" => distribution::statistics::calc_stdevp(args, fetcher),
+        "STDEV.S" => distribution::statistics::calc_stdev(args, fetcher),
+        "STDEVA" => distribution::statistics::calc_stdeva(args, fetcher),
+        "STDEVP" => distribution::statistics::calc_stdevp(args, fetcher),
+        "STDEVPA" => distribution::statistics::calc_stdevpa(args, fetcher),
+        "SUBSTITUTE" => replace::calc_substitute(args, fetcher),
         "SUM" => sum::calc(args, fetcher),
+        "SUMIF" => conditional::calc_sumif(args, fetcher),
+        "SUMIFS" => conditional::calc_sumifs(args, fetcher),
         "SWITCH" => switch::calc(args, fetcher),
+        "T" => tn::calc_t(args, fetcher),
+        "T.DIST" => distribution::tdist::calc_tdist(args, fetcher),
+        "T.DIST.2T" => distribution::tdist::calc_tdist_2t(args, fetcher),
+        "T.DIST.RT" => distribution::tdist::calc_tdist_rt(args, fetcher),
+        "T.INV" => distribution::tdist::calc_tinv(args, fetcher),
+        "T.INV.2T" => distribution::tdist::calc_tinv_2t(args, fetcher),
         "TAN" => scalar_number::calc_tan(args, fetcher),
         "TANH" => scalar_number::calc_tanh(args, fetcher),
+        "TDIST" => distribution::tdist::calc_tdist_legacy(args, fetcher),
+        "TEXT" => text::calc(args, fetcher),
+        "TEXTAFTER" => textsplit::calc_textafter(args, fetcher),
+        "TEXTBEFORE" => textsplit::calc_textbefore(args, fetcher),
+        "TEXTJOIN" => concatenate::calc_textjoin(args, fetcher),
+        "TEXTSPLIT" => textsplit::calc_textsplit(args, fetcher),
         "TIME" => datetime::time::calc(args, fetcher),
-        "TODAY" => datetime::today::calc(args),
+        "TINV" => distribution::tdist::calc_tinv_2t(args, fetcher),
+        "TODAY" => datetime::today::calc(args, fetcher),
+        "TREND" => distribution::regression::calc_trend(args, fetcher),
         "TRIM" => scalar_text::calc_trim(args, fetcher),
         "TRUE" => boolean::calc_true(args),
+        "UNICHAR" => charcode::calc_unichar(args, fetcher),
+        "UNICODE" => charcode::calc_unicode(args, fetcher),
         "UPPER" => scalar_text::calc_upper(args, fetcher),
+        "VALUE" => value::calc_value(args, fetcher),
         "VAR" => distribution::statistics::calc_var(args, fetcher),
+        "VAR.P" => distribution::statistics::calc_varp(args, fetcher),
         "VAR.S" => distribution::statistics::calc_var(args, fetcher),
+        "VARA" => distribution::statistics::calc_vara(args, fetcher),
+        "VARP" => distribution::statistics::calc_varp(args, fetcher),
+        "VARPA" => distribution::statistics::calc_varpa(args, fetcher),
+        "VLOOKUP" => lookup::hvlookup::calc_vlookup(args, fetcher),
+        "XLOOKUP" => lookup::xlookup::calc_xlookup(args, fetcher),
+        "XMATCH" => lookup::xlookup::calc_xmatch(args, fetcher),
         "YEAR" => datetime::ymd::calc_year(args, fetcher),
         _ => CalcVertex::from_error(ast::Error::Name),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::utils::tests_utils::TestFetcher;
+    use super::{function_calculate, CalcValue, CalcVertex, Value};
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+    use logisheets_parser::ast;
+
+    #[test]
+    fn arg_count_test() {
+        let mut fetcher = TestFetcher {};
+        let res = function_calculate("ABS", vec![], &mut fetcher);
+        assert!(matches!(
+            res,
+            CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Value)))
+        ));
+        let args = vec![CalcVertex::from_number(1.), CalcVertex::from_number(2.)];
+        let res = function_calculate("ABS", args, &mut fetcher);
+        assert!(matches!(
+            res,
+            CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Value)))
+        ));
+        let res = function_calculate("ABS", vec![CalcVertex::from_number(-1.)], &mut fetcher);
+        assert!(matches!(
+            res,
+            CalcVertex::Value(CalcValue::Scalar(Value::Number(n))) if n == 1.
+        ));
+    }
+
+    #[test]
+    fn text_functions_test() {
+        let cases = [
+            ("=MID(A1, 7, 20)", "Flow"),
+            ("=FIND(\"F\", A1, 2)", "7"),
+            ("=SEARCH(\"f?ow\", A1)", "7"),
+            ("=SUBSTITUTE(A1, \"Fl\", \"Gl\", 2)", "Fluid Glow"),
+            ("=REPLACE(A1, 1, 5, \"Air\")", "Air Flow"),
+            ("=PROPER(\"the CAT\")", "The Cat"),
+            ("=TEXT(1234.567, \"$#,##0.00\")", "$1,234.57"),
+            ("=TEXTJOIN(\"-\", TRUE, A1:A4)", "Fluid Flow-1,2;3-abc"),
+            ("=CONCAT(A3, 1, TRUE)", "abc1TRUE"),
+            ("=TEXTBEFORE(A1, \" \")", "Fluid"),
+            ("=TEXTAFTER(A1, \"l\", -1)", "ow"),
+            (
+                "=VALUE(\"$1,000\")+NUMBERVALUE(\"2,5\", \",\", \".\")",
+                "1002.5",
+            ),
+            ("=CODE(CHAR(65))+UNICODE(UNICHAR(8364))", "8429"),
+            ("=FIXED(1234.567, -1)", "1,230"),
+            ("=T(A3)&N(TRUE)", "abc1"),
+        ];
+        let payloads = vec![
+            input(0, 0, 0, "Fluid Flow"),
+            input(0, 1, 0, "1,2;3"),
+            input(0, 2, 0, "abc"),
+            input(0, 0, 6, "=TEXTSPLIT(A2, \",\", \";\")"),
+        ]
+        .into_iter()
+        .chain(
+            cases
+                .iter()
+                .enumerate()
+                .map(|(i, (f, _))| input(0, i, 4, f)),
+        )
+        .collect();
+        let mut wb = Workbook::default();
+        edit(&mut wb, payloads);
+        cases.iter().enumerate().for_each(|(i, (f, e))| {
+            assert_eq!(value(&mut wb, 0, i, 4), *e, "{}", f);
+        });
+        let split = [(0, 6), (0, 7), (1, 6), (1, 7)].map(|(r, c)| value(&mut wb, 0, r, c));
+        assert_eq!(split, ["1", "2", "3", "#N/A"]);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn calc_replace<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(old_text, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(start, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(n, third);
+    let fourth = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(new_text, fourth);
+    assert_or_return!(start >= 1. && n >= 0., ast::Error::Value);
+    let res = replace(&old_text, start as usize, n as usize, &new_text);
+    CalcVertex::from_string(res)
+}
+
+pub fn calc_substitute<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 3 || args.len() == 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(text, first);
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(old_text, second);
+    let third = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(new_text, third);
+    let instance = match args_iter.next() {
+        Some(arg) => {
+            let fourth = fetcher.get_calc_value(arg);
+            assert_f64_from_calc_value!(instance, fourth);
+            assert_or_return!(instance >= 1., ast::Error::Value);
+            Some(instance as usize)
+        }
+        None => None,
+    };
+    CalcVertex::from_string(substitute(&text, &old_text, &new_text, instance))
+}
+
+fn replace(old_text: &str, start: usize, n: usize, new_text: &str) -> String {
+    let graphemes = old_text.graphemes(true).collect::<Vec<_>>();
+    let start = (start - 1).min(graphemes.len());
+    let end = start.saturating_add(n).min(graphemes.len());
+    let mut res = graphemes[..start].concat();
+    res.push_str(new_text);
+    res.push_str(&graphemes[end..].concat());
+    res
+}
+
+// Replace every occurrence, or only the given one, of the old text.
+fn substitute(text: &str, old_text: &str, new_text: &str, instance: Option<usize>) -> String {
+    if old_text.is_empty() {
+        return text.to_string();
+    }
+    match instance {
+        None => text.replace(old_text, new_text),
+        Some(n) => match text.match_indices(old_text).nth(n - 1) {
+            Some((i, _)) => {
+                let mut res = text[..i].to_string();
+                res.push_str(new_text);
+                res.push_str(&text[i + old_text.len()..]);
+                res
+            }
+            None => text.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace, substitute};
+
+    #[test]
+    fn replace_test() {
+        assert_eq!(replace("abcdefghijk", 6, 5, "*"), "abcde*k");
+        assert_eq!(replace("2009", 3, 2, "10"), "2010");
+        assert_eq!(replace("123456", 1, 3, "@"), "@456");
+        assert_eq!(replace("abc", 5, 1, "d"), "abcd");
+        assert_eq!(replace("逻辑汇", 2, 1, "x"), "逻x汇");
+    }
+
+    #[test]
+    fn substitute_test() {
+        assert_eq!(substitute("Sales Data", "Sales", "Cost", None), "Cost Data");
+        assert_eq!(
+            substitute("Quarter 1, 2008", "1", "2", Some(1)),
+            "Quarter 2, 2008"
+        );
+        assert_eq!(
+            substitute("Quarter 1, 2011", "1", "2", Some(3)),
+            "Quarter 1, 2012"
+        );
+        assert_eq!(substitute("aaa", "a", "b", Some(4)), "aaa");
+        assert_eq!(substitute("abc", "", "x", None), "abc");
+    }
+}
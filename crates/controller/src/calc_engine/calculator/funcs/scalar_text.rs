@@ -77,3 +77,55 @@ where
 {
     calc(args, fetcher, |a| a.to_lowercase().to_string())
 }
+
+pub fn calc_proper<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, proper)
+}
+
+pub fn calc_clean<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, clean)
+}
+
+// A letter is capitalized unless it follows another letter.
+fn proper(s: &str) -> String {
+    let mut prev_alphabetic = false;
+    s.chars().fold(String::new(), |mut res, c| {
+        if prev_alphabetic {
+            res.extend(c.to_lowercase());
+        } else {
+            res.extend(c.to_uppercase());
+        }
+        prev_alphabetic = c.is_alphabetic();
+        res
+    })
+}
+
+// Only the first 32 nonprinting characters of the 7-bit ASCII are removed.
+fn clean(s: &str) -> String {
+    s.chars().filter(|c| *c as u32 >= 32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean, proper};
+
+    #[test]
+    fn proper_test() {
+        assert_eq!(proper("this is a TITLE"), "This Is A Title");
+        assert_eq!(proper("2-way street"), "2-Way Street");
+        assert_eq!(proper("76BudGet"), "76Budget");
+        assert_eq!(proper("ÉCOLE d'été"), "École D'Été");
+    }
+
+    #[test]
+    fn clean_test() {
+        assert_eq!(clean("\u{9}Monthly report\u{a}"), "Monthly report");
+        assert_eq!(clean("逻辑\u{7f}"), "逻辑\u{7f}");
+    }
+}
@@ -0,0 +1,40 @@
+use super::lookup::{MAX_COLS, MAX_ROWS};
+use super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+
+// The results beyond a full column are too large to build and spill.
+const MAX_CELLS: f64 = MAX_ROWS as f64;
+
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty() && args.len() <= 4, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let rows_arg = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_f64_from_calc_value!(rows, rows_arg);
+    let cols_arg = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(1.)));
+    assert_f64_from_calc_value!(cols, cols_arg);
+    let start_arg = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(1.)));
+    assert_f64_from_calc_value!(start, start_arg);
+    let step_arg = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(1.)));
+    assert_f64_from_calc_value!(step, step_arg);
+    let rows = rows.trunc();
+    let cols = cols.trunc();
+    assert_or_return!(rows >= 1. && cols >= 1., ast::Error::Value);
+    assert_or_return!(
+        rows <= MAX_ROWS as f64 && cols <= MAX_COLS as f64 && rows * cols <= MAX_CELLS,
+        ast::Error::Num
+    );
+    let (rows, cols) = (rows as usize, cols as usize);
+    let data = (0..rows)
+        .map(|i| {
+            (0..cols)
+                .map(|j| Value::Number(start + step * (i * cols + j) as f64))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    CalcVertex::Value(CalcValue::Range(MatrixValue::from(data)))
+}
@@ -0,0 +1,100 @@
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use crate::formatter::NumFormat;
+use logisheets_base::datetime::{get_serial_num_by_datetime, DateSystem};
+use logisheets_parser::ast;
+
+pub fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 2, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let value = match fetcher.get_calc_value(args_iter.next().unwrap()) {
+        CalcValue::Scalar(Value::Error(e)) => return CalcVertex::from_error(e),
+        CalcValue::Scalar(v) => v,
+        _ => return CalcVertex::from_error(ast::Error::Value),
+    };
+    let second = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(code, second);
+    match text(value, &code, fetcher.get_date_system()) {
+        Ok(s) => CalcVertex::from_string(s),
+        Err(e) => CalcVertex::from_error(e),
+    }
+}
+
+// Texts that look like numbers are formatted as numbers, and dates are
+// formatted as their serial numbers.
+fn text(value: Value, code: &str, system: DateSystem) -> Result<String, ast::Error> {
+    let format = NumFormat::new(code).with_date_system(system);
+    match value {
+        Value::Blank => Ok(format.format_number(0.).text),
+        Value::Number(n) => Ok(format.format_number(n).text),
+        Value::Text(t) => match t.trim().parse::<f64>() {
+            Ok(n) => Ok(format.format_number(n).text),
+            Err(_) => Ok(format.format_text(&t).text),
+        },
+        Value::Boolean(true) => Ok(format.format_text("TRUE").text),
+        Value::Boolean(false) => Ok(format.format_text("FALSE").text),
+        Value::Error(e) => Err(e),
+        Value::Date(d) => {
+            let n = get_serial_num_by_datetime(&d, system).ok_or(ast::Error::Value)?;
+            Ok(format.format_number(n).text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::text;
+    use crate::calc_engine::calculator::calc_vertex::Value;
+    use logisheets_base::datetime::DateSystem;
+
+    #[test]
+    fn text_test() {
+        let num = |n: f64, code: &str| text(Value::Number(n), code, DateSystem::Date1900).unwrap();
+        assert_eq!(num(1234.567, "$#,##0.00"), "$1,234.57");
+        assert_eq!(num(0.285, "0.0%"), "28.5%");
+        assert_eq!(num(4.34, "# ?/?"), "4 1/3");
+        assert_eq!(num(12200000., "0.00E+00"), "1.22E+07");
+        assert_eq!(
+            num(1234567898., "[<=9999999]###-####;(###) ###-####"),
+            "(123) 456-7898"
+        );
+        assert_eq!(num(1234., "0000000"), "0001234");
+        assert_eq!(num(39637., "MM/DD/YY"), "07/08/08");
+        assert_eq!(num(39637., "dddd"), "Tuesday");
+        assert_eq!(num(0.5347, "H:MM AM/PM"), "12:49 PM");
+        assert_eq!(
+            text(
+                Value::Text(String::from("12.5")),
+                "0.00",
+                DateSystem::Date1900
+            )
+            .unwrap(),
+            "12.50"
+        );
+        assert_eq!(
+            text(
+                Value::Text(String::from("abc")),
+                "0.00",
+                DateSystem::Date1900
+            )
+            .unwrap(),
+            "abc"
+        );
+        assert_eq!(
+            text(
+                Value::Text(String::from("abc")),
+                "@\"!\"",
+                DateSystem::Date1900
+            )
+            .unwrap(),
+            "abc!"
+        );
+        let date = chrono::DateTime::parse_from_rfc3339("2008-07-08T12:00:00+00:00").unwrap();
+        let date = |system| text(Value::Date(date), "yyyy-mm-dd hh:mm", system).unwrap();
+        assert_eq!(date(DateSystem::Date1900), "2008-07-08 12:00");
+        assert_eq!(date(DateSystem::Date1904), "2008-07-08 12:00");
+    }
+}
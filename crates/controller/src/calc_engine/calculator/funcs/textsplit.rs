@@ -0,0 +1,312 @@
+use super::concatenate::get_texts;
+use super::utils::{get_condition_result, ConditionResult};
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::matrix_value::MatrixValue;
+use logisheets_parser::ast;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn calc_textbefore<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, true)
+}
+
+pub fn calc_textafter<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    calc(args, fetcher, false)
+}
+
+fn calc<C>(args: Vec<CalcVertex>, fetcher: &mut C, before: bool) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 2 && args.len() <= 6, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(text, first);
+    let delimiters = match get_texts(
+        fetcher.get_calc_value(args_iter.next().unwrap()),
+        fetcher.get_date_system(),
+    ) {
+        Ok(d) => d,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let third = fetcher.get_calc_value(args_iter.next().unwrap_or(CalcVertex::from_number(1.)));
+    assert_f64_from_calc_value!(instance, third);
+    let ignore_case = match get_flag(args_iter.next(), fetcher) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let match_end = match get_flag(args_iter.next(), fetcher) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let instance = instance.trunc() as i64;
+    let len = text.graphemes(true).count() as i64;
+    assert_or_return!(
+        instance != 0 && instance.abs() <= len.max(1),
+        ast::Error::Value
+    );
+    let found = split_at_instance(&text, &delimiters, instance, ignore_case, match_end);
+    match (found, before) {
+        (Some((b, _)), true) => CalcVertex::from_string(b),
+        (Some((_, a)), false) => CalcVertex::from_string(a),
+        (None, _) => match args_iter.next() {
+            Some(arg) => CalcVertex::Value(fetcher.get_calc_value(arg)),
+            None => CalcVertex::from_error(ast::Error::Na),
+        },
+    }
+}
+
+pub fn calc_textsplit<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() >= 2 && args.len() <= 6, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(text, first);
+    let col_delimiters = match get_texts(
+        fetcher.get_calc_value(args_iter.next().unwrap()),
+        fetcher.get_date_system(),
+    ) {
+        Ok(d) => d,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let row_delimiters = match args_iter.next() {
+        Some(arg) => match get_texts(fetcher.get_calc_value(arg), fetcher.get_date_system()) {
+            Ok(d) => d,
+            Err(e) => return CalcVertex::from_error(e),
+        },
+        None => vec![],
+    };
+    let ignore_empty = match get_flag(args_iter.next(), fetcher) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let ignore_case = match get_flag(args_iter.next(), fetcher) {
+        Ok(b) => b,
+        Err(e) => return CalcVertex::from_error(e),
+    };
+    let pad_with = match args_iter.next().map(|arg| fetcher.get_calc_value(arg)) {
+        Some(CalcValue::Scalar(v)) => v,
+        Some(_) => return CalcVertex::from_error(ast::Error::Value),
+        None => Value::Error(ast::Error::Na),
+    };
+    let col_delimiters = non_empty(col_delimiters);
+    let row_delimiters = non_empty(row_delimiters);
+    assert_or_return!(
+        !col_delimiters.is_empty() || !row_delimiters.is_empty(),
+        ast::Error::Value
+    );
+    let cells = textsplit(
+        &text,
+        &col_delimiters,
+        &row_delimiters,
+        ignore_empty,
+        ignore_case,
+    );
+    let width = cells.iter().map(|r| r.len()).max().unwrap_or(0);
+    if cells.len() == 1 && width == 1 {
+        return CalcVertex::from_string(cells[0][0].clone());
+    }
+    let data = cells
+        .into_iter()
+        .map(|row| {
+            let padding = width - row.len();
+            row.into_iter()
+                .map(Value::Text)
+                .chain(std::iter::repeat_n(pad_with.clone(), padding))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    CalcVertex::Value(CalcValue::Range(MatrixValue::from(data)))
+}
+
+fn get_flag<C>(arg: Option<CalcVertex>, fetcher: &mut C) -> Result<bool, ast::Error>
+where
+    C: Connector,
+{
+    match arg {
+        Some(arg) => match get_condition_result(fetcher.get_calc_value(arg)) {
+            ConditionResult::True => Ok(true),
+            ConditionResult::False => Ok(false),
+            ConditionResult::Error(e) => Err(e),
+        },
+        None => Ok(false),
+    }
+}
+
+fn non_empty(delimiters: Vec<String>) -> Vec<String> {
+    delimiters.into_iter().filter(|d| !d.is_empty()).collect()
+}
+
+// Find the delimiters from left to right and return the grapheme ranges of
+// them. The earlier delimiter wins if several match at the same place.
+fn find_delimiters(
+    graphemes: &[&str],
+    delimiters: &[String],
+    ignore_case: bool,
+) -> Vec<(usize, usize)> {
+    let delimiters = delimiters
+        .iter()
+        .filter(|d| !d.is_empty())
+        .map(|d| d.graphemes(true).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let eq = |a: &str, b: &str| {
+        if ignore_case {
+            a.to_lowercase() == b.to_lowercase()
+        } else {
+            a == b
+        }
+    };
+    let mut result = vec![];
+    let mut i = 0;
+    while i < graphemes.len() {
+        let found = delimiters.iter().find(|d| {
+            graphemes.len() - i >= d.len() && d.iter().zip(&graphemes[i..]).all(|(a, b)| eq(a, b))
+        });
+        match found {
+            Some(d) => {
+                result.push((i, i + d.len()));
+                i += d.len();
+            }
+            None => i += 1,
+        }
+    }
+    result
+}
+
+// Return the texts before and after the delimiter of the given instance,
+// counting from the end if the instance is negative.
+fn split_at_instance(
+    text: &str,
+    delimiters: &[String],
+    instance: i64,
+    ignore_case: bool,
+    match_end: bool,
+) -> Option<(String, String)> {
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+    let len = graphemes.len();
+    // An empty delimiter matches at once at the start or the end.
+    let (start, end) = if delimiters.iter().any(|d| d.is_empty()) {
+        if instance > 0 {
+            (0, 0)
+        } else {
+            (len, len)
+        }
+    } else {
+        let mut matches = find_delimiters(&graphemes, delimiters, ignore_case);
+        if match_end && instance > 0 {
+            matches.push((len, len));
+        } else if match_end {
+            matches.insert(0, (0, 0));
+        }
+        let idx = if instance > 0 {
+            instance as usize - 1
+        } else {
+            matches
+                .len()
+                .checked_sub(instance.unsigned_abs() as usize)?
+        };
+        *matches.get(idx)?
+    };
+    Some((graphemes[..start].concat(), graphemes[end..].concat()))
+}
+
+fn split(text: &str, delimiters: &[String], ignore_empty: bool, ignore_case: bool) -> Vec<String> {
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+    let mut pieces = vec![];
+    let mut last = 0;
+    find_delimiters(&graphemes, delimiters, ignore_case)
+        .into_iter()
+        .for_each(|(start, end)| {
+            pieces.push(graphemes[last..start].concat());
+            last = end;
+        });
+    pieces.push(graphemes[last..].concat());
+    if ignore_empty {
+        pieces.retain(|p| !p.is_empty());
+    }
+    pieces
+}
+
+fn textsplit(
+    text: &str,
+    col_delimiters: &[String],
+    row_delimiters: &[String],
+    ignore_empty: bool,
+    ignore_case: bool,
+) -> Vec<Vec<String>> {
+    let rows = split(text, row_delimiters, ignore_empty, ignore_case);
+    let cells = rows
+        .iter()
+        .map(|r| split(r, col_delimiters, ignore_empty, ignore_case))
+        .filter(|r| !r.is_empty())
+        .collect::<Vec<_>>();
+    if cells.is_empty() {
+        vec![vec![String::new()]]
+    } else {
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_at_instance, textsplit};
+
+    fn before_after(text: &str, d: &str, instance: i64) -> Option<(String, String)> {
+        split_at_instance(text, &[d.to_string()], instance, false, false)
+    }
+
+    #[test]
+    fn split_at_instance_test() {
+        let text = "Little Red Riding Hood's red hood";
+        let (b, a) = before_after(text, "Red", 1).unwrap();
+        assert_eq!(b, "Little ");
+        assert_eq!(a, " Riding Hood's red hood");
+        let (b, a) = before_after(text, "red", -1).unwrap();
+        assert_eq!(b, "Little Red Riding Hood's ");
+        assert_eq!(a, " hood");
+        assert_eq!(before_after(text, "red", 2), None);
+        let r = split_at_instance(text, &[String::from("red")], 2, true, false);
+        assert_eq!(r.unwrap().0, "Little Red Riding Hood's ");
+        let r = split_at_instance(text, &[String::from("x")], 1, false, true);
+        assert_eq!(r.unwrap().0, text);
+        let r = split_at_instance(
+            "a-b_c",
+            &[String::from("_"), String::from("-")],
+            1,
+            false,
+            false,
+        );
+        assert_eq!(r.unwrap().1, "b_c");
+        assert_eq!(before_after("abc", "", 1).unwrap().1, "abc");
+        assert_eq!(before_after("abc", "", -1).unwrap().0, "abc");
+    }
+
+    #[test]
+    fn textsplit_test() {
+        let s = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let r = textsplit("Dakota Lennon Sanchez", &s(&[" "]), &[], false, false);
+        assert_eq!(r, vec![s(&["Dakota", "Lennon", "Sanchez"])]);
+        let r = textsplit("1,2,3;4,5,6", &s(&[","]), &s(&[";"]), false, false);
+        assert_eq!(r, vec![s(&["1", "2", "3"]), s(&["4", "5", "6"])]);
+        let r = textsplit(
+            "Do. Or do not. There is no try. -Anonymous",
+            &s(&["."]),
+            &[],
+            false,
+            false,
+        );
+        assert_eq!(r[0].len(), 4);
+        let r = textsplit("a,,b", &s(&[","]), &[], true, false);
+        assert_eq!(r, vec![s(&["a", "b"])]);
+        let r = textsplit("aXbxc", &s(&["x"]), &[], false, true);
+        assert_eq!(r, vec![s(&["a", "b", "c"])]);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_parser::ast;
+
+pub fn calc_t<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    match fetcher.get_calc_value(args.into_iter().next().unwrap()) {
+        CalcValue::Scalar(Value::Text(t)) => CalcVertex::from_string(t),
+        CalcValue::Scalar(Value::Error(e)) => CalcVertex::from_error(e),
+        CalcValue::Scalar(_) => CalcVertex::from_string(String::new()),
+        _ => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+pub fn calc_n<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    match fetcher.get_calc_value(args.into_iter().next().unwrap()) {
+        CalcValue::Scalar(Value::Number(n)) => CalcVertex::from_number(n),
+        CalcValue::Scalar(Value::Boolean(b)) => CalcVertex::from_number(b as u8 as f64),
+        CalcValue::Scalar(Value::Error(e)) => CalcVertex::from_error(e),
+        CalcValue::Scalar(_) => CalcVertex::from_number(0.),
+        _ => CalcVertex::from_error(ast::Error::Value),
+    }
+}
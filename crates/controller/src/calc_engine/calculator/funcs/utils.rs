@@ -1,6 +1,7 @@
 use logisheets_parser::ast;
 
-use super::{CalcValue, Value};
+use super::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
 
 pub enum ConditionResult {
     True,
@@ -69,6 +70,12 @@ pub fn is_error(value: &CalcValue) -> bool {
     }
 }
 
+// An omitted argument, like the second one in ADDRESS(1,,2), is parsed as a
+// blank constant. A reference to an empty cell is not omitted.
+pub fn is_omitted(arg: &CalcVertex) -> bool {
+    matches!(arg, CalcVertex::Value(CalcValue::Scalar(Value::Blank)))
+}
+
 fn get_f64(v: Value) -> Result<Option<f64>, ast::Error> {
     match v {
         Value::Number(f) => Ok(Some(f)),
@@ -136,6 +143,70 @@ pub fn get_nums_from_value(value: CalcValue) -> Result<Vec<f64>, ast::Error> {
     }
 }
 
+// Collect the numbers of an argument of a statistical function like Excel.
+// Texts and booleans typed directly are converted, while those in references
+// and arrays are ignored, or counted as 0 and 1 by the `A` functions if
+// `count_all` is set.
+pub fn get_nums_from_arg<C>(
+    arg: CalcVertex,
+    fetcher: &mut C,
+    count_all: bool,
+) -> Result<Vec<f64>, ast::Error>
+where
+    C: Connector,
+{
+    let direct = matches!(arg, CalcVertex::Value(CalcValue::Scalar(_)));
+    let value = fetcher.get_calc_value(arg);
+    match value {
+        CalcValue::Scalar(v) if direct => match v {
+            Value::Text(t) => match t.trim().parse::<f64>() {
+                Ok(n) => Ok(vec![n]),
+                Err(_) => Err(ast::Error::Value),
+            },
+            Value::Blank => Ok(vec![0.]),
+            v => Ok(get_ref_num(v, true)?.into_iter().collect()),
+        },
+        v => get_ref_nums(v, count_all),
+    }
+}
+
+fn get_ref_nums(value: CalcValue, count_all: bool) -> Result<Vec<f64>, ast::Error> {
+    match value {
+        CalcValue::Scalar(v) => Ok(get_ref_num(v, count_all)?.into_iter().collect()),
+        CalcValue::Range(m) => {
+            // Sort the positions to keep the numbers in the order of cells.
+            let mut positions = m.get_stored_positions();
+            positions.sort();
+            positions.into_iter().try_fold(vec![], |mut prev, (i, j)| {
+                let v = match m.visit(i, j) {
+                    Ok(v) => v.clone(),
+                    Err(v) => v,
+                };
+                prev.extend(get_ref_num(v, count_all)?);
+                Ok(prev)
+            })
+        }
+        CalcValue::Cube(c) => c.into_iter().try_fold(vec![], |mut prev, v| {
+            prev.extend(get_ref_num(v, count_all)?);
+            Ok(prev)
+        }),
+        CalcValue::Union(u) => u.into_iter().try_fold(vec![], |mut prev, v| {
+            prev.extend(get_ref_nums(*v, count_all)?);
+            Ok(prev)
+        }),
+    }
+}
+
+fn get_ref_num(value: Value, count_all: bool) -> Result<Option<f64>, ast::Error> {
+    match value {
+        Value::Number(n) => Ok(Some(n)),
+        Value::Error(e) => Err(e),
+        Value::Boolean(b) if count_all => Ok(Some(b as u8 as f64)),
+        Value::Text(_) if count_all => Ok(Some(0.)),
+        _ => Ok(None),
+    }
+}
+
 #[cfg(test)]
 pub mod tests_utils {
     use logisheets_base::async_func::{AsyncCalcResult, AsyncFuncCommitTrait, Task};
@@ -227,8 +298,39 @@ pub mod tests_utils {
             todo!()
         }
 
+        fn get_spill_size(
+            &mut self,
+            _sheet_id: logisheets_base::SheetId,
+            _row: usize,
+            _col: usize,
+        ) -> Option<(usize, usize)> {
+            todo!()
+        }
+
+        fn release_spills(
+            &mut self,
+            _modified: &[FormulaId],
+        ) -> std::collections::HashSet<FormulaId> {
+            todo!()
+        }
+
+        fn get_sheet_id_by_name(&self, _name: &str) -> Option<logisheets_base::SheetId> {
+            todo!()
+        }
+
+        fn add_dynamic_dep(
+            &mut self,
+            _reference: &crate::calc_engine::calculator::calc_vertex::CalcReference,
+        ) {
+            todo!()
+        }
+
         fn is_async_func(&self, _func_name: &str) -> bool {
             false
         }
+
+        fn get_date_system(&self) -> logisheets_base::datetime::DateSystem {
+            logisheets_base::datetime::DateSystem::Date1900
+        }
     }
 }
@@ -0,0 +1,255 @@
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
+use crate::calc_engine::connector::Connector;
+use logisheets_base::datetime::{get_serial_num_by_date, DateSystem};
+use logisheets_parser::ast;
+
+pub fn calc_value<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(args.len() == 1, ast::Error::Unspecified);
+    let arg = fetcher.get_calc_value(args.into_iter().next().unwrap());
+    match arg {
+        CalcValue::Scalar(Value::Blank) => CalcVertex::from_number(0.),
+        CalcValue::Scalar(Value::Number(n)) => CalcVertex::from_number(n),
+        CalcValue::Scalar(Value::Text(t)) => match parse_value(&t, fetcher.get_date_system()) {
+            Some(n) => CalcVertex::from_number(n),
+            None => CalcVertex::from_error(ast::Error::Value),
+        },
+        CalcValue::Scalar(Value::Error(e)) => CalcVertex::from_error(e),
+        _ => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+pub fn calc_numbervalue<C>(args: Vec<CalcVertex>, fetcher: &mut C) -> CalcVertex
+where
+    C: Connector,
+{
+    assert_or_return!(!args.is_empty() && args.len() <= 3, ast::Error::Unspecified);
+    let mut args_iter = args.into_iter();
+    let first = fetcher.get_calc_value(args_iter.next().unwrap());
+    assert_text_from_calc_value!(text, first);
+    let second = fetcher.get_calc_value(
+        args_iter
+            .next()
+            .unwrap_or(CalcVertex::from_string(String::from("."))),
+    );
+    assert_text_from_calc_value!(decimal_sep, second);
+    let third = fetcher.get_calc_value(
+        args_iter
+            .next()
+            .unwrap_or(CalcVertex::from_string(String::from(","))),
+    );
+    assert_text_from_calc_value!(group_sep, third);
+    let decimal_sep = decimal_sep.chars().next();
+    let group_sep = group_sep.chars().next();
+    assert_or_return!(
+        decimal_sep.is_some() && decimal_sep != group_sep,
+        ast::Error::Value
+    );
+    match numbervalue(&text, decimal_sep.unwrap(), group_sep) {
+        Some(n) => CalcVertex::from_number(n),
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+// Numbers, percentages, currencies, dates and times are recognized in the
+// en-US format.
+fn parse_value(text: &str, system: DateSystem) -> Option<f64> {
+    let text = text.trim();
+    if let Some(n) = parse_number(text) {
+        return Some(n);
+    }
+    if let Some(t) = parse_time(text) {
+        return Some(t);
+    }
+    let (date, time) = match text.split_once(' ') {
+        Some((d, t)) => (d, parse_time(t.trim())?),
+        None => (text, 0.),
+    };
+    parse_date(date, system).map(|d| d + time)
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    let (text, parenthesized) = match text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(t) => (t.trim(), true),
+        None => (text, false),
+    };
+    let (text, percent) = match text.strip_suffix('%') {
+        Some(t) => (t.trim_end(), true),
+        None => (text, false),
+    };
+    let (text, negative) = strip_sign(text);
+    let text = text.strip_prefix('$').unwrap_or(text);
+    let (text, negative) = match strip_sign(text) {
+        (t, n) if !negative => (t, n),
+        (t, false) => (t, true),
+        _ => return None,
+    };
+    if parenthesized && negative {
+        return None;
+    }
+    let split = text.find(['.', 'e', 'E']);
+    let (int_part, rest) = text.split_at(split.unwrap_or(text.len()));
+    let mut groups = int_part.split(',');
+    let first = groups.next()?;
+    let grouped = int_part.contains(',');
+    if grouped && (first.is_empty() || first.len() > 3 || groups.any(|g| g.len() != 3)) {
+        return None;
+    }
+    let s = format!("{}{}", int_part.replace(',', ""), rest);
+    if !s.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let n = s.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    let n = if negative || parenthesized { -n } else { n };
+    if percent {
+        Some(n / 100.)
+    } else {
+        Some(n)
+    }
+}
+
+fn strip_sign(text: &str) -> (&str, bool) {
+    if let Some(t) = text.strip_prefix('-') {
+        (t, true)
+    } else {
+        (text.strip_prefix('+').unwrap_or(text), false)
+    }
+}
+
+// Parse `h:mm`, `h:mm:ss` or `mm:ss.0`, followed by an optional AM/PM.
+fn parse_time(text: &str) -> Option<f64> {
+    let upper = text.to_uppercase();
+    let (text, pm) = if let Some(t) = upper.strip_suffix("PM") {
+        (t.trim_end(), Some(true))
+    } else if let Some(t) = upper.strip_suffix("AM") {
+        (t.trim_end(), Some(false))
+    } else {
+        (upper.as_str(), None)
+    };
+    let parts = text.split(':').collect::<Vec<_>>();
+    let (h, m, s) = match parts.as_slice() {
+        [h, m] if m.contains('.') => (0., h.parse::<f64>().ok()?, m.parse::<f64>().ok()?),
+        [h, m] => (h.parse::<f64>().ok()?, m.parse::<f64>().ok()?, 0.),
+        [h, m, s] => (
+            h.parse::<f64>().ok()?,
+            m.parse::<f64>().ok()?,
+            s.parse::<f64>().ok()?,
+        ),
+        _ => return None,
+    };
+    if [h, m].iter().any(|v| v.fract() != 0.) || m >= 60. || s >= 60. || h < 0. || m < 0. || s < 0.
+    {
+        return None;
+    }
+    let h = match pm {
+        Some(_) if !(1. ..=12.).contains(&h) => return None,
+        Some(pm) => h % 12. + if pm { 12. } else { 0. },
+        None => h,
+    };
+    Some((h * 3600. + m * 60. + s) / 86400.)
+}
+
+// Parse `yyyy-mm-dd`, `yyyy/mm/dd` or `m/d/yyyy`.
+fn parse_date(text: &str, system: DateSystem) -> Option<f64> {
+    let parts = text
+        .split(['-', '/'])
+        .map(|p| p.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (y, m, d) = match parts.as_slice() {
+        [y, m, d] if *y >= 1000 => (*y, *m, *d),
+        [m, d, y] => (*y, *m, *d),
+        _ => return None,
+    };
+    if y > 9999 || !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    let serial = get_serial_num_by_date(y, m, d, system)?;
+    // Reject the days like 2/30 that roll over to the next month.
+    match get_serial_num_by_date(y, m + 1, 1, system) {
+        Some(next) if serial < next => Some(serial as f64),
+        None if m == 12 => Some(serial as f64),
+        _ => None,
+    }
+}
+
+// The spaces are ignored and each trailing `%` divides the number by 100.
+fn numbervalue(text: &str, decimal_sep: char, group_sep: Option<char>) -> Option<f64> {
+    let text = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    if text.is_empty() {
+        return Some(0.);
+    }
+    let trimmed = text.trim_end_matches('%');
+    let percents = text.len() - trimmed.len();
+    let mut decimal_seen = false;
+    let mut s = String::new();
+    for c in trimmed.chars() {
+        if c == decimal_sep {
+            if decimal_seen {
+                return None;
+            }
+            decimal_seen = true;
+            s.push('.');
+        } else if Some(c) == group_sep {
+            if decimal_seen {
+                return None;
+            }
+        } else if c.is_ascii_digit() || c == '-' || c == '+' || c == 'e' || c == 'E' {
+            s.push(c);
+        } else {
+            return None;
+        }
+    }
+    let n = s.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    Some(n / 100_f64.powi(percents as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{numbervalue, parse_value};
+    use logisheets_base::datetime::DateSystem;
+
+    #[test]
+    fn parse_value_test() {
+        assert_eq!(parse_value("$1,000", DateSystem::Date1900), Some(1000.));
+        assert_eq!(parse_value(" -12.5 ", DateSystem::Date1900), Some(-12.5));
+        assert_eq!(parse_value("(5)", DateSystem::Date1900), Some(-5.));
+        assert_eq!(parse_value("50%", DateSystem::Date1900), Some(0.5));
+        assert_eq!(parse_value("1.5E3", DateSystem::Date1900), Some(1500.));
+        assert_eq!(parse_value("1,00", DateSystem::Date1900), None);
+        assert_eq!(parse_value("abc", DateSystem::Date1900), None);
+        assert_eq!(parse_value("1e400", DateSystem::Date1900), None);
+        let diff = parse_value("16:48:00", DateSystem::Date1900).unwrap()
+            - parse_value("12:00:00", DateSystem::Date1900).unwrap();
+        assert!((diff - 0.2).abs() < 1e-10);
+        assert_eq!(parse_value("6:00 PM", DateSystem::Date1900), Some(0.75));
+        assert_eq!(
+            parse_value("2012-11-10", DateSystem::Date1900),
+            Some(41223.)
+        );
+        assert_eq!(
+            parse_value("11/10/2012 6:00", DateSystem::Date1900),
+            Some(41223.25)
+        );
+        assert_eq!(parse_value("2/30/2012", DateSystem::Date1900), None);
+        assert_eq!(
+            parse_value("2012-11-10", DateSystem::Date1904),
+            Some(39761.)
+        );
+    }
+
+    #[test]
+    fn numbervalue_test() {
+        assert_eq!(numbervalue("2.500,27", ',', Some('.')), Some(2500.27));
+        assert_eq!(numbervalue("3.5%", '.', Some(',')), Some(0.035));
+        assert_eq!(numbervalue(" 1 000 ", '.', Some(',')), Some(1000.));
+        assert_eq!(numbervalue("", '.', Some(',')), Some(0.));
+        assert_eq!(numbervalue("1.2.3", '.', Some(',')), None);
+        assert_eq!(numbervalue("1.2,3", '.', Some(',')), None);
+        assert_eq!(numbervalue("1e400", '.', Some(',')), None);
+    }
+}
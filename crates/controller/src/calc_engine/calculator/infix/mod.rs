@@ -148,7 +148,8 @@ where
             CalcValue::Range(lhs_range.calc_scalar(rhs_scalar, Box::new(func), false))
         }
         (CalcValue::Range(lhs_range), CalcValue::Range(rhs_range)) => {
-            CalcValue::Range(lhs_range.calc_range(rhs_range, Box::new(func), false))
+            let pad = Value::Error(ast::Error::Na);
+            CalcValue::Range(lhs_range.calc_range(rhs_range, Box::new(func), pad))
         }
         _ => {
             let e = Value::Error(ast::Error::Value);
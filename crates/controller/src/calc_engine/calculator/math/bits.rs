@@ -138,7 +138,7 @@ pub fn oct2dec(oct_num: &str) -> Option<f64> {
                 Some(r as f64)
             }
         }
-        Err(_) => todo!(),
+        Err(_) => None,
     }
 }
 
@@ -157,7 +157,7 @@ pub fn hex2dec(hex_num: &str) -> Option<f64> {
                 Some(r as f64)
             }
         }
-        Err(_) => todo!(),
+        Err(_) => None,
     }
 }
 
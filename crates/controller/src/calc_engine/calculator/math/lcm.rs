@@ -1,9 +1,12 @@
 use super::gcd::gcd;
 
 pub fn multi_lcm(nums: &[u32]) -> u32 {
+    if nums.contains(&0) {
+        return 0;
+    }
     let multi = nums.iter().fold(1_u32, |prev, t| {
         let d = gcd(prev, *t);
-        prev * *t / d
+        (prev / d).saturating_mul(*t)
     });
     multi
 }
@@ -19,5 +22,8 @@ mod tests {
         let input = [32_u32, 4_u32, 6_u32, 8_u32];
         let result = multi_lcm(&input);
         assert_eq!(result, 96);
+        let input = [0_u32, 4_u32];
+        let result = multi_lcm(&input);
+        assert_eq!(result, 0);
     }
 }
@@ -1,6 +1,8 @@
 pub mod calc_vertex;
 pub mod calculator;
 mod compare;
-mod funcs;
+pub mod funcs;
 pub mod infix;
 mod math;
+
+pub use funcs::is_volatile;
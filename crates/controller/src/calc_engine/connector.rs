@@ -1,13 +1,13 @@
 use crate::vertex_manager::vertex::FormulaId;
 use logisheets_base::{
-    async_func::AsyncFuncCommitTrait, get_active_sheet::GetActiveSheetTrait,
+    async_func::AsyncFuncCommitTrait, datetime::DateSystem, get_active_sheet::GetActiveSheetTrait,
     get_curr_addr::GetCurrAddrTrait, set_curr_cell::SetCurrCellTrait, CellId, FuncId, SheetId,
     TextId,
 };
 use logisheets_parser::ast;
 use std::collections::HashSet;
 
-use super::calculator::calc_vertex::{CalcValue, CalcVertex};
+use super::calculator::calc_vertex::{CalcReference, CalcValue, CalcVertex};
 
 pub trait Connector:
     AsyncFuncCommitTrait + GetActiveSheetTrait + GetCurrAddrTrait + SetCurrCellTrait
@@ -19,5 +19,20 @@ pub trait Connector:
     fn get_cell_idx(&mut self, sheet_id: SheetId, cell_id: &CellId) -> Option<(usize, usize)>;
     fn get_cell_id(&mut self, sheet_id: SheetId, row: usize, col: usize) -> Option<CellId>;
     fn commit_calc_values(&mut self, vertex: FormulaId, result: CalcValue) -> HashSet<FormulaId>;
+    // The size of the array result spilling from this cell.
+    fn get_spill_size(
+        &mut self,
+        sheet_id: SheetId,
+        row: usize,
+        col: usize,
+    ) -> Option<(usize, usize)>;
+    // Clear the array results whose anchors or areas are modified, and return
+    // the formulas to recalculate.
+    fn release_spills(&mut self, modified: &[FormulaId]) -> HashSet<FormulaId>;
+    fn get_sheet_id_by_name(&self, name: &str) -> Option<SheetId>;
+    // Record a reference built at calc time, like the results of OFFSET and
+    // INDIRECT, as a dependency of the current cell.
+    fn add_dynamic_dep(&mut self, reference: &CalcReference);
     fn is_async_func(&self, func_name: &str) -> bool;
+    fn get_date_system(&self) -> DateSystem;
 }
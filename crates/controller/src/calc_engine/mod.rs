@@ -13,6 +13,7 @@ use crate::vertex_manager::VertexManager;
 use calc_order::{calc_order, CalcUnit};
 
 use self::connector::Connector;
+use calculator::calc_vertex::{CalcValue, Value};
 use calculator::calculator::calc;
 use cycle::CycleCalculator;
 
@@ -29,25 +30,27 @@ impl<C> CalcEngine<C>
 where
     C: Connector,
 {
-    pub fn start(self) -> HashSet<FormulaId> {
+    // Return the circular references found in this calculation.
+    pub fn start(self) -> Vec<Vec<FormulaId>> {
+        let mut engine = self;
+        let modified = engine
+            .vertex_manager
+            .dirty_nodes
+            .iter()
+            .cloned()
+            .collect_vec();
+        let released = engine.connector.release_spills(&modified);
+        engine.vertex_manager.dirty_nodes.extend(released);
+        engine.calc()
+    }
+
+    fn calc(self) -> Vec<Vec<FormulaId>> {
         let graph = self.vertex_manager.status.graph;
         let dirty_nodes = self.vertex_manager.dirty_nodes;
         let calc_rdeps = self.vertex_manager.calc_rdeps;
         let rdeps_fetcher = |v: &FormulaId| {
             let vertex = Vertex::Cell(v.clone());
-            let graph_rdeps = graph
-                .get_rdeps(&vertex)
-                .and_then(|s| {
-                    let rdeps = s
-                        .clone()
-                        .into_iter()
-                        .fold(HashSet::<FormulaId>::new(), |p, vt| match vt {
-                            Vertex::Cell(fid) => p.update(fid),
-                            _ => p,
-                        });
-                    Some(rdeps)
-                })
-                .unwrap_or(HashSet::new());
+            let graph_rdeps = graph.get_cell_rdeps(&vertex);
             let t_rdeps = calc_rdeps.get(v);
             match t_rdeps {
                 Some(rdeps) => graph_rdeps.union(rdeps.clone()).into_iter().collect_vec(),
@@ -57,16 +60,17 @@ where
         let order = calc_order(&rdeps_fetcher, dirty_nodes);
         let formulas = self.vertex_manager.status.formulas;
         let names = self.vertex_manager.status.names;
-        let CalcConfig { iter_limit, error } = self.config;
+        let config = self.config;
         let mut connector = self.connector;
 
         let mut dirties = HashSet::<FormulaId>::new();
+        let mut cycles = Vec::<Vec<FormulaId>>::new();
         order.into_iter().for_each(|unit| match unit {
-            CalcUnit::Cycle(fids) => {
+            CalcUnit::Cycle(fids) if config.iterative => {
                 let cycle_calc = CycleCalculator {
                     vertices: fids,
-                    error,
-                    iter_limit,
+                    error: config.error,
+                    iter_limit: config.iter_limit,
                     connector: &mut connector,
                     names: &names,
                     formulas: &formulas,
@@ -74,6 +78,14 @@ where
                 let dirty = cycle_calc.start();
                 dirties.extend(dirty);
             }
+            CalcUnit::Cycle(fids) => {
+                fids.iter().for_each(|fid| {
+                    let v = CalcValue::Scalar(Value::Number(0.));
+                    let dirty = connector.commit_calc_values(*fid, v);
+                    dirties.extend(dirty);
+                });
+                cycles.push(fids);
+            }
             CalcUnit::Node(fid) => {
                 if let Some(ast_node) = formulas.get(&fid) {
                     let curr_sheet = fid.0;
@@ -95,18 +107,124 @@ where
                     names,
                     range_vertices: self.vertex_manager.status.range_vertices,
                     sts_vertices: self.vertex_manager.status.sts_vertices,
+                    volatiles: self.vertex_manager.status.volatiles,
                 },
                 dirty_nodes: dirties,
                 calc_rdeps: im::HashMap::new(),
             };
-            let config = CalcConfig { iter_limit, error };
             let engine = CalcEngine {
                 vertex_manager: vm,
                 config,
                 connector,
             };
-            engine.start();
+            cycles.extend(engine.calc());
         }
-        HashSet::new()
+        cycles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    // The empty cells are read as 0.
+    fn numbers(wb: &mut Workbook, cells: &[(usize, usize)]) -> Vec<f64> {
+        cells
+            .iter()
+            .map(|(r, c)| match value(wb, 0, *r, *c).as_str() {
+                "" => 0.,
+                v => v.parse().unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spill_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 1, 0, "2"),
+                input(0, 2, 0, "3"),
+                input(0, 0, 4, "=SUM(C1:C5)"),
+                input(0, 0, 5, "=C2"),
+                input(0, 0, 6, "=SUM(C1#)"),
+            ],
+        );
+        edit(&mut wb, vec![input(0, 0, 2, "=A1:A3*2")]);
+        let cells = [(0, 2), (1, 2), (2, 2), (0, 4), (0, 5), (0, 6)];
+        assert_eq!(numbers(&mut wb, &cells), vec![2., 4., 6., 12., 4., 12.]);
+
+        edit(&mut wb, vec![input(0, 2, 0, "10")]);
+        assert_eq!(numbers(&mut wb, &cells), vec![2., 4., 20., 26., 4., 26.]);
+
+        edit(&mut wb, vec![input(0, 0, 2, "=SEQUENCE(4,1,5)")]);
+        let cells = [(0, 2), (1, 2), (2, 2), (3, 2), (0, 4), (0, 6)];
+        assert_eq!(numbers(&mut wb, &cells), vec![5., 6., 7., 8., 26., 26.]);
+
+        edit(&mut wb, vec![input(0, 2, 2, "x")]);
+        assert_eq!(value(&mut wb, 0, 0, 2), "#SPILL!");
+        assert_eq!(value(&mut wb, 0, 1, 2), "");
+        assert_eq!(value(&mut wb, 0, 2, 2), "x");
+        assert_eq!(value(&mut wb, 0, 3, 2), "");
+        assert_eq!(value(&mut wb, 0, 0, 6), "#REF!");
+        assert_eq!(numbers(&mut wb, &[(0, 5)]), vec![0.]);
+
+        edit(&mut wb, vec![input(0, 2, 2, "")]);
+        let cells = [(0, 2), (1, 2), (2, 2), (3, 2), (0, 5), (0, 6)];
+        assert_eq!(numbers(&mut wb, &cells), vec![5., 6., 7., 8., 6., 26.]);
+
+        edit(&mut wb, vec![input(0, 0, 2, "=SEQUENCE(2)")]);
+        let cells = [(0, 2), (1, 2), (2, 2), (3, 2), (0, 4)];
+        assert_eq!(numbers(&mut wb, &cells), vec![1., 2., 0., 0., 3.]);
+        assert!(wb.controller.undo());
+        assert_eq!(numbers(&mut wb, &cells), vec![5., 6., 7., 8., 26.]);
+
+        edit(&mut wb, vec![input(0, 0, 2, "=SEQUENCE(1)")]);
+        let cells = [(0, 2), (1, 2), (0, 6)];
+        assert_eq!(numbers(&mut wb, &cells), vec![1., 0., 1.]);
+
+        edit(&mut wb, vec![input(0, 0, 2, "=SEQUENCE(1e7)")]);
+        assert_eq!(value(&mut wb, 0, 0, 2), "#NUM!");
+
+        // The spill areas are kept in saving and loading.
+        edit(&mut wb, vec![input(0, 0, 2, "=SEQUENCE(A1+1)")]);
+        let saved = wb.controller.save().unwrap();
+        let mut wb = Workbook::from_file(&saved, String::from("spill")).unwrap();
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_formula(0, 2).unwrap(), "SEQUENCE(A1 + 1)");
+        assert_eq!(ws.get_formula(1, 2).unwrap(), "");
+        let cells = [(0, 2), (1, 2), (2, 2), (0, 6)];
+        assert_eq!(numbers(&mut wb, &cells), vec![1., 2., 0., 3.]);
+        edit(&mut wb, vec![input(0, 0, 0, "2")]);
+        let cells = [(0, 2), (1, 2), (2, 2), (3, 2), (0, 6)];
+        assert_eq!(numbers(&mut wb, &cells), vec![1., 2., 3., 0., 6.]);
+        edit(&mut wb, vec![input(0, 0, 0, "0")]);
+        assert_eq!(numbers(&mut wb, &cells), vec![1., 0., 0., 0., 1.]);
+    }
+    #[test]
+    fn spill_index_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 1, 0, "2"),
+                input(0, 2, 0, "3"),
+                input(0, 0, 2, "=SEQUENCE(3,2)"),
+                input(0, 0, 4, "=INDEX(SEQUENCE(3,2),3,2)"),
+                input(0, 1, 4, "=INDEX(A1:A3*2,2,1,1)"),
+                input(0, 2, 4, "=INDEX(C1#,2,2)"),
+                input(0, 0, 5, "=INDEX(C1#,0,2)"),
+            ],
+        );
+        let cells = [(0, 4), (1, 4), (2, 4), (0, 5), (1, 5), (2, 5)];
+        assert_eq!(numbers(&mut wb, &cells), vec![6., 4., 4., 2., 4., 6.]);
+
+        edit(&mut wb, vec![input(0, 0, 2, "=SEQUENCE(2,2,10)")]);
+        let cells = [(2, 4), (0, 5), (1, 5), (2, 5)];
+        assert_eq!(numbers(&mut wb, &cells), vec![13., 11., 13., 0.]);
     }
 }
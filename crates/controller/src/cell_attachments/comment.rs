@@ -1,11 +1,15 @@
-use im::HashMap;
+use chrono::Local;
+use im::{HashMap, Vector};
 use logisheets_base::{AuthorId, CellId, SheetId};
+use rand::{thread_rng, Rng};
 
 use crate::id_manager::AuthorIdManager;
 
 #[derive(Debug, Clone)]
 pub struct Comments {
     pub authors: AuthorIdManager,
+    // The identities of the authors of threaded comments.
+    pub persons: HashMap<AuthorId, Person>,
     pub data: HashMap<SheetId, SheetComments>,
 }
 
@@ -14,28 +18,116 @@ impl Comments {
         self.data.get(sheet_id)?.comments.get(cell_id)
     }
 
+    pub fn get_thread(&self, sheet_id: &SheetId, cell_id: &CellId) -> Option<&CommentThread> {
+        self.data.get(sheet_id)?.threads.get(cell_id)
+    }
+
+    pub fn get_thread_mut(
+        &mut self,
+        sheet_id: SheetId,
+        cell_id: &CellId,
+    ) -> Option<&mut CommentThread> {
+        self.data.get_mut(&sheet_id)?.threads.get_mut(cell_id)
+    }
+
+    // Get the thread of the cell. A thread is started by the comment of the
+    // cell if there is no thread yet. Return None if there is no comment.
+    pub fn get_or_create_thread(
+        &mut self,
+        sheet_id: SheetId,
+        cell_id: CellId,
+    ) -> Option<&mut CommentThread> {
+        if self.get_thread(&sheet_id, &cell_id).is_none() {
+            let comment = self.get_comment(&sheet_id, &cell_id)?.clone();
+            self.get_person(comment.author);
+            let thread = CommentThread {
+                comments: im::vector![ThreadedComment::new(comment.author, comment.text)],
+                done: false,
+            };
+            self.add_thread(sheet_id, cell_id, thread);
+        }
+        self.get_thread_mut(sheet_id, &cell_id)
+    }
+
+    // Return the authors and the texts of the replies in the thread.
+    pub fn get_replies(&self, sheet_id: &SheetId, cell_id: &CellId) -> Vec<(String, String)> {
+        match self.get_thread(sheet_id, cell_id) {
+            Some(thread) => thread
+                .comments
+                .iter()
+                .skip(1)
+                .map(|c| {
+                    let author = self.get_author_name(&c.author).unwrap_or_default();
+                    (author, c.text.clone())
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn is_resolved(&self, sheet_id: &SheetId, cell_id: &CellId) -> bool {
+        self.get_thread(sheet_id, cell_id).is_some_and(|t| t.done)
+    }
+
     pub fn get_author_name(&self, author_id: &AuthorId) -> Option<String> {
         self.authors.get_string(&author_id)
     }
 
     pub fn add_comment(&mut self, sheet_id: SheetId, cell_id: CellId, comment: Comment) {
-        match self.data.get_mut(&sheet_id) {
-            Some(sc) => {
-                sc.comments.insert(cell_id, comment);
-            }
-            None => {
-                let mut new_sheet_comment = SheetComments::new();
-                new_sheet_comment.comments.insert(cell_id, comment);
-                self.data.insert(sheet_id, new_sheet_comment);
-            }
+        self.get_sheet_comments(sheet_id)
+            .comments
+            .insert(cell_id, comment);
+    }
+
+    pub fn add_thread(&mut self, sheet_id: SheetId, cell_id: CellId, thread: CommentThread) {
+        self.get_sheet_comments(sheet_id)
+            .threads
+            .insert(cell_id, thread);
+    }
+
+    // Remove the comment and the thread of the cell.
+    pub fn remove_comment(&mut self, sheet_id: SheetId, cell_id: &CellId) {
+        if let Some(sc) = self.data.get_mut(&sheet_id) {
+            sc.comments.remove(cell_id);
+            sc.threads.remove(cell_id);
         }
     }
+
+    // Get the person of the author. A new identity is assigned if the author
+    // has never written a threaded comment.
+    pub fn get_person(&mut self, author: AuthorId) -> &Person {
+        self.persons.entry(author).or_insert_with(|| Person {
+            id: new_guid(),
+            user_id: None,
+            provider_id: None,
+        })
+    }
+
+    // Assign the identities to the authors and the mentioned persons of
+    // threaded comments who have none.
+    pub fn assign_persons(&mut self) {
+        let authors = self
+            .data
+            .values()
+            .flat_map(|sc| sc.threads.values())
+            .flat_map(|t| t.comments.iter())
+            .flat_map(|c| std::iter::once(c.author).chain(c.mentions.iter().map(|m| m.person)))
+            .collect::<Vec<_>>();
+        authors.into_iter().for_each(|author| {
+            self.get_person(author);
+        });
+    }
+
+    fn get_sheet_comments(&mut self, sheet_id: SheetId) -> &mut SheetComments {
+        self.data.entry(sheet_id).or_insert_with(SheetComments::new)
+    }
 }
 
 impl Default for Comments {
     fn default() -> Self {
         Comments {
             authors: AuthorIdManager::new(0),
+            persons: HashMap::new(),
             data: HashMap::new(),
         }
     }
@@ -44,12 +136,14 @@ impl Default for Comments {
 #[derive(Debug, Clone)]
 pub struct SheetComments {
     pub comments: HashMap<CellId, Comment>,
+    pub threads: HashMap<CellId, CommentThread>,
 }
 
 impl SheetComments {
     pub fn new() -> Self {
         SheetComments {
             comments: HashMap::new(),
+            threads: HashMap::new(),
         }
     }
 }
@@ -59,3 +153,186 @@ pub struct Comment {
     pub author: AuthorId,
     pub text: String,
 }
+
+#[derive(Debug, Clone)]
+pub struct Person {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub provider_id: Option<String>,
+}
+
+// The first comment starts the thread and the others are the replies.
+#[derive(Debug, Clone)]
+pub struct CommentThread {
+    pub comments: Vector<ThreadedComment>,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThreadedComment {
+    pub id: String,
+    pub author: AuthorId,
+    pub text: String,
+    pub date_time: Option<String>,
+    pub mentions: Vec<Mention>,
+}
+
+impl ThreadedComment {
+    pub fn new(author: AuthorId, text: String) -> Self {
+        ThreadedComment {
+            id: new_guid(),
+            author,
+            text,
+            date_time: Some(Local::now().format("%Y-%m-%dT%H:%M:%S%.3f").to_string()),
+            mentions: vec![],
+        }
+    }
+}
+
+// `start` and `length` locate the mention in the text.
+#[derive(Debug, Clone)]
+pub struct Mention {
+    pub id: String,
+    pub person: AuthorId,
+    pub start: u32,
+    pub length: u32,
+}
+
+// Threaded comments and persons are identified by GUIDs like
+// {7F9C1A2B-3D4E-4F50-8A6B-7C8D9E0F1A2B}.
+pub fn new_guid() -> String {
+    let mut rng = thread_rng();
+    let bytes: [u8; 16] = rng.gen();
+    let hex = bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<String>();
+    format!(
+        "{{{}-{}-{}-{}-{}}}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::display::{Comment, DisplayPatch, DisplayRequest};
+    use crate::controller::edit_action::{
+        AddComment, DeleteComment, EditAction, EditPayload, ReplyComment, ResolveComment,
+        UpdateComment,
+    };
+    use crate::test_utils::edit;
+    use crate::Workbook;
+
+    fn comments(wb: &mut Workbook) -> Vec<Comment> {
+        let response = wb.controller.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        response
+            .patches
+            .into_iter()
+            .find_map(|p| match p {
+                DisplayPatch::Comments(c) => Some(c.comments),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn update(row: usize, col: usize, author: &str, text: &str) -> EditPayload {
+        EditPayload::UpdateComment(UpdateComment {
+            sheet_idx: 0,
+            row,
+            col,
+            author: author.to_string(),
+            text: text.to_string(),
+        })
+    }
+
+    fn reply(row: usize, col: usize, author: &str, text: &str) -> EditPayload {
+        EditPayload::ReplyComment(ReplyComment {
+            sheet_idx: 0,
+            row,
+            col,
+            author: author.to_string(),
+            text: text.to_string(),
+        })
+    }
+
+    #[test]
+    fn comment_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![EditPayload::AddComment(AddComment {
+                sheet_idx: 0,
+                row: 1,
+                col: 1,
+                author: String::from("Alice"),
+                text: String::from("Check this"),
+            })],
+        );
+        let c = comments(&mut wb);
+        assert_eq!(c.len(), 1);
+        assert_eq!((c[0].row, c[0].col), (1, 1));
+        assert_eq!(c[0].author, "Alice");
+        assert_eq!(c[0].content, "Check this");
+
+        edit(&mut wb, vec![update(1, 1, "Alice", "Check this again")]);
+        // No comment here
+        edit(&mut wb, vec![reply(0, 0, "Bob", "Nothing")]);
+        edit(&mut wb, vec![reply(1, 1, "Bob", "Done")]);
+        edit(
+            &mut wb,
+            vec![EditPayload::ResolveComment(ResolveComment {
+                sheet_idx: 0,
+                row: 1,
+                col: 1,
+                resolved: true,
+            })],
+        );
+        let c = comments(&mut wb);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c[0].content, "Check this again");
+        assert_eq!(c[0].replies.len(), 1);
+        assert_eq!(c[0].replies[0].author, "Bob");
+        assert_eq!(c[0].replies[0].content, "Done");
+        assert!(c[0].resolved);
+
+        let saved = wb.controller.save().unwrap();
+        let mut loaded = Workbook::from_file(&saved, String::from("comment")).unwrap();
+        let c = comments(&mut loaded);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c[0].content, "Check this again");
+        assert_eq!(c[0].replies.len(), 1);
+        assert_eq!(c[0].replies[0].author, "Bob");
+        assert!(c[0].resolved);
+
+        // Another author takes over the thread.
+        edit(&mut wb, vec![update(1, 1, "Carol", "Checked")]);
+        let saved = wb.controller.save().unwrap();
+        let mut loaded = Workbook::from_file(&saved, String::from("comment")).unwrap();
+        let c = comments(&mut loaded);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c[0].author, "Carol");
+        assert_eq!(c[0].content, "Checked");
+        assert_eq!(c[0].replies.len(), 1);
+        assert_eq!(c[0].replies[0].author, "Bob");
+        assert!(c[0].resolved);
+
+        edit(
+            &mut wb,
+            vec![EditPayload::DeleteComment(DeleteComment {
+                sheet_idx: 0,
+                row: 1,
+                col: 1,
+            })],
+        );
+        assert!(comments(&mut wb).is_empty());
+        wb.handle_action(EditAction::Undo);
+        assert_eq!(comments(&mut wb)[0].replies.len(), 1);
+    }
+}
@@ -64,3 +64,100 @@ impl MergeCells {
         Some((start_cell.clone(), end_cell.clone()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::edit_action::{
+        ColShift, EditAction, EditPayload, MergeCells, RowShift, SplitMergedCells,
+    };
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    fn merge(start_row: usize, start_col: usize, end_row: usize, end_col: usize) -> EditPayload {
+        EditPayload::MergeCells(MergeCells {
+            sheet_idx: 0,
+            start_row,
+            start_col,
+            end_row,
+            end_col,
+        })
+    }
+
+    fn row_shift(row: usize, insert: bool) -> EditPayload {
+        EditPayload::RowShift(RowShift {
+            sheet_idx: 0,
+            row,
+            count: 1,
+            insert,
+        })
+    }
+
+    fn merged(wb: &mut Workbook) -> Vec<(usize, usize, usize, usize)> {
+        let mut merges = wb
+            .get_sheet_by_idx(0)
+            .unwrap()
+            .get_merge_cells()
+            .into_iter()
+            .map(|m| (m.row_start, m.col_start, m.row_end, m.col_end))
+            .collect::<Vec<_>>();
+        merges.sort();
+        merges
+    }
+
+    #[test]
+    fn merge_cells_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 0, 1, "2"),
+                input(0, 1, 0, "=B1*3"),
+                input(0, 0, 2, "=SUM(A1:B2)"),
+            ],
+        );
+        edit(&mut wb, vec![merge(0, 0, 1, 1)]);
+        assert_eq!(merged(&mut wb), vec![(0, 0, 1, 1)]);
+        assert_eq!(value(&mut wb, 0, 0, 0), "1");
+        assert_eq!(value(&mut wb, 0, 0, 1), "");
+        assert_eq!(value(&mut wb, 0, 1, 0), "");
+        let formula = wb.get_sheet_by_idx(0).unwrap().get_formula(1, 0).unwrap();
+        assert_eq!(formula, "");
+        assert_eq!(value(&mut wb, 0, 0, 2), "1");
+
+        // Overlapping
+        let effect = edit(&mut wb, vec![merge(1, 1, 2, 2)]);
+        assert_eq!(effect.invalid_merges.len(), 1);
+        assert_eq!(merged(&mut wb), vec![(0, 0, 1, 1)]);
+
+        edit(&mut wb, vec![row_shift(1, true)]);
+        assert_eq!(merged(&mut wb), vec![(0, 0, 2, 1)]);
+        edit(&mut wb, vec![row_shift(0, false)]);
+        assert_eq!(merged(&mut wb), vec![(0, 0, 1, 1)]);
+        edit(&mut wb, vec![row_shift(1, false)]);
+        assert_eq!(merged(&mut wb), vec![(0, 0, 0, 1)]);
+        edit(
+            &mut wb,
+            vec![EditPayload::ColShift(ColShift {
+                sheet_idx: 0,
+                col: 1,
+                count: 1,
+                insert: false,
+            })],
+        );
+        assert!(merged(&mut wb).is_empty());
+
+        wb.handle_action(EditAction::Undo);
+        assert_eq!(merged(&mut wb), vec![(0, 0, 0, 1)]);
+
+        edit(
+            &mut wb,
+            vec![EditPayload::SplitMergedCells(SplitMergedCells {
+                sheet_idx: 0,
+                row: 0,
+                col: 1,
+            })],
+        );
+        assert!(merged(&mut wb).is_empty());
+    }
+}
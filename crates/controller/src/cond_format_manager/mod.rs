@@ -0,0 +1,41 @@
+use im::HashMap;
+use logisheets_base::{NormalCellId, SheetId};
+use logisheets_parser::ast;
+use logisheets_workbook::prelude::CtCfRule;
+
+#[derive(Debug, Clone, Default)]
+pub struct CondFormatManager {
+    pub data: HashMap<SheetId, Vec<CondFormat>>,
+}
+
+// A `conditionalFormatting` element of a sheet.
+#[derive(Debug, Clone)]
+pub struct CondFormat {
+    // The start and end cells of the ranges in the sqref. The formulas in
+    // the rules are relative to the start cell of the first range.
+    pub ranges: Vec<(NormalCellId, NormalCellId)>,
+    pub rules: Vec<CondFormatRule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CondFormatRule {
+    // The formulas in `rule` are kept as they are loaded. The parsed ones
+    // are used in calculating and saving, so that they move with the cells.
+    pub rule: CtCfRule,
+    pub formulas: Vec<ast::Node>,
+}
+
+impl CondFormatManager {
+    pub fn add_cond_format(&mut self, sheet_id: SheetId, cond_format: CondFormat) {
+        match self.data.get_mut(&sheet_id) {
+            Some(v) => v.push(cond_format),
+            None => {
+                self.data.insert(sheet_id, vec![cond_format]);
+            }
+        }
+    }
+
+    pub fn get_cond_formats(&self, sheet_id: SheetId) -> &[CondFormat] {
+        self.data.get(&sheet_id).map_or(&[], |v| v.as_slice())
+    }
+}
@@ -5,6 +5,7 @@ use logisheets_base::get_active_sheet::GetActiveSheetTrait;
 use logisheets_base::get_curr_addr::GetCurrAddrTrait;
 use logisheets_base::set_curr_cell::SetCurrCellTrait;
 use logisheets_base::{
+    datetime::DateSystem,
     matrix_value::{cross_product_usize, MatrixValue},
     Addr, CellId, CellValue, Error, FuncId, NameId, SheetId, TextId,
 };
@@ -19,16 +20,20 @@ use crate::{
     },
     calc_engine::calculator::{
         calc_vertex::Value,
+        calculator::calc,
         infix::range::{get_range, get_range_without_prefix},
     },
     calc_engine::connector::Connector,
     cell::Cell,
-    container::DataContainer,
+    container::{DataContainer, SpillRange},
     ext_book_manager::ExtBooksManager,
-    id_manager::{FuncIdManager, TextIdManager},
+    id_manager::{FuncIdManager, SheetIdManager, TextIdManager},
     navigator::Navigator,
     vertex_manager::status::Status as VertexStatus,
-    vertex_manager::vertex::FormulaId,
+    vertex_manager::vertex::{
+        FormulaId, MutAddrRange, MutColRange, MutReferenceVertex, MutRowRange, SheetRangeVertex,
+        Vertex,
+    },
     workbook::sheet_pos_manager::SheetPosManager,
 };
 
@@ -39,13 +44,21 @@ pub struct CalcConnector<'a> {
     pub ext_links: &'a mut ExtBooksManager,
     pub text_id_manager: &'a mut TextIdManager,
     pub func_id_manager: &'a FuncIdManager,
-    pub names_storage: HashMap<NameId, CalcValue>,
+    // Names being calculated, used to find the names referring to themselves.
+    pub calculating_names: HashSet<NameId>,
     pub cells_stroage: HashMap<FormulaId, CalcValue>,
     pub sheet_pos_manager: &'a SheetPosManager,
+    pub sheet_id_manager: &'a SheetIdManager,
+    // References found by calculating the formulas, waiting to be committed
+    // with the values.
+    pub calc_dynamic_deps: HashMap<FormulaId, im::HashSet<Vertex>>,
+    // The dynamic dependencies that are new to the graph.
+    pub dynamic_deps: &'a mut HashMap<FormulaId, im::HashSet<Vertex>>,
     pub async_func_manager: &'a mut AsyncFuncManager,
     pub async_funcs: &'a HashSet<String>,
     pub active_sheet: SheetId,
     pub curr_addr: Addr,
+    pub date_system: DateSystem,
 }
 
 impl<'a> GetActiveSheetTrait for CalcConnector<'a> {
@@ -93,19 +106,20 @@ impl<'a> Connector for CalcConnector<'a> {
             }
             ast::CellReference::UnMut(unmut_ref) => self.convert_unmut_ref(unmut_ref),
             ast::CellReference::Name(nid) => {
-                if let Some(nf) = self.vertex_status.names.get(nid) {
-                    match &nf.pure {
-                        ast::PureNode::Reference(f) => self.convert(f),
-                        _ => {
-                            if let Some(v) = self.names_storage.get(nid) {
-                                CalcVertex::Value(v.clone())
-                            } else {
-                                CalcVertex::from_error(ast::Error::Name)
-                            }
+                let vertex_status = self.vertex_status;
+                match vertex_status.names.get(nid) {
+                    Some(nf) => {
+                        if !self.calculating_names.insert(*nid) {
+                            return CalcVertex::from_error(ast::Error::Ref);
                         }
+                        let result = match &nf.pure {
+                            ast::PureNode::Reference(f) => self.convert(f),
+                            _ => CalcVertex::Value(calc(nf, self)),
+                        };
+                        self.calculating_names.remove(nid);
+                        result
                     }
-                } else {
-                    CalcVertex::from_error(ast::Error::Value)
+                    None => CalcVertex::from_error(ast::Error::Name),
                 }
             }
         }
@@ -186,33 +200,112 @@ impl<'a> Connector for CalcConnector<'a> {
     }
 
     fn commit_calc_values(&mut self, vertex: FormulaId, result: CalcValue) -> HashSet<FormulaId> {
-        let dirties = HashSet::<FormulaId>::new();
-        let sheet_id = vertex.0;
-        let cell_id = vertex.1;
-        let cell_idx = self.navigator.fetch_cell_idx(sheet_id, &cell_id).unwrap();
-        match result {
-            CalcValue::Scalar(v) => {
-                let cell_value = value_to_cell_value(v, &mut |t| self.text_id_manager.get_id(&t));
-                self.set_cell_value(sheet_id, cell_idx.0, cell_idx.1, cell_value);
-                dirties
-            }
-            CalcValue::Range(_) => unreachable!(),
-            CalcValue::Union(_) => {
-                self.set_cell_value(
-                    sheet_id,
-                    cell_idx.0,
-                    cell_idx.1,
-                    CellValue::Error(Error::Value),
-                );
-                dirties
+        // A formula referring to new cells is calculated again after them.
+        let recalc_self = self.commit_dynamic_deps(&vertex);
+        let mut dirties = self.commit_calc_result(vertex, result);
+        if recalc_self {
+            dirties.insert(vertex);
+        }
+        dirties
+    }
+
+    fn get_spill_size(
+        &mut self,
+        sheet_id: SheetId,
+        row: usize,
+        col: usize,
+    ) -> Option<(usize, usize)> {
+        let cell_id = self.navigator.fetch_cell_id(sheet_id, row, col)?;
+        let spill = self
+            .container
+            .get_sheet_container(sheet_id)
+            .spills
+            .get(&cell_id)?;
+        if spill.blocked {
+            None
+        } else {
+            Some((spill.rows, spill.cols))
+        }
+    }
+
+    fn release_spills(&mut self, modified: &[FormulaId]) -> HashSet<FormulaId> {
+        let mut result = HashSet::new();
+        let modified_set = modified.iter().cloned().collect::<HashSet<_>>();
+        let sheets = self
+            .container
+            .data
+            .iter()
+            .filter(|(_, c)| !c.spills.is_empty())
+            .map(|(sheet_id, _)| *sheet_id)
+            .collect::<Vec<_>>();
+        sheets.into_iter().for_each(|sheet_id| {
+            let modified_cells = modified
+                .iter()
+                .filter(|(s, _)| *s == sheet_id)
+                .filter_map(|(_, cid)| self.navigator.fetch_cell_idx(sheet_id, cid))
+                .collect::<Vec<_>>();
+            if modified_cells.is_empty() {
+                return;
             }
-            CalcValue::Cube(_) => unreachable!(),
+            let spills = self.container.get_sheet_container(sheet_id).spills.clone();
+            spills.into_iter().for_each(|(anchor_id, spill)| {
+                let anchor = match self.navigator.fetch_cell_idx(sheet_id, &anchor_id) {
+                    Some(idx) => idx,
+                    None => {
+                        self.container
+                            .get_sheet_container(sheet_id)
+                            .spills
+                            .remove(&anchor_id);
+                        return;
+                    }
+                };
+                if !modified_cells
+                    .iter()
+                    .any(|(r, c)| spill.contains(anchor, *r, *c))
+                {
+                    return;
+                }
+                let changed = self.release_spill(sheet_id, &anchor_id, anchor, &modified_set);
+                result.extend(self.get_rdeps_of_cells(sheet_id, &changed));
+                let anchor_fid = (sheet_id, anchor_id);
+                if self.vertex_status.formulas.contains_key(&anchor_fid) {
+                    result.insert(anchor_fid);
+                }
+            });
+        });
+        result
+    }
+
+    fn get_sheet_id_by_name(&self, name: &str) -> Option<SheetId> {
+        let sheet_id = self.sheet_id_manager.has(name)?;
+        self.sheet_pos_manager.get_sheet_idx(sheet_id)?;
+        Some(sheet_id)
+    }
+
+    fn add_dynamic_dep(&mut self, reference: &CalcReference) {
+        if reference.from_sheet.is_some() {
+            return;
+        }
+        let (sheet_id, addr) = (self.active_sheet, self.curr_addr);
+        let cell_id = match self.navigator.fetch_cell_id(sheet_id, addr.row, addr.col) {
+            Some(cell_id) => cell_id,
+            None => return,
+        };
+        if let Some(vertex) = self.reference_to_vertex(reference) {
+            self.calc_dynamic_deps
+                .entry((sheet_id, cell_id))
+                .or_default()
+                .insert(vertex);
         }
     }
 
     fn is_async_func(&self, func_name: &str) -> bool {
         self.async_funcs.get(func_name).is_some()
     }
+
+    fn get_date_system(&self) -> DateSystem {
+        self.date_system
+    }
 }
 
 impl<'a> CalcConnector<'a> {
@@ -233,25 +326,27 @@ impl<'a> CalcConnector<'a> {
         }
     }
 
+    // Only the populated cells are visited and stored.
     fn get_matrix_between_rows(
         &mut self,
         sheet_id: SheetId,
         row_start: usize,
         row_end: usize,
     ) -> MatrixValue<Value> {
-        let sheet_container = self.container.data.get(&sheet_id);
-        if sheet_container.is_none() {
-            return MatrixValue::new(0, 0);
-        }
-        let sheet_container = sheet_container.unwrap().clone();
+        let sheet_container = match self.container.data.get(&sheet_id) {
+            Some(c) => c,
+            None => return MatrixValue::new(0, 0),
+        };
+        let navigator = &mut self.navigator;
+        let text_id_manager = &self.text_id_manager;
         // todo!() usize::MAX
         let mut matrix = MatrixValue::<Value>::new(row_end - row_start + 1, 65535);
         sheet_container.cells.iter().for_each(|(id, cell)| {
-            let idx = self.navigator.fetch_cell_idx(sheet_id, id);
+            let idx = navigator.fetch_cell_idx(sheet_id, id);
             if let Some((r, c)) = idx {
                 if r >= row_start && r <= row_end {
                     let v = Value::from_cell_value(cell.value.clone(), &|t| {
-                        self.text_id_manager.get_string(t)
+                        text_id_manager.get_string(t)
                     });
                     matrix.insert(r - row_start, c, v);
                 }
@@ -260,25 +355,27 @@ impl<'a> CalcConnector<'a> {
         matrix
     }
 
+    // Only the populated cells are visited and stored.
     fn get_matrix_between_cols(
         &mut self,
         sheet_id: SheetId,
         col_start: usize,
         col_end: usize,
     ) -> MatrixValue<Value> {
-        let sheet_container = self.container.data.get(&sheet_id);
-        if sheet_container.is_none() {
-            return MatrixValue::new(0, 0);
-        }
-        let sheet_container = sheet_container.unwrap().clone();
+        let sheet_container = match self.container.data.get(&sheet_id) {
+            Some(c) => c,
+            None => return MatrixValue::new(0, 0),
+        };
+        let navigator = &mut self.navigator;
+        let text_id_manager = &self.text_id_manager;
         // todo!() usize::MAX
         let mut matrix = MatrixValue::<Value>::new(65535, col_end - col_start + 1);
         sheet_container.cells.iter().for_each(|(id, cell)| {
-            let idx = self.navigator.fetch_cell_idx(sheet_id, id);
+            let idx = navigator.fetch_cell_idx(sheet_id, id);
             if let Some((r, c)) = idx {
                 if c >= col_start && c <= col_end {
                     let v = Value::from_cell_value(cell.value.clone(), &|t| {
-                        self.text_id_manager.get_string(t)
+                        text_id_manager.get_string(t)
                     });
                     matrix.insert(r, c - col_start, v);
                 }
@@ -356,6 +453,328 @@ impl<'a> CalcConnector<'a> {
         }
     }
 
+    // Write the array result into the anchor cell and its neighbours, or
+    // #SPILL! if some of them are occupied. Return the cells whose values are
+    // changed, except the anchor.
+    fn spill(
+        &mut self,
+        sheet_id: SheetId,
+        anchor_id: CellId,
+        anchor: (usize, usize),
+        matrix: MatrixValue<Value>,
+    ) -> Vec<(usize, usize)> {
+        let (rows, cols) = matrix.get_size();
+        let prev = self
+            .container
+            .get_sheet_container(sheet_id)
+            .spills
+            .get(&anchor_id)
+            .cloned()
+            .filter(|s| !s.blocked);
+        let curr = SpillRange {
+            rows,
+            cols,
+            blocked: false,
+        };
+        let blocked = self.is_spill_blocked(sheet_id, &anchor_id, anchor, &curr, &prev);
+        let mut changed = Vec::new();
+        if let Some(prev) = prev {
+            cross_product_usize(0, prev.rows - 1, 0, prev.cols - 1)
+                .into_iter()
+                .map(|(i, j)| (anchor.0 + i, anchor.1 + j))
+                .filter(|&pos| pos != anchor)
+                .filter(|(r, c)| blocked || !curr.contains(anchor, *r, *c))
+                .for_each(|(r, c)| {
+                    if self.replace_cell_value(sheet_id, r, c, CellValue::Blank) {
+                        changed.push((r, c));
+                    }
+                });
+        }
+        let spill = SpillRange { blocked, ..curr };
+        self.container
+            .get_sheet_container(sheet_id)
+            .spills
+            .insert(anchor_id, spill);
+        if blocked {
+            let v = CellValue::Error(Error::Spill);
+            self.set_cell_value(sheet_id, anchor.0, anchor.1, v);
+            return changed;
+        }
+        cross_product_usize(0, rows - 1, 0, cols - 1)
+            .into_iter()
+            .for_each(|(i, j)| {
+                let value = visit_matrix(&matrix, i, j);
+                let v = value_to_cell_value(value, &mut |t| self.text_id_manager.get_id(&t));
+                let (r, c) = (anchor.0 + i, anchor.1 + j);
+                if (r, c) == anchor {
+                    self.set_cell_value(sheet_id, r, c, v);
+                } else if self.replace_cell_value(sheet_id, r, c, v) {
+                    changed.push((r, c));
+                }
+            });
+        changed
+    }
+
+    // A spill is blocked by the formulas, the values not written by this
+    // anchor and the results of the other anchors.
+    fn is_spill_blocked(
+        &mut self,
+        sheet_id: SheetId,
+        anchor_id: &CellId,
+        anchor: (usize, usize),
+        curr: &SpillRange,
+        prev: &Option<SpillRange>,
+    ) -> bool {
+        let others = self
+            .container
+            .get_sheet_container(sheet_id)
+            .spills
+            .clone()
+            .into_iter()
+            .filter(|(id, s)| id != anchor_id && !s.blocked)
+            .filter_map(|(id, s)| {
+                let idx = self.navigator.fetch_cell_idx(sheet_id, &id)?;
+                Some((idx, s))
+            })
+            .collect::<Vec<_>>();
+        cross_product_usize(0, curr.rows - 1, 0, curr.cols - 1)
+            .into_iter()
+            .map(|(i, j)| (anchor.0 + i, anchor.1 + j))
+            .filter(|&pos| pos != anchor)
+            .any(|(r, c)| {
+                let cell_id = match self.navigator.fetch_cell_id(sheet_id, r, c) {
+                    Some(id) => id,
+                    None => return true,
+                };
+                if self
+                    .vertex_status
+                    .formulas
+                    .contains_key(&(sheet_id, cell_id))
+                {
+                    return true;
+                }
+                if others.iter().any(|(idx, s)| s.contains(*idx, r, c)) {
+                    return true;
+                }
+                match prev {
+                    Some(p) if p.contains(anchor, r, c) => false,
+                    _ => !matches!(
+                        self.get_cell_value(sheet_id, r, c),
+                        None | Some(CellValue::Blank)
+                    ),
+                }
+            })
+    }
+
+    // Remove the spill record of the anchor and clear the cells it wrote,
+    // leaving the formulas and the cells in `kept` untouched.
+    fn release_spill(
+        &mut self,
+        sheet_id: SheetId,
+        anchor_id: &CellId,
+        anchor: (usize, usize),
+        kept: &HashSet<FormulaId>,
+    ) -> Vec<(usize, usize)> {
+        let spill = self
+            .container
+            .get_sheet_container(sheet_id)
+            .spills
+            .remove(anchor_id);
+        let spill = match spill {
+            Some(s) if !s.blocked => s,
+            _ => return vec![],
+        };
+        cross_product_usize(0, spill.rows - 1, 0, spill.cols - 1)
+            .into_iter()
+            .map(|(i, j)| (anchor.0 + i, anchor.1 + j))
+            .filter(|&pos| pos != anchor)
+            .filter(|(r, c)| {
+                let fid = match self.navigator.fetch_cell_id(sheet_id, *r, *c) {
+                    Some(cid) => (sheet_id, cid),
+                    None => return false,
+                };
+                if kept.contains(&fid) || self.vertex_status.formulas.contains_key(&fid) {
+                    return false;
+                }
+                self.replace_cell_value(sheet_id, *r, *c, CellValue::Blank)
+            })
+            .collect()
+    }
+
+    fn commit_calc_result(&mut self, vertex: FormulaId, result: CalcValue) -> HashSet<FormulaId> {
+        let sheet_id = vertex.0;
+        let cell_id = vertex.1;
+        let cell_idx = self.navigator.fetch_cell_idx(sheet_id, &cell_id).unwrap();
+        let value = match result {
+            CalcValue::Scalar(v) => v,
+            CalcValue::Range(matrix) => match matrix.get_size() {
+                (0, _) | (_, 0) => Value::Error(ast::Error::Value),
+                // A 1x1 array spills too, so that `A1#` refers to it.
+                _ => {
+                    let changed = self.spill(sheet_id, cell_id, cell_idx, matrix);
+                    let mut dirties = self.get_rdeps_of_cells(sheet_id, &changed);
+                    dirties.remove(&vertex);
+                    return dirties;
+                }
+            },
+            CalcValue::Union(_) => Value::Error(ast::Error::Value),
+            CalcValue::Cube(_) => unreachable!(),
+        };
+        let cell_value = value_to_cell_value(value, &mut |t| self.text_id_manager.get_id(&t));
+        self.set_cell_value(sheet_id, cell_idx.0, cell_idx.1, cell_value);
+        let changed = self.release_spill(sheet_id, &cell_id, cell_idx, &HashSet::new());
+        let mut dirties = self.get_rdeps_of_cells(sheet_id, &changed);
+        dirties.remove(&vertex);
+        dirties
+    }
+
+    // Keep the references found in calculating this formula. Return true if
+    // the graph does not know them yet.
+    fn commit_dynamic_deps(&mut self, vertex: &FormulaId) -> bool {
+        let deps = match self.calc_dynamic_deps.remove(vertex) {
+            Some(deps) => deps,
+            None => return false,
+        };
+        let known = match self.vertex_status.graph.get_deps(&Vertex::Cell(*vertex)) {
+            Some(known) => deps.iter().all(|d| known.contains(d)),
+            None => false,
+        };
+        if known {
+            self.dynamic_deps.remove(vertex);
+            return false;
+        }
+        match self.dynamic_deps.insert(*vertex, deps.clone()) {
+            Some(prev) => prev != deps,
+            None => true,
+        }
+    }
+
+    fn reference_to_vertex(&mut self, reference: &CalcReference) -> Option<Vertex> {
+        let sheet_id = reference.sheet;
+        let navigator = &mut self.navigator;
+        let range = |reference| SheetRangeVertex {
+            sheet_id,
+            reference,
+        };
+        let vertex = match &reference.reference {
+            Reference::Addr(addr) => {
+                let cell_id = navigator.fetch_cell_id(sheet_id, addr.row, addr.col)?;
+                Vertex::Cell((sheet_id, cell_id))
+            }
+            Reference::ColumnRange(cr) => {
+                let start = navigator.fetch_col_id(sheet_id, cr.start)?;
+                let end = navigator.fetch_col_id(sheet_id, cr.end)?;
+                Vertex::SheetRange(range(MutReferenceVertex::ColRange(MutColRange {
+                    start,
+                    end,
+                })))
+            }
+            Reference::RowRange(rr) => {
+                let start = navigator.fetch_row_id(sheet_id, rr.start)?;
+                let end = navigator.fetch_row_id(sheet_id, rr.end)?;
+                Vertex::SheetRange(range(MutReferenceVertex::RowRange(MutRowRange {
+                    start,
+                    end,
+                })))
+            }
+            Reference::Range(r) => {
+                let start = navigator.fetch_cell_id(sheet_id, r.start.row, r.start.col)?;
+                let end = navigator.fetch_cell_id(sheet_id, r.end.row, r.end.col)?;
+                Vertex::SheetRange(range(MutReferenceVertex::AddrRange(MutAddrRange {
+                    start,
+                    end,
+                })))
+            }
+        };
+        Some(vertex)
+    }
+
+    // The formulas referring to these cells directly or by ranges.
+    fn get_rdeps_of_cells(
+        &mut self,
+        sheet_id: SheetId,
+        cells: &[(usize, usize)],
+    ) -> HashSet<FormulaId> {
+        let mut result = HashSet::new();
+        if cells.is_empty() {
+            return result;
+        }
+        let graph = &self.vertex_status.graph;
+        cells.iter().for_each(|(r, c)| {
+            if let Some(cid) = self.navigator.fetch_cell_id(sheet_id, *r, *c) {
+                let v = Vertex::Cell((sheet_id, cid));
+                result.extend(graph.get_cell_rdeps(&v));
+            }
+        });
+        if let Some(ranges) = self.vertex_status.range_vertices.get(&sheet_id) {
+            ranges.iter().for_each(|srv| {
+                let (start, end) = match &srv.reference {
+                    MutReferenceVertex::ColRange(cr) => {
+                        let s = self.navigator.fetch_col_idx(sheet_id, cr.start);
+                        let e = self.navigator.fetch_col_idx(sheet_id, cr.end);
+                        match (s, e) {
+                            (Some(s), Some(e)) => ((0, s), (usize::MAX, e)),
+                            _ => return,
+                        }
+                    }
+                    MutReferenceVertex::RowRange(rr) => {
+                        let s = self.navigator.fetch_row_idx(sheet_id, rr.start);
+                        let e = self.navigator.fetch_row_idx(sheet_id, rr.end);
+                        match (s, e) {
+                            (Some(s), Some(e)) => ((s, 0), (e, usize::MAX)),
+                            _ => return,
+                        }
+                    }
+                    MutReferenceVertex::AddrRange(ar) => {
+                        let s = self.navigator.fetch_cell_idx(sheet_id, &ar.start);
+                        let e = self.navigator.fetch_cell_idx(sheet_id, &ar.end);
+                        match (s, e) {
+                            (Some(s), Some(e)) => (s, e),
+                            _ => return,
+                        }
+                    }
+                };
+                let hit = cells
+                    .iter()
+                    .any(|(r, c)| *r >= start.0 && *r <= end.0 && *c >= start.1 && *c <= end.1);
+                if hit {
+                    let v = Vertex::SheetRange(srv.clone());
+                    result.extend(graph.get_cell_rdeps(&v));
+                }
+            });
+        }
+        self.vertex_status.sts_vertices.iter().for_each(|sts| {
+            let v = Vertex::StsRange(sts.clone());
+            result.extend(graph.get_cell_rdeps(&v));
+        });
+        result
+    }
+
+    // Set the value and tell whether it differs from the previous one.
+    fn replace_cell_value(
+        &mut self,
+        sheet_id: SheetId,
+        row_idx: usize,
+        col_idx: usize,
+        value: CellValue,
+    ) -> bool {
+        let prev = self
+            .get_cell_value(sheet_id, row_idx, col_idx)
+            .unwrap_or(CellValue::Blank);
+        let changed = match (&prev, &value) {
+            (CellValue::Blank, CellValue::Blank) => false,
+            (CellValue::Boolean(a), CellValue::Boolean(b)) => a != b,
+            (CellValue::Number(a), CellValue::Number(b)) => a != b,
+            (CellValue::String(a), CellValue::String(b)) => a != b,
+            (CellValue::Error(a), CellValue::Error(b)) => a.to_string() != b.to_string(),
+            _ => true,
+        };
+        if changed {
+            self.set_cell_value(sheet_id, row_idx, col_idx, value);
+        }
+        changed
+    }
+
     fn convert_a1ref_range(
         &mut self,
         sheet_id: SheetId,
@@ -544,6 +963,13 @@ fn unmut_ref_to_reference(r: &UnMutA1Reference) -> Reference {
     }
 }
 
+fn visit_matrix(matrix: &MatrixValue<Value>, i: usize, j: usize) -> Value {
+    match matrix.visit(i, j) {
+        Ok(v) => v.clone(),
+        Err(v) => v,
+    }
+}
+
 fn value_to_cell_value<F>(value: Value, text_converter: &mut F) -> CellValue
 where
     F: FnMut(String) -> TextId,
@@ -563,6 +989,7 @@ where
             ast::Error::Ref => CellValue::Error(Error::Ref),
             ast::Error::Value => CellValue::Error(Error::Value),
             ast::Error::GettingData => CellValue::Error(Error::GettingData),
+            ast::Error::Spill => CellValue::Error(Error::Spill),
         },
         Value::Date(d) => CellValue::Date(d),
     }
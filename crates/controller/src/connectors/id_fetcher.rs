@@ -3,7 +3,7 @@ use crate::{
     id_manager::{FuncIdManager, NameIdManager, SheetIdManager, TextIdManager},
     navigator::Navigator,
 };
-use logisheets_base::{id_fetcher::IdFetcherTrait, ExtBookId};
+use logisheets_base::{id_fetcher::IdFetcherTrait, ExtBookId, SheetId};
 
 pub struct IdFetcher<'a> {
     pub sheet_id_manager: &'a mut SheetIdManager,
@@ -44,6 +44,10 @@ impl<'a> IdFetcherTrait for IdFetcher<'a> {
         self.sheet_id_manager.get_id(sheet_name)
     }
 
+    fn find_sheet_id(&mut self, sheet_name: &str) -> Option<logisheets_base::SheetId> {
+        self.sheet_id_manager.has(sheet_name)
+    }
+
     fn fetch_name_id(&mut self, workbook: &Option<&str>, name: &str) -> logisheets_base::NameId {
         let book_id = match workbook {
             Some(book) => self.fetch_ext_book_id(book),
@@ -52,6 +56,14 @@ impl<'a> IdFetcherTrait for IdFetcher<'a> {
         self.name_id_manager.get_id(&(book_id, name.to_owned()))
     }
 
+    fn fetch_local_name_id(
+        &mut self,
+        sheet_id: SheetId,
+        name: &str,
+    ) -> Option<logisheets_base::NameId> {
+        self.name_id_manager.has_local(sheet_id, name)
+    }
+
     fn fetch_ext_book_id(&mut self, book: &str) -> logisheets_base::ExtBookId {
         self.external_links_manager.fetch_ext_book_id(book)
     }
@@ -42,11 +42,16 @@ impl<'a> NameFetcherTrait for NameFetcher<'a> {
 
     fn fetch_defined_name(&self, nid: &logisheets_base::NameId) -> String {
         match self.name_id_manager.get_string(nid) {
-            Some((_, name)) => name,
+            Some((0, name)) => name,
+            Some((book_id, name)) => format!("{}!{}", self.fetch_book_name(&book_id), name),
             None => String::from(""),
         }
     }
 
+    fn fetch_name_scope(&self, nid: &logisheets_base::NameId) -> Option<logisheets_base::SheetId> {
+        self.name_id_manager.get_scope(nid)
+    }
+
     fn fetch_cell_idx(
         &mut self,
         sheet_id: &logisheets_base::SheetId,
@@ -115,10 +115,18 @@ impl<'a> IdFetcherTrait for VertexConnector<'a> {
         self.get_id_fetcher().fetch_sheet_id(sheet_name)
     }
 
+    fn find_sheet_id(&mut self, sheet_name: &str) -> Option<SheetId> {
+        self.get_id_fetcher().find_sheet_id(sheet_name)
+    }
+
     fn fetch_name_id(&mut self, workbook: &Option<&str>, name: &str) -> NameId {
         self.get_id_fetcher().fetch_name_id(workbook, name)
     }
 
+    fn fetch_local_name_id(&mut self, sheet_id: SheetId, name: &str) -> Option<NameId> {
+        self.get_id_fetcher().fetch_local_name_id(sheet_id, name)
+    }
+
     fn fetch_ext_book_id(&mut self, book: &str) -> ExtBookId {
         self.get_id_fetcher().fetch_ext_book_id(book)
     }
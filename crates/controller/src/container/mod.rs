@@ -85,4 +85,24 @@ pub struct SheetDataContainer {
     pub cells: HashMap<CellId, Cell>,
     pub row_info: RowInfoManager,
     pub col_info: ColInfoManager,
+    // Array results keyed by their anchor cells.
+    pub spills: HashMap<CellId, SpillRange>,
+}
+
+// The area an array result takes, starting from the anchor cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpillRange {
+    pub rows: usize,
+    pub cols: usize,
+    // Some cells in this area are occupied and the anchor shows #SPILL!.
+    pub blocked: bool,
+}
+
+impl SpillRange {
+    pub fn contains(&self, anchor: (usize, usize), row: usize, col: usize) -> bool {
+        row >= anchor.0
+            && row < anchor.0 + self.rows
+            && col >= anchor.1
+            && col < anchor.1 + self.cols
+    }
 }
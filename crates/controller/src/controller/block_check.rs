@@ -0,0 +1,112 @@
+use logisheets_base::{BlockId, CellId};
+
+use super::edit_action::{BlockConflict, EditPayload, InvalidBlock};
+use super::status::Status;
+use crate::navigator::{BlockError, Navigator};
+
+// Take out the payloads creating a block with a taken id or over another
+// block. The blocks are created in a copy of the navigator so that the ones
+// created earlier in the same action are checked too.
+pub fn check_block_inputs(
+    status: &Status,
+    payloads: Vec<EditPayload>,
+) -> (Vec<EditPayload>, Vec<InvalidBlock>) {
+    let mut scratch: Option<Navigator> = None;
+    let mut invalid_blocks = vec![];
+    let payloads = payloads
+        .into_iter()
+        .filter(|p| {
+            let cb = match p {
+                EditPayload::CreateBlock(cb) => cb,
+                _ => return true,
+            };
+            let sheet_id = match status.sheet_pos_manager.get_sheet_id(cb.sheet_idx) {
+                Some(sheet_id) => sheet_id,
+                None => return true,
+            };
+            let navigator = scratch.get_or_insert_with(|| status.navigator.clone());
+            let block_id = cb.id as BlockId;
+            let result = match navigator.fetch_cell_id(sheet_id, cb.master_row, cb.master_col) {
+                Some(CellId::NormalCell(master)) => {
+                    navigator.create_block(sheet_id, block_id, master, cb.row_cnt, cb.col_cnt)
+                }
+                // The master cell is in another block.
+                Some(CellId::BlockCell(bc)) => Err(BlockError::Overlap(bc.block_id)),
+                None => return true,
+            };
+            let reason = match result {
+                Ok(_) => return true,
+                Err(BlockError::IdExists) => BlockConflict::IdExists,
+                Err(BlockError::Overlap(id)) => BlockConflict::Overlap(id as usize),
+            };
+            invalid_blocks.push(InvalidBlock {
+                sheet_idx: cb.sheet_idx,
+                id: cb.id,
+                reason,
+            });
+            false
+        })
+        .collect();
+    (payloads, invalid_blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::edit_action::{
+        BlockConflict, CreateBlock, EditAction, EditPayload, PayloadsAction,
+    };
+    use crate::Workbook;
+
+    fn create_block(id: usize, master_row: usize, master_col: usize) -> EditPayload {
+        EditPayload::CreateBlock(CreateBlock {
+            sheet_idx: 0,
+            id,
+            master_row,
+            master_col,
+            row_cnt: 2,
+            col_cnt: 2,
+        })
+    }
+
+    #[test]
+    fn block_conflict_test() {
+        let mut wb = Workbook::default();
+        let effect = wb
+            .handle_action(EditAction::Payloads(PayloadsAction {
+                payloads: vec![
+                    create_block(1, 0, 0),
+                    create_block(1, 5, 5),
+                    create_block(2, 1, 1),
+                    create_block(3, 0, 2),
+                ],
+                undoable: true,
+            }))
+            .unwrap();
+        let invalid = effect
+            .invalid_blocks
+            .iter()
+            .map(|b| (b.id, b.reason.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(invalid.len(), 2);
+        assert!(matches!(invalid[0], (1, BlockConflict::IdExists)));
+        assert!(matches!(invalid[1], (2, BlockConflict::Overlap(1))));
+
+        // The first block is kept at its place.
+        let sheet_id = wb.controller.get_sheet_id_by_idx(0).unwrap();
+        let navigator = &mut wb.controller.status.navigator;
+        let master = navigator.get_master_cell(sheet_id, 1).unwrap();
+        assert_eq!(navigator.fetch_cell_idx(sheet_id, &master), Some((0, 0)));
+        assert_eq!(navigator.get_block_size(sheet_id, 3), Some((2, 2)));
+
+        let effect = wb
+            .handle_action(EditAction::Payloads(PayloadsAction {
+                payloads: vec![create_block(4, 1, 3)],
+                undoable: true,
+            }))
+            .unwrap();
+        assert!(matches!(
+            effect.invalid_blocks[0].reason,
+            BlockConflict::Overlap(3)
+        ));
+    }
+}
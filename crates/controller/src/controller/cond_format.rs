@@ -0,0 +1,1124 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use logisheets_base::datetime::get_serial_num_by_date;
+use logisheets_base::{Addr, SheetId};
+use logisheets_parser::ast::{
+    self, A1Reference, A1ReferenceRange, Address, CellReference, MutRef, MutRefWithPrefix, PureNode,
+};
+use logisheets_parser::{context::Context, Parser};
+use logisheets_workbook::prelude::*;
+
+use super::display::{CellCondFormat, DataBar, Icon};
+use super::status::Status;
+use super::style::{Color, StyleConverter};
+use crate::async_func_manager::AsyncFuncManager;
+use crate::calc_engine::calculator::calc_vertex::{CalcValue, Value};
+use crate::calc_engine::calculator::calculator::calc;
+use crate::cond_format_manager::CondFormatRule;
+use crate::connectors::{CalcConnector, IdFetcher};
+use crate::navigator::Navigator;
+use crate::settings::Settings;
+
+type Pos = (usize, usize);
+
+// Evaluate the conditional formats of a sheet. The formulas are calculated in
+// a copy of the status, so that nothing is written back to the workbook.
+pub struct CondFormatEvaluator<'a> {
+    pub status: Status,
+    pub book_name: &'a str,
+    pub settings: &'a Settings,
+}
+
+impl<'a> CondFormatEvaluator<'a> {
+    pub fn evaluate(mut self, sheet_id: SheetId) -> Vec<CellCondFormat> {
+        let values = self.load_values(sheet_id);
+        // Ranges like `A:A` are clipped to the used area of the sheet.
+        let extent = values
+            .keys()
+            .fold((0, 0), |(r, c), (row, col)| (r.max(*row), c.max(*col)));
+        let formats = self
+            .status
+            .cond_format_manager
+            .get_cond_formats(sheet_id)
+            .to_vec();
+        let mut rules = vec![];
+        formats.iter().for_each(|cf| {
+            let (anchor, cells) = match self.get_cells(sheet_id, &cf.ranges, extent) {
+                Some(r) => r,
+                None => return,
+            };
+            cf.rules
+                .iter()
+                .for_each(|rule| rules.push((rule, anchor, cells.clone())));
+        });
+        rules.sort_by_key(|(rule, _, _)| rule.rule.priority);
+        // The results of the cells and whether a rule with `stopIfTrue`
+        // has been matched.
+        let mut result = BTreeMap::<Pos, (CellCondFormat, bool)>::new();
+        rules.into_iter().for_each(|(rule, anchor, cells)| {
+            let cells = cells
+                .into_iter()
+                .filter(|p| !result.get(p).is_some_and(|(_, stopped)| *stopped))
+                .collect::<Vec<_>>();
+            let matched = self.apply_rule(sheet_id, rule, anchor, &cells, &values);
+            matched.into_iter().for_each(|m| {
+                let (curr, stopped) = result.entry((m.row, m.col)).or_insert((
+                    CellCondFormat {
+                        row: m.row,
+                        col: m.col,
+                        style: None,
+                        color: None,
+                        data_bar: None,
+                        icon: None,
+                    },
+                    false,
+                ));
+                merge_cell_cond_format(curr, m);
+                *stopped = rule.rule.stop_if_true;
+            });
+        });
+        result
+            .into_values()
+            .map(|(c, _)| c)
+            .filter(|c| {
+                c.style.is_some() || c.color.is_some() || c.data_bar.is_some() || c.icon.is_some()
+            })
+            .collect()
+    }
+
+    fn load_values(&mut self, sheet_id: SheetId) -> HashMap<Pos, Value> {
+        let mut res = HashMap::new();
+        let sheet_data = match self.status.container.data.get(&sheet_id) {
+            Some(d) => d,
+            None => return res,
+        };
+        let navigator = &mut self.status.navigator;
+        let text_id_manager = &self.status.text_id_manager;
+        sheet_data.cells.iter().for_each(|(cell_id, cell)| {
+            if let Some(pos) = navigator.fetch_cell_idx(sheet_id, cell_id) {
+                let v =
+                    Value::from_cell_value(cell.value.clone(), &|t| text_id_manager.get_string(t));
+                res.insert(pos, v);
+            }
+        });
+        res
+    }
+
+    // Return the start cell of the first range and all the cells in the ranges.
+    fn get_cells(
+        &mut self,
+        sheet_id: SheetId,
+        ranges: &[(logisheets_base::NormalCellId, logisheets_base::NormalCellId)],
+        extent: Pos,
+    ) -> Option<(Pos, Vec<Pos>)> {
+        let navigator = &mut self.status.navigator;
+        let mut anchor = None;
+        let mut cells = BTreeSet::new();
+        ranges.iter().for_each(|(start, end)| {
+            let start = navigator.fetch_normal_cell_idx(sheet_id, start);
+            let end = navigator.fetch_normal_cell_idx(sheet_id, end);
+            if let (Some(start), Some(end)) = (start, end) {
+                anchor.get_or_insert(start);
+                for r in start.0..=end.0.min(extent.0) {
+                    for c in start.1..=end.1.min(extent.1) {
+                        cells.insert((r, c));
+                    }
+                }
+            }
+        });
+        Some((anchor?, cells.into_iter().collect()))
+    }
+
+    fn apply_rule(
+        &mut self,
+        sheet_id: SheetId,
+        rule: &CondFormatRule,
+        anchor: Pos,
+        cells: &[Pos],
+        values: &HashMap<Pos, Value>,
+    ) -> Vec<CellCondFormat> {
+        let blank = Value::Blank;
+        let value = |p: &Pos| values.get(p).unwrap_or(&blank);
+        let cf_rule = &rule.rule;
+        let matched = match cf_rule.ty {
+            StCfType::ColorScale | StCfType::DataBar | StCfType::IconSet => {
+                return self.apply_visual_rule(sheet_id, cf_rule, anchor, cells, values)
+            }
+            StCfType::CellIs => {
+                let op = match cf_rule.operator {
+                    Some(op) => op,
+                    None => return vec![],
+                };
+                cells
+                    .iter()
+                    .filter(|p| {
+                        let mut args = rule
+                            .formulas
+                            .iter()
+                            .map(|f| self.calc_formula(sheet_id, f, anchor, **p));
+                        let v = value(p);
+                        match (args.next(), args.next()) {
+                            (Some(a), b) => cell_is(v, op, &a, b.as_ref()),
+                            (None, _) => false,
+                        }
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>()
+            }
+            StCfType::Expression => match rule.formulas.first() {
+                Some(f) => cells
+                    .iter()
+                    .filter(|p| is_truthy(&self.calc_formula(sheet_id, f, anchor, **p)))
+                    .cloned()
+                    .collect(),
+                None => return vec![],
+            },
+            StCfType::Top10 => {
+                let mut nums = get_numbers(cells, values);
+                let rank = cf_rule.rank.unwrap_or(10) as usize;
+                let cnt = match cf_rule.percent {
+                    true => (nums.len() * rank / 100).max(1),
+                    false => rank,
+                };
+                if !cf_rule.bottom {
+                    nums.reverse();
+                }
+                match nums.get(cnt.min(nums.len()).wrapping_sub(1)) {
+                    Some(t) => filter_numbers(cells, values, |n| match cf_rule.bottom {
+                        true => n <= *t,
+                        false => n >= *t,
+                    }),
+                    None => vec![],
+                }
+            }
+            StCfType::AboveAverage => {
+                let nums = get_numbers(cells, values);
+                if nums.is_empty() {
+                    return vec![];
+                }
+                let cnt = nums.len() as f64;
+                let avg = nums.iter().sum::<f64>() / cnt;
+                let std_dev = (nums.iter().map(|n| (n - avg).powi(2)).sum::<f64>() / cnt).sqrt();
+                let bound = cf_rule.std_dev.unwrap_or(0) as f64 * std_dev;
+                filter_numbers(cells, values, |n| {
+                    match (cf_rule.above_average, cf_rule.equal_average) {
+                        (true, true) => n >= avg + bound,
+                        (true, false) => n > avg + bound,
+                        (false, true) => n <= avg - bound,
+                        (false, false) => n < avg - bound,
+                    }
+                })
+            }
+            StCfType::DuplicateValues | StCfType::UniqueValues => {
+                let mut counts = HashMap::<String, usize>::new();
+                cells
+                    .iter()
+                    .filter_map(|p| dup_key(value(p)))
+                    .for_each(|k| {
+                        *counts.entry(k).or_insert(0) += 1;
+                    });
+                let dup = cf_rule.ty == StCfType::DuplicateValues;
+                cells
+                    .iter()
+                    .filter(|p| match dup_key(value(p)) {
+                        Some(k) => (counts[&k] > 1) == dup,
+                        None => false,
+                    })
+                    .cloned()
+                    .collect()
+            }
+            StCfType::ContainsText
+            | StCfType::NotContainsText
+            | StCfType::BeginsWith
+            | StCfType::EndsWith => {
+                let text = match &cf_rule.text {
+                    Some(t) => t.to_lowercase(),
+                    None => return vec![],
+                };
+                cells
+                    .iter()
+                    .filter(|p| match to_text(value(p)) {
+                        Some(s) => match cf_rule.ty {
+                            StCfType::ContainsText => s.contains(&text),
+                            StCfType::NotContainsText => !s.contains(&text),
+                            StCfType::BeginsWith => s.starts_with(&text),
+                            _ => s.ends_with(&text),
+                        },
+                        None => false,
+                    })
+                    .cloned()
+                    .collect()
+            }
+            StCfType::ContainsBlanks | StCfType::NotContainsBlanks => {
+                let blanks = cf_rule.ty == StCfType::ContainsBlanks;
+                cells
+                    .iter()
+                    .filter(|p| {
+                        let is_blank = match value(p) {
+                            Value::Blank => true,
+                            Value::Text(s) => s.trim().is_empty(),
+                            _ => false,
+                        };
+                        is_blank == blanks
+                    })
+                    .cloned()
+                    .collect()
+            }
+            StCfType::ContainsErrors | StCfType::NotContainsErrors => {
+                let errors = cf_rule.ty == StCfType::ContainsErrors;
+                cells
+                    .iter()
+                    .filter(|p| matches!(value(p), Value::Error(_)) == errors)
+                    .cloned()
+                    .collect()
+            }
+            StCfType::TimePeriod => {
+                let period = match cf_rule.time_period {
+                    Some(p) => p,
+                    None => return vec![],
+                };
+                let today = Local::now().naive_local().date();
+                let date_system = self.settings.date_system;
+                let serial = get_serial_num_by_date(
+                    today.year() as u32,
+                    today.month(),
+                    today.day(),
+                    date_system,
+                );
+                let serial = match serial {
+                    Some(s) => s as i64,
+                    None => return vec![],
+                };
+                filter_numbers(cells, values, |n| {
+                    // The numbers out of the range of dates match no period.
+                    if !n.is_finite() {
+                        return false;
+                    }
+                    let date = (n.floor() as i64)
+                        .checked_sub(serial)
+                        .and_then(Duration::try_days)
+                        .and_then(|d| today.checked_add_signed(d));
+                    match date {
+                        Some(date) => in_time_period(date, today, period),
+                        None => false,
+                    }
+                })
+            }
+        };
+        let style = cf_rule.dxf_id.and_then(|id| {
+            let dxf = self.status.style_manager.dxfs.get(id as usize)?.clone();
+            let converter = StyleConverter {
+                theme_manager: &self.settings.theme,
+            };
+            Some(converter.convert_dxf(dxf))
+        });
+        matched
+            .into_iter()
+            .map(|(row, col)| CellCondFormat {
+                row,
+                col,
+                style: style.clone(),
+                color: None,
+                data_bar: None,
+                icon: None,
+            })
+            .collect()
+    }
+
+    fn apply_visual_rule(
+        &mut self,
+        sheet_id: SheetId,
+        rule: &CtCfRule,
+        anchor: Pos,
+        cells: &[Pos],
+        values: &HashMap<Pos, Value>,
+    ) -> Vec<CellCondFormat> {
+        let nums = get_numbers(cells, values);
+        let cfvos = match (&rule.color_scale, &rule.data_bar, &rule.icon_set) {
+            (Some(cs), _, _) if rule.ty == StCfType::ColorScale => &cs.cfvos,
+            (_, Some(db), _) if rule.ty == StCfType::DataBar => &db.cfvos,
+            (_, _, Some(is)) if rule.ty == StCfType::IconSet => &is.cfvos,
+            _ => return vec![],
+        };
+        let thresholds = cfvos
+            .iter()
+            .map(|cfvo| self.cfvo_value(sheet_id, cfvo, &nums, anchor))
+            .collect::<Option<Vec<_>>>();
+        let thresholds = match thresholds {
+            Some(t) if t.len() >= 2 => t,
+            _ => return vec![],
+        };
+        let converter = StyleConverter {
+            theme_manager: &self.settings.theme,
+        };
+        let colors = rule.color_scale.as_ref().map(|cs| {
+            cs.colors
+                .iter()
+                .map(|c| converter.convert_color(c.clone()))
+                .collect::<Vec<_>>()
+        });
+        let bar_color = rule
+            .data_bar
+            .as_ref()
+            .map(|db| converter.convert_color(db.color.clone()));
+        cells
+            .iter()
+            .filter_map(|(row, col)| {
+                let n = match values.get(&(*row, *col)) {
+                    Some(Value::Number(n)) => *n,
+                    _ => return None,
+                };
+                let mut res = CellCondFormat {
+                    row: *row,
+                    col: *col,
+                    style: None,
+                    color: None,
+                    data_bar: None,
+                    icon: None,
+                };
+                match rule.ty {
+                    StCfType::ColorScale => {
+                        let colors = colors.as_ref()?;
+                        if colors.len() != thresholds.len() {
+                            return None;
+                        }
+                        res.color = Some(get_scale_color(n, &thresholds, colors));
+                    }
+                    StCfType::DataBar => {
+                        let db = rule.data_bar.as_ref()?;
+                        let (min, max) = (thresholds[0], thresholds[1]);
+                        let frac = match max > min {
+                            true => ((n - min) / (max - min)).clamp(0., 1.),
+                            false => 0.,
+                        };
+                        let (min_len, max_len) = (db.min_length as f64, db.max_length as f64);
+                        res.data_bar = Some(DataBar {
+                            color: bar_color.clone()?,
+                            length: min_len + frac * (max_len - min_len),
+                            show_value: db.show_value,
+                        });
+                    }
+                    _ => {
+                        let is = rule.icon_set.as_ref()?;
+                        // The first cfvo is the lower bound of the lowest icon.
+                        let idx = cfvos
+                            .iter()
+                            .zip(thresholds.iter())
+                            .skip(1)
+                            .filter(|(cfvo, t)| match cfvo.gte {
+                                true => n >= **t,
+                                false => n > **t,
+                            })
+                            .count();
+                        let idx = match is.reverse {
+                            true => cfvos.len() - 1 - idx,
+                            false => idx,
+                        };
+                        res.icon = Some(Icon {
+                            icon_set: is.icon_set,
+                            idx,
+                            show_value: is.show_value,
+                        });
+                    }
+                }
+                Some(res)
+            })
+            .collect()
+    }
+
+    // `nums` is sorted.
+    fn cfvo_value(
+        &mut self,
+        sheet_id: SheetId,
+        cfvo: &CtCfvo,
+        nums: &[f64],
+        anchor: Pos,
+    ) -> Option<f64> {
+        let (min, max) = (*nums.first()?, *nums.last()?);
+        let val = || -> Option<f64> { cfvo.val.as_ref()?.trim().parse::<f64>().ok() };
+        match cfvo.ty {
+            StCfvoType::Min => Some(min),
+            StCfvoType::Max => Some(max),
+            StCfvoType::Num => val(),
+            StCfvoType::Percent => Some(min + (max - min) * val()? / 100.),
+            StCfvoType::Percentile => {
+                let rank = (nums.len() - 1) as f64 * val()?.clamp(0., 100.) / 100.;
+                let lo = rank.floor() as usize;
+                let hi = rank.ceil() as usize;
+                Some(nums[lo] + (rank - lo as f64) * (nums[hi] - nums[lo]))
+            }
+            StCfvoType::Formula => {
+                let f = cfvo.val.as_ref()?;
+                let f = f.strip_prefix('=').unwrap_or(f);
+                match self.calc_text_formula(sheet_id, f, anchor) {
+                    Value::Number(n) => Some(n),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    // The formulas of the cfvos are not parsed in loading, and they are
+    // parsed here.
+    fn calc_text_formula(&mut self, sheet_id: SheetId, formula: &str, anchor: Pos) -> Value {
+        let status = &mut self.status;
+        let mut id_fetcher = IdFetcher {
+            sheet_id_manager: &mut status.sheet_id_manager,
+            text_id_manager: &mut status.text_id_manager,
+            func_id_manager: &mut status.func_id_manager,
+            name_id_manager: &mut status.name_id_manager,
+            external_links_manager: &mut status.external_links_manager,
+            navigator: &mut status.navigator,
+        };
+        let mut context = Context {
+            sheet_id,
+            book_name: self.book_name,
+            id_fetcher: &mut id_fetcher,
+        };
+        match (Parser {}).parse(formula, &mut context) {
+            Some(node) => self.calc_formula(sheet_id, &node, anchor, anchor),
+            None => Value::Error(ast::Error::Name),
+        }
+    }
+
+    // Calculate the formula for the cell at `pos`. The relative references
+    // in the formula are moved by the offset from the anchor.
+    fn calc_formula(
+        &mut self,
+        sheet_id: SheetId,
+        node: &ast::Node,
+        anchor: Pos,
+        pos: Pos,
+    ) -> Value {
+        let dr = pos.0 as isize - anchor.0 as isize;
+        let dc = pos.1 as isize - anchor.1 as isize;
+        let status = &mut self.status;
+        let node = shift_node(&mut status.navigator, node, dr, dc);
+        let mut dynamic_deps = HashMap::new();
+        let mut async_func_manager = AsyncFuncManager::default();
+        let mut connector = CalcConnector {
+            vertex_status: &status.vertex_manager.status,
+            navigator: &mut status.navigator,
+            container: &mut status.container,
+            ext_links: &mut status.external_links_manager,
+            text_id_manager: &mut status.text_id_manager,
+            func_id_manager: &status.func_id_manager,
+            calculating_names: HashSet::new(),
+            cells_stroage: HashMap::new(),
+            sheet_pos_manager: &status.sheet_pos_manager,
+            sheet_id_manager: &status.sheet_id_manager,
+            calc_dynamic_deps: HashMap::new(),
+            dynamic_deps: &mut dynamic_deps,
+            async_func_manager: &mut async_func_manager,
+            async_funcs: &self.settings.async_funcs,
+            active_sheet: sheet_id,
+            curr_addr: Addr {
+                row: pos.0,
+                col: pos.1,
+            },
+            date_system: self.settings.date_system,
+        };
+        match calc(&node, &mut connector) {
+            CalcValue::Scalar(v) => v,
+            CalcValue::Range(m) => match m.visit(0, 0) {
+                Ok(v) => v.clone(),
+                Err(v) => v,
+            },
+            _ => Value::Error(ast::Error::Value),
+        }
+    }
+}
+
+fn merge_cell_cond_format(curr: &mut CellCondFormat, new: CellCondFormat) {
+    match (&mut curr.style, new.style) {
+        (None, s) => curr.style = s,
+        (Some(curr), Some(s)) => {
+            curr.font = curr.font.take().or(s.font);
+            curr.fill = curr.fill.take().or(s.fill);
+            curr.border = curr.border.take().or(s.border);
+            curr.formatter = curr.formatter.take().or(s.formatter);
+        }
+        (Some(_), None) => {}
+    }
+    curr.color = curr.color.take().or(new.color);
+    curr.data_bar = curr.data_bar.take().or(new.data_bar);
+    curr.icon = curr.icon.take().or(new.icon);
+}
+
+pub fn shift_node(navigator: &mut Navigator, node: &ast::Node, dr: isize, dc: isize) -> ast::Node {
+    if dr == 0 && dc == 0 {
+        return node.clone();
+    }
+    let pure = match &node.pure {
+        PureNode::Func(func) => PureNode::Func(ast::Func {
+            op: func.op.clone(),
+            args: func
+                .args
+                .iter()
+                .map(|arg| shift_node(navigator, arg, dr, dc))
+                .collect(),
+        }),
+        PureNode::Reference(CellReference::Mut(mut_ref)) => {
+            let sheet_id = mut_ref.sheet_id;
+            let reference = match &mut_ref.reference {
+                MutRef::A1Reference(r) => {
+                    shift_a1_ref(navigator, sheet_id, r, dr, dc).map(MutRef::A1Reference)
+                }
+                MutRef::A1ReferenceRange(r) => {
+                    let start = shift_a1_ref(navigator, sheet_id, &r.start, dr, dc);
+                    let end = shift_a1_ref(navigator, sheet_id, &r.end, dr, dc);
+                    match (start, end) {
+                        (Some(start), Some(end)) => {
+                            Some(MutRef::A1ReferenceRange(A1ReferenceRange { start, end }))
+                        }
+                        _ => None,
+                    }
+                }
+            };
+            match reference {
+                Some(reference) => PureNode::Reference(CellReference::Mut(MutRefWithPrefix {
+                    sheet_id,
+                    reference,
+                })),
+                None => PureNode::Value(ast::Value::Error(ast::Error::Ref)),
+            }
+        }
+        p => p.clone(),
+    };
+    ast::Node {
+        pure,
+        bracket: node.bracket,
+    }
+}
+
+fn shift_a1_ref(
+    navigator: &mut Navigator,
+    sheet_id: SheetId,
+    r: &A1Reference,
+    dr: isize,
+    dc: isize,
+) -> Option<A1Reference> {
+    let shift = |idx: usize, abs: bool, d: isize| match abs {
+        true => Some(idx),
+        false => idx.checked_add_signed(d),
+    };
+    match r {
+        A1Reference::Addr(addr) => {
+            let (row, col) = navigator.fetch_cell_idx(sheet_id, &addr.cell_id)?;
+            let row = shift(row, addr.row_abs, dr)?;
+            let col = shift(col, addr.col_abs, dc)?;
+            Some(A1Reference::Addr(Address {
+                cell_id: navigator.fetch_cell_id(sheet_id, row, col)?,
+                row_abs: addr.row_abs,
+                col_abs: addr.col_abs,
+            }))
+        }
+        A1Reference::A1RowRange(rr) => {
+            let start = navigator.fetch_row_idx(sheet_id, rr.start)?;
+            let end = navigator.fetch_row_idx(sheet_id, rr.end)?;
+            let start = shift(start, rr.start_abs, dr)?;
+            let end = shift(end, rr.end_abs, dr)?;
+            Some(A1Reference::A1RowRange(ast::RowRange {
+                start: navigator.fetch_row_id(sheet_id, start)?,
+                start_abs: rr.start_abs,
+                end: navigator.fetch_row_id(sheet_id, end)?,
+                end_abs: rr.end_abs,
+            }))
+        }
+        A1Reference::A1ColumnRange(cr) => {
+            let start = navigator.fetch_col_idx(sheet_id, cr.start)?;
+            let end = navigator.fetch_col_idx(sheet_id, cr.end)?;
+            let start = shift(start, cr.start_abs, dc)?;
+            let end = shift(end, cr.end_abs, dc)?;
+            Some(A1Reference::A1ColumnRange(ast::ColRange {
+                start: navigator.fetch_col_id(sheet_id, start)?,
+                start_abs: cr.start_abs,
+                end: navigator.fetch_col_id(sheet_id, end)?,
+                end_abs: cr.end_abs,
+            }))
+        }
+    }
+}
+
+// Sorted numbers in the cells.
+fn get_numbers(cells: &[Pos], values: &HashMap<Pos, Value>) -> Vec<f64> {
+    let mut nums = cells
+        .iter()
+        .filter_map(|p| match values.get(p) {
+            Some(Value::Number(n)) => Some(*n),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    nums.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    nums
+}
+
+fn filter_numbers<F>(cells: &[Pos], values: &HashMap<Pos, Value>, pred: F) -> Vec<Pos>
+where
+    F: Fn(f64) -> bool,
+{
+    cells
+        .iter()
+        .filter(|p| match values.get(p) {
+            Some(Value::Number(n)) => pred(*n),
+            _ => false,
+        })
+        .cloned()
+        .collect()
+}
+
+fn cell_is(v: &Value, op: StConditionalFormattingOperator, a: &Value, b: Option<&Value>) -> bool {
+    use StConditionalFormattingOperator::*;
+    let between = |a: &Value, b: &Value| {
+        let (lo, hi) = match compare(a, b) {
+            Some(Ordering::Greater) => (b, a),
+            Some(_) => (a, b),
+            None => return None,
+        };
+        Some(compare(v, lo)? != Ordering::Less && compare(v, hi)? != Ordering::Greater)
+    };
+    let res = match op {
+        Between => b.and_then(|b| between(a, b)),
+        NotBetween => b.and_then(|b| between(a, b)).map(|r| !r),
+        _ => compare(v, a).map(|ord| match op {
+            LessThan => ord == Ordering::Less,
+            LessThanOrEqual => ord != Ordering::Greater,
+            Equal => ord == Ordering::Equal,
+            NotEqual => ord != Ordering::Equal,
+            GreaterThanOrEqual => ord != Ordering::Less,
+            GreaterThan => ord == Ordering::Greater,
+            _ => false,
+        }),
+    };
+    res.unwrap_or(false)
+}
+
+// Like Excel, blank cells are regarded as 0 or empty texts, texts are
+// compared case-insensitively, and numbers < texts < booleans.
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    let rank = |v: &Value| match v {
+        Value::Number(_) => Some(0),
+        Value::Text(_) => Some(1),
+        Value::Boolean(_) => Some(2),
+        _ => None,
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.partial_cmp(y),
+        (Value::Text(x), Value::Text(y)) => Some(x.to_lowercase().cmp(&y.to_lowercase())),
+        (Value::Boolean(x), Value::Boolean(y)) => Some(x.cmp(y)),
+        (Value::Blank, Value::Blank) => Some(Ordering::Equal),
+        (Value::Blank, Value::Number(y)) => 0_f64.partial_cmp(y),
+        (Value::Number(x), Value::Blank) => x.partial_cmp(&0_f64),
+        (Value::Blank, Value::Text(y)) => Some(String::new().cmp(&y.to_lowercase())),
+        (Value::Text(x), Value::Blank) => Some(x.to_lowercase().cmp(&String::new())),
+        _ => Some(rank(a)?.cmp(&rank(b)?)),
+    }
+}
+
+fn is_truthy(v: &Value) -> bool {
+    match v {
+        Value::Boolean(b) => *b,
+        Value::Number(n) => *n != 0.,
+        _ => false,
+    }
+}
+
+fn dup_key(v: &Value) -> Option<String> {
+    match v {
+        Value::Number(n) => Some(format!("n{}", n)),
+        Value::Text(s) => Some(format!("t{}", s.to_lowercase())),
+        Value::Boolean(b) => Some(format!("b{}", b)),
+        _ => None,
+    }
+}
+
+fn to_text(v: &Value) -> Option<String> {
+    match v {
+        Value::Blank => Some(String::new()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Text(s) => Some(s.to_lowercase()),
+        Value::Boolean(b) => Some(String::from(if *b { "true" } else { "false" })),
+        _ => None,
+    }
+}
+
+// Weeks start on Sunday.
+fn in_time_period(date: NaiveDate, today: NaiveDate, period: StTimePeriod) -> bool {
+    let days = (date - today).num_days();
+    let week_start = -(today.weekday().num_days_from_sunday() as i64);
+    let month_diff = (date.year() - today.year()) * 12 + date.month() as i32 - today.month() as i32;
+    match period {
+        StTimePeriod::Today => days == 0,
+        StTimePeriod::Yesterday => days == -1,
+        StTimePeriod::Tomorrow => days == 1,
+        StTimePeriod::Last7Days => (-6..=0).contains(&days),
+        StTimePeriod::ThisWeek => (week_start..week_start + 7).contains(&days),
+        StTimePeriod::LastWeek => (week_start - 7..week_start).contains(&days),
+        StTimePeriod::NextWeek => (week_start + 7..week_start + 14).contains(&days),
+        StTimePeriod::ThisMonth => month_diff == 0,
+        StTimePeriod::LastMonth => month_diff == -1,
+        StTimePeriod::NextMonth => month_diff == 1,
+    }
+}
+
+fn get_scale_color(n: f64, thresholds: &[f64], colors: &[Color]) -> Color {
+    let last = thresholds.len() - 1;
+    if n <= thresholds[0] {
+        return colors[0].clone();
+    }
+    if n >= thresholds[last] {
+        return colors[last].clone();
+    }
+    let i = thresholds
+        .iter()
+        .rposition(|t| *t <= n)
+        .unwrap_or(0)
+        .min(last - 1);
+    let (lo, hi) = (thresholds[i], thresholds[i + 1]);
+    let frac = match hi > lo {
+        true => (n - lo) / (hi - lo),
+        false => 0.,
+    };
+    let lerp = |a: Option<f64>, b: Option<f64>| {
+        let (a, b) = (a.unwrap_or(0.), b.unwrap_or(0.));
+        Some(a + (b - a) * frac)
+    };
+    let (c0, c1) = (&colors[i], &colors[i + 1]);
+    Color {
+        red: lerp(c0.red, c1.red),
+        green: lerp(c0.green, c1.green),
+        blue: lerp(c0.blue, c1.blue),
+        alpha: lerp(c0.alpha, c1.alpha),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cond_format_manager::{CondFormat, CondFormatRule};
+    use crate::controller::display::{CellCondFormat, DisplayPatch, DisplayRequest};
+    use crate::controller::edit_action::{EditPayload, RowShift};
+    use crate::controller::Controller;
+    use crate::file_loader2::load;
+    use crate::test_utils::{edit, input};
+    use crate::Workbook;
+    use logisheets_parser::ast;
+    use logisheets_workbook::prelude::*;
+    use xmlserde::xml_deserialize_from_str;
+
+    #[test]
+    fn cond_format_test() {
+        let mut wb = Workbook::default();
+        let payloads = (0..5)
+            .flat_map(|row| {
+                vec![
+                    input(0, row, 0, &(row + 1).to_string()),
+                    input(0, row, 1, &(row % 2).to_string()),
+                ]
+            })
+            .collect();
+        edit(&mut wb, payloads);
+        let dxf = |code: &str| CtDxf {
+            font: None,
+            num_fmt: Some(CtNumFmt {
+                num_fmt_id: 0,
+                format_code: code.to_string(),
+            }),
+            fill: None,
+            alignment: None,
+            border: None,
+            protection: None,
+        };
+        wb.controller.status.style_manager.dxfs = vec![dxf("0.00"), dxf("0%")];
+        let rule = |ty: StCfType, priority: i32| CtCfRule {
+            formulas: vec![],
+            color_scale: None,
+            data_bar: None,
+            icon_set: None,
+            ty,
+            dxf_id: None,
+            priority,
+            stop_if_true: false,
+            above_average: true,
+            percent: false,
+            bottom: false,
+            operator: None,
+            text: None,
+            time_period: None,
+            rank: None,
+            std_dev: None,
+            equal_average: false,
+        };
+        let sheet_id = wb.controller.get_sheet_id_by_idx(0).unwrap();
+        let navigator = &mut wb.controller.status.navigator;
+        let b1 = navigator.fetch_cell_id(sheet_id, 0, 1).unwrap();
+        let range = (
+            navigator.fetch_norm_cell_id(sheet_id, 0, 0).unwrap(),
+            navigator.fetch_norm_cell_id(sheet_id, 4, 0).unwrap(),
+        );
+        // A1:A5 > 3, and B1 for each row.
+        let cell_is = CondFormatRule {
+            rule: CtCfRule {
+                dxf_id: Some(0),
+                operator: Some(StConditionalFormattingOperator::GreaterThan),
+                ..rule(StCfType::CellIs, 1)
+            },
+            formulas: vec![ast::Node {
+                pure: ast::PureNode::Value(ast::Value::Number(3.)),
+                bracket: false,
+            }],
+        };
+        let expression = CondFormatRule {
+            rule: CtCfRule {
+                dxf_id: Some(1),
+                ..rule(StCfType::Expression, 2)
+            },
+            formulas: vec![ast::Node {
+                pure: ast::PureNode::Reference(ast::CellReference::from_cell_id(sheet_id, b1)),
+                bracket: false,
+            }],
+        };
+        let cfvo = |ty: StCfvoType| CtCfvo {
+            ty,
+            val: None,
+            gte: true,
+        };
+        let data_bar = CondFormatRule {
+            rule: CtCfRule {
+                data_bar: Some(CtDataBar {
+                    cfvos: vec![cfvo(StCfvoType::Min), cfvo(StCfvoType::Max)],
+                    color: CtColor {
+                        auto: None,
+                        indexed: None,
+                        rgb: Some(String::from("FF638EC6")),
+                        theme: None,
+                        tint: 0.,
+                    },
+                    min_length: 0,
+                    max_length: 100,
+                    show_value: true,
+                }),
+                ..rule(StCfType::DataBar, 3)
+            },
+            formulas: vec![],
+        };
+        wb.controller.status.cond_format_manager.add_cond_format(
+            sheet_id,
+            CondFormat {
+                ranges: vec![range],
+                rules: vec![cell_is, expression, data_bar],
+            },
+        );
+
+        // (row, formatter, data bar length)
+        let cond_formats = |wb: &mut Controller| {
+            let response = wb.get_display_response(DisplayRequest {
+                sheet_idx: 0,
+                version: 0,
+            });
+            response
+                .patches
+                .into_iter()
+                .find_map(|p| match p {
+                    DisplayPatch::CondFormats(c) => Some(c.cells),
+                    _ => None,
+                })
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| {
+                    assert_eq!(c.col, 0);
+                    let formatter = c.style.and_then(|s| s.formatter).unwrap_or_default();
+                    (c.row, formatter, c.data_bar.unwrap().length.round())
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = vec![
+            (0, String::from(""), 0.),
+            (1, String::from("0%"), 25.),
+            (2, String::from(""), 50.),
+            (3, String::from("0.00"), 75.),
+            (4, String::from("0.00"), 100.),
+        ];
+        assert_eq!(cond_formats(&mut wb.controller), expected);
+
+        edit(
+            &mut wb,
+            vec![EditPayload::RowShift(RowShift {
+                sheet_idx: 0,
+                row: 0,
+                count: 1,
+                insert: false,
+            })],
+        );
+        let expected = vec![
+            (0, String::from("0%"), 0.),
+            (1, String::from(""), 33.),
+            (2, String::from("0.00"), 67.),
+            (3, String::from("0.00"), 100.),
+        ];
+        assert_eq!(cond_formats(&mut wb.controller), expected);
+
+        let book = read(&wb.controller.save().unwrap()).unwrap();
+        let mut loaded = load(book, String::from("Book1"));
+        assert_eq!(cond_formats(&mut loaded), expected);
+    }
+
+    // Replace the conditional formats of the first sheet and the dxfs with
+    // the given XML, and load the workbook again.
+    fn reload_with_cond_formats(wb: &Workbook, cond_formats: &str, dxfs: &str) -> Controller {
+        let mut book = read(&wb.controller.save().unwrap()).unwrap();
+        let worksheet = format!("<worksheet><sheetData/>{}</worksheet>", cond_formats);
+        let worksheet = xml_deserialize_from_str::<WorksheetPart>(&worksheet).unwrap();
+        let styles = format!("<styleSheet>{}</styleSheet>", dxfs);
+        let styles = xml_deserialize_from_str::<StylesheetPart>(&styles).unwrap();
+        let sheet = book.xl.worksheets.values_mut().next().unwrap();
+        sheet.worksheet_part.conditional_formatting = worksheet.conditional_formatting;
+        book.xl.styles.1.dxfs = styles.dxfs;
+        load(book, String::from("Book1"))
+    }
+
+    fn cond_format_cells(wb: &mut Controller) -> Vec<CellCondFormat> {
+        let response = wb.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        response
+            .patches
+            .into_iter()
+            .find_map(|p| match p {
+                DisplayPatch::CondFormats(c) => Some(c.cells),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn cond_format_time_period_test() {
+        let mut wb = Workbook::default();
+        let payloads = ["=TODAY()", "99999999", "-99999999", "=TODAY()+1"]
+            .iter()
+            .enumerate()
+            .map(|(row, content)| input(0, row, 0, content))
+            .collect();
+        edit(&mut wb, payloads);
+        let mut wb = reload_with_cond_formats(
+            &wb,
+            r#"<conditionalFormatting sqref="A1:A4">
+                <cfRule type="timePeriod" dxfId="0" priority="1" timePeriod="today">
+                    <formula>FLOOR(A1,1)=TODAY()</formula>
+                </cfRule>
+            </conditionalFormatting>"#,
+            r#"<dxfs count="1"><dxf><numFmt numFmtId="0" formatCode="0.00"/></dxf></dxfs>"#,
+        );
+        // The numbers out of the range of dates are skipped.
+        let rows = cond_format_cells(&mut wb)
+            .into_iter()
+            .map(|c| c.row)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![0]);
+    }
+
+    #[test]
+    fn cond_format_rule_types_test() {
+        let mut wb = Workbook::default();
+        let columns = [
+            vec!["1", "2", "3", "4", "5"],
+            vec!["apple", "Banana", "APPLE", "cherry", "grape"],
+            vec!["=TODAY()-1", "=TODAY()", "=TODAY()+7"],
+        ];
+        let payloads = columns
+            .iter()
+            .enumerate()
+            .flat_map(|(col, contents)| {
+                contents
+                    .iter()
+                    .enumerate()
+                    .map(move |(row, content)| input(0, row, col, content))
+            })
+            .collect();
+        edit(&mut wb, payloads);
+        let cells = |sqref: &str, rule: &str| {
+            let cond_formats = format!(
+                r#"<conditionalFormatting sqref="{}">{}</conditionalFormatting>"#,
+                sqref, rule
+            );
+            let dxfs =
+                r#"<dxfs count="1"><dxf><numFmt numFmtId="0" formatCode="0.00"/></dxf></dxfs>"#;
+            let mut loaded = reload_with_cond_formats(&wb, &cond_formats, dxfs);
+            cond_format_cells(&mut loaded)
+        };
+        let rows = |sqref: &str, rule: &str| {
+            cells(sqref, rule)
+                .into_iter()
+                .map(|c| {
+                    assert_eq!(c.style.unwrap().formatter.unwrap(), "0.00");
+                    c.row
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let top = r#"<cfRule type="top10" dxfId="0" priority="1" rank="2"/>"#;
+        assert_eq!(rows("A1:A5", top), vec![3, 4]);
+        let bottom =
+            r#"<cfRule type="top10" dxfId="0" priority="1" rank="40" percent="1" bottom="1"/>"#;
+        assert_eq!(rows("A1:A5", bottom), vec![0, 1]);
+
+        let above = r#"<cfRule type="aboveAverage" dxfId="0" priority="1"/>"#;
+        assert_eq!(rows("A1:A5", above), vec![3, 4]);
+        let below = r#"<cfRule type="aboveAverage" dxfId="0" priority="1" aboveAverage="0" equalAverage="1"/>"#;
+        assert_eq!(rows("A1:A5", below), vec![0, 1, 2]);
+
+        let duplicate = r#"<cfRule type="duplicateValues" dxfId="0" priority="1"/>"#;
+        assert_eq!(rows("B1:B5", duplicate), vec![0, 2]);
+
+        let contains = r#"<cfRule type="containsText" dxfId="0" priority="1" operator="containsText" text="AN">
+                <formula>NOT(ISERROR(SEARCH("AN",B1)))</formula>
+            </cfRule>"#;
+        assert_eq!(rows("B1:B5", contains), vec![1]);
+
+        let yesterday = r#"<cfRule type="timePeriod" dxfId="0" priority="1" timePeriod="yesterday">
+                <formula>FLOOR(C1,1)=TODAY()-1</formula>
+            </cfRule>"#;
+        assert_eq!(rows("C1:C3", yesterday), vec![0]);
+        let next_week = r#"<cfRule type="timePeriod" dxfId="0" priority="1" timePeriod="nextWeek">
+                <formula>AND(ROUNDDOWN(C1,0)-TODAY()&gt;(7-WEEKDAY(TODAY())),ROUNDDOWN(C1,0)-TODAY()&lt;(15-WEEKDAY(TODAY())))</formula>
+            </cfRule>"#;
+        assert_eq!(rows("C1:C3", next_week), vec![2]);
+
+        let color_scale = r#"<cfRule type="colorScale" priority="1">
+                <colorScale>
+                    <cfvo type="min"/><cfvo type="max"/>
+                    <color rgb="FFFF0000"/><color rgb="FF00FF00"/>
+                </colorScale>
+            </cfRule>"#;
+        let colors = cells("A1:A5", color_scale)
+            .into_iter()
+            .map(|c| {
+                let color = c.color.unwrap();
+                (c.row, color.red.unwrap(), color.green.unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(colors.len(), 5);
+        assert_eq!(colors[0], (0, 255., 0.));
+        assert_eq!(colors[4], (4, 0., 255.));
+        assert!(colors[2].1 > 0. && colors[2].1 < 255.);
+
+        let icon_set = r#"<cfRule type="iconSet" priority="1">
+                <iconSet iconSet="3Arrows">
+                    <cfvo type="percent" val="0"/>
+                    <cfvo type="percent" val="33"/>
+                    <cfvo type="percent" val="67"/>
+                </iconSet>
+            </cfRule>"#;
+        let icons = cells("A1:A5", icon_set)
+            .into_iter()
+            .map(|c| {
+                let icon = c.icon.unwrap();
+                assert_eq!(icon.icon_set, StIconSetType::ThreeArrows);
+                (c.row, icon.idx)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(icons, vec![(0, 0), (1, 0), (2, 1), (3, 2), (4, 2)]);
+    }
+}
@@ -1,5 +1,6 @@
-use super::style::Style;
+use super::style::{Color, DiffStyle, Style};
 use logisheets_base::BlockId;
+use logisheets_workbook::prelude::StIconSetType;
 use serde::Serialize;
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -21,6 +22,27 @@ pub enum DisplayPatch {
     Comments(SheetComments),
     Blocks(SheetBlocks),
     SheetNames(SheetNames),
+    Names(DefinedNames),
+    StaleCells(SheetStaleCells),
+    CondFormats(SheetCondFormats),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/defined_names.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DefinedNames {
+    pub names: Vec<DefinedName>,
+}
+
+// `sheet_idx` is None if the name is defined in the workbook scope. The
+// references in `refers_to` always come with their sheet names.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/defined_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DefinedName {
+    pub name: String,
+    pub sheet_idx: Option<usize>,
+    pub refers_to: String,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -73,6 +95,23 @@ pub struct SheetComments {
     pub comments: Vec<Comment>,
 }
 
+// The cells waiting for calculation in the manual calculation mode.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/sheet_stale_cells.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct SheetStaleCells {
+    pub sheet_idx: usize,
+    pub cells: Vec<StaleCell>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/stale_cell.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct StaleCell {
+    pub row: usize,
+    pub col: usize,
+}
+
 #[derive(Debug, Clone, Serialize, TS)]
 #[ts(export, export_to = "../../src/bindings/merge_cell.ts")]
 #[serde(rename_all = "camelCase")]
@@ -91,6 +130,16 @@ pub struct Comment {
     pub col: usize,
     pub author: String,
     pub content: String,
+    pub replies: Vec<CommentReply>,
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/comment_reply.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReply {
+    pub author: String,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -156,6 +205,10 @@ pub struct CellFormulaValue {
     pub col: usize,
     pub formula: String,
     pub value: Value,
+    // The value rendered with the number format of the cell.
+    pub formatted_text: String,
+    // The color given by the number format, like [Red]0.00.
+    pub color: Option<Color>,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -182,3 +235,48 @@ pub struct SheetStyles {
     pub sheet_idx: usize,
     pub styles: Vec<CellStyle>,
 }
+
+// The results of the conditional formats. Only the cells affected by some
+// rules are given.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/sheet_cond_formats.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct SheetCondFormats {
+    pub sheet_idx: usize,
+    pub cells: Vec<CellCondFormat>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/cell_cond_format.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct CellCondFormat {
+    pub row: usize,
+    pub col: usize,
+    // The differential formats of the matched rules. The parts from the rules
+    // with higher priorities take precedence.
+    pub style: Option<DiffStyle>,
+    // The fill color given by a color scale.
+    pub color: Option<Color>,
+    pub data_bar: Option<DataBar>,
+    pub icon: Option<Icon>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/data_bar.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DataBar {
+    pub color: Color,
+    // The percentage of the cell width.
+    pub length: f64,
+    pub show_value: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/icon.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct Icon {
+    pub icon_set: StIconSetType,
+    // The index of the icon in the set, 0 for the lowest values.
+    pub idx: usize,
+    pub show_value: bool,
+}
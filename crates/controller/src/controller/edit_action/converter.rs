@@ -1,22 +1,30 @@
-use logisheets_base::{BlockId, CellValue, TextId};
+use logisheets_base::datetime::DateSystem;
+use logisheets_base::input::{recognize_input, Locale, RecognizedInput};
+use logisheets_base::{BlockId, CellValue, SheetId, StyleId};
 
 use super::style_payload::{StyleUpdate, StyleUpdateType};
 use super::{
-    CellInput, ColShift, CreateBlock, EditPayload, LineShiftInBlock, MoveBlock, RowShift,
-    SetColWidth, SetRowHeight,
+    BlockInput, BlockStyleUpdate, CellInput, ColShift, CopySheet, CreateBlock, DefineName,
+    DeleteName, DeleteSheet, EditPayload, InsertSheet, LineShiftInBlock, MergeCells, MoveBlock,
+    MoveSheet, RenameName, RowShift, SetColWidth, SetRowHeight, SetVisible, SplitMergedCells,
 };
 use crate::container::DataContainer;
 use crate::id_manager::TextIdManager;
 use crate::navigator::Navigator;
+use crate::payloads::name::{AddName, NamePayload, RemoveName, RenameName as EditRenameName};
 use crate::payloads::sheet_process::style::{CellStylePayload, FontPayloadType};
 use crate::payloads::sheet_process::{
-    BlockDeleteColsPayload, BlockDeleteRowsPayload, BlockInsertColsPayload, BlockInsertRowsPayload,
-    BlockPayload, CellChange, CellPayload, ColInfoUpdate, CreateBlock as EditCreateBlock,
-    Direction, FormulaPayload, LineInfoUpdate, LinePayload, LineShift, MoveBlock as EditMoveBlock,
-    RowInfoUpdate, SheetPayload, SheetProcess, ShiftPayload, ShiftType,
+    AddCommentPayload, BlockDeleteColsPayload, BlockDeleteRowsPayload, BlockInsertColsPayload,
+    BlockInsertRowsPayload, BlockPayload, CellChange, CellPayload, ColInfoUpdate, CommentPayload,
+    CreateBlock as EditCreateBlock, DeleteCommentPayload, Direction, FormulaPayload,
+    LineInfoUpdate, LinePayload, LineShift, MergeCellsPayload, MergePayload,
+    MoveBlock as EditMoveBlock, ReplyCommentPayload, ResolveCommentPayload, RowInfoUpdate,
+    SheetPayload, SheetProcess, ShiftPayload, ShiftType, SplitMergedCellsPayload,
+    UpdateCommentPayload,
 };
-use crate::payloads::sheet_shift::SheetRenamePayload;
+use crate::payloads::sheet_shift::{SheetRenamePayload, SheetShiftPayload, SheetShiftType};
 use crate::payloads::Process;
+use crate::style_manager::StyleManager;
 use crate::workbook::sheet_pos_manager::SheetPosManager;
 
 pub struct Converter<'a> {
@@ -24,6 +32,9 @@ pub struct Converter<'a> {
     pub navigator: &'a mut Navigator,
     pub container: &'a mut DataContainer,
     pub text_id_manager: &'a mut TextIdManager,
+    pub style_manager: &'a StyleManager,
+    pub locale: &'a Locale,
+    pub date_system: DateSystem,
 }
 
 impl<'a> Converter<'a> {
@@ -31,24 +42,81 @@ impl<'a> Converter<'a> {
         let mut res = Vec::<Process>::with_capacity(payloads.len());
         payloads.into_iter().for_each(|c| {
             let proc = match c {
-                EditPayload::CellInput(c) => self.convert_cell_input(c),
+                EditPayload::CellInput(c) => {
+                    res.extend(self.convert_cell_input(c));
+                    None
+                }
                 EditPayload::RowShift(rs) => self.convert_row_shift(rs),
                 EditPayload::ColShift(cs) => self.convert_col_shift(cs),
                 EditPayload::StyleUpdate(su) => self.convert_style_update(su),
                 EditPayload::CreateBlock(cb) => self.convert_create_block(cb),
                 EditPayload::MoveBlock(mb) => self.convert_move_block(mb),
                 EditPayload::LineShiftInBlock(input) => self.convert_line_shift_in_block(input),
-                EditPayload::BlockInput(_) => todo!(),
-                EditPayload::BlockStyleUpdate(_) => todo!(),
+                EditPayload::BlockInput(bi) => {
+                    res.extend(self.convert_block_input(bi));
+                    None
+                }
+                EditPayload::BlockStyleUpdate(bsu) => self.convert_block_style_update(bsu),
                 EditPayload::SheetRename(sheet_rename) => {
                     Some(Process::SheetRename(SheetRenamePayload {
                         old_name: sheet_rename.old_name,
                         new_name: sheet_rename.new_name,
                     }))
                 }
+                EditPayload::InsertSheet(is) => self.convert_insert_sheet(is),
+                EditPayload::DeleteSheet(ds) => self.convert_delete_sheet(ds),
+                EditPayload::MoveSheet(ms) => self.convert_move_sheet(ms),
+                EditPayload::CopySheet(cs) => self.convert_copy_sheet(cs),
                 EditPayload::SetColWidth(scw) => self.convert_set_col_width(scw),
                 EditPayload::SetRowHeight(srh) => self.convert_set_row_height(srh),
-                EditPayload::SetVisible(_) => todo!(),
+                EditPayload::SetVisible(sv) => self.convert_set_visible(sv),
+                EditPayload::MergeCells(mc) => self.convert_merge_cells(mc),
+                EditPayload::SplitMergedCells(smc) => self.convert_split_merged_cells(smc),
+                EditPayload::AddComment(ac) => self.convert_comment(
+                    ac.sheet_idx,
+                    CommentPayload::Add(AddCommentPayload {
+                        row: ac.row,
+                        col: ac.col,
+                        author: ac.author,
+                        text: ac.text,
+                    }),
+                ),
+                EditPayload::UpdateComment(uc) => self.convert_comment(
+                    uc.sheet_idx,
+                    CommentPayload::Update(UpdateCommentPayload {
+                        row: uc.row,
+                        col: uc.col,
+                        author: uc.author,
+                        text: uc.text,
+                    }),
+                ),
+                EditPayload::DeleteComment(dc) => self.convert_comment(
+                    dc.sheet_idx,
+                    CommentPayload::Delete(DeleteCommentPayload {
+                        row: dc.row,
+                        col: dc.col,
+                    }),
+                ),
+                EditPayload::ReplyComment(rc) => self.convert_comment(
+                    rc.sheet_idx,
+                    CommentPayload::Reply(ReplyCommentPayload {
+                        row: rc.row,
+                        col: rc.col,
+                        author: rc.author,
+                        text: rc.text,
+                    }),
+                ),
+                EditPayload::ResolveComment(rc) => self.convert_comment(
+                    rc.sheet_idx,
+                    CommentPayload::Resolve(ResolveCommentPayload {
+                        row: rc.row,
+                        col: rc.col,
+                        resolved: rc.resolved,
+                    }),
+                ),
+                EditPayload::DefineName(dn) => self.convert_define_name(dn),
+                EditPayload::DeleteName(dn) => self.convert_delete_name(dn),
+                EditPayload::RenameName(rn) => self.convert_rename_name(rn),
             };
             match proc {
                 Some(p) => {
@@ -60,10 +128,90 @@ impl<'a> Converter<'a> {
         res
     }
 
+    fn convert_insert_sheet(&mut self, is: InsertSheet) -> Option<Process> {
+        if is.sheet_idx > self.sheet_pos_manager.pos.len() {
+            return None;
+        }
+        Some(Process::SheetShift(SheetShiftPayload {
+            idx: is.sheet_idx,
+            ty: SheetShiftType::Insert,
+        }))
+    }
+
+    fn convert_delete_sheet(&mut self, ds: DeleteSheet) -> Option<Process> {
+        self.sheet_pos_manager.get_sheet_id(ds.sheet_idx)?;
+        if self.sheet_pos_manager.pos.len() == 1 {
+            return None;
+        }
+        Some(Process::SheetShift(SheetShiftPayload {
+            idx: ds.sheet_idx,
+            ty: SheetShiftType::Delete,
+        }))
+    }
+
+    fn convert_move_sheet(&mut self, ms: MoveSheet) -> Option<Process> {
+        self.sheet_pos_manager.get_sheet_id(ms.sheet_idx)?;
+        self.sheet_pos_manager.get_sheet_id(ms.new_idx)?;
+        Some(Process::SheetShift(SheetShiftPayload {
+            idx: ms.sheet_idx,
+            ty: SheetShiftType::Move(ms.new_idx),
+        }))
+    }
+
+    fn convert_copy_sheet(&mut self, cs: CopySheet) -> Option<Process> {
+        self.sheet_pos_manager.get_sheet_id(cs.sheet_idx)?;
+        if cs.new_idx > self.sheet_pos_manager.pos.len() {
+            return None;
+        }
+        Some(Process::SheetShift(SheetShiftPayload {
+            idx: cs.sheet_idx,
+            ty: SheetShiftType::Copy(cs.new_idx),
+        }))
+    }
+
+    // Get the sheet id of a name scope. Return None if the sheet does not exist.
+    fn get_name_scope(&self, sheet_idx: Option<usize>) -> Option<Option<SheetId>> {
+        match sheet_idx {
+            Some(idx) => Some(Some(self.sheet_pos_manager.get_sheet_id(idx)?)),
+            None => Some(None),
+        }
+    }
+
+    fn convert_define_name(&mut self, dn: DefineName) -> Option<Process> {
+        let scope = self.get_name_scope(dn.sheet_idx)?;
+        let refer_to = match dn.refers_to.strip_prefix('=') {
+            Some(f) => f.to_string(),
+            None => dn.refers_to,
+        };
+        Some(Process::Name(NamePayload::Add(AddName {
+            name: dn.name,
+            scope,
+            refer_to,
+        })))
+    }
+
+    fn convert_delete_name(&mut self, dn: DeleteName) -> Option<Process> {
+        let scope = self.get_name_scope(dn.sheet_idx)?;
+        Some(Process::Name(NamePayload::Remove(RemoveName {
+            name: dn.name,
+            scope,
+        })))
+    }
+
+    fn convert_rename_name(&mut self, rn: RenameName) -> Option<Process> {
+        let scope = self.get_name_scope(rn.sheet_idx)?;
+        Some(Process::Name(NamePayload::Rename(EditRenameName {
+            old_name: rn.old_name,
+            new_name: rn.new_name,
+            scope,
+        })))
+    }
+
     fn convert_set_row_height(&mut self, srh: SetRowHeight) -> Option<Process> {
         let sheet_id = self.sheet_pos_manager.get_sheet_id(srh.sheet_idx)?;
         let line_payload = LinePayload {
             idx: srh.row,
+            cnt: 1,
             change: LineInfoUpdate::Row(RowInfoUpdate::Height(srh.height)),
         };
         let sp = SheetProcess {
@@ -77,6 +225,7 @@ impl<'a> Converter<'a> {
         let sheet_id = self.sheet_pos_manager.get_sheet_id(scw.sheet_idx)?;
         let line_payload = LinePayload {
             idx: scw.col,
+            cnt: 1,
             change: LineInfoUpdate::Col(ColInfoUpdate::Width(scw.width)),
         };
         let sp = SheetProcess {
@@ -86,6 +235,57 @@ impl<'a> Converter<'a> {
         Some(Process::Sheet(sp))
     }
 
+    fn convert_set_visible(&mut self, sv: SetVisible) -> Option<Process> {
+        let sheet_id = self.sheet_pos_manager.get_sheet_id(sv.sheet_idx)?;
+        let change = if sv.is_row {
+            LineInfoUpdate::Row(RowInfoUpdate::Hidden(!sv.visible))
+        } else {
+            LineInfoUpdate::Col(ColInfoUpdate::Hidden(!sv.visible))
+        };
+        Some(Process::Sheet(SheetProcess {
+            sheet_id,
+            payload: SheetPayload::Line(LinePayload {
+                idx: sv.start,
+                cnt: sv.count,
+                change,
+            }),
+        }))
+    }
+
+    fn convert_merge_cells(&mut self, mc: MergeCells) -> Option<Process> {
+        let sheet_id = self.sheet_pos_manager.get_sheet_id(mc.sheet_idx)?;
+        let p = MergeCellsPayload {
+            start_row: mc.start_row,
+            start_col: mc.start_col,
+            end_row: mc.end_row,
+            end_col: mc.end_col,
+        };
+        Some(Process::Sheet(SheetProcess {
+            sheet_id,
+            payload: SheetPayload::Merge(MergePayload::Merge(p)),
+        }))
+    }
+
+    fn convert_split_merged_cells(&mut self, smc: SplitMergedCells) -> Option<Process> {
+        let sheet_id = self.sheet_pos_manager.get_sheet_id(smc.sheet_idx)?;
+        let p = SplitMergedCellsPayload {
+            row: smc.row,
+            col: smc.col,
+        };
+        Some(Process::Sheet(SheetProcess {
+            sheet_id,
+            payload: SheetPayload::Merge(MergePayload::Split(p)),
+        }))
+    }
+
+    fn convert_comment(&mut self, sheet_idx: usize, p: CommentPayload) -> Option<Process> {
+        let sheet_id = self.sheet_pos_manager.get_sheet_id(sheet_idx)?;
+        Some(Process::Sheet(SheetProcess {
+            sheet_id,
+            payload: SheetPayload::Comment(p),
+        }))
+    }
+
     fn convert_style_update(&mut self, su: StyleUpdate) -> Option<Process> {
         let StyleUpdate {
             sheet_idx,
@@ -146,16 +346,158 @@ impl<'a> Converter<'a> {
         Some(Process::Sheet(proc))
     }
 
-    fn convert_cell_input(&mut self, input: CellInput) -> Option<Process> {
+    fn convert_cell_input(&mut self, input: CellInput) -> Vec<Process> {
         let CellInput {
             sheet_idx,
             row,
             col,
             content,
         } = input;
+        match self.sheet_pos_manager.get_sheet_id(sheet_idx) {
+            Some(sheet_id) => self.get_input_procs(sheet_id, row, col, content),
+            None => vec![],
+        }
+    }
+
+    fn convert_block_input(&mut self, input: BlockInput) -> Vec<Process> {
+        let BlockInput {
+            sheet_idx,
+            block_id,
+            row,
+            col,
+            input,
+        } = input;
+        let idx = self
+            .sheet_pos_manager
+            .get_sheet_id(sheet_idx)
+            .and_then(|sheet_id| {
+                let (row, col) =
+                    self.get_block_cell_idx(sheet_id, block_id as BlockId, row, col)?;
+                Some((sheet_id, row, col))
+            });
+        match idx {
+            Some((sheet_id, row, col)) => self.get_input_procs(sheet_id, row, col, input),
+            None => vec![],
+        }
+    }
+
+    // A recognized number also sets the number format of the cell if the
+    // cell is in the General format, as Excel does.
+    fn get_input_procs(
+        &mut self,
+        sheet_id: SheetId,
+        row: usize,
+        col: usize,
+        mut content: String,
+    ) -> Vec<Process> {
+        let payloads = if content.starts_with('=') {
+            let formula = content.split_off(1);
+            vec![SheetPayload::Formula(FormulaPayload { row, col, formula })]
+        } else {
+            match recognize_input(&content, self.locale, self.date_system) {
+                Some(RecognizedInput { value, num_fmt }) => {
+                    let mut payloads = vec![SheetPayload::Cell(CellPayload {
+                        row,
+                        col,
+                        change: CellChange::Value(CellValue::Number(value)),
+                    })];
+                    match num_fmt {
+                        Some(code) if self.is_general(sheet_id, row, col) => {
+                            payloads.push(SheetPayload::Cell(CellPayload {
+                                row,
+                                col,
+                                change: CellChange::DiffStyle(vec![CellStylePayload::NumFmt(code)]),
+                            }))
+                        }
+                        _ => {}
+                    }
+                    payloads
+                }
+                None => {
+                    let fetcher = &mut |t: &str| self.text_id_manager.get_id(t);
+                    let value = CellValue::from_string(content, fetcher);
+                    vec![SheetPayload::Cell(CellPayload {
+                        row,
+                        col,
+                        change: CellChange::Value(value),
+                    })]
+                }
+            }
+        };
+        payloads
+            .into_iter()
+            .map(|payload| Process::Sheet(SheetProcess { sheet_id, payload }))
+            .collect()
+    }
+
+    // A cell without its own style takes the style of its row, or of its
+    // column, like what is displayed.
+    fn is_general(&mut self, sheet_id: SheetId, row: usize, col: usize) -> bool {
+        let cell_style = self
+            .navigator
+            .fetch_cell_id(sheet_id, row, col)
+            .and_then(|cell_id| self.container.get_cell(sheet_id, &cell_id))
+            .map(|cell| cell.style);
+        let style = match cell_style {
+            Some(style) => style,
+            None => self.get_line_style(sheet_id, row, col),
+        };
+        let code = self.style_manager.get_cell_style(style).formatter;
+        code.trim() == "" || code.eq_ignore_ascii_case("General")
+    }
+
+    fn get_line_style(&mut self, sheet_id: SheetId, row: usize, col: usize) -> StyleId {
+        let row_style = self
+            .navigator
+            .fetch_row_id(sheet_id, row)
+            .and_then(|row_id| self.container.get_row_info(sheet_id, row_id))
+            .filter(|info| info.custom_format)
+            .map(|info| info.style);
+        if let Some(style) = row_style {
+            return style;
+        }
+        self.navigator
+            .fetch_col_id(sheet_id, col)
+            .and_then(|col_id| self.container.get_col_info(sheet_id, col_id))
+            .map_or(0, |info| info.style)
+    }
+
+    fn convert_block_style_update(&mut self, bsu: BlockStyleUpdate) -> Option<Process> {
+        let BlockStyleUpdate {
+            sheet_idx,
+            block_id,
+            row,
+            col,
+            style_update,
+        } = bsu;
         let sheet_id = self.sheet_pos_manager.get_sheet_id(sheet_idx)?;
-        let payload = get_input_payload(row, col, content, &mut |t| self.text_id_manager.get_id(t));
-        Some(Process::Sheet(SheetProcess { sheet_id, payload }))
+        let (row, col) = self.get_block_cell_idx(sheet_id, block_id as BlockId, row, col)?;
+        let p = CellPayload {
+            row,
+            col,
+            change: CellChange::DiffStyle(get_style_payload(style_update)),
+        };
+        Some(Process::Sheet(SheetProcess {
+            sheet_id,
+            payload: SheetPayload::Cell(p),
+        }))
+    }
+
+    // Translate the block-relative position into the position in the sheet.
+    fn get_block_cell_idx(
+        &mut self,
+        sheet_id: SheetId,
+        block_id: BlockId,
+        row: usize,
+        col: usize,
+    ) -> Option<(usize, usize)> {
+        let (row_cnt, col_cnt) = self.navigator.get_block_size(sheet_id, block_id)?;
+        if row >= row_cnt || col >= col_cnt {
+            return None;
+        }
+        let master = self.navigator.get_master_cell(sheet_id, block_id)?;
+        let (master_row, master_col) = self.navigator.fetch_cell_idx(sheet_id, &master)?;
+        Some((master_row + row, master_col + col))
     }
 
     fn convert_create_block(&mut self, input: CreateBlock) -> Option<Process> {
@@ -245,37 +587,6 @@ impl<'a> Converter<'a> {
     }
 }
 
-fn get_input_payload<F>(
-    row: usize,
-    col: usize,
-    mut content: String,
-    fetcher: &mut F,
-) -> SheetPayload
-where
-    F: FnMut(&str) -> TextId,
-{
-    let (formula, content) = if content.starts_with('=') {
-        (true, content.split_off(1))
-    } else {
-        (false, content)
-    };
-    if formula {
-        let f = FormulaPayload {
-            row,
-            col,
-            formula: content,
-        };
-        SheetPayload::Formula(f)
-    } else {
-        let cell_value = CellValue::from_string(content, fetcher);
-        SheetPayload::Cell(CellPayload {
-            row,
-            col,
-            change: CellChange::Value(cell_value),
-        })
-    }
-}
-
 fn get_style_payload(sut: StyleUpdateType) -> Vec<CellStylePayload> {
     let mut result = Vec::<CellStylePayload>::new();
     if let Some(fb) = sut.set_font_bold {
@@ -293,3 +604,126 @@ fn get_style_payload(sut: StyleUpdateType) -> Vec<CellStylePayload> {
     // todo!()
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::container::{col_info_manager::ColInfo, row_info_manager::RowInfo};
+    use crate::controller::display::{DisplayPatch, DisplayRequest};
+    use crate::controller::edit_action::EditAction;
+    use crate::test_utils::{edit, input, set_num_fmt};
+    use crate::{Value, Workbook};
+    use logisheets_base::input::{DateOrder, Locale};
+
+    // The formatted texts of the cells, in the order of rows and columns.
+    fn formatted(wb: &mut Workbook) -> Vec<String> {
+        let response = wb.controller.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        let mut values = response
+            .patches
+            .into_iter()
+            .find_map(|p| match p {
+                DisplayPatch::Values(v) => Some(v.values),
+                _ => None,
+            })
+            .unwrap()
+            .into_iter()
+            .map(|v| ((v.row, v.col), v.formatted_text))
+            .collect::<Vec<_>>();
+        values.sort();
+        values.into_iter().map(|(_, t)| t).collect()
+    }
+
+    #[test]
+    fn input_recognizer_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "12%"),
+                input(0, 0, 1, "$1,200.50"),
+                input(0, 0, 2, "2024-03-01"),
+                input(0, 0, 3, "3:45 PM"),
+                input(0, 0, 4, "0 1/2"),
+                input(0, 0, 5, "=A1*B1"),
+            ],
+        );
+        assert_eq!(
+            formatted(&mut wb),
+            vec![
+                "12%",
+                "$1,200.50",
+                "2024-03-01",
+                "3:45 PM",
+                " 1/2",
+                "144.06"
+            ]
+        );
+
+        // The format of a cell is kept if it is not General.
+        set_num_fmt(&mut wb, 0, 0, 0, "0.000");
+        edit(&mut wb, vec![input(0, 0, 0, "50%")]);
+        assert_eq!(formatted(&mut wb)[0], "0.500");
+
+        wb.handle_action(EditAction::SetLocale(Locale {
+            decimal_separator: ',',
+            group_separator: '.',
+            date_order: DateOrder::Dmy,
+            currency_symbols: vec![String::from("€")],
+        }));
+        edit(
+            &mut wb,
+            vec![input(0, 1, 0, "1.234,5 €"), input(0, 1, 1, "1.3.2024")],
+        );
+        let texts = formatted(&mut wb);
+        assert_eq!(texts[6..], ["1,234.50 €", "1/3/2024"]);
+
+        // The format of the row or the column of a new cell is kept too, so
+        // the percent format is not applied.
+        let controller = &mut wb.controller;
+        let sheet_id = controller.get_sheet_id_by_idx(0).unwrap();
+        let status = &mut controller.status;
+        let cell_id = status.navigator.fetch_cell_id(sheet_id, 0, 0).unwrap();
+        let style_id = status.container.get_cell(sheet_id, &cell_id).unwrap().style;
+        let row_id = status.navigator.fetch_row_id(sheet_id, 2).unwrap();
+        status.container.set_row_info(
+            sheet_id,
+            row_id,
+            RowInfo {
+                custom_format: true,
+                style: style_id,
+                ..RowInfo::default()
+            },
+        );
+        let col_id = status.navigator.fetch_col_id(sheet_id, 7).unwrap();
+        status.container.set_col_info(
+            sheet_id,
+            col_id,
+            ColInfo {
+                style: style_id,
+                ..ColInfo::default()
+            },
+        );
+        edit(&mut wb, vec![input(0, 2, 0, "25%"), input(0, 3, 7, "75%")]);
+        let texts = formatted(&mut wb);
+        assert_eq!(texts[8..], ["0.25", "0.75"]);
+
+        // The numbers out of the double range are kept as texts.
+        edit(
+            &mut wb,
+            vec![
+                input(0, 4, 0, "1e400"),
+                input(0, 4, 1, "inf"),
+                input(0, 4, 2, "NaN"),
+            ],
+        );
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        ["1e400", "inf", "NaN"]
+            .iter()
+            .enumerate()
+            .for_each(|(col, t)| {
+                assert!(matches!(ws.get_value(4, col).unwrap(), Value::Str(s) if s == *t));
+            });
+    }
+}
@@ -1,3 +1,5 @@
+use logisheets_astchecker::ArgCount;
+pub use logisheets_base::input::{DateOrder, Locale};
 use logisheets_base::{async_func::Task, CellId};
 use serde::Serialize;
 
@@ -18,6 +20,25 @@ pub enum EditAction {
     Undo,
     Redo,
     Payloads(PayloadsAction),
+    /// Calculate the stale cells in the manual calculation mode.
+    Recalculate,
+    /// Calculate the stale cells in the sheet at this index.
+    RecalculateSheet(usize),
+    SetDateSystem(SetDateSystem),
+    /// Change the conventions used to recognize the numbers and dates typed
+    /// into cells. The existing cells are not changed.
+    SetLocale(Locale),
+}
+
+/// Switch the date system of the workbook. If `shift_dates` is true, the
+/// numbers displayed as dates are shifted so that they show the same dates
+/// in the new system. This action can not be undone.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/set_date_system.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct SetDateSystem {
+    pub date1904: bool,
+    pub shift_dates: bool,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -43,7 +64,49 @@ pub enum EditPayload {
     SetRowHeight(SetRowHeight),
     StyleUpdate(StyleUpdate),
     SheetRename(SheetRename),
+    InsertSheet(InsertSheet),
+    DeleteSheet(DeleteSheet),
+    MoveSheet(MoveSheet),
+    CopySheet(CopySheet),
     SetVisible(SetVisible),
+    MergeCells(MergeCells),
+    SplitMergedCells(SplitMergedCells),
+    AddComment(AddComment),
+    UpdateComment(UpdateComment),
+    DeleteComment(DeleteComment),
+    ReplyComment(ReplyComment),
+    ResolveComment(ResolveComment),
+    DefineName(DefineName),
+    DeleteName(DeleteName),
+    RenameName(RenameName),
+}
+
+/// Define a name or update the formula of an existing name. A name is defined
+/// in the workbook scope if `sheet_idx` is None.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/define_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DefineName {
+    pub name: String,
+    pub sheet_idx: Option<usize>,
+    pub refers_to: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/delete_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteName {
+    pub name: String,
+    pub sheet_idx: Option<usize>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/rename_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct RenameName {
+    pub old_name: String,
+    pub new_name: String,
+    pub sheet_idx: Option<usize>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -54,6 +117,42 @@ pub struct SheetRename {
     pub new_name: String,
 }
 
+/// Insert a new sheet at `sheet_idx`. It is named like `Sheet2` automatically.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/insert_sheet.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct InsertSheet {
+    pub sheet_idx: usize,
+}
+
+/// Delete a sheet. The references to it will become #REF!. The last sheet
+/// can not be deleted.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/delete_sheet.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSheet {
+    pub sheet_idx: usize,
+}
+
+/// Move a sheet so that its index becomes `new_idx`.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/move_sheet.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct MoveSheet {
+    pub sheet_idx: usize,
+    pub new_idx: usize,
+}
+
+/// Duplicate a sheet and put the copy at `new_idx`. The references to the
+/// sheet itself in the copied formulas refer to the copy.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/copy_sheet.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct CopySheet {
+    pub sheet_idx: usize,
+    pub new_idx: usize,
+}
+
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../src/bindings/row_shift.ts")]
 #[serde(rename_all = "camelCase")]
@@ -124,6 +223,8 @@ pub struct MoveBlock {
     pub new_master_col: usize,
 }
 
+/// Input a value or a formula into a block cell. `row` and `col` are relative
+/// to the master cell of the block.
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../src/bindings/block_input.ts")]
 #[serde(rename_all = "camelCase")]
@@ -147,6 +248,8 @@ pub struct LineShiftInBlock {
     pub insert: bool,
 }
 
+/// Update the style of a block cell. `row` and `col` are relative to the
+/// master cell of the block.
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../src/bindings/block_style_update.ts")]
 #[serde(rename_all = "camelCase")]
@@ -158,6 +261,7 @@ pub struct BlockStyleUpdate {
     pub style_update: StyleUpdateType,
 }
 
+/// Hide or unhide `count` rows or columns from `start`.
 #[derive(Default, Debug, Serialize, TS)]
 #[ts(export, export_to = "../../src/bindings/set_visible.ts")]
 #[serde(rename_all = "camelCase")]
@@ -165,9 +269,91 @@ pub struct SetVisible {
     pub is_row: bool,
     pub sheet_idx: usize,
     pub start: usize,
+    pub count: usize,
     pub visible: bool,
 }
 
+/// Merge the cells in the range. It is rejected if the range overlaps other
+/// merged cells. Only the value of the top-left cell is kept.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/merge_cells.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct MergeCells {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+}
+
+/// Split the merged cells which cover the cell at `row` and `col`.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/split_merged_cells.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct SplitMergedCells {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Add a comment to the cell. The existing comment and its replies of this
+/// cell are replaced.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/add_comment.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct AddComment {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    pub author: String,
+    pub text: String,
+}
+
+/// Update the comment of the cell. It is ignored if there is no comment.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/update_comment.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateComment {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    pub author: String,
+    pub text: String,
+}
+
+/// Delete the comment of the cell and its replies.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/delete_comment.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteComment {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Reply to the comment of the cell. It is ignored if there is no comment.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/reply_comment.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyComment {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    pub author: String,
+    pub text: String,
+}
+
+/// Mark the comment thread of the cell as resolved or reopen it.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/resolve_comment.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveComment {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    pub resolved: bool,
+}
+
 #[derive(Default, Debug, Serialize, TS)]
 #[ts(export_to = "../../src/bindings/action_effect.ts")]
 #[serde(rename_all = "camelCase")]
@@ -176,4 +362,104 @@ pub struct ActionEffect {
     pub sheets: Vec<usize>,
     pub async_tasks: Vec<Task>,
     pub dirtys: Vec<(SheetId, CellId)>,
+    // Circular references found in calculation. Only reported when the
+    // iterative calculation is disabled.
+    pub cycles: Vec<Vec<CycleCell>>,
+    // Formula inputs whose function calls break the signatures. These cells
+    // and names are left unchanged.
+    pub rejections: Vec<FormulaRejection>,
+    // Names that can not be defined, deleted or renamed, like the ones that
+    // formulas can not refer to. These payloads are ignored.
+    pub invalid_names: Vec<InvalidName>,
+    // Blocks whose ids are taken or which overlap other blocks in the sheet.
+    // These payloads are ignored.
+    pub invalid_blocks: Vec<InvalidBlock>,
+    // Cells to merge over other merged cells. These payloads are ignored.
+    pub invalid_merges: Vec<InvalidMerge>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/cycle_cell.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct CycleCell {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/formula_rejection.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaRejection {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    // The name whose formula is rejected. The `sheet_idx` is the scope of
+    // the name, or 0 for a workbook name, and the `row` and `col` are 0.
+    pub name: Option<String>,
+    pub func_name: String,
+    pub reason: RejectReason,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/invalid_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidName {
+    pub name: String,
+    pub sheet_idx: Option<usize>,
+    pub reason: NameConflict,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/name_conflict.ts")]
+#[serde(rename_all = "camelCase")]
+pub enum NameConflict {
+    // Formulas can not refer to the name, like `A1` or `has space`.
+    Invalid,
+    // Another name in the scope has the new name.
+    Exists,
+    // No name in the scope has this name.
+    NotFound,
+    // The formula that the name refers to can not be parsed.
+    Unparsable,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/invalid_block.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidBlock {
+    pub sheet_idx: usize,
+    pub id: usize,
+    pub reason: BlockConflict,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/invalid_merge.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidMerge {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/block_conflict.ts")]
+#[serde(rename_all = "camelCase")]
+pub enum BlockConflict {
+    IdExists,
+    // The id of the block overlapped.
+    Overlap(usize),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/reject_reason.ts")]
+#[serde(rename_all = "camelCase")]
+pub enum RejectReason {
+    // The valid count of arguments.
+    ArgCount(ArgCount),
+    // The index of the invalid argument, like a value given to an argument
+    // accepting references only.
+    Arg(usize),
 }
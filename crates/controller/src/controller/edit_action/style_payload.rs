@@ -14,7 +14,7 @@ pub struct StyleUpdate {
 
 pub type Color = String;
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Default, Serialize, TS)]
 #[ts(export_to = "../../src/bindings/style_update_type.ts")]
 #[serde(rename_all = "camelCase")]
 pub struct StyleUpdateType {
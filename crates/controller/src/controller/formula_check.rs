@@ -0,0 +1,270 @@
+use logisheets_astchecker::{AstChecker, FuncCheckError};
+use logisheets_base::{BlockId, SheetId};
+use logisheets_parser::{context::Context, Parser};
+
+use super::edit_action::{EditPayload, FormulaRejection, RejectReason};
+use super::status::Status;
+use crate::connectors::IdFetcher;
+
+// Take out the formula inputs that break the function signatures, including
+// the formulas in block cells and the ones that names refer to. The formulas
+// are parsed in a copy of the status so that the ids registered in parsing
+// the rejected ones do not leak into the workbook.
+pub fn check_formula_inputs(
+    status: &Status,
+    book_name: &str,
+    payloads: Vec<EditPayload>,
+) -> (Vec<EditPayload>, Vec<FormulaRejection>) {
+    let mut scratch: Option<Status> = None;
+    let mut rejections = vec![];
+    let payloads = payloads
+        .into_iter()
+        .filter(|p| {
+            let formula = match get_formula(p) {
+                Some(f) => f,
+                None => return true,
+            };
+            let status = scratch.get_or_insert_with(|| status.clone());
+            let target = match get_target(status, p) {
+                Some(t) => t,
+                None => return true,
+            };
+            match check_formula(status, book_name, target.sheet_id, formula) {
+                Some((func_name, reason)) => {
+                    rejections.push(FormulaRejection {
+                        sheet_idx: target.sheet_idx,
+                        row: target.row,
+                        col: target.col,
+                        name: target.name,
+                        func_name,
+                        reason,
+                    });
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect();
+    (payloads, rejections)
+}
+
+fn get_formula(payload: &EditPayload) -> Option<&str> {
+    match payload {
+        EditPayload::CellInput(input) => input.content.strip_prefix('='),
+        EditPayload::BlockInput(input) => input.input.strip_prefix('='),
+        EditPayload::DefineName(dn) => {
+            Some(dn.refers_to.strip_prefix('=').unwrap_or(&dn.refers_to))
+        }
+        _ => None,
+    }
+}
+
+struct Target {
+    // The sheet where the formula is parsed.
+    sheet_id: SheetId,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+    name: Option<String>,
+}
+
+fn get_target(status: &mut Status, payload: &EditPayload) -> Option<Target> {
+    match payload {
+        EditPayload::CellInput(input) => Some(Target {
+            sheet_id: status.sheet_pos_manager.get_sheet_id(input.sheet_idx)?,
+            sheet_idx: input.sheet_idx,
+            row: input.row,
+            col: input.col,
+            name: None,
+        }),
+        EditPayload::BlockInput(input) => {
+            let sheet_id = status.sheet_pos_manager.get_sheet_id(input.sheet_idx)?;
+            let navigator = &mut status.navigator;
+            let block_id = input.block_id as BlockId;
+            let (rows, cols) = navigator.get_block_size(sheet_id, block_id)?;
+            if input.row >= rows || input.col >= cols {
+                return None;
+            }
+            let master = navigator.get_master_cell(sheet_id, block_id)?;
+            let (master_row, master_col) = navigator.fetch_cell_idx(sheet_id, &master)?;
+            Some(Target {
+                sheet_id,
+                sheet_idx: input.sheet_idx,
+                row: master_row + input.row,
+                col: master_col + input.col,
+                name: None,
+            })
+        }
+        // The references without a sheet prefix are in the scope sheet, or
+        // in the first sheet for the workbook names.
+        EditPayload::DefineName(dn) => {
+            let sheet_idx = dn.sheet_idx.unwrap_or(0);
+            Some(Target {
+                sheet_id: status.sheet_pos_manager.get_sheet_id(sheet_idx)?,
+                sheet_idx,
+                row: 0,
+                col: 0,
+                name: Some(dn.name.clone()),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn check_formula(
+    status: &mut Status,
+    book_name: &str,
+    sheet_id: SheetId,
+    formula: &str,
+) -> Option<(String, RejectReason)> {
+    let mut id_fetcher = IdFetcher {
+        sheet_id_manager: &mut status.sheet_id_manager,
+        text_id_manager: &mut status.text_id_manager,
+        func_id_manager: &mut status.func_id_manager,
+        name_id_manager: &mut status.name_id_manager,
+        external_links_manager: &mut status.external_links_manager,
+        navigator: &mut status.navigator,
+    };
+    let mut context = Context {
+        sheet_id,
+        book_name,
+        id_fetcher: &mut id_fetcher,
+    };
+    // Syntax errors are handled when the formula is executed.
+    let ast = (Parser {}).parse(formula, &mut context)?;
+    let func_id_manager = &status.func_id_manager;
+    let checker = AstChecker::new(|id| func_id_manager.get_string(id));
+    let FuncCheckError {
+        id,
+        break_arg_count,
+        break_args,
+    } = checker.func_check(&ast).err()?;
+    let func_name = func_id_manager.get_string(&id).unwrap_or_default();
+    let reason = match (break_arg_count, break_args) {
+        (Some(count), _) => RejectReason::ArgCount(count),
+        (None, Some((idx, _))) => RejectReason::Arg(idx),
+        (None, None) => return None,
+    };
+    Some((func_name, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::edit_action::{
+        BlockInput, CreateBlock, DefineName, EditPayload, RejectReason,
+    };
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    #[test]
+    fn formula_rejection_test() {
+        let mut wb = Workbook::default();
+        let effect = edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "=ABS(1,2,3)"),
+                input(0, 0, 1, "=ROW(1)"),
+                input(0, 0, 2, "=SUM(1,ROW(A1),ABS(-2))"),
+                input(0, 0, 3, "=IF(1,ABS(),2)"),
+            ],
+        );
+        let rejections = effect
+            .rejections
+            .iter()
+            .map(|r| (r.col, r.func_name.as_str(), r.reason.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(rejections.len(), 3);
+        assert!(matches!(
+            rejections[0],
+            (0, "ABS", RejectReason::ArgCount(ref c)) if c.eq == Some(1)
+        ));
+        assert!(matches!(rejections[1], (1, "ROW", RejectReason::Arg(0))));
+        assert!(matches!(
+            rejections[2],
+            (3, "ABS", RejectReason::ArgCount(_))
+        ));
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_formula(0, 0).unwrap(), "");
+        assert_eq!(ws.get_formula(0, 1).unwrap(), "");
+        assert_eq!(value(&mut wb, 0, 0, 2), "4");
+    }
+
+    // Every call the registry accepts has to be calculated.
+    #[test]
+    fn accepted_formula_test() {
+        let mut wb = Workbook::default();
+        let effect = edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 0, 1, "2"),
+                input(0, 1, 0, "3"),
+                input(0, 1, 1, "4"),
+                input(0, 5, 0, "=INDEX(A1:B3,2,2)"),
+                input(0, 5, 1, "=INDEX(A1:B3,2,2,1)"),
+                input(0, 5, 2, "=INDEX(A1:A3,2)"),
+            ],
+        );
+        assert!(effect.rejections.is_empty());
+        assert_eq!(value(&mut wb, 0, 5, 0), "4");
+        assert_eq!(value(&mut wb, 0, 5, 1), "4");
+        assert_eq!(value(&mut wb, 0, 5, 2), "3");
+    }
+
+    #[test]
+    fn block_and_name_rejection_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![EditPayload::CreateBlock(CreateBlock {
+                sheet_idx: 0,
+                id: 1,
+                master_row: 2,
+                master_col: 2,
+                row_cnt: 2,
+                col_cnt: 2,
+            })],
+        );
+        let block_input = |row: usize, col: usize, input: &str| {
+            EditPayload::BlockInput(BlockInput {
+                sheet_idx: 0,
+                block_id: 1,
+                row,
+                col,
+                input: input.to_string(),
+            })
+        };
+        let define_name = |name: &str, refers_to: &str| {
+            EditPayload::DefineName(DefineName {
+                name: name.to_string(),
+                sheet_idx: None,
+                refers_to: refers_to.to_string(),
+            })
+        };
+        let effect = edit(
+            &mut wb,
+            vec![
+                block_input(0, 1, "=ABS(1,2)"),
+                block_input(1, 1, "=ABS(-3)"),
+                define_name("Bad", "=ROW(1)"),
+                define_name("Good", "=ABS(-4)"),
+                input(0, 0, 0, "=Bad"),
+                input(0, 0, 1, "=Good"),
+            ],
+        );
+        assert_eq!(effect.rejections.len(), 2);
+        let block = &effect.rejections[0];
+        assert_eq!((block.row, block.col, block.name.as_deref()), (2, 3, None));
+        assert!(matches!(block.reason, RejectReason::ArgCount(_)));
+        let name = &effect.rejections[1];
+        assert_eq!(name.name.as_deref(), Some("Bad"));
+        assert_eq!(name.func_name, "ROW");
+        assert!(matches!(name.reason, RejectReason::Arg(0)));
+
+        let formula = wb.get_sheet_by_idx(0).unwrap().get_formula(2, 3).unwrap();
+        assert_eq!(formula, "");
+        assert_eq!(value(&mut wb, 0, 3, 3), "3");
+        assert_eq!(value(&mut wb, 0, 0, 0), "#NAME?");
+        assert_eq!(value(&mut wb, 0, 0, 1), "4");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use logisheets_base::SheetId;
+
+use super::edit_action::{EditPayload, InvalidMerge};
+use super::status::Status;
+use crate::navigator::Navigator;
+
+type Range = (usize, usize, usize, usize);
+
+// Take out the payloads merging cells over other merged cells. The merges and
+// the splits are applied to a copy of the merged ranges so that the ones done
+// earlier in the same action are checked too.
+pub fn check_merge_inputs(
+    status: &Status,
+    payloads: Vec<EditPayload>,
+) -> (Vec<EditPayload>, Vec<InvalidMerge>) {
+    let mut navigator: Option<Navigator> = None;
+    let mut scratch = HashMap::<SheetId, Vec<Range>>::new();
+    let mut invalid_merges = vec![];
+    let payloads = payloads
+        .into_iter()
+        .filter(|p| {
+            let sheet_idx = match p {
+                EditPayload::MergeCells(p) => p.sheet_idx,
+                EditPayload::SplitMergedCells(p) => p.sheet_idx,
+                _ => return true,
+            };
+            let sheet_id = match status.sheet_pos_manager.get_sheet_id(sheet_idx) {
+                Some(sheet_id) => sheet_id,
+                None => return true,
+            };
+            let ranges = scratch.entry(sheet_id).or_insert_with(|| {
+                let navigator = navigator.get_or_insert_with(|| status.navigator.clone());
+                get_merged_ranges(status, navigator, sheet_id)
+            });
+            match p {
+                EditPayload::MergeCells(mc) => {
+                    let range = (mc.start_row, mc.start_col, mc.end_row, mc.end_col);
+                    // A single cell or a reversed range is ignored in merging.
+                    if mc.start_row > mc.end_row
+                        || mc.start_col > mc.end_col
+                        || (mc.start_row, mc.start_col) == (mc.end_row, mc.end_col)
+                    {
+                        return true;
+                    }
+                    if ranges.iter().any(|r| overlaps(r, &range)) {
+                        invalid_merges.push(InvalidMerge {
+                            sheet_idx,
+                            start_row: mc.start_row,
+                            start_col: mc.start_col,
+                            end_row: mc.end_row,
+                            end_col: mc.end_col,
+                        });
+                        return false;
+                    }
+                    ranges.push(range);
+                    true
+                }
+                EditPayload::SplitMergedCells(sm) => {
+                    ranges.retain(|r| !overlaps(r, &(sm.row, sm.col, sm.row, sm.col)));
+                    true
+                }
+                _ => true,
+            }
+        })
+        .collect();
+    (payloads, invalid_merges)
+}
+
+fn overlaps(a: &Range, b: &Range) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+fn get_merged_ranges(status: &Status, navigator: &mut Navigator, sheet_id: SheetId) -> Vec<Range> {
+    let merges = match status
+        .cell_attachment_manager
+        .merge_cells
+        .data
+        .get(&sheet_id)
+    {
+        Some(m) => m,
+        None => return vec![],
+    };
+    merges
+        .iter()
+        .filter_map(|(start, end)| {
+            let (sr, sc) = navigator.fetch_normal_cell_idx(sheet_id, start)?;
+            let (er, ec) = navigator.fetch_normal_cell_idx(sheet_id, end)?;
+            Some((sr, sc, er, ec))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::edit_action::{EditPayload, MergeCells, SplitMergedCells};
+    use crate::test_utils::edit;
+    use crate::Workbook;
+
+    fn merge(start_row: usize, start_col: usize, end_row: usize, end_col: usize) -> EditPayload {
+        EditPayload::MergeCells(MergeCells {
+            sheet_idx: 0,
+            start_row,
+            start_col,
+            end_row,
+            end_col,
+        })
+    }
+
+    #[test]
+    fn merge_conflict_test() {
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![merge(0, 0, 1, 1)]);
+        let effect = edit(
+            &mut wb,
+            vec![
+                merge(1, 1, 2, 2),
+                merge(3, 0, 3, 3),
+                merge(2, 3, 4, 4),
+                // A single cell is not merged and not rejected either.
+                merge(3, 3, 3, 3),
+                EditPayload::SplitMergedCells(SplitMergedCells {
+                    sheet_idx: 0,
+                    row: 1,
+                    col: 1,
+                }),
+                merge(1, 1, 2, 2),
+            ],
+        );
+        let invalid_merges = effect
+            .invalid_merges
+            .iter()
+            .map(|m| (m.start_row, m.start_col, m.end_row, m.end_col))
+            .collect::<Vec<_>>();
+        assert_eq!(invalid_merges, vec![(1, 1, 2, 2), (2, 3, 4, 4)]);
+        let mut merges = wb
+            .get_sheet_by_idx(0)
+            .unwrap()
+            .get_merge_cells()
+            .into_iter()
+            .map(|m| (m.row_start, m.col_start, m.row_end, m.col_end))
+            .collect::<Vec<_>>();
+        merges.sort();
+        assert_eq!(merges, vec![(1, 1, 2, 2), (3, 0, 3, 3)]);
+    }
+}
@@ -1,20 +1,39 @@
 use logisheets_base::async_func::{AsyncCalcResult, Task};
-use logisheets_base::{CellId, SheetId};
+use logisheets_base::datetime::{DateSystem, DATE_1904_OFFSET};
+use logisheets_base::{CellId, CellValue, SheetId};
 
 use logisheets_workbook::prelude::{read, SerdeErr};
+use std::collections::HashMap;
+mod block_check;
+mod cond_format;
 pub mod display;
 pub mod edit_action;
+mod formula_check;
+mod merge_check;
+mod name_check;
 pub mod status;
 pub mod style;
+pub mod trace;
 mod transaction;
 mod viewer;
 use crate::file_loader2::load;
+use crate::file_saver::save;
+use crate::formatter::NumFormat;
+use crate::payloads::sheet_process::{
+    cell::{CellChange, CellPayload},
+    SheetPayload, SheetProcess,
+};
 use crate::payloads::sheet_shift::{SheetShiftPayload, SheetShiftType};
 use crate::payloads::Process;
 use crate::settings::Settings;
-use edit_action::{ActionEffect, Converter};
+use block_check::check_block_inputs;
+use edit_action::{ActionEffect, Converter, CycleCell, SetDateSystem};
+use formula_check::check_formula_inputs;
+use merge_check::check_merge_inputs;
+use name_check::check_name_inputs;
 use status::Status;
-use transaction::{Transaction, TransactionContext};
+use trace::{TraceNode, Tracer};
+use transaction::{Recalc, Transaction, TransactionContext};
 use viewer::SheetViewer;
 
 use self::display::{DisplayRequest, DisplayResponse};
@@ -26,8 +45,15 @@ pub struct Controller {
     pub async_func_manager: AsyncFuncManager,
     pub curr_book_name: String,
     pub settings: Settings,
-    pub undo_stack: Vec<Status>,
-    pub redo_stack: Vec<Status>,
+    pub undo_stack: Vec<UndoRecord>,
+    pub redo_stack: Vec<UndoRecord>,
+}
+
+/// A status in the undo or redo stack. The date system is kept along with
+/// it because changing the date system is undoable as well.
+pub struct UndoRecord {
+    pub status: Status,
+    pub date_system: DateSystem,
 }
 
 impl Default for Controller {
@@ -44,14 +70,14 @@ impl Default for Controller {
             idx: 0,
             ty: SheetShiftType::Insert,
         });
-        empty.handle_process(vec![add_sheet], false);
+        empty.handle_process(vec![add_sheet], false, None);
         empty
     }
 }
 
 impl Controller {
-    pub fn save(&self) -> Vec<u8> {
-        vec![]
+    pub fn save(&self) -> Result<Vec<u8>, SerdeErr> {
+        save(self)
     }
 
     pub fn from(status: Status, book_name: String, settings: Settings) -> Self {
@@ -92,20 +118,44 @@ impl Controller {
                 true => Some(ActionEffect::default()),
                 false => None,
             },
+            EditAction::Recalculate => Some(self.recalculate(Recalc::Book)),
+            EditAction::RecalculateSheet(sheet_idx) => {
+                let sheet_id = self.get_sheet_id_by_idx(sheet_idx)?;
+                Some(self.recalculate(Recalc::Sheet(sheet_id)))
+            }
+            EditAction::SetDateSystem(action) => Some(self.set_date_system(action)),
+            EditAction::SetLocale(locale) => {
+                self.settings.locale = locale;
+                Some(ActionEffect::default())
+            }
             EditAction::Payloads(action) => {
+                let (payloads, rejections) =
+                    check_formula_inputs(&self.status, &self.curr_book_name, action.payloads);
+                let (payloads, invalid_names) =
+                    check_name_inputs(&self.status, &self.curr_book_name, payloads);
+                let (payloads, invalid_blocks) = check_block_inputs(&self.status, payloads);
+                let (payloads, invalid_merges) = check_merge_inputs(&self.status, payloads);
                 let mut c = Converter {
                     sheet_pos_manager: &self.status.sheet_pos_manager,
                     navigator: &mut self.status.navigator,
                     container: &mut self.status.container,
                     text_id_manager: &mut self.status.text_id_manager,
+                    style_manager: &self.status.style_manager,
+                    locale: &self.settings.locale,
+                    date_system: self.settings.date_system,
                 };
-                let proc = c.convert_edit_payloads(action.payloads);
-                self.handle_process(proc, action.undoable);
+                let proc = c.convert_edit_payloads(payloads);
+                let cycles = self.handle_process(proc, action.undoable, None);
                 let (tasks, dirties) = self.async_func_manager.get_calc_tasks();
                 Some(ActionEffect {
                     sheets: vec![],
                     async_tasks: tasks,
                     dirtys: dirties,
+                    cycles,
+                    rejections,
+                    invalid_names,
+                    invalid_blocks,
+                    invalid_merges,
                 })
             }
         }
@@ -120,27 +170,143 @@ impl Controller {
         tasks.into_iter().zip(res.into_iter()).for_each(|(t, r)| {
             self.async_func_manager.add_value(t, r);
         });
-        self.handle_process(vec![Process::Recalc(dirtys)], false);
-        Some(ActionEffect::default())
+        let cycles = self.handle_process(vec![Process::Recalc(dirtys)], false, None);
+        Some(ActionEffect {
+            cycles,
+            ..ActionEffect::default()
+        })
+    }
+
+    fn recalculate(&mut self, recalc: Recalc) -> ActionEffect {
+        let cycles = self.handle_process(vec![], false, Some(recalc));
+        let (tasks, dirties) = self.async_func_manager.get_calc_tasks();
+        ActionEffect {
+            sheets: vec![],
+            async_tasks: tasks,
+            dirtys: dirties,
+            cycles,
+            ..ActionEffect::default()
+        }
     }
 
-    fn handle_process(&mut self, proc: Vec<Process>, undoable: bool) {
+    fn set_date_system(&mut self, action: SetDateSystem) -> ActionEffect {
+        let system = match action.date1904 {
+            true => DateSystem::Date1904,
+            false => DateSystem::Date1900,
+        };
+        if system == self.settings.date_system {
+            return ActionEffect::default();
+        }
+        let record = UndoRecord {
+            status: self.status.clone(),
+            date_system: self.settings.date_system,
+        };
+        self.settings.date_system = system;
+        let mut proc = vec![];
+        if action.shift_dates {
+            let offset = match system {
+                DateSystem::Date1900 => DATE_1904_OFFSET as f64,
+                DateSystem::Date1904 => -(DATE_1904_OFFSET as f64),
+            };
+            let navigator = &mut self.status.navigator;
+            let formulas = &self.status.vertex_manager.status.formulas;
+            let style_manager = &self.status.style_manager;
+            let mut date_formats = HashMap::<String, bool>::new();
+            self.status
+                .container
+                .data
+                .iter()
+                .for_each(|(sheet_id, sheet_data)| {
+                    sheet_data.cells.iter().for_each(|(cell_id, cell)| {
+                        let n = match cell.value {
+                            CellValue::Number(n) => n + offset,
+                            _ => return,
+                        };
+                        if n < 0. || formulas.contains_key(&(*sheet_id, *cell_id)) {
+                            return;
+                        }
+                        let code = &style_manager.get_cell_style(cell.style).formatter;
+                        let is_date = *date_formats
+                            .entry(code.clone())
+                            .or_insert_with_key(|code| NumFormat::new(code).has_date());
+                        if !is_date {
+                            return;
+                        }
+                        if let Some((row, col)) = navigator.fetch_cell_idx(*sheet_id, cell_id) {
+                            proc.push(Process::Sheet(SheetProcess {
+                                sheet_id: *sheet_id,
+                                payload: SheetPayload::Cell(CellPayload {
+                                    row,
+                                    col,
+                                    change: CellChange::Value(CellValue::Number(n)),
+                                }),
+                            }));
+                        }
+                    })
+                });
+        }
+        // Every formula may depend on the date system.
+        let formulas = self.status.vertex_manager.status.formulas.keys();
+        proc.push(Process::Recalc(formulas.cloned().collect()));
+        let cycles = self.handle_process(proc, false, None);
+        self.undo_stack.push(record);
+        let (tasks, dirties) = self.async_func_manager.get_calc_tasks();
+        ActionEffect {
+            sheets: vec![],
+            async_tasks: tasks,
+            dirtys: dirties,
+            cycles,
+            ..ActionEffect::default()
+        }
+    }
+
+    // Return the cells in circular references if the iterative calculation
+    // is disabled.
+    fn handle_process(
+        &mut self,
+        proc: Vec<Process>,
+        undoable: bool,
+        recalc: Option<Recalc>,
+    ) -> Vec<Vec<CycleCell>> {
         let context = TransactionContext {
             book_name: &self.curr_book_name,
             calc_config: self.settings.calc_config.clone(),
             async_funcs: &self.settings.async_funcs,
+            date_system: self.settings.date_system,
         };
         let transcation = Transaction {
             async_func_manager: &mut self.async_func_manager,
             status: self.status.clone(),
             context,
             proc,
+            recalc,
         };
-        let mut new_status = transcation.start();
+        let (mut new_status, cycles) = transcation.start();
         std::mem::swap(&mut new_status, &mut self.status);
         if undoable {
-            self.undo_stack.push(new_status);
+            self.undo_stack.push(UndoRecord {
+                status: new_status,
+                date_system: self.settings.date_system,
+            });
         }
+        let status = &mut self.status;
+        cycles
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .into_iter()
+                    .filter_map(|(sheet_id, cell_id)| {
+                        let sheet_idx = status.sheet_pos_manager.get_sheet_idx(sheet_id)?;
+                        let (row, col) = status.navigator.fetch_cell_idx(sheet_id, &cell_id)?;
+                        Some(CycleCell {
+                            sheet_idx,
+                            row,
+                            col,
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
     }
 
     pub fn get_display_response(&mut self, req: DisplayRequest) -> DisplayResponse {
@@ -149,11 +315,56 @@ impl Controller {
         response
     }
 
+    /// Trace the cells, ranges and names that the formula in this cell
+    /// refers to, up to `depth` levels.
+    pub fn trace_precedents(
+        &mut self,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+        depth: usize,
+    ) -> Vec<TraceNode> {
+        let sheet_id = match self.get_sheet_id_by_idx(sheet_idx) {
+            Some(id) => id,
+            None => return vec![],
+        };
+        match self.status.navigator.fetch_cell_id(sheet_id, row, col) {
+            Some(cell_id) => Tracer {
+                status: &mut self.status,
+            }
+            .precedents((sheet_id, cell_id), depth),
+            None => vec![],
+        }
+    }
+
+    /// Trace the formulas and names that refer to this cell, up to `depth`
+    /// levels.
+    pub fn trace_dependents(
+        &mut self,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+        depth: usize,
+    ) -> Vec<TraceNode> {
+        let sheet_id = match self.get_sheet_id_by_idx(sheet_idx) {
+            Some(id) => id,
+            None => return vec![],
+        };
+        match self.status.navigator.fetch_cell_id(sheet_id, row, col) {
+            Some(cell_id) => Tracer {
+                status: &mut self.status,
+            }
+            .dependents((sheet_id, cell_id), depth),
+            None => vec![],
+        }
+    }
+
     pub fn undo(&mut self) -> bool {
         match self.undo_stack.pop() {
-            Some(mut last_status) => {
-                std::mem::swap(&mut self.status, &mut last_status);
-                self.redo_stack.push(last_status);
+            Some(mut last) => {
+                std::mem::swap(&mut self.status, &mut last.status);
+                std::mem::swap(&mut self.settings.date_system, &mut last.date_system);
+                self.redo_stack.push(last);
                 true
             }
             None => false,
@@ -162,9 +373,10 @@ impl Controller {
 
     pub fn redo(&mut self) -> bool {
         match self.redo_stack.pop() {
-            Some(mut next_status) => {
-                std::mem::swap(&mut self.status, &mut next_status);
-                self.undo_stack.push(next_status);
+            Some(mut next) => {
+                std::mem::swap(&mut self.status, &mut next.status);
+                std::mem::swap(&mut self.settings.date_system, &mut next.date_system);
+                self.undo_stack.push(next);
                 true
             }
             None => false,
@@ -204,6 +416,499 @@ mod tests {
         assert_eq!(len, 1);
     }
 
+    #[test]
+    fn save_test() {
+        use crate::Workbook;
+        use std::fs;
+        let files = fs::read_dir("../../tests")
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().map_or(false, |ext| ext == "xlsx"))
+            .collect::<Vec<_>>();
+        assert!(!files.is_empty());
+        files.iter().for_each(|path| {
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            let buf = fs::read(path).unwrap();
+            let controller = Controller::from_file(name.to_string(), &buf).unwrap();
+            let saved = controller.save().unwrap();
+            let mut expected = Workbook { controller };
+            let mut actual = Workbook::from_file(&saved, name.to_string()).unwrap();
+            let sheet_ids = expected.controller.status.sheet_pos_manager.pos.clone();
+            let saved_ids = &actual.controller.status.sheet_pos_manager.pos;
+            assert_eq!(sheet_ids.len(), saved_ids.len(), "{}", name);
+            sheet_ids
+                .into_iter()
+                .enumerate()
+                .for_each(|(idx, sheet_id)| {
+                    let status = &mut expected.controller.status;
+                    let cells = status.container.get_sheet_container(sheet_id).cells.clone();
+                    let coords = cells
+                        .keys()
+                        .filter_map(|id| status.navigator.fetch_cell_idx(sheet_id, id))
+                        .collect::<Vec<_>>();
+                    let mut e = expected.get_sheet_by_idx(idx).unwrap();
+                    let mut a = actual.get_sheet_by_idx(idx).unwrap();
+                    coords.into_iter().for_each(|(row, col)| {
+                        let ev = format!("{:?}", e.get_value(row, col).unwrap());
+                        let av = format!("{:?}", a.get_value(row, col).unwrap());
+                        assert_eq!(ev, av);
+                        let ef = e.get_formula(row, col).unwrap();
+                        let af = a.get_formula(row, col).unwrap();
+                        assert_eq!(ef, af);
+                        let es = format!("{:?}", e.get_style(row, col).unwrap());
+                        let as_ = format!("{:?}", a.get_style(row, col).unwrap());
+                        assert_eq!(es, as_);
+                    });
+                    assert_eq!(e.get_merge_cells().len(), a.get_merge_cells().len());
+                    assert_eq!(e.get_comments().len(), a.get_comments().len());
+                });
+        });
+
+        // The external links with their cached values.
+        use crate::calc_engine::calculator::calc_vertex::Value;
+        use crate::test_utils::{edit, input};
+        use logisheets_base::Addr;
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![input(0, 0, 0, "=[Book2.xlsx]Data!B2*2")]);
+        let manager = &mut wb.controller.status.external_links_manager;
+        let book_id = manager.book_id_manager.has("Book2.xlsx").unwrap();
+        let sheet_id = wb.controller.get_sheet_id_by_name("Data").unwrap();
+        let manager = &mut wb.controller.status.external_links_manager;
+        manager
+            .books
+            .get_mut(&book_id)
+            .unwrap()
+            .data_set
+            .insert((sheet_id, Addr { row: 1, col: 1 }), Value::Number(21.));
+        let saved = wb.controller.save().unwrap();
+        let mut loaded = Workbook::from_file(&saved, String::from("Book1")).unwrap();
+        let formula = loaded
+            .get_sheet_by_idx(0)
+            .unwrap()
+            .get_formula(0, 0)
+            .unwrap();
+        assert_eq!(
+            formula,
+            wb.get_sheet_by_idx(0).unwrap().get_formula(0, 0).unwrap()
+        );
+        let manager = &loaded.controller.status.external_links_manager;
+        let book_id = manager.book_id_manager.has("Book2.xlsx").unwrap();
+        let sheet_id = loaded.controller.get_sheet_id_by_name("Data").unwrap();
+        let value = manager.books[&book_id].data_set[&(sheet_id, Addr { row: 1, col: 1 })].clone();
+        assert!(matches!(value, Value::Number(n) if n == 21.));
+
+        // The spill anchors are saved as dynamic array formulas.
+        use logisheets_workbook::prelude::StCellFormulaType;
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![input(0, 0, 0, "=SEQUENCE(2,2)")]);
+        let saved = wb.controller.save().unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&saved)).unwrap();
+        assert!(archive.by_name("xl/metadata.xml").is_ok());
+        let parts = logisheets_workbook::workbook::Workbook::from_file(&saved).unwrap();
+        let cells = parts
+            .xl
+            .worksheets
+            .values()
+            .flat_map(|ws| ws.worksheet_part.sheet_data.rows.iter())
+            .flat_map(|row| row.cells.iter())
+            .collect::<Vec<_>>();
+        let anchor = cells.iter().find(|c| c.f.is_some()).unwrap();
+        let f = anchor.f.as_ref().unwrap();
+        assert_eq!(anchor.cm, 1);
+        assert!(matches!(f.t, StCellFormulaType::Array));
+        assert_eq!(f.reference.as_deref(), Some("A1:B2"));
+        assert_eq!(cells.iter().filter(|c| c.cm > 0).count(), 1);
+        let mut loaded = Workbook::from_file(&saved, String::from("spill")).unwrap();
+        assert_eq!(crate::test_utils::value(&mut loaded, 0, 1, 1), "4");
+    }
+
+    fn create_block_for_test(wb: &mut Controller) {
+        use super::edit_action::CreateBlock;
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::CreateBlock(CreateBlock {
+                sheet_idx: 0,
+                id: 7,
+                master_row: 1,
+                master_col: 1,
+                row_cnt: 3,
+                col_cnt: 3,
+            })],
+            undoable: true,
+        }));
+    }
+
+    #[test]
+    fn block_input_test() {
+        use super::edit_action::BlockInput;
+        use crate::{Value, Workbook};
+        let mut controller = Controller::default();
+        create_block_for_test(&mut controller);
+        let input = |row: usize, col: usize, input: &str| {
+            EditPayload::BlockInput(BlockInput {
+                sheet_idx: 0,
+                block_id: 7,
+                row,
+                col,
+                input: input.to_string(),
+            })
+        };
+        controller.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![input(1, 2, "3"), input(0, 0, "=D3*2"), input(3, 0, "9")],
+            undoable: true,
+        }));
+        let mut wb = Workbook { controller };
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(matches!(ws.get_value(2, 3).unwrap(), Value::Number(n) if n == 3.));
+        assert!(matches!(ws.get_value(1, 1).unwrap(), Value::Number(n) if n == 6.));
+        assert_eq!(ws.get_formula(1, 1).unwrap(), "D3 * 2");
+        // Out of the block.
+        assert!(matches!(ws.get_value(4, 1).unwrap(), Value::Empty));
+    }
+
+    #[test]
+    fn block_style_update_test() {
+        use super::display::{DisplayPatch, DisplayRequest};
+        use super::edit_action::style_payload::StyleUpdateType;
+        use super::edit_action::BlockStyleUpdate;
+        let mut wb = Controller::default();
+        create_block_for_test(&mut wb);
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::BlockStyleUpdate(BlockStyleUpdate {
+                sheet_idx: 0,
+                block_id: 7,
+                row: 0,
+                col: 1,
+                style_update: StyleUpdateType {
+                    set_font_bold: Some(true),
+                    ..Default::default()
+                },
+            })],
+            undoable: true,
+        }));
+        let resp = wb.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        let styles = resp
+            .patches
+            .into_iter()
+            .find_map(|p| match p {
+                DisplayPatch::Styles(s) => Some(s.styles),
+                _ => None,
+            })
+            .unwrap();
+        let bold = |row: usize, col: usize| {
+            styles
+                .iter()
+                .any(|s| s.row == row && s.col == col && s.style.font.bold)
+        };
+        assert!(bold(1, 2));
+        assert!(!bold(1, 1));
+    }
+
+    #[test]
+    fn set_visible_test() {
+        use super::display::{DisplayPatch, DisplayRequest};
+        use super::edit_action::SetVisible;
+        let mut wb = Controller::default();
+        let set_visible = |is_row: bool, start: usize, count: usize, visible: bool| {
+            EditAction::Payloads(PayloadsAction {
+                payloads: vec![EditPayload::SetVisible(SetVisible {
+                    is_row,
+                    sheet_idx: 0,
+                    start,
+                    count,
+                    visible,
+                })],
+                undoable: true,
+            })
+        };
+        wb.handle_action(set_visible(true, 2, 3, false));
+        wb.handle_action(set_visible(false, 1, 1, false));
+        wb.handle_action(set_visible(true, 3, 1, true));
+        let resp = wb.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        let mut hidden_rows = vec![];
+        let mut hidden_cols = vec![];
+        resp.patches.into_iter().for_each(|p| match p {
+            DisplayPatch::RowInfo(r) => r.info.into_iter().for_each(|i| {
+                if i.hidden {
+                    hidden_rows.push(i.idx)
+                }
+            }),
+            DisplayPatch::ColInfo(c) => c.info.into_iter().for_each(|i| {
+                if i.hidden {
+                    hidden_cols.push(i.idx)
+                }
+            }),
+            _ => {}
+        });
+        hidden_rows.sort();
+        assert_eq!(hidden_rows, vec![2, 4]);
+        assert_eq!(hidden_cols, vec![1]);
+
+        wb.undo();
+        wb.undo();
+        let resp = wb.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        let any_hidden_col = resp.patches.into_iter().any(|p| match p {
+            DisplayPatch::ColInfo(c) => c.info.iter().any(|i| i.hidden),
+            _ => false,
+        });
+        assert!(!any_hidden_col);
+    }
+
+    #[test]
+    fn circular_reference_test() {
+        use crate::test_utils::{edit, input};
+        use crate::{Value, Workbook};
+        let mut wb = Workbook {
+            controller: Controller::default(),
+        };
+        edit(&mut wb, vec![input(0, 0, 0, "=B1/2+1")]);
+        let effect = edit(&mut wb, vec![input(0, 0, 1, "=A1/2")]);
+        assert_eq!(effect.cycles.len(), 1);
+        let mut cells = effect.cycles[0]
+            .iter()
+            .map(|c| (c.sheet_idx, c.row, c.col))
+            .collect::<Vec<_>>();
+        cells.sort();
+        assert_eq!(cells, vec![(0, 0, 0), (0, 0, 1)]);
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(matches!(ws.get_value(0, 0).unwrap(), Value::Number(n) if n == 0.));
+        assert!(matches!(ws.get_value(0, 1).unwrap(), Value::Number(n) if n == 0.));
+
+        let effect = edit(&mut wb, vec![input(0, 1, 0, "=A2+1")]);
+        assert_eq!(effect.cycles.len(), 1);
+        assert_eq!(effect.cycles[0].len(), 1);
+
+        wb.controller.settings.calc_config.iterative = true;
+        let effect = edit(&mut wb, vec![input(0, 0, 1, "=A1/2")]);
+        assert!(effect.cycles.is_empty());
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(
+            matches!(ws.get_value(0, 0).unwrap(), Value::Number(n) if (n - 4. / 3.).abs() < 0.01)
+        );
+    }
+
+    #[test]
+    fn manual_calc_mode_test() {
+        use super::display::{DisplayPatch, DisplayRequest};
+        use super::edit_action::InsertSheet;
+        use crate::settings::CalcMode;
+        use crate::test_utils::{edit, input};
+        use crate::{Value, Workbook};
+        let mut wb = Workbook {
+            controller: Controller::default(),
+        };
+        wb.controller.settings.calc_config.mode = CalcMode::Manual;
+        let stale_cells = |wb: &mut Workbook, sheet_idx: usize| {
+            let response = wb.controller.get_display_response(DisplayRequest {
+                sheet_idx,
+                version: 0,
+            });
+            let mut cells = response
+                .patches
+                .into_iter()
+                .find_map(|p| match p {
+                    DisplayPatch::StaleCells(s) => Some(s.cells),
+                    _ => None,
+                })
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| (c.row, c.col))
+                .collect::<Vec<_>>();
+            cells.sort();
+            cells
+        };
+        edit(
+            &mut wb,
+            vec![EditPayload::InsertSheet(InsertSheet { sheet_idx: 1 })],
+        );
+        edit(&mut wb, vec![input(0, 0, 0, "1")]);
+        edit(&mut wb, vec![input(0, 1, 0, "=A1+1")]);
+        edit(&mut wb, vec![input(0, 2, 0, "=A2*2")]);
+        edit(&mut wb, vec![input(1, 0, 0, "=3")]);
+        assert_eq!(stale_cells(&mut wb, 0), vec![(1, 0), (2, 0)]);
+        assert_eq!(stale_cells(&mut wb, 1), vec![(0, 0)]);
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(matches!(ws.get_value(2, 0).unwrap(), Value::Empty));
+
+        wb.controller.handle_action(EditAction::RecalculateSheet(0));
+        assert!(stale_cells(&mut wb, 0).is_empty());
+        assert_eq!(stale_cells(&mut wb, 1), vec![(0, 0)]);
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(matches!(ws.get_value(2, 0).unwrap(), Value::Number(n) if n == 4.));
+
+        edit(&mut wb, vec![input(0, 0, 0, "2")]);
+        assert_eq!(stale_cells(&mut wb, 0), vec![(1, 0), (2, 0)]);
+        wb.controller.handle_action(EditAction::Recalculate);
+        assert!(stale_cells(&mut wb, 0).is_empty());
+        assert!(stale_cells(&mut wb, 1).is_empty());
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(matches!(ws.get_value(2, 0).unwrap(), Value::Number(n) if n == 6.));
+        let mut ws = wb.get_sheet_by_idx(1).unwrap();
+        assert!(matches!(ws.get_value(0, 0).unwrap(), Value::Number(n) if n == 3.));
+    }
+
+    #[test]
+    fn volatile_func_test() {
+        use crate::test_utils::{edit, input};
+        use crate::{Value, Workbook};
+        let mut wb = Workbook {
+            controller: Controller::default(),
+        };
+        let values = |wb: &mut Workbook| {
+            let mut ws = wb.get_sheet_by_idx(0).unwrap();
+            match (ws.get_value(0, 0).unwrap(), ws.get_value(1, 0).unwrap()) {
+                (Value::Number(a), Value::Number(b)) => (a, b),
+                _ => panic!(),
+            }
+        };
+        edit(&mut wb, vec![input(0, 0, 0, "=RAND()")]);
+        edit(&mut wb, vec![input(0, 1, 0, "=A1+1")]);
+        let (a, b) = values(&mut wb);
+        assert_eq!(b, a + 1.);
+
+        // An unrelated edit refreshes the volatile cell and its dependents.
+        edit(&mut wb, vec![input(0, 0, 1, "1")]);
+        let (new_a, new_b) = values(&mut wb);
+        assert_ne!(new_a, a);
+        assert_eq!(new_b, new_a + 1.);
+
+        wb.controller.handle_action(EditAction::Recalculate);
+        let (a, _) = values(&mut wb);
+        assert_ne!(a, new_a);
+
+        // The formulas referring to a volatile name are volatile, too.
+        use super::edit_action::DefineName;
+        edit(
+            &mut wb,
+            vec![
+                EditPayload::DefineName(DefineName {
+                    name: String::from("Seed"),
+                    sheet_idx: None,
+                    refers_to: String::from("=RAND()"),
+                }),
+                EditPayload::DefineName(DefineName {
+                    name: String::from("Shifted"),
+                    sheet_idx: None,
+                    refers_to: String::from("=Seed+1"),
+                }),
+                input(0, 2, 0, "=Shifted+1"),
+            ],
+        );
+        let named = |wb: &mut Workbook| {
+            let mut ws = wb.get_sheet_by_idx(0).unwrap();
+            match ws.get_value(2, 0).unwrap() {
+                Value::Number(n) => n,
+                _ => panic!(),
+            }
+        };
+        let c = named(&mut wb);
+        edit(&mut wb, vec![input(0, 0, 1, "2")]);
+        assert_ne!(named(&mut wb), c);
+    }
+
+    #[test]
+    fn func_registry_test() {
+        use crate::test_utils::{edit, input};
+        use crate::{Value, Workbook};
+        let mut wb = Workbook {
+            controller: Controller::default(),
+        };
+        let effect = edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "=abs(-2)"),
+                input(0, 0, 1, "=ABS(1,2)"),
+                input(0, 0, 2, "=NOSUCHFUNC(1)"),
+                input(0, 0, 3, "=SUMIFS(A1:A2,A1:A2)"),
+            ],
+        );
+        // The calls breaking the signatures are rejected in input.
+        let rejected = effect.rejections.iter().map(|r| r.col).collect::<Vec<_>>();
+        assert_eq!(rejected, vec![1, 3]);
+        let mut ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(matches!(ws.get_value(0, 0).unwrap(), Value::Number(n) if n == 2.));
+        assert!(matches!(ws.get_value(0, 1).unwrap(), Value::Empty));
+        assert!(matches!(ws.get_value(0, 2).unwrap(), Value::Error(e) if e == "#NAME?"));
+        assert!(matches!(ws.get_value(0, 3).unwrap(), Value::Empty));
+    }
+
+    #[test]
+    fn date_system_test() {
+        use super::edit_action::SetDateSystem;
+        use crate::file_loader2::load;
+        use crate::test_utils::{edit, input, set_num_fmt};
+        use crate::{Value, Workbook};
+        use logisheets_base::datetime::DateSystem;
+        use logisheets_workbook::prelude::read;
+        let mut wb = Workbook {
+            controller: Controller::default(),
+        };
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "=DATE(2012,11,10)"),
+                input(0, 0, 1, "=YEAR(C1)"),
+                input(0, 0, 2, "41223"),
+                input(0, 0, 3, "41223"),
+            ],
+        );
+        set_num_fmt(&mut wb, 0, 0, 2, "yyyy-mm-dd");
+
+        let set_date_system = |wb: &mut Workbook, date1904: bool| {
+            wb.handle_action(EditAction::SetDateSystem(SetDateSystem {
+                date1904,
+                shift_dates: true,
+            }));
+        };
+        let values = |wb: &mut Workbook| {
+            let mut ws = wb.get_sheet_by_idx(0).unwrap();
+            (0..4)
+                .map(|col| match ws.get_value(0, col).unwrap() {
+                    Value::Number(n) => n,
+                    _ => panic!(),
+                })
+                .collect::<Vec<_>>()
+        };
+        set_date_system(&mut wb, true);
+        // Only the numbers displayed as dates are shifted.
+        assert_eq!(values(&mut wb), vec![39761., 2012., 39761., 41223.]);
+
+        let book = read(&wb.controller.save().unwrap()).unwrap();
+        assert!(book.xl.workbook_part.workbook_pr.as_ref().unwrap().date1904);
+        let mut loaded = Workbook {
+            controller: load(book, String::from("Book1")),
+        };
+        assert_eq!(loaded.controller.settings.date_system, DateSystem::Date1904);
+        assert_eq!(values(&mut loaded)[..2], [39761., 2012.]);
+
+        // Switching the date system is undone like any other edit, and the
+        // inputs made before it are still in the history.
+        assert!(wb.controller.undo());
+        assert_eq!(wb.controller.settings.date_system, DateSystem::Date1900);
+        assert_eq!(values(&mut wb), vec![41223., 2012., 41223., 41223.]);
+        assert!(wb.controller.redo());
+        assert_eq!(wb.controller.settings.date_system, DateSystem::Date1904);
+        assert_eq!(values(&mut wb), vec![39761., 2012., 39761., 41223.]);
+
+        set_date_system(&mut wb, false);
+        assert_eq!(values(&mut wb), vec![41223., 2012., 41223., 41223.]);
+        assert!(wb.controller.undo());
+        assert!(wb.controller.undo());
+        assert!(wb.controller.undo());
+        assert!(matches!(
+            wb.get_sheet_by_idx(0).unwrap().get_value(0, 2).unwrap(),
+            Value::Empty
+        ));
+    }
+
     #[test]
     fn from_file_test() {
         use std::fs;
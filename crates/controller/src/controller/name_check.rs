@@ -0,0 +1,334 @@
+use logisheets_base::SheetId;
+use logisheets_lexer::is_valid_name;
+use logisheets_parser::{context::Context, Parser};
+
+use super::edit_action::{EditPayload, InvalidName, NameConflict};
+use super::status::Status;
+use crate::connectors::IdFetcher;
+
+// Take out the payloads defining, deleting or renaming names that can not be
+// done, like defining a name that formulas can not refer to or renaming to
+// a name defined already. The payloads are applied to a copy of the status
+// so that the names changed earlier in the same action are checked too.
+pub fn check_name_inputs(
+    status: &Status,
+    book_name: &str,
+    payloads: Vec<EditPayload>,
+) -> (Vec<EditPayload>, Vec<InvalidName>) {
+    let mut scratch: Option<Status> = None;
+    let mut invalid_names = vec![];
+    let payloads = payloads
+        .into_iter()
+        .filter(|p| {
+            let (name, sheet_idx) = match p {
+                EditPayload::DefineName(p) => (&p.name, p.sheet_idx),
+                EditPayload::DeleteName(p) => (&p.name, p.sheet_idx),
+                EditPayload::RenameName(p) => (&p.new_name, p.sheet_idx),
+                _ => return true,
+            };
+            let status = scratch.get_or_insert_with(|| status.clone());
+            // The payloads in an unknown scope are ignored in converting.
+            let scope = match sheet_idx {
+                Some(idx) => match status.sheet_pos_manager.get_sheet_id(idx) {
+                    Some(sheet_id) => Some(sheet_id),
+                    None => return true,
+                },
+                None => None,
+            };
+            let result = match p {
+                EditPayload::DefineName(p) => {
+                    define_name(status, book_name, scope, &p.name, &p.refers_to)
+                }
+                EditPayload::DeleteName(p) => delete_name(status, scope, &p.name),
+                EditPayload::RenameName(p) => rename_name(status, scope, &p.old_name, &p.new_name),
+                _ => unreachable!(),
+            };
+            match result {
+                Ok(_) => true,
+                Err(reason) => {
+                    invalid_names.push(InvalidName {
+                        name: name.clone(),
+                        sheet_idx,
+                        reason,
+                    });
+                    false
+                }
+            }
+        })
+        .collect();
+    (payloads, invalid_names)
+}
+
+// Defining a name defined already gives it the new formula.
+fn define_name(
+    status: &mut Status,
+    book_name: &str,
+    scope: Option<SheetId>,
+    name: &str,
+    refers_to: &str,
+) -> Result<(), NameConflict> {
+    if !is_valid_name(name) {
+        return Err(NameConflict::Invalid);
+    }
+    // The references without a sheet prefix are in the scope sheet, or in
+    // the first sheet for the workbook names.
+    let sheet_id = match scope.or_else(|| status.sheet_pos_manager.get_sheet_id(0)) {
+        Some(sheet_id) => sheet_id,
+        None => return Ok(()),
+    };
+    let mut id_fetcher = IdFetcher {
+        sheet_id_manager: &mut status.sheet_id_manager,
+        text_id_manager: &mut status.text_id_manager,
+        func_id_manager: &mut status.func_id_manager,
+        name_id_manager: &mut status.name_id_manager,
+        external_links_manager: &mut status.external_links_manager,
+        navigator: &mut status.navigator,
+    };
+    let mut context = Context {
+        sheet_id,
+        book_name,
+        id_fetcher: &mut id_fetcher,
+    };
+    let formula = refers_to.strip_prefix('=').unwrap_or(refers_to);
+    let ast = (Parser {})
+        .parse(formula, &mut context)
+        .ok_or(NameConflict::Unparsable)?;
+    let name_id = status.name_id_manager.get_scoped_id(scope, name);
+    status.vertex_manager.status.names.insert(name_id, ast);
+    Ok(())
+}
+
+fn delete_name(
+    status: &mut Status,
+    scope: Option<SheetId>,
+    name: &str,
+) -> Result<(), NameConflict> {
+    let names = &mut status.vertex_manager.status.names;
+    match status.name_id_manager.find(scope, name) {
+        Some(name_id) if names.contains_key(&name_id) => {
+            names.remove(&name_id);
+            Ok(())
+        }
+        _ => Err(NameConflict::NotFound),
+    }
+}
+
+// Formulas can refer to a name before it is defined, so the new name can be
+// one that is only referred to.
+fn rename_name(
+    status: &mut Status,
+    scope: Option<SheetId>,
+    old_name: &str,
+    new_name: &str,
+) -> Result<(), NameConflict> {
+    if !is_valid_name(new_name) {
+        return Err(NameConflict::Invalid);
+    }
+    let manager = &mut status.name_id_manager;
+    let names = &status.vertex_manager.status.names;
+    let name_id = match manager.find(scope, old_name) {
+        Some(name_id) if names.contains_key(&name_id) => name_id,
+        _ => return Err(NameConflict::NotFound),
+    };
+    if let Some(existed) = manager.find(scope, new_name) {
+        if existed != name_id && names.contains_key(&existed) {
+            return Err(NameConflict::Exists);
+        }
+    }
+    manager.rename(name_id, new_name.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::display::{DefinedName, DisplayPatch, DisplayRequest};
+    use crate::controller::edit_action::{
+        DefineName, DeleteName, EditPayload, NameConflict, RenameName,
+    };
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    fn define(name: &str, refers_to: &str) -> EditPayload {
+        EditPayload::DefineName(DefineName {
+            name: name.to_string(),
+            sheet_idx: None,
+            refers_to: refers_to.to_string(),
+        })
+    }
+
+    fn names(wb: &mut Workbook) -> Vec<DefinedName> {
+        wb.controller
+            .get_display_response(DisplayRequest {
+                sheet_idx: 0,
+                version: 0,
+            })
+            .patches
+            .into_iter()
+            .find_map(|p| match p {
+                DisplayPatch::Names(names) => Some(names.names),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn name_payloads_test() {
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![input(0, 0, 0, "10")]);
+        edit(&mut wb, vec![define("Double", "=$A$1*2")]);
+        edit(&mut wb, vec![input(0, 0, 1, "=Double+1")]);
+        edit(&mut wb, vec![input(0, 0, 2, "=Later")]);
+        edit(&mut wb, vec![input(0, 0, 3, "=Nowhere!Double")]);
+        edit(&mut wb, vec![input(0, 0, 4, "=Sheet1!Double")]);
+        assert_eq!(value(&mut wb, 0, 0, 1), "21");
+        assert_eq!(value(&mut wb, 0, 0, 2), "#NAME?");
+        assert_eq!(value(&mut wb, 0, 0, 3), "#NAME?");
+        assert_eq!(value(&mut wb, 0, 0, 4), "20");
+        let formula = wb.get_sheet_by_idx(0).unwrap().get_formula(0, 3).unwrap();
+        assert_eq!(formula, "Nowhere!Double");
+        // An unknown prefix is an external workbook rather than a sheet.
+        let status = &wb.controller.status;
+        assert!(status.sheet_id_manager.has("Nowhere").is_none());
+        let books = &status.external_links_manager.book_id_manager;
+        assert!(books.has("Nowhere").is_some());
+        assert!(books.has("Sheet1").is_none());
+
+        edit(
+            &mut wb,
+            vec![EditPayload::RenameName(RenameName {
+                old_name: String::from("Double"),
+                new_name: String::from("Later"),
+                sheet_idx: None,
+            })],
+        );
+        let formula = wb.get_sheet_by_idx(0).unwrap().get_formula(0, 1).unwrap();
+        assert_eq!(formula, "Later + 1");
+        assert_eq!(value(&mut wb, 0, 0, 1), "21");
+        assert_eq!(value(&mut wb, 0, 0, 2), "20");
+        edit(&mut wb, vec![input(0, 0, 0, "5")]);
+        assert_eq!(value(&mut wb, 0, 0, 2), "10");
+
+        let defined = names(&mut wb);
+        assert_eq!(defined.len(), 1);
+        assert_eq!(defined[0].name, "Later");
+        assert_eq!(defined[0].sheet_idx, None);
+        assert_eq!(defined[0].refers_to, "Sheet1!$A$1 * 2");
+
+        edit(
+            &mut wb,
+            vec![EditPayload::DeleteName(DeleteName {
+                name: String::from("Later"),
+                sheet_idx: None,
+            })],
+        );
+        let formula = wb.get_sheet_by_idx(0).unwrap().get_formula(0, 1).unwrap();
+        assert_eq!(formula, "Later + 1");
+        assert_eq!(value(&mut wb, 0, 0, 1), "#NAME?");
+        assert!(names(&mut wb).is_empty());
+        assert!(wb.controller.undo());
+        assert_eq!(value(&mut wb, 0, 0, 1), "11");
+    }
+
+    #[test]
+    fn invalid_name_test() {
+        let mut wb = Workbook::default();
+        let payloads = ["A1", "has space", "Rate2", "R2D2", "", "1x", "Rate"]
+            .iter()
+            .map(|n| define(n, "=1"))
+            .chain(std::iter::once(EditPayload::RenameName(RenameName {
+                old_name: String::from("Rate"),
+                new_name: String::from("R1C1"),
+                sheet_idx: Some(0),
+            })))
+            .collect();
+        let effect = edit(&mut wb, payloads);
+        let invalid_names = effect
+            .invalid_names
+            .iter()
+            .map(|n| (n.name.as_str(), n.sheet_idx))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            invalid_names,
+            vec![
+                ("A1", None),
+                ("has space", None),
+                ("Rate2", None),
+                ("R2D2", None),
+                ("", None),
+                ("1x", None),
+                ("R1C1", Some(0)),
+            ]
+        );
+        assert!(effect
+            .invalid_names
+            .iter()
+            .all(|n| matches!(n.reason, NameConflict::Invalid)));
+        let defined = names(&mut wb);
+        assert_eq!(defined.len(), 1);
+        assert_eq!(defined[0].name, "Rate");
+    }
+
+    #[test]
+    fn name_conflict_test() {
+        let rename = |old_name: &str, new_name: &str| {
+            EditPayload::RenameName(RenameName {
+                old_name: old_name.to_string(),
+                new_name: new_name.to_string(),
+                sheet_idx: None,
+            })
+        };
+        let delete = |name: &str| {
+            EditPayload::DeleteName(DeleteName {
+                name: name.to_string(),
+                sheet_idx: None,
+            })
+        };
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![input(0, 0, 0, "=Later")]);
+        let effect = edit(
+            &mut wb,
+            vec![
+                define("Rate", "=0.1"),
+                define("Tax", "=0.2"),
+                define("Broken", "=SUM(1,"),
+                rename("Rate", "Tax"),
+                rename("Missing", "Other"),
+                delete("Missing"),
+                // The names changed earlier in the action are checked, too.
+                delete("Tax"),
+                rename("Rate", "Tax"),
+                rename("Tax", "Later"),
+                rename("Tax", "Other"),
+                define("Rate", "=0.3"),
+            ],
+        );
+        let invalid_names = effect
+            .invalid_names
+            .iter()
+            .map(|n| (n.name.as_str(), n.reason.clone()))
+            .collect::<Vec<_>>();
+        assert!(
+            matches!(
+                invalid_names.as_slice(),
+                [
+                    ("Broken", NameConflict::Unparsable),
+                    ("Tax", NameConflict::Exists),
+                    ("Other", NameConflict::NotFound),
+                    ("Missing", NameConflict::NotFound),
+                    ("Other", NameConflict::NotFound),
+                ]
+            ),
+            "{:?}",
+            invalid_names
+        );
+        let defined = names(&mut wb);
+        assert_eq!(defined.len(), 2);
+        assert!(defined
+            .iter()
+            .any(|n| n.name == "Rate" && n.refers_to == "0.3"));
+        assert!(defined
+            .iter()
+            .any(|n| n.name == "Later" && n.refers_to == "0.1"));
+        assert_eq!(value(&mut wb, 0, 0, 0), "0.1");
+    }
+}
@@ -1,6 +1,7 @@
 use im::HashMap;
 
 use crate::cell_attachments::CellAttachmentsManager;
+use crate::cond_format_manager::CondFormatManager;
 use crate::container::DataContainer;
 use crate::ext_book_manager::ExtBooksManager;
 use crate::id_manager::FuncIdManager;
@@ -26,6 +27,7 @@ pub struct Status {
     pub sheet_pos_manager: SheetPosManager,
     pub style_manager: StyleManager,
     pub cell_attachment_manager: CellAttachmentsManager,
+    pub cond_format_manager: CondFormatManager,
 }
 
 impl Default for Status {
@@ -44,6 +46,7 @@ impl Default for Status {
             sheet_pos_manager: SheetPosManager::default(),
             style_manager: StyleManager::default(),
             cell_attachment_manager: CellAttachmentsManager::default(),
+            cond_format_manager: CondFormatManager::default(),
         }
     }
 }
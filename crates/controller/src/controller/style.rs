@@ -1,9 +1,9 @@
 use crate::style_manager::RawStyle;
 use crate::theme_manager::ThemeManager;
 use logisheets_workbook::prelude::{
-    CtBorder, CtBorderPr, CtCellAlignment, CtCellProtection, CtColor, CtFill, CtFont, CtFontFamily,
-    CtFontName, CtFontScheme, CtUnderlineProperty, CtVerticalAlignFontProperty, StBorderStyle,
-    StGradientType, StPatternType,
+    CtBorder, CtBorderPr, CtCellAlignment, CtCellProtection, CtColor, CtDxf, CtFill, CtFont,
+    CtFontFamily, CtFontName, CtFontScheme, CtUnderlineProperty, CtVerticalAlignFontProperty,
+    StBorderStyle, StGradientType, StPatternType,
 };
 use serde::Serialize;
 
@@ -19,6 +19,17 @@ pub struct Style {
     pub formatter: String,
 }
 
+// The style parts given by a conditional format.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/diff_style.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStyle {
+    pub font: Option<Font>,
+    pub fill: Option<Fill>,
+    pub border: Option<Border>,
+    pub formatter: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, TS)]
 #[ts(export_to = "../../src/bindings/font.ts")]
 #[serde(rename_all = "camelCase")]
@@ -128,6 +139,15 @@ impl<'a> StyleConverter<'a> {
         }
     }
 
+    pub fn convert_dxf(&self, dxf: CtDxf) -> DiffStyle {
+        DiffStyle {
+            font: dxf.font.map(|f| self.convert_font(f)),
+            fill: dxf.fill.map(|f| self.convert_fill(f)),
+            border: dxf.border.map(|b| self.convert_border(b)),
+            formatter: dxf.num_fmt.map(|n| n.format_code),
+        }
+    }
+
     fn convert_font(&self, font: CtFont) -> Font {
         Font {
             bold: font.bold,
@@ -212,7 +232,7 @@ impl<'a> StyleConverter<'a> {
         }
     }
 
-    fn convert_color(&self, color: CtColor) -> Color {
+    pub fn convert_color(&self, color: CtColor) -> Color {
         let tint = color.tint;
         let rgb = {
             if let Some(rgb) = &color.rgb {
@@ -0,0 +1,485 @@
+use std::collections::HashSet;
+
+use logisheets_base::{NameId, SheetId};
+use logisheets_parser::ast;
+use serde::Serialize;
+
+use super::status::Status;
+use crate::calc_engine::calculator::funcs::lookup::{MAX_COLS, MAX_ROWS};
+use crate::vertex_manager::vertex::{FormulaId, MutReferenceVertex, UnMutRefVertex, Vertex};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/trace_node.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct TraceNode {
+    // The direct precedents or dependents are at depth 1.
+    pub depth: usize,
+    pub vertex: TraceVertex,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/trace_vertex.ts")]
+#[serde(rename_all = "camelCase")]
+pub enum TraceVertex {
+    Range(TraceRange),
+    Name(TraceName),
+    External(TraceExternal),
+}
+
+// A single cell is a range whose start and end are the same. The whole
+// columns or rows are given by their bounds in the sheet.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/trace_range.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct TraceRange {
+    pub sheet_idx: usize,
+    pub row_start: usize,
+    pub col_start: usize,
+    pub row_end: usize,
+    pub col_end: usize,
+}
+
+impl TraceRange {
+    fn contains(&self, sheet_idx: usize, row: usize, col: usize) -> bool {
+        self.sheet_idx == sheet_idx
+            && row >= self.row_start
+            && row <= self.row_end
+            && col >= self.col_start
+            && col <= self.col_end
+    }
+}
+
+// `sheet_idx` is None if the name is defined in the workbook scope.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/trace_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct TraceName {
+    pub name: String,
+    pub sheet_idx: Option<usize>,
+}
+
+// The references to other workbooks can only be reported as they are written.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/trace_external.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct TraceExternal {
+    pub workbook: String,
+    pub from_sheet: String,
+    pub to_sheet: String,
+    pub row_start: usize,
+    pub col_start: usize,
+    pub row_end: usize,
+    pub col_end: usize,
+}
+
+pub struct Tracer<'a> {
+    pub status: &'a mut Status,
+}
+
+impl<'a> Tracer<'a> {
+    pub fn precedents(&mut self, fid: FormulaId, depth: usize) -> Vec<TraceNode> {
+        let mut result = vec![];
+        let mut visited = HashSet::<Vertex>::new();
+        let mut expanded = HashSet::<FormulaId>::new();
+        let mut frontier = vec![fid];
+        for d in 1..=depth {
+            let mut level = vec![];
+            let mut next = vec![];
+            frontier.into_iter().for_each(|f| {
+                if !expanded.insert(f) {
+                    return;
+                }
+                if let Some(node) = self.status.vertex_manager.status.formulas.get(&f) {
+                    let node = node.clone();
+                    self.find_externals(&node, &mut level);
+                }
+                let mut stack = self.get_deps(&Vertex::Cell(f));
+                while let Some(v) = stack.pop() {
+                    if !visited.insert(v.clone()) {
+                        continue;
+                    }
+                    match &v {
+                        Vertex::Cell(c) => {
+                            if let Some(r) = self.get_ranges(&v).pop() {
+                                level.push(TraceVertex::Range(r));
+                            }
+                            next.push(*c);
+                        }
+                        Vertex::SheetRange(_) | Vertex::StsRange(_) => {
+                            // The formulas in the range are traced in the next
+                            // level but not reported one by one.
+                            self.get_ranges(&v).into_iter().for_each(|r| {
+                                next.extend(self.get_formulas_in(&r));
+                                level.push(TraceVertex::Range(r));
+                            });
+                        }
+                        // A name is reported and what it refers to is at the
+                        // same level.
+                        Vertex::Name(nid) => {
+                            if let Some(n) = self.get_name(nid) {
+                                level.push(TraceVertex::Name(n));
+                            }
+                            if let Some(node) = self.status.vertex_manager.status.names.get(nid) {
+                                let node = node.clone();
+                                self.find_externals(&node, &mut level);
+                            }
+                            stack.extend(self.get_deps(&v));
+                        }
+                        Vertex::External => {}
+                    }
+                }
+            });
+            push_level(&mut result, level, d);
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        result
+    }
+
+    pub fn dependents(&mut self, fid: FormulaId, depth: usize) -> Vec<TraceNode> {
+        let mut result = vec![];
+        let mut visited = HashSet::<Vertex>::new();
+        let mut frontier = vec![fid];
+        for d in 1..=depth {
+            let mut level = vec![];
+            let mut next = vec![];
+            frontier.into_iter().for_each(|f| {
+                let mut stack = self.get_cell_rdeps(f);
+                while let Some(v) = stack.pop() {
+                    if !visited.insert(v.clone()) {
+                        continue;
+                    }
+                    match &v {
+                        Vertex::Cell(c) => {
+                            if let Some(r) = self.get_ranges(&v).pop() {
+                                level.push(TraceVertex::Range(r));
+                            }
+                            next.push(*c);
+                        }
+                        Vertex::Name(nid) => {
+                            if let Some(n) = self.get_name(nid) {
+                                level.push(TraceVertex::Name(n));
+                            }
+                            stack.extend(self.get_rdeps(&v));
+                        }
+                        _ => {}
+                    }
+                }
+            });
+            push_level(&mut result, level, d);
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        result
+    }
+
+    fn get_deps(&self, v: &Vertex) -> Vec<Vertex> {
+        let graph = &self.status.vertex_manager.status.graph;
+        graph
+            .get_deps(v)
+            .map_or(vec![], |deps| deps.iter().cloned().collect())
+    }
+
+    fn get_rdeps(&self, v: &Vertex) -> Vec<Vertex> {
+        let graph = &self.status.vertex_manager.status.graph;
+        graph
+            .get_rdeps(v)
+            .map_or(vec![], |rdeps| rdeps.iter().cloned().collect())
+    }
+
+    // Get the vertices depending on the cell itself or the ranges containing
+    // it.
+    fn get_cell_rdeps(&mut self, fid: FormulaId) -> Vec<Vertex> {
+        let (sheet_id, cell_id) = fid;
+        let sheet_idx = self.status.sheet_pos_manager.get_sheet_idx(sheet_id);
+        let idx = self.status.navigator.fetch_cell_idx(sheet_id, &cell_id);
+        let (sheet_idx, (row, col)) = match (sheet_idx, idx) {
+            (Some(s), Some(i)) => (s, i),
+            _ => return vec![],
+        };
+        let vertex_status = &self.status.vertex_manager.status;
+        let mut vertices = vec![Vertex::Cell(fid)];
+        if let Some(ranges) = vertex_status.range_vertices.get(&sheet_id) {
+            vertices.extend(ranges.iter().map(|r| Vertex::SheetRange(r.clone())));
+        }
+        vertices.extend(
+            vertex_status
+                .sts_vertices
+                .iter()
+                .map(|sts| Vertex::StsRange(sts.clone())),
+        );
+        let mut result = vec![];
+        vertices.into_iter().for_each(|v| {
+            let contained = match v {
+                Vertex::Cell(_) => true,
+                _ => self
+                    .get_ranges(&v)
+                    .iter()
+                    .any(|r| r.contains(sheet_idx, row, col)),
+            };
+            if contained {
+                result.extend(self.get_rdeps(&v));
+            }
+        });
+        result
+    }
+
+    fn get_ranges(&mut self, v: &Vertex) -> Vec<TraceRange> {
+        let status = &mut *self.status;
+        match v {
+            Vertex::Cell((sheet_id, cell_id)) => {
+                let sheet_idx = status.sheet_pos_manager.get_sheet_idx(*sheet_id);
+                let idx = status.navigator.fetch_cell_idx(*sheet_id, cell_id);
+                match (sheet_idx, idx) {
+                    (Some(sheet_idx), Some((row, col))) => vec![TraceRange {
+                        sheet_idx,
+                        row_start: row,
+                        col_start: col,
+                        row_end: row,
+                        col_end: col,
+                    }],
+                    _ => vec![],
+                }
+            }
+            Vertex::SheetRange(srv) => {
+                let sheet_id = srv.sheet_id;
+                let sheet_idx = match status.sheet_pos_manager.get_sheet_idx(sheet_id) {
+                    Some(idx) => idx,
+                    None => return vec![],
+                };
+                let navigator = &mut status.navigator;
+                let bounds = match &srv.reference {
+                    MutReferenceVertex::ColRange(cr) => {
+                        let start = navigator.fetch_col_idx(sheet_id, cr.start);
+                        let end = navigator.fetch_col_idx(sheet_id, cr.end);
+                        start.zip(end).map(|(s, e)| (0, s, MAX_ROWS - 1, e))
+                    }
+                    MutReferenceVertex::RowRange(rr) => {
+                        let start = navigator.fetch_row_idx(sheet_id, rr.start);
+                        let end = navigator.fetch_row_idx(sheet_id, rr.end);
+                        start.zip(end).map(|(s, e)| (s, 0, e, MAX_COLS - 1))
+                    }
+                    MutReferenceVertex::AddrRange(ar) => {
+                        let start = navigator.fetch_cell_idx(sheet_id, &ar.start);
+                        let end = navigator.fetch_cell_idx(sheet_id, &ar.end);
+                        start.zip(end).map(|((rs, cs), (re, ce))| (rs, cs, re, ce))
+                    }
+                };
+                bounds.map_or(vec![], |b| vec![to_range(sheet_idx, b)])
+            }
+            Vertex::StsRange(sts) => {
+                let pos_manager = &status.sheet_pos_manager;
+                let start = pos_manager.get_sheet_idx(sts.start);
+                let end = pos_manager.get_sheet_idx(sts.end);
+                let bounds = unmut_bounds(&sts.reference);
+                match (start, end) {
+                    (Some(s), Some(e)) => (s.min(e)..=s.max(e))
+                        .map(|sheet_idx| to_range(sheet_idx, bounds))
+                        .collect(),
+                    _ => vec![],
+                }
+            }
+            _ => vec![],
+        }
+    }
+
+    fn get_formulas_in(&mut self, range: &TraceRange) -> Vec<FormulaId> {
+        let status = &mut *self.status;
+        let sheet_id = match status.sheet_pos_manager.get_sheet_id(range.sheet_idx) {
+            Some(id) => id,
+            None => return vec![],
+        };
+        let navigator = &mut status.navigator;
+        status
+            .vertex_manager
+            .status
+            .formulas
+            .keys()
+            .filter(|(s, c)| {
+                *s == sheet_id
+                    && navigator
+                        .fetch_cell_idx(sheet_id, c)
+                        .is_some_and(|(r, c)| range.contains(range.sheet_idx, r, c))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn get_name(&self, nid: &NameId) -> Option<TraceName> {
+        let name_id_manager = &self.status.name_id_manager;
+        let (_, name) = name_id_manager.get_string(nid)?;
+        let sheet_idx = name_id_manager
+            .get_scope(nid)
+            .and_then(|s| self.status.sheet_pos_manager.get_sheet_idx(s));
+        Some(TraceName { name, sheet_idx })
+    }
+
+    fn find_externals(&self, node: &ast::Node, result: &mut Vec<TraceVertex>) {
+        match &node.pure {
+            ast::PureNode::Func(func) => func
+                .args
+                .iter()
+                .for_each(|arg| self.find_externals(arg, result)),
+            ast::PureNode::Reference(ast::CellReference::UnMut(unmut_ref)) => {
+                let (book, from, to) = match &unmut_ref.prefix {
+                    ast::UnMutRefPrefix::External(ast::ExternalUnMutRefPrefix::Sheet(s)) => {
+                        (s.workbook, s.sheet, s.sheet)
+                    }
+                    ast::UnMutRefPrefix::External(ast::ExternalUnMutRefPrefix::SheetToSheet(
+                        sts,
+                    )) => (sts.workbook, sts.from_sheet, sts.to_sheet),
+                    ast::UnMutRefPrefix::Local(_) => return,
+                };
+                let sheet_name = |id: SheetId| {
+                    self.status
+                        .sheet_id_manager
+                        .get_string(&id)
+                        .unwrap_or_default()
+                };
+                let reference = UnMutRefVertex::from_unmut_ref(unmut_ref.reference.clone());
+                let (row_start, col_start, row_end, col_end) = unmut_bounds(&reference);
+                result.push(TraceVertex::External(TraceExternal {
+                    workbook: self
+                        .status
+                        .external_links_manager
+                        .fetch_book_name(&book)
+                        .unwrap_or_default(),
+                    from_sheet: sheet_name(from),
+                    to_sheet: sheet_name(to),
+                    row_start,
+                    col_start,
+                    row_end,
+                    col_end,
+                }))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn push_level(result: &mut Vec<TraceNode>, mut level: Vec<TraceVertex>, depth: usize) {
+    level.sort();
+    level.dedup();
+    result.extend(level.into_iter().map(|vertex| TraceNode { depth, vertex }));
+}
+
+fn to_range(sheet_idx: usize, bounds: (usize, usize, usize, usize)) -> TraceRange {
+    let (row_start, col_start, row_end, col_end) = bounds;
+    TraceRange {
+        sheet_idx,
+        row_start,
+        col_start,
+        row_end,
+        col_end,
+    }
+}
+
+fn unmut_bounds(reference: &UnMutRefVertex) -> (usize, usize, usize, usize) {
+    match reference {
+        UnMutRefVertex::ColRange(cr) => (0, cr.start, MAX_ROWS - 1, cr.end),
+        UnMutRefVertex::RowRange(rr) => (rr.start, 0, rr.end, MAX_COLS - 1),
+        UnMutRefVertex::AddrRange(ar) => (ar.row_start, ar.col_start, ar.row_end, ar.col_end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TraceExternal, TraceName, TraceNode, TraceRange, TraceVertex};
+    use crate::controller::edit_action::{DefineName, EditPayload, InsertSheet};
+    use crate::test_utils::{edit, input};
+    use crate::Workbook;
+
+    fn cell(depth: usize, sheet_idx: usize, row: usize, col: usize) -> TraceNode {
+        TraceNode {
+            depth,
+            vertex: TraceVertex::Range(TraceRange {
+                sheet_idx,
+                row_start: row,
+                col_start: col,
+                row_end: row,
+                col_end: col,
+            }),
+        }
+    }
+
+    fn double(depth: usize) -> TraceNode {
+        TraceNode {
+            depth,
+            vertex: TraceVertex::Name(TraceName {
+                name: String::from("Double"),
+                sheet_idx: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn trace_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![EditPayload::InsertSheet(InsertSheet { sheet_idx: 1 })],
+        );
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 0, 1, "=A1+1"),
+                input(0, 0, 2, "=SUM(A1:B1)"),
+                EditPayload::DefineName(DefineName {
+                    name: String::from("Double"),
+                    sheet_idx: None,
+                    refers_to: String::from("=Sheet1!$C$1*2"),
+                }),
+                input(0, 0, 3, "=Double+Sheet2!A1"),
+                input(1, 0, 0, "=Sheet1!A1*10"),
+                input(1, 0, 1, "=[Book2.xlsx]Sheet1!B2"),
+            ],
+        );
+        let wb = &mut wb.controller;
+
+        let precedents = wb.trace_precedents(0, 0, 3, 1);
+        assert_eq!(precedents.len(), 3);
+        assert!(precedents.contains(&double(1)));
+        assert!(precedents.contains(&cell(1, 0, 0, 2)));
+        assert!(precedents.contains(&cell(1, 1, 0, 0)));
+        let precedents = wb.trace_precedents(0, 0, 3, 5);
+        assert_eq!(precedents.len(), 5);
+        assert!(precedents.contains(&cell(2, 0, 0, 0)));
+        let range = TraceNode {
+            depth: 2,
+            vertex: TraceVertex::Range(TraceRange {
+                sheet_idx: 0,
+                row_start: 0,
+                col_start: 0,
+                row_end: 0,
+                col_end: 1,
+            }),
+        };
+        assert!(precedents.contains(&range));
+        let external = TraceVertex::External(TraceExternal {
+            workbook: String::from("Book2.xlsx"),
+            from_sheet: String::from("Sheet1"),
+            to_sheet: String::from("Sheet1"),
+            row_start: 1,
+            col_start: 1,
+            row_end: 1,
+            col_end: 1,
+        });
+        assert_eq!(wb.trace_precedents(1, 0, 1, 1)[0].vertex, external);
+
+        let dependents = wb.trace_dependents(0, 0, 0, 1);
+        assert_eq!(
+            dependents,
+            vec![cell(1, 0, 0, 1), cell(1, 0, 0, 2), cell(1, 1, 0, 0)]
+        );
+        let dependents = wb.trace_dependents(0, 0, 0, 3);
+        assert_eq!(dependents.len(), 5);
+        assert!(dependents.contains(&double(2)));
+        assert!(dependents.contains(&cell(2, 0, 0, 3)));
+        assert!(wb.trace_dependents(0, 0, 3, 3).is_empty());
+    }
+}
@@ -1,22 +1,43 @@
 use std::collections::{HashMap, HashSet};
 
-use logisheets_base::{Addr, CellId, SheetId};
+use logisheets_base::datetime::DateSystem;
+use logisheets_base::matrix_value::cross_product_usize;
+use logisheets_base::{Addr, CellId, CellValue, NameId, NormalCellId, SheetId};
+use logisheets_lexer::is_valid_name;
+use logisheets_parser::{context::Context, Parser};
 
+use super::cond_format::shift_node;
 use super::status::Status;
 use crate::async_func_manager::AsyncFuncManager;
+use crate::calc_engine::calculator::is_volatile;
 use crate::calc_engine::CalcEngine;
-use crate::connectors::{CalcConnector, VertexConnector};
+use crate::cell_attachments::comment::{Comment, ThreadedComment};
+use crate::cell_attachments::merge_cell::MergeCells;
+use crate::cond_format_manager::{CondFormat, CondFormatManager, CondFormatRule};
+use crate::connectors::{CalcConnector, IdFetcher, VertexConnector};
 use crate::data_executor::DataExecutor;
-use crate::payloads::name::NamePayload;
-use crate::payloads::sheet_process::SheetProcess;
-use crate::payloads::sheet_shift::{SheetRenamePayload, SheetShiftPayload};
+use crate::navigator::Navigator;
+use crate::payloads::name::{AddName, NamePayload, RemoveName, RenameName};
+use crate::payloads::sheet_process::{
+    CellChange, CellPayload, CommentPayload, Direction, LineShift, MergeCellsPayload, MergePayload,
+    SheetPayload, SheetProcess, ShiftPayload, ShiftType, SplitMergedCellsPayload,
+};
+use crate::payloads::sheet_shift::{SheetRenamePayload, SheetShiftPayload, SheetShiftType};
 use crate::payloads::Process;
-use crate::settings::CalcConfig;
+use crate::settings::{CalcConfig, CalcMode};
+use crate::vertex_manager::executors::input_formula::{
+    add_dynamic_deps, add_name_node, remove_ast_node,
+};
+use crate::vertex_manager::executors::name::{remove_name_node, replace_name_node};
+use crate::vertex_manager::executors::sheet::{copy_sheet, delete_sheet};
+use crate::vertex_manager::vertex::{FormulaId, Vertex};
+use crate::vertex_manager::VertexManager;
 
 pub struct TransactionContext<'a> {
     pub book_name: &'a str,
     pub calc_config: CalcConfig,
     pub async_funcs: &'a HashSet<String>,
+    pub date_system: DateSystem,
 }
 
 pub struct Transaction<'a> {
@@ -24,12 +45,34 @@ pub struct Transaction<'a> {
     pub async_func_manager: &'a mut AsyncFuncManager,
     pub context: TransactionContext<'a>,
     pub proc: Vec<Process>,
+    pub recalc: Option<Recalc>,
+}
+
+// Calculate the queued dirty nodes, even in the manual mode.
+pub enum Recalc {
+    Book,
+    Sheet(SheetId),
 }
 
 impl<'a> Transaction<'a> {
-    pub fn start(self) -> Status {
+    // Return the new status and the circular references met in calculation.
+    pub fn start(self) -> (Status, Vec<Vec<FormulaId>>) {
         let context = self.context;
         let mut async_func_manager = self.async_func_manager;
+        let mut status = self
+            .proc
+            .into_iter()
+            .fold(self.status, |prev, proc| handle(prev, proc, &context));
+        let func_id_manager = &status.func_id_manager;
+        let edited = status.vertex_manager.dirty_nodes.clone();
+        status.vertex_manager.update_volatiles(edited, |id| {
+            func_id_manager
+                .get_string(id)
+                .is_some_and(|name| is_volatile(&name))
+        });
+        if context.calc_config.mode == CalcMode::Manual && self.recalc.is_none() {
+            return (status, vec![]);
+        }
         let Status {
             mut vertex_manager,
             mut navigator,
@@ -42,11 +85,24 @@ impl<'a> Transaction<'a> {
             sheet_pos_manager,
             style_manager,
             cell_attachment_manager,
-        } = self
-            .proc
-            .into_iter()
-            .fold(self.status, |prev, proc| handle(prev, proc, &context));
-        let vm = vertex_manager.clone_for_calc();
+            cond_format_manager,
+        } = status;
+        let calc_sheet = match self.recalc {
+            Some(Recalc::Sheet(sheet_id)) => Some(sheet_id),
+            _ => None,
+        };
+        let mut vm = match calc_sheet {
+            Some(sheet_id) => vertex_manager.clone_for_calc_sheet(sheet_id),
+            None => vertex_manager.clone_for_calc(),
+        };
+        // Volatile functions are calculated every time.
+        let volatiles = vm
+            .status
+            .volatiles
+            .iter()
+            .filter(|(s, _)| calc_sheet.is_none_or(|sheet_id| *s == sheet_id));
+        vm.dirty_nodes = vm.dirty_nodes.clone().union(volatiles.cloned().collect());
+        let mut dynamic_deps = HashMap::new();
         let connector = CalcConnector {
             vertex_status: &vertex_manager.status,
             navigator: &mut navigator,
@@ -54,21 +110,30 @@ impl<'a> Transaction<'a> {
             ext_links: &mut external_links_manager,
             text_id_manager: &mut text_id_manager,
             func_id_manager: &mut func_id_manager,
-            names_storage: HashMap::new(),
+            calculating_names: HashSet::new(),
             cells_stroage: HashMap::new(),
             sheet_pos_manager: &sheet_pos_manager,
+            sheet_id_manager: &sheet_id_manager,
+            calc_dynamic_deps: HashMap::new(),
+            dynamic_deps: &mut dynamic_deps,
             async_func_manager: &mut async_func_manager,
             active_sheet: 0,
             curr_addr: Addr::default(),
             async_funcs: &context.async_funcs,
+            date_system: context.date_system,
         };
         let calc_engine = CalcEngine {
             vertex_manager: vm,
             config: context.calc_config,
             connector,
         };
-        calc_engine.start();
-        Status {
+        let cycles = calc_engine.start();
+        vertex_manager.status = dynamic_deps
+            .into_iter()
+            .fold(vertex_manager.status, |status, (fid, deps)| {
+                add_dynamic_deps(status, fid, deps)
+            });
+        let status = Status {
             navigator,
             vertex_manager,
             container,
@@ -80,7 +145,9 @@ impl<'a> Transaction<'a> {
             sheet_pos_manager,
             style_manager,
             cell_attachment_manager,
-        }
+            cond_format_manager,
+        };
+        (status, cycles)
     }
 }
 
@@ -111,44 +178,182 @@ fn handle_sheet_rename_payload(status: Status, payload: SheetRenamePayload) -> S
 }
 
 fn handle_sheet_shift_payload(status: Status, payload: SheetShiftPayload) -> Status {
-    let Status {
-        navigator,
-        vertex_manager,
-        container,
-        mut sheet_id_manager,
-        func_id_manager,
-        text_id_manager,
-        name_id_manager,
-        external_links_manager,
-        sheet_pos_manager,
-        style_manager,
-        cell_attachment_manager,
-    } = status;
-    let sheet_pos = sheet_pos_manager.execute(&payload, &mut sheet_id_manager);
-    Status {
-        navigator,
-        vertex_manager,
-        container,
-        sheet_id_manager,
-        func_id_manager,
-        text_id_manager,
-        name_id_manager,
-        external_links_manager,
-        sheet_pos_manager: sheet_pos,
-        style_manager,
-        cell_attachment_manager,
+    let mut res = status;
+    let sheet_id = res.sheet_pos_manager.get_sheet_id(payload.idx);
+    res.sheet_pos_manager = res
+        .sheet_pos_manager
+        .execute(&payload, &mut res.sheet_id_manager);
+    match (payload.ty, sheet_id) {
+        (SheetShiftType::Delete, Some(sheet_id)) => delete_sheet_data(res, sheet_id),
+        (SheetShiftType::Copy(to), Some(sheet_id)) => {
+            match res.sheet_pos_manager.get_sheet_id(to) {
+                Some(new_id) => copy_sheet_data(res, sheet_id, new_id),
+                None => res,
+            }
+        }
+        _ => res,
     }
 }
 
-fn handle_name_proc(
-    _status: Status,
-    _payload: NamePayload,
-    _context: &TransactionContext,
-) -> Status {
-    todo!()
+fn delete_sheet_data(status: Status, sheet_id: SheetId) -> Status {
+    let mut res = status;
+    if let Some(name) = res.sheet_id_manager.get_string(&sheet_id) {
+        res.sheet_id_manager.ids.remove(&name);
+    }
+    res.navigator.sheet_navs.remove(&sheet_id);
+    res.container.data.remove(&sheet_id);
+    res.cell_attachment_manager.comments.data.remove(&sheet_id);
+    res.cell_attachment_manager
+        .merge_cells
+        .data
+        .remove(&sheet_id);
+    res.cond_format_manager.data.remove(&sheet_id);
+    let names = res.name_id_manager.remove_local_names(sheet_id);
+    names.iter().for_each(|nid| {
+        res.name_id_manager.hiddens.remove(nid);
+    });
+    let vertex_manager = &mut res.vertex_manager;
+    let (vertex_status, cells) = delete_sheet(vertex_manager.status.clone(), sheet_id, &names);
+    vertex_manager.status = vertex_status;
+    vertex_manager.dirty_nodes.retain(|(s, _)| *s != sheet_id);
+    vertex_manager.dirty_nodes.extend(cells);
+    res
+}
+
+fn copy_sheet_data(status: Status, from: SheetId, to: SheetId) -> Status {
+    let mut res = status;
+    if let Some(nav) = res.navigator.sheet_navs.get(&from).cloned() {
+        res.navigator.sheet_navs.insert(to, nav);
+    }
+    if let Some(data) = res.container.data.get(&from).cloned() {
+        res.container.data.insert(to, data);
+    }
+    let attachments = &mut res.cell_attachment_manager;
+    if let Some(comments) = attachments.comments.data.get(&from).cloned() {
+        attachments.comments.data.insert(to, comments);
+    }
+    if let Some(merges) = attachments.merge_cells.data.get(&from).cloned() {
+        attachments.merge_cells.data.insert(to, merges);
+    }
+    let cond_formats = &mut res.cond_format_manager;
+    if let Some(formats) = cond_formats.data.get(&from).cloned() {
+        cond_formats.data.insert(to, formats);
+    }
+    let names = res.name_id_manager.copy_local_names(from, to);
+    let vertex_manager = &mut res.vertex_manager;
+    let (vertex_status, cells) = copy_sheet(vertex_manager.status.clone(), from, to, &names);
+    vertex_manager.status = vertex_status;
+    vertex_manager.dirty_nodes.extend(cells);
+    res
+}
+
+fn handle_name_proc(status: Status, payload: NamePayload, context: &TransactionContext) -> Status {
+    match payload {
+        NamePayload::Add(p) => add_name(status, p, context),
+        NamePayload::Remove(p) => remove_name(status, p),
+        NamePayload::Rename(p) => rename_name(status, p),
+    }
+}
+
+fn add_name(status: Status, payload: AddName, context: &TransactionContext) -> Status {
+    let mut res = status;
+    if !is_valid_name(&payload.name) {
+        return res;
+    }
+    // References without a sheet prefix are in the scope sheet, or in the
+    // first sheet for the workbook names.
+    let sheet_id = match payload.scope {
+        Some(sheet_id) => sheet_id,
+        None => match res.sheet_pos_manager.get_sheet_id(0) {
+            Some(sheet_id) => sheet_id,
+            None => return res,
+        },
+    };
+    let name_id = res
+        .name_id_manager
+        .get_scoped_id(payload.scope, &payload.name);
+    let mut id_fetcher = IdFetcher {
+        sheet_id_manager: &mut res.sheet_id_manager,
+        text_id_manager: &mut res.text_id_manager,
+        func_id_manager: &mut res.func_id_manager,
+        name_id_manager: &mut res.name_id_manager,
+        external_links_manager: &mut res.external_links_manager,
+        navigator: &mut res.navigator,
+    };
+    let mut parser_context = Context {
+        sheet_id,
+        book_name: context.book_name,
+        id_fetcher: &mut id_fetcher,
+    };
+    let ast = match (Parser {}).parse(&payload.refer_to, &mut parser_context) {
+        Some(ast) => ast,
+        None => return res,
+    };
+    let vertex_manager = &mut res.vertex_manager;
+    vertex_manager.status = add_name_node(vertex_manager.status.clone(), name_id, ast);
+    mark_name_rdeps_dirty(vertex_manager, name_id);
+    res
+}
+
+// The name id is kept after removing so that the formulas referring to it can
+// still be displayed. They will be calculated as #NAME?.
+fn remove_name(status: Status, payload: RemoveName) -> Status {
+    let mut res = status;
+    let name_id = match res.name_id_manager.find(payload.scope, &payload.name) {
+        Some(name_id) => name_id,
+        None => return res,
+    };
+    res.name_id_manager.hiddens.remove(&name_id);
+    let vertex_manager = &mut res.vertex_manager;
+    vertex_manager.status = remove_name_node(vertex_manager.status.clone(), name_id);
+    mark_name_rdeps_dirty(vertex_manager, name_id);
+    res
+}
+
+fn rename_name(status: Status, payload: RenameName) -> Status {
+    let mut res = status;
+    if !is_valid_name(&payload.new_name) {
+        return res;
+    }
+    let name_id = match res.name_id_manager.find(payload.scope, &payload.old_name) {
+        Some(name_id) => name_id,
+        None => return res,
+    };
+    if let Some(existed) = res.name_id_manager.find(payload.scope, &payload.new_name) {
+        if res.vertex_manager.status.names.contains_key(&existed) {
+            return res;
+        }
+        // Formulas can refer to a name before it is defined. Now they refer
+        // to the renamed one.
+        let (vertex_status, cells) =
+            replace_name_node(res.vertex_manager.status.clone(), existed, name_id);
+        res.vertex_manager.status = vertex_status;
+        res.vertex_manager.dirty_nodes.extend(cells);
+    }
+    res.name_id_manager.rename(name_id, payload.new_name);
+    mark_name_rdeps_dirty(&mut res.vertex_manager, name_id);
+    res
+}
+
+fn mark_name_rdeps_dirty(vertex_manager: &mut VertexManager, name_id: NameId) {
+    let cells = vertex_manager
+        .status
+        .graph
+        .get_cell_rdeps(&Vertex::Name(name_id));
+    vertex_manager.dirty_nodes.extend(cells);
 }
 
 fn handle_sheet_proc(status: Status, proc: SheetProcess, context: &TransactionContext) -> Status {
+    if let SheetPayload::Merge(p) = proc.payload {
+        return handle_merge_payload(status, proc.sheet_id, p, context);
+    }
+    if let SheetPayload::Comment(p) = proc.payload {
+        return handle_comment_payload(status, proc.sheet_id, p);
+    }
+    let line_shift = match &proc.payload {
+        SheetPayload::Shift(ShiftPayload::Line(ls)) => Some(ls.clone()),
+        _ => None,
+    };
     let Status {
         navigator,
         vertex_manager,
@@ -160,7 +365,8 @@ fn handle_sheet_proc(status: Status, proc: SheetProcess, context: &TransactionCo
         mut name_id_manager,
         mut sheet_pos_manager,
         style_manager,
-        cell_attachment_manager,
+        mut cell_attachment_manager,
+        mut cond_format_manager,
     } = status;
     let mut old_navigator = navigator.clone();
     let data_executor = DataExecutor {
@@ -193,6 +399,22 @@ fn handle_sheet_proc(status: Status, proc: SheetProcess, context: &TransactionCo
             external_links_manager: &mut external_links_manager,
         },
     );
+    if let Some(ls) = line_shift {
+        cell_attachment_manager.merge_cells = shift_merge_cells(
+            cell_attachment_manager.merge_cells,
+            active_sheet,
+            &ls,
+            &mut old_navigator,
+            &mut new_navigator,
+        );
+        cond_format_manager = shift_cond_formats(
+            cond_format_manager,
+            active_sheet,
+            &ls,
+            &mut old_navigator,
+            &mut new_navigator,
+        );
+    }
     Status {
         navigator: new_navigator,
         vertex_manager,
@@ -205,5 +427,442 @@ fn handle_sheet_proc(status: Status, proc: SheetProcess, context: &TransactionCo
         sheet_pos_manager,
         style_manager: new_style_manager,
         cell_attachment_manager,
+        cond_format_manager,
+    }
+}
+
+fn handle_merge_payload(
+    status: Status,
+    sheet_id: SheetId,
+    payload: MergePayload,
+    context: &TransactionContext,
+) -> Status {
+    match payload {
+        MergePayload::Merge(p) => merge_cells(status, sheet_id, p, context),
+        MergePayload::Split(p) => split_merged_cells(status, sheet_id, p),
+    }
+}
+
+fn merge_cells(
+    status: Status,
+    sheet_id: SheetId,
+    payload: MergeCellsPayload,
+    context: &TransactionContext,
+) -> Status {
+    let MergeCellsPayload {
+        start_row,
+        start_col,
+        end_row,
+        end_col,
+    } = payload;
+    let mut res = status;
+    if start_row > end_row || start_col > end_col || (start_row, start_col) == (end_row, end_col) {
+        return res;
+    }
+    let overlapped =
+        get_merged_ranges(&mut res, sheet_id)
+            .into_iter()
+            .any(|(_, (sr, sc), (er, ec))| {
+                sr <= end_row && start_row <= er && sc <= end_col && start_col <= ec
+            });
+    if overlapped {
+        return res;
+    }
+    let start = res
+        .navigator
+        .fetch_norm_cell_id(sheet_id, start_row, start_col);
+    let end = res.navigator.fetch_norm_cell_id(sheet_id, end_row, end_col);
+    let (start, end) = match (start, end) {
+        (Some(s), Some(e)) => (s, e),
+        _ => return res,
+    };
+    res.cell_attachment_manager
+        .merge_cells
+        .add_merge_cell2(sheet_id, start, end);
+    // Only the value of the top-left cell is kept, just like Excel.
+    let cleared = cross_product_usize(start_row, end_row, start_col, end_col)
+        .into_iter()
+        .filter(|pos| *pos != (start_row, start_col))
+        .filter_map(|(row, col)| {
+            let cell_id = res.navigator.fetch_cell_id(sheet_id, row, col)?;
+            let has_formula = res
+                .vertex_manager
+                .status
+                .formulas
+                .contains_key(&(sheet_id, cell_id));
+            if has_formula || res.container.get_cell(sheet_id, &cell_id).is_some() {
+                Some((row, col, cell_id))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    cleared
+        .into_iter()
+        .fold(res, |mut prev, (row, col, cell_id)| {
+            let vertex_manager = &mut prev.vertex_manager;
+            vertex_manager.status =
+                remove_ast_node(vertex_manager.status.clone(), sheet_id, cell_id);
+            let proc = SheetProcess {
+                sheet_id,
+                payload: SheetPayload::Cell(CellPayload {
+                    row,
+                    col,
+                    change: CellChange::Value(CellValue::Blank),
+                }),
+            };
+            handle_sheet_proc(prev, proc, context)
+        })
+}
+
+fn split_merged_cells(
+    status: Status,
+    sheet_id: SheetId,
+    payload: SplitMergedCellsPayload,
+) -> Status {
+    let SplitMergedCellsPayload { row, col } = payload;
+    let mut res = status;
+    let target = get_merged_ranges(&mut res, sheet_id)
+        .into_iter()
+        .find(|(_, (sr, sc), (er, ec))| *sr <= row && row <= *er && *sc <= col && col <= *ec);
+    if let Some((start, _, _)) = target {
+        let merge_cells = res.cell_attachment_manager.merge_cells;
+        res.cell_attachment_manager.merge_cells = merge_cells.remove_merge_cell(sheet_id, start);
+    }
+    res
+}
+
+fn handle_comment_payload(status: Status, sheet_id: SheetId, payload: CommentPayload) -> Status {
+    let (row, col) = match &payload {
+        CommentPayload::Add(p) => (p.row, p.col),
+        CommentPayload::Update(p) => (p.row, p.col),
+        CommentPayload::Delete(p) => (p.row, p.col),
+        CommentPayload::Reply(p) => (p.row, p.col),
+        CommentPayload::Resolve(p) => (p.row, p.col),
+    };
+    let mut res = status;
+    let cell_id = match res.navigator.fetch_cell_id(sheet_id, row, col) {
+        Some(cell_id) => cell_id,
+        None => return res,
+    };
+    let comments = &mut res.cell_attachment_manager.comments;
+    match payload {
+        CommentPayload::Add(p) => {
+            let author = comments.authors.get_id(&p.author);
+            let comment = Comment {
+                author,
+                text: p.text,
+            };
+            comments.remove_comment(sheet_id, &cell_id);
+            comments.add_comment(sheet_id, cell_id, comment);
+        }
+        CommentPayload::Update(p) => {
+            if comments.get_comment(&sheet_id, &cell_id).is_some() {
+                let author = comments.authors.get_id(&p.author);
+                if comments.get_thread(&sheet_id, &cell_id).is_some() {
+                    comments.get_person(author);
+                }
+                if let Some(thread) = comments.get_thread_mut(sheet_id, &cell_id) {
+                    if let Some(root) = thread.comments.front_mut() {
+                        root.author = author;
+                        root.text = p.text.clone();
+                    }
+                }
+                let comment = Comment {
+                    author,
+                    text: p.text,
+                };
+                comments.add_comment(sheet_id, cell_id, comment);
+            }
+        }
+        CommentPayload::Delete(_) => comments.remove_comment(sheet_id, &cell_id),
+        CommentPayload::Reply(p) => {
+            let author = comments.authors.get_id(&p.author);
+            comments.get_person(author);
+            if let Some(thread) = comments.get_or_create_thread(sheet_id, cell_id) {
+                thread
+                    .comments
+                    .push_back(ThreadedComment::new(author, p.text));
+            }
+        }
+        CommentPayload::Resolve(p) => {
+            if let Some(thread) = comments.get_or_create_thread(sheet_id, cell_id) {
+                thread.done = p.resolved;
+            }
+        }
+    }
+    res
+}
+
+// The start cell of a merged range and the positions of its two corners.
+type MergedRange = (NormalCellId, (usize, usize), (usize, usize));
+
+fn get_merged_ranges(status: &mut Status, sheet_id: SheetId) -> Vec<MergedRange> {
+    let merges = match status
+        .cell_attachment_manager
+        .merge_cells
+        .data
+        .get(&sheet_id)
+    {
+        Some(m) => m.clone(),
+        None => return vec![],
+    };
+    merges
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let s = status.navigator.fetch_normal_cell_idx(sheet_id, &start)?;
+            let e = status.navigator.fetch_normal_cell_idx(sheet_id, &end)?;
+            Some((start, s, e))
+        })
+        .collect()
+}
+
+// Inserting lines needs nothing since the merged cells are recorded by ids.
+// When deleting lines, the merged cells shrink and they are removed if only
+// one cell or nothing is left.
+fn shift_merge_cells(
+    merge_cells: MergeCells,
+    sheet_id: SheetId,
+    ls: &LineShift,
+    old_navigator: &mut Navigator,
+    new_navigator: &mut Navigator,
+) -> MergeCells {
+    if let ShiftType::Insert = ls.ty {
+        return merge_cells;
+    }
+    let merges = match merge_cells.data.get(&sheet_id) {
+        Some(m) => m.clone(),
+        None => return merge_cells,
+    };
+    let new_merges = merges
+        .into_iter()
+        .filter_map(|(s, e)| {
+            let (s, e) = shrink_range(sheet_id, s, e, ls, old_navigator, new_navigator)?;
+            if s == e {
+                return None;
+            }
+            Some((s, e))
+        })
+        .collect();
+    MergeCells {
+        data: merge_cells.data.update(sheet_id, new_merges),
+    }
+}
+
+// The ranges of conditional formats grow with the inserted lines because
+// they are kept by the ids of their start and end cells.
+fn shift_cond_formats(
+    manager: CondFormatManager,
+    sheet_id: SheetId,
+    ls: &LineShift,
+    old_navigator: &mut Navigator,
+    new_navigator: &mut Navigator,
+) -> CondFormatManager {
+    if let ShiftType::Insert = ls.ty {
+        return manager;
+    }
+    let formats = match manager.data.get(&sheet_id) {
+        Some(f) => f.clone(),
+        None => return manager,
+    };
+    let new_formats = formats
+        .into_iter()
+        .filter_map(|f| {
+            let old_anchor = old_navigator.fetch_normal_cell_idx(sheet_id, &f.ranges.first()?.0)?;
+            let ranges = f
+                .ranges
+                .into_iter()
+                .filter_map(|(s, e)| shrink_range(sheet_id, s, e, ls, old_navigator, new_navigator))
+                .collect::<Vec<_>>();
+            let new_anchor = old_navigator.fetch_normal_cell_idx(sheet_id, &ranges.first()?.0)?;
+            // The formulas are relative to the anchor. Move them with it if
+            // the anchor is deleted.
+            let dr = new_anchor.0 as isize - old_anchor.0 as isize;
+            let dc = new_anchor.1 as isize - old_anchor.1 as isize;
+            let rules = f
+                .rules
+                .into_iter()
+                .map(|r| CondFormatRule {
+                    formulas: r
+                        .formulas
+                        .iter()
+                        .map(|node| shift_node(old_navigator, node, dr, dc))
+                        .collect(),
+                    rule: r.rule,
+                })
+                .collect();
+            Some(CondFormat { ranges, rules })
+        })
+        .collect();
+    CondFormatManager {
+        data: manager.data.update(sheet_id, new_formats),
+    }
+}
+
+// Find the range left after deleting the lines. None if the whole range is
+// deleted.
+fn shrink_range(
+    sheet_id: SheetId,
+    s: NormalCellId,
+    e: NormalCellId,
+    ls: &LineShift,
+    old_navigator: &mut Navigator,
+    new_navigator: &mut Navigator,
+) -> Option<(NormalCellId, NormalCellId)> {
+    let start = ls.start;
+    let end = ls.start + ls.cnt as usize;
+    let shrink = |s: usize, e: usize| -> Option<(usize, usize)> {
+        let new_s = if s < start {
+            s
+        } else if s >= end {
+            s - ls.cnt as usize
+        } else {
+            start
+        };
+        let new_e = if e < start {
+            e
+        } else if e >= end {
+            e - ls.cnt as usize
+        } else {
+            start.checked_sub(1)?
+        };
+        if new_e < new_s {
+            None
+        } else {
+            Some((new_s, new_e))
+        }
+    };
+    let (sr, sc) = old_navigator.fetch_normal_cell_idx(sheet_id, &s)?;
+    let (er, ec) = old_navigator.fetch_normal_cell_idx(sheet_id, &e)?;
+    let ((sr, er), (sc, ec)) = match ls.direction {
+        Direction::Horizontal => (shrink(sr, er)?, (sc, ec)),
+        Direction::Vertical => ((sr, er), shrink(sc, ec)?),
+    };
+    let s = new_navigator.fetch_norm_cell_id(sheet_id, sr, sc)?;
+    let e = new_navigator.fetch_norm_cell_id(sheet_id, er, ec)?;
+    Some((s, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::edit_action::{
+        CopySheet, DefineName, DeleteSheet, EditAction, EditPayload, InsertSheet, MoveSheet,
+    };
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+
+    fn define_local(sheet_idx: usize, name: &str, refers_to: &str) -> EditPayload {
+        EditPayload::DefineName(DefineName {
+            name: name.to_string(),
+            sheet_idx: Some(sheet_idx),
+            refers_to: refers_to.to_string(),
+        })
+    }
+
+    #[test]
+    fn copy_sheet_names_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![input(0, 0, 0, "3"), define_local(0, "loc", "=$A$1*2")],
+        );
+        edit(&mut wb, vec![input(0, 0, 1, "=loc")]);
+        edit(
+            &mut wb,
+            vec![EditPayload::CopySheet(CopySheet {
+                sheet_idx: 0,
+                new_idx: 1,
+            })],
+        );
+        assert_eq!(value(&mut wb, 1, 0, 1), "6");
+        assert_eq!(
+            wb.get_sheet_by_idx(1).unwrap().get_formula(0, 1).unwrap(),
+            "loc"
+        );
+
+        // The copied name refers to the cells of the copy.
+        edit(&mut wb, vec![input(1, 0, 0, "5")]);
+        assert_eq!(value(&mut wb, 1, 0, 1), "10");
+        assert_eq!(value(&mut wb, 0, 0, 1), "6");
+        let names = &wb.controller.status.name_id_manager;
+        let sheet_id = wb.controller.get_sheet_id_by_idx(1).unwrap();
+        assert!(names.has_local(sheet_id, "loc").is_some());
+    }
+
+    #[test]
+    fn delete_sheet_names_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![EditPayload::InsertSheet(InsertSheet { sheet_idx: 1 })],
+        );
+        edit(
+            &mut wb,
+            vec![input(1, 0, 0, "7"), define_local(1, "loc", "=$A$1")],
+        );
+        edit(&mut wb, vec![input(0, 0, 0, "=Sheet2!loc")]);
+        assert_eq!(value(&mut wb, 0, 0, 0), "7");
+
+        edit(
+            &mut wb,
+            vec![EditPayload::DeleteSheet(DeleteSheet { sheet_idx: 1 })],
+        );
+        assert_eq!(value(&mut wb, 0, 0, 0), "#REF!");
+        let status = &wb.controller.status;
+        assert!(status.name_id_manager.local_ids.is_empty());
+        assert!(status.vertex_manager.status.names.is_empty());
+
+        wb.handle_action(EditAction::Undo);
+        assert_eq!(value(&mut wb, 0, 0, 0), "7");
+    }
+
+    #[test]
+    fn sheet_shift_test() {
+        let mut wb = Workbook::default();
+        edit(
+            &mut wb,
+            vec![
+                input(0, 0, 0, "1"),
+                input(0, 0, 1, "=A1+Sheet1!A1"),
+                EditPayload::InsertSheet(InsertSheet { sheet_idx: 1 }),
+            ],
+        );
+        edit(&mut wb, vec![input(1, 0, 0, "=Sheet1!B1*10")]);
+        assert_eq!(value(&mut wb, 1, 0, 0), "20");
+
+        edit(
+            &mut wb,
+            vec![EditPayload::CopySheet(CopySheet {
+                sheet_idx: 0,
+                new_idx: 1,
+            })],
+        );
+        edit(&mut wb, vec![input(1, 0, 0, "5")]);
+        assert!(wb.get_sheet_by_name("Sheet1 (2)").is_ok());
+        let f = wb.get_sheet_by_idx(1).unwrap().get_formula(0, 1).unwrap();
+        assert_eq!(f, "A1 + A1");
+        assert_eq!(value(&mut wb, 1, 0, 1), "10");
+        assert_eq!(value(&mut wb, 0, 0, 1), "2");
+
+        edit(
+            &mut wb,
+            vec![EditPayload::MoveSheet(MoveSheet {
+                sheet_idx: 2,
+                new_idx: 0,
+            })],
+        );
+        assert_eq!(value(&mut wb, 0, 0, 0), "20");
+
+        edit(
+            &mut wb,
+            vec![EditPayload::DeleteSheet(DeleteSheet { sheet_idx: 1 })],
+        );
+        assert!(wb.get_sheet_by_name("Sheet1").is_err());
+        assert_eq!(value(&mut wb, 0, 0, 0), "#REF!");
+        assert_eq!(value(&mut wb, 1, 0, 1), "10");
+
+        wb.handle_action(EditAction::Undo);
+        assert_eq!(value(&mut wb, 1, 0, 1), "2");
+        assert_eq!(value(&mut wb, 0, 0, 0), "20");
     }
 }
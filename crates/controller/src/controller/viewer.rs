@@ -1,15 +1,20 @@
-use logisheets_base::{CellValue, SheetId};
+use logisheets_base::{CellValue, SheetId, CURR_BOOK};
 use logisheets_parser::unparse::Stringify;
 
 use crate::connectors::NameFetcher;
 use crate::controller::display::{
-    SheetColInfo, SheetComments, SheetMergeCells, SheetRowInfo, SheetStyles, SheetValues, Value,
+    CellCondFormat, SheetColInfo, SheetComments, SheetCondFormats, SheetMergeCells, SheetRowInfo,
+    SheetStaleCells, SheetStyles, SheetValues, StaleCell, Value,
 };
+use crate::formatter::{FormattedValue, NumFormat};
 use crate::id_manager::TextIdManager;
+use logisheets_workbook::prelude::CtColor;
+use std::collections::HashMap;
 
+use super::cond_format::CondFormatEvaluator;
 use super::display::{
-    BlockInfo, CellFormulaValue, CellStyle, ColInfo, Comment, DisplayPatch, DisplayResponse,
-    MergeCell, RowInfo, SheetBlocks,
+    BlockInfo, CellFormulaValue, CellStyle, ColInfo, Comment, CommentReply, DefinedName,
+    DefinedNames, DisplayPatch, DisplayResponse, MergeCell, RowInfo, SheetBlocks,
 };
 use super::style::StyleConverter;
 use super::Controller;
@@ -23,6 +28,9 @@ pub struct SheetViewer {
     pub comments: Vec<Comment>,
     pub merge_cells: Vec<MergeCell>,
     pub blocks: Vec<BlockInfo>,
+    pub names: Vec<DefinedName>,
+    pub stale_cells: Vec<StaleCell>,
+    pub cond_formats: Vec<CellCondFormat>,
 }
 
 impl SheetViewer {
@@ -34,6 +42,8 @@ impl SheetViewer {
             .unwrap();
         let mut viewer = self;
         viewer.load_sheet(controller, sheet_id);
+        viewer.load_names(controller);
+        viewer.load_cond_formats(controller, sheet_id);
         let patches = viewer.to_patches(sheet_idx);
         DisplayResponse { patches }
     }
@@ -48,6 +58,7 @@ impl SheetViewer {
         let external_links_manager = &controller.status.external_links_manager;
         let text_id_manager = &controller.status.text_id_manager;
         let name_id_manager = &controller.status.name_id_manager;
+        let date_system = controller.settings.date_system;
         let sheet_data = s.data.get(&sheet_id);
         if let Some(sheet_data) = sheet_data {
             sheet_data
@@ -83,6 +94,7 @@ impl SheetViewer {
             let style_converter = StyleConverter {
                 theme_manager: &controller.settings.theme,
             };
+            let mut num_formats = HashMap::<String, NumFormat>::new();
             sheet_data.cells.iter().for_each(|(cell_id, cell)| {
                 let coord = navigator.fetch_cell_idx(sheet_id, cell_id);
                 if coord.is_none() {
@@ -90,6 +102,9 @@ impl SheetViewer {
                 }
                 let (row, col) = coord.unwrap();
                 let raw_style = style_manager.get_cell_style(cell.style);
+                let num_format = num_formats
+                    .entry(raw_style.formatter.clone())
+                    .or_insert_with_key(|code| NumFormat::new(code).with_date_system(date_system));
                 self.styles.push(CellStyle {
                     row,
                     col,
@@ -111,7 +126,19 @@ impl SheetViewer {
                     Some(n) => (n.unparse(&mut name_fetcher, sheet_id), true),
                     None => (String::from(""), false),
                 };
-                let v = convert_value(row, col, &cell.value, formula, has_formula, text_id_manager);
+                let mut v =
+                    convert_value(row, col, &cell.value, formula, has_formula, text_id_manager);
+                let FormattedValue { text, color } = format_value(&v.value, num_format);
+                v.formatted_text = text;
+                v.color = color.map(|indexed| {
+                    style_converter.convert_color(CtColor {
+                        auto: None,
+                        indexed: Some(indexed as u32),
+                        rgb: None,
+                        theme: None,
+                        tint: 0_f64,
+                    })
+                });
                 self.values.push(v);
             });
         }
@@ -124,15 +151,31 @@ impl SheetViewer {
                     let author = comments
                         .get_author_name(&c.author)
                         .unwrap_or(String::from("unknown author"));
+                    let replies = comments
+                        .get_replies(&sheet_id, cell_id)
+                        .into_iter()
+                        .map(|(author, content)| CommentReply { author, content })
+                        .collect();
                     self.comments.push(Comment {
                         row,
                         col,
                         author,
                         content: c.text.clone(),
+                        replies,
+                        resolved: comments.is_resolved(&sheet_id, cell_id),
                     })
                 }
             });
         }
+        vertex_manager
+            .get_stale_formulas()
+            .into_iter()
+            .filter(|(s, _)| *s == sheet_id)
+            .for_each(|(_, cell_id)| {
+                if let Some((row, col)) = navigator.fetch_cell_idx(sheet_id, &cell_id) {
+                    self.stale_cells.push(StaleCell { row, col })
+                }
+            });
         let merge_cells_manager = &cell_attachments.merge_cells;
         if let Some(merge_cells) = merge_cells_manager.data.get(&sheet_id) {
             merge_cells.iter().for_each(|(start, end)| {
@@ -169,6 +212,67 @@ impl SheetViewer {
         }
     }
 
+    fn load_names(&mut self, controller: &mut Controller) {
+        let status = &controller.status;
+        let name_id_manager = &status.name_id_manager;
+        let mut navigator = status.navigator.clone();
+        status
+            .vertex_manager
+            .status
+            .names
+            .iter()
+            .for_each(|(name_id, node)| {
+                let (book, name) = match name_id_manager.get_string(name_id) {
+                    Some(r) => r,
+                    None => return,
+                };
+                if book != CURR_BOOK {
+                    return;
+                }
+                let sheet_idx = match name_id_manager.get_scope(name_id) {
+                    Some(sheet_id) => match status.sheet_pos_manager.get_sheet_idx(sheet_id) {
+                        Some(idx) => Some(idx),
+                        None => return,
+                    },
+                    None => None,
+                };
+                let mut name_fetcher = NameFetcher {
+                    func_manager: &status.func_id_manager,
+                    sheet_id_manager: &status.sheet_id_manager,
+                    external_links_manager: &status.external_links_manager,
+                    text_id_manager: &status.text_id_manager,
+                    name_id_manager,
+                    navigator: &mut navigator,
+                };
+                // Unparse in a sheet that does not exist to keep the sheet names.
+                let refers_to = node.unparse(&mut name_fetcher, SheetId::MAX);
+                self.names.push(DefinedName {
+                    name,
+                    sheet_idx,
+                    refers_to,
+                });
+            });
+        self.names
+            .sort_by(|a, b| (&a.name, a.sheet_idx).cmp(&(&b.name, b.sheet_idx)));
+    }
+
+    fn load_cond_formats(&mut self, controller: &Controller, sheet_id: SheetId) {
+        let status = &controller.status;
+        if status
+            .cond_format_manager
+            .get_cond_formats(sheet_id)
+            .is_empty()
+        {
+            return;
+        }
+        let evaluator = CondFormatEvaluator {
+            status: status.clone(),
+            book_name: &controller.curr_book_name,
+            settings: &controller.settings,
+        };
+        self.cond_formats = evaluator.evaluate(sheet_id);
+    }
+
     fn to_patches(self, sheet_idx: usize) -> Vec<DisplayPatch> {
         let mut res = vec![];
         if self.values.len() > 0 {
@@ -222,6 +326,23 @@ impl SheetViewer {
             };
             res.push(DisplayPatch::Blocks(blocks))
         }
+        // Sent even if empty, so that the last deleted name is cleared too.
+        let names = DefinedNames { names: self.names };
+        res.push(DisplayPatch::Names(names));
+        if !self.stale_cells.is_empty() {
+            let stale_cells = SheetStaleCells {
+                sheet_idx,
+                cells: self.stale_cells,
+            };
+            res.push(DisplayPatch::StaleCells(stale_cells))
+        }
+        if !self.cond_formats.is_empty() {
+            let cond_formats = SheetCondFormats {
+                sheet_idx,
+                cells: self.cond_formats,
+            };
+            res.push(DisplayPatch::CondFormats(cond_formats))
+        }
         res
     }
 }
@@ -267,5 +388,60 @@ fn convert_value(
         col,
         formula,
         value,
+        formatted_text: String::from(""),
+        color: None,
+    }
+}
+
+fn format_value(value: &Value, num_format: &NumFormat) -> FormattedValue {
+    match value {
+        Value::Number(n) => num_format.format_number(*n),
+        Value::Str(s) if s.is_empty() => FormattedValue {
+            text: String::from(""),
+            color: None,
+        },
+        Value::Str(s) => num_format.format_text(s),
+        Value::Bool(b) => FormattedValue {
+            text: String::from(if *b { "TRUE" } else { "FALSE" }),
+            color: None,
+        },
+        Value::Error(e) => FormattedValue {
+            text: e.clone(),
+            color: None,
+        },
+        Value::Empty => FormattedValue {
+            text: String::from(""),
+            color: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::display::{DisplayPatch, DisplayRequest};
+    use crate::test_utils::{edit, input, set_num_fmt};
+    use crate::Workbook;
+
+    #[test]
+    fn formatted_value_test() {
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![input(0, 0, 0, "-0.256")]);
+        set_num_fmt(&mut wb, 0, 0, 0, "0.0%;[Red]-0.0%");
+        let response = wb.controller.get_display_response(DisplayRequest {
+            sheet_idx: 0,
+            version: 0,
+        });
+        let values = response
+            .patches
+            .into_iter()
+            .find_map(|p| match p {
+                DisplayPatch::Values(v) => Some(v.values),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(values[0].formatted_text, "-25.6%");
+        let color = values[0].color.as_ref().unwrap();
+        assert_eq!(color.red, Some(255.));
+        assert_eq!(color.green, Some(0.));
     }
 }
@@ -44,18 +44,19 @@ impl DataExecutor {
             }
             SheetPayload::Shift(s) => self.handle_shift_payload(sheet_id, s),
             SheetPayload::Line(l) => {
-                let line_idx = l.idx;
-                match &l.change {
+                (l.idx..l.idx + l.cnt).fold(self, |prev, line_idx| match &l.change {
                     LineInfoUpdate::Row(riu) => {
-                        self.handle_row_info_payload(sheet_id, line_idx, riu.clone())
+                        prev.handle_row_info_payload(sheet_id, line_idx, riu.clone())
                     }
                     LineInfoUpdate::Col(ciu) => {
-                        self.handle_col_info_payload(sheet_id, line_idx, ciu.clone())
+                        prev.handle_col_info_payload(sheet_id, line_idx, ciu.clone())
                     }
-                }
+                })
             }
             SheetPayload::Property(_) => self,
             SheetPayload::Formula(_) => self,
+            SheetPayload::Merge(_) => self,
+            SheetPayload::Comment(_) => self,
             SheetPayload::Block(bp) => self.handle_block_payload(sheet_id, bp),
         }
     }
@@ -310,7 +311,9 @@ impl DataExecutor {
                 if let Some(CellId::NormalCell(master)) =
                     navigator.fetch_cell_id(sheet_id, master_row, master_col)
                 {
-                    navigator.create_block(sheet_id, master, c.row_cnt, c.col_cnt);
+                    // The conflicting blocks are reported before execution.
+                    let _ =
+                        navigator.create_block(sheet_id, c.block_id, master, c.row_cnt, c.col_cnt);
                     navigator
                 } else {
                     navigator
@@ -0,0 +1,470 @@
+use logisheets_astchecker::{
+    get_all_func_signatures, get_func_signature, ArgCount, AstChecker, FuncCheckError,
+};
+use logisheets_base::get_active_sheet::GetActiveSheetTrait;
+use logisheets_base::get_book_name::GetBookNameTrait;
+use logisheets_base::id_fetcher::IdFetcherTrait;
+use logisheets_base::{
+    CellId, ColId, ExtBookId, FuncId, NameId, NormalCellId, RowId, SheetId, TextId,
+};
+use logisheets_lexer::{try_lex, Rule};
+use logisheets_parser::{context::ContextTrait, Parser};
+use pest::iterators::Pair;
+use serde::Serialize;
+
+// The byte span is counted in the formula given by the user, including the
+// leading '='.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/formula_diagnostic.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaDiagnostic {
+    pub start: usize,
+    pub end: usize,
+    pub kind: DiagnosticKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/diagnostic_kind.ts")]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticKind {
+    Syntax(SyntaxError),
+    UnmatchedParenthesis,
+    UnknownFunction(UnknownFunction),
+    ArgCount(ArgCountError),
+    Arg(ArgError),
+    InvalidSheetName(InvalidSheetName),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/syntax_error.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct SyntaxError {
+    pub expected: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/unknown_function.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownFunction {
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/arg_count_error.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ArgCountError {
+    pub name: String,
+    pub count: usize,
+    pub expected: ArgCount,
+}
+
+// An argument not accepted by the function, like a value given to an
+// argument accepting references only.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/arg_error.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ArgError {
+    pub name: String,
+    pub idx: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/invalid_sheet_name.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidSheetName {
+    pub name: String,
+}
+
+/// Check the formula without a workbook. The sheet names are not checked if
+/// `sheet_names` is empty. `custom_funcs` are the functions outside the
+/// registry, like the async functions.
+pub fn diagnose_formula(
+    formula: &str,
+    sheet_names: &[String],
+    custom_funcs: &[String],
+) -> Vec<FormulaDiagnostic> {
+    let trimmed = formula.trim_start();
+    let trimmed = trimmed.strip_prefix('=').unwrap_or(trimmed);
+    let offset = formula.len() - trimmed.len();
+    let f = trimmed.trim_end();
+
+    let unmatched = find_unmatched_parentheses(f);
+    let mut result = unmatched
+        .iter()
+        .map(|idx| FormulaDiagnostic {
+            start: *idx,
+            end: idx + 1,
+            kind: DiagnosticKind::UnmatchedParenthesis,
+        })
+        .collect::<Vec<_>>();
+    match try_lex(f) {
+        Ok(pair) => check_pair(pair, sheet_names, custom_funcs, &mut result),
+        // The lexer stopping at an unmatched parenthesis, or at the end
+        // of the formula for the unclosed ones, adds nothing new.
+        Err(e) if unmatched.contains(&e.start) => {}
+        Err(e) if e.start >= f.len() && !unmatched.is_empty() => {}
+        Err(e) => {
+            // Mark the char where the lexer stops at least.
+            let end = f[e.end..]
+                .chars()
+                .next()
+                .map_or(e.end, |c| e.end + c.len_utf8());
+            let mut expected = e
+                .expected
+                .into_iter()
+                .map(|r| format!("{:?}", r))
+                .collect::<Vec<_>>();
+            expected.dedup();
+            result.push(FormulaDiagnostic {
+                start: e.start,
+                end,
+                kind: DiagnosticKind::Syntax(SyntaxError { expected }),
+            })
+        }
+    }
+    result.sort_by_key(|d| d.start);
+    result
+        .into_iter()
+        .map(|d| FormulaDiagnostic {
+            start: d.start + offset,
+            end: d.end + offset,
+            kind: d.kind,
+        })
+        .collect()
+}
+
+fn check_pair(
+    pair: Pair<Rule>,
+    sheet_names: &[String],
+    custom_funcs: &[String],
+    result: &mut Vec<FormulaDiagnostic>,
+) {
+    match pair.as_rule() {
+        Rule::function_call => {
+            let span = pair.as_span();
+            let name_pair = pair.clone().into_inner().next().unwrap();
+            let name = name_pair.as_str().to_uppercase();
+            let custom = custom_funcs.iter().any(|f| f.to_uppercase() == name);
+            match get_func_signature(&name) {
+                Some(_) => match check_call(span.as_str()) {
+                    Some((
+                        count,
+                        FuncCheckError {
+                            break_arg_count: Some(expected),
+                            ..
+                        },
+                    )) => result.push(FormulaDiagnostic {
+                        start: span.start(),
+                        end: span.end(),
+                        kind: DiagnosticKind::ArgCount(ArgCountError {
+                            name,
+                            count,
+                            expected,
+                        }),
+                    }),
+                    Some((
+                        _,
+                        FuncCheckError {
+                            break_args: Some((idx, _)),
+                            ..
+                        },
+                    )) => {
+                        // The arguments follow the function name.
+                        let arg = pair.clone().into_inner().nth(idx + 1).unwrap().as_span();
+                        result.push(FormulaDiagnostic {
+                            start: arg.start(),
+                            end: arg.end(),
+                            kind: DiagnosticKind::Arg(ArgError { name, idx }),
+                        })
+                    }
+                    _ => {}
+                },
+                None if custom => {}
+                None => {
+                    let name_span = name_pair.as_span();
+                    result.push(FormulaDiagnostic {
+                        start: name_span.start(),
+                        end: name_span.end(),
+                        kind: DiagnosticKind::UnknownFunction(UnknownFunction {
+                            suggestions: suggest_funcs(&name),
+                            name,
+                        }),
+                    })
+                }
+            }
+        }
+        Rule::work_sheet_prefix if !sheet_names.is_empty() => {
+            check_sheet_names(pair.clone(), sheet_names, result);
+            return;
+        }
+        _ => {}
+    }
+    pair.into_inner()
+        .for_each(|p| check_pair(p, sheet_names, custom_funcs, result));
+}
+
+fn check_sheet_names(
+    prefix: Pair<Rule>,
+    sheet_names: &[String],
+    result: &mut Vec<FormulaDiagnostic>,
+) {
+    let pairs = prefix.into_inner().flatten().collect::<Vec<_>>();
+    // The sheets in other workbooks are unknown.
+    let external = pairs.iter().any(|p| {
+        matches!(
+            p.as_rule(),
+            Rule::workbook_name | Rule::workbook_name_special
+        )
+    });
+    if external {
+        return;
+    }
+    pairs
+        .into_iter()
+        .filter(|p| matches!(p.as_rule(), Rule::sheet_name | Rule::sheet_name_special))
+        .for_each(|p| {
+            let name = p.as_str().replace("''", "'");
+            let exists = sheet_names
+                .iter()
+                .any(|s| s.to_uppercase() == name.to_uppercase());
+            if !exists {
+                let span = p.as_span();
+                result.push(FormulaDiagnostic {
+                    start: span.start(),
+                    end: span.end(),
+                    kind: DiagnosticKind::InvalidSheetName(InvalidSheetName { name }),
+                })
+            }
+        });
+}
+
+// Return the byte indexes of the parentheses that are not matched. The ones
+// in the strings and the quoted sheet names are skipped.
+fn find_unmatched_parentheses(f: &str) -> Vec<usize> {
+    let mut stack = vec![];
+    let mut unmatched = vec![];
+    let mut quote: Option<char> = None;
+    f.char_indices().for_each(|(idx, c)| match (quote, c) {
+        (Some(q), _) if q == c => quote = None,
+        (Some(_), _) => {}
+        (None, '"') | (None, '\'') => quote = Some(c),
+        (None, '(') => stack.push(idx),
+        (None, ')') if stack.pop().is_none() => unmatched.push(idx),
+        _ => {}
+    });
+    unmatched.extend(stack);
+    unmatched.sort();
+    unmatched
+}
+
+// Run the same check as the formula inputs on the call like
+// "SUM(A1, (B1, B2), C1)". Return the count of arguments and the error.
+fn check_call(call: &str) -> Option<(usize, FuncCheckError)> {
+    let mut fetcher = FuncNameFetcher::default();
+    let node = (Parser {}).parse(call, &mut fetcher)?;
+    let count = match &node.pure {
+        logisheets_parser::ast::PureNode::Func(f) => f.args.len(),
+        _ => 0,
+    };
+    let checker = AstChecker::new(|id| fetcher.names.get(*id as usize).cloned());
+    Some((count, checker.call_check(&node).err()?))
+}
+
+// Parse formulas without a workbook. Only the function names are kept.
+#[derive(Default)]
+struct FuncNameFetcher {
+    names: Vec<String>,
+}
+
+impl ContextTrait for FuncNameFetcher {}
+
+impl GetActiveSheetTrait for FuncNameFetcher {
+    fn get_active_sheet(&self) -> SheetId {
+        0
+    }
+}
+
+impl GetBookNameTrait for FuncNameFetcher {
+    fn get_book_name(&self) -> &str {
+        ""
+    }
+}
+
+impl IdFetcherTrait for FuncNameFetcher {
+    fn fetch_row_id(&mut self, _: SheetId, row_idx: usize) -> Option<RowId> {
+        Some(row_idx as RowId)
+    }
+
+    fn fetch_col_id(&mut self, _: SheetId, col_idx: usize) -> Option<ColId> {
+        Some(col_idx as ColId)
+    }
+
+    fn fetch_cell_id(&mut self, _: SheetId, row_idx: usize, col_idx: usize) -> Option<CellId> {
+        Some(CellId::NormalCell(NormalCellId {
+            row: row_idx as RowId,
+            col: col_idx as ColId,
+            follow_row: None,
+            follow_col: None,
+        }))
+    }
+
+    fn fetch_sheet_id(&mut self, _: &str) -> SheetId {
+        0
+    }
+
+    fn find_sheet_id(&mut self, _: &str) -> Option<SheetId> {
+        Some(0)
+    }
+
+    fn fetch_name_id(&mut self, _: &Option<&str>, _: &str) -> NameId {
+        0
+    }
+
+    fn fetch_local_name_id(&mut self, _: SheetId, _: &str) -> Option<NameId> {
+        None
+    }
+
+    fn fetch_ext_book_id(&mut self, _: &str) -> ExtBookId {
+        0
+    }
+
+    fn fetch_text_id(&mut self, _: &str) -> TextId {
+        0
+    }
+
+    fn fetch_func_id(&mut self, func_name: &str) -> FuncId {
+        let name = func_name.to_uppercase();
+        match self.names.iter().position(|n| *n == name) {
+            Some(idx) => idx as FuncId,
+            None => {
+                self.names.push(name);
+                (self.names.len() - 1) as FuncId
+            }
+        }
+    }
+}
+
+fn suggest_funcs(name: &str) -> Vec<String> {
+    let limit = std::cmp::max(1, name.len() / 3);
+    let mut candidates = get_all_func_signatures()
+        .into_iter()
+        .filter_map(|sign| {
+            let distance = edit_distance(name, &sign.name);
+            if distance <= limit || sign.name.starts_with(name) {
+                Some((distance, sign.name.clone()))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.into_iter().take(3).map(|(_, n)| n).collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    a.chars().enumerate().for_each(|(i, ca)| {
+        let mut curr = vec![i + 1];
+        b.iter().enumerate().for_each(|(j, cb)| {
+            let cost = if ca == *cb { 0 } else { 1 };
+            let v = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            curr.push(v);
+        });
+        prev = curr;
+    });
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnose(f: &str) -> Vec<FormulaDiagnostic> {
+        let sheets = vec![String::from("Sheet1"), String::from("It's")];
+        diagnose_formula(f, &sheets, &[String::from("MYFUNC")])
+    }
+
+    #[test]
+    fn syntax_error_test() {
+        let r = diagnose("=SUM(A1:,B2)");
+        assert_eq!(r.len(), 1);
+        assert_eq!((r[0].start, r[0].end), (8, 9));
+        match &r[0].kind {
+            DiagnosticKind::Syntax(e) => {
+                assert!(e.expected.contains(&String::from("a1_reference")))
+            }
+            _ => panic!(),
+        }
+        assert!(diagnose("=SUM(A1:A3, B2)").is_empty());
+    }
+
+    #[test]
+    fn parenthesis_test() {
+        let r = diagnose("=SUM(A1:,B2");
+        assert_eq!(r.len(), 2);
+        assert_eq!(
+            (r[0].start, r[0].kind.clone()),
+            (4, DiagnosticKind::UnmatchedParenthesis)
+        );
+        assert_eq!((r[1].start, r[1].end), (8, 9));
+        assert!(matches!(r[1].kind, DiagnosticKind::Syntax(_)));
+        let r = diagnose("=1+2)+\"(\"");
+        assert_eq!(r.len(), 1);
+        assert_eq!((r[0].start, r[0].end), (4, 5));
+        let r = diagnose("=SUM(1");
+        assert_eq!(r.len(), 1);
+        assert_eq!(r[0].kind, DiagnosticKind::UnmatchedParenthesis);
+    }
+
+    #[test]
+    fn arg_test() {
+        let r = diagnose("=1+ROW(1)");
+        assert_eq!(r.len(), 1);
+        assert_eq!((r[0].start, r[0].end), (7, 8));
+        assert_eq!(
+            r[0].kind,
+            DiagnosticKind::Arg(ArgError {
+                name: String::from("ROW"),
+                idx: 0,
+            })
+        );
+        let r = diagnose("=SUMIF(A1:A3, 1, 2)");
+        assert_eq!((r[0].start, r[0].end), (17, 18));
+        assert!(diagnose("=ROW(A1)+ROW(OFFSET(A1, 1, 1))").is_empty());
+    }
+
+    #[test]
+    fn function_test() {
+        let r = diagnose("=1+SUMM(1)+MYFUNC(2)");
+        assert_eq!(r.len(), 1);
+        assert_eq!((r[0].start, r[0].end), (3, 7));
+        match &r[0].kind {
+            DiagnosticKind::UnknownFunction(f) => {
+                assert_eq!(f.name, "SUMM");
+                assert_eq!(f.suggestions[0], "SUM");
+            }
+            _ => panic!(),
+        }
+        let r = diagnose("=ABS(1, IF(TRUE, 1, 2))");
+        assert_eq!(r.len(), 1);
+        assert_eq!((r[0].start, r[0].end), (1, 23));
+        match &r[0].kind {
+            DiagnosticKind::ArgCount(e) => assert_eq!(e.count, 2),
+            _ => panic!(),
+        }
+        assert!(diagnose("=SUMIFS(A1:A3, B1:B3, \">1,2\")").is_empty());
+        assert!(diagnose("=PI()").is_empty());
+    }
+
+    #[test]
+    fn sheet_name_test() {
+        assert!(diagnose("=sheet1!A1+'It''s'!B2").is_empty());
+        let r = diagnose("=Sheet2!A1");
+        assert_eq!(r.len(), 1);
+        assert_eq!((r[0].start, r[0].end), (1, 7));
+        assert!(diagnose("=[Book2]Sheet2!A1").is_empty());
+        assert!(diagnose_formula("=Sheet2!A1", &[], &[]).is_empty());
+    }
+}
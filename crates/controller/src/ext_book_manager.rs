@@ -14,7 +14,8 @@ pub struct ExtBooksManager {
 impl ExtBooksManager {
     pub fn new() -> Self {
         ExtBooksManager {
-            book_id_manager: BookIdManager::new(0),
+            // 0 is for the workbook itself.
+            book_id_manager: BookIdManager::new(1),
             books: HashMap::new(),
             orders: Vector::new(),
         }
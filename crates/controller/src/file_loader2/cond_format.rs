@@ -0,0 +1,77 @@
+use logisheets_base::{NormalCellId, SheetId};
+use logisheets_parser::{ast, context::Context, Parser};
+use logisheets_workbook::prelude::*;
+
+use crate::cond_format_manager::{CondFormat, CondFormatManager, CondFormatRule};
+
+use super::{
+    fetcher::Fetcher,
+    utils::{parse_cell, parse_range},
+};
+
+pub fn load_cond_formats(
+    sheet_id: SheetId,
+    book_name: &str,
+    cond_formats: &[CtConditionalFormatting],
+    manager: &mut CondFormatManager,
+    fetcher: &mut Fetcher,
+) {
+    cond_formats.iter().for_each(|cf| {
+        let ranges = load_sqref(sheet_id, &cf.sqref, fetcher);
+        if ranges.is_empty() {
+            return;
+        }
+        let rules = cf
+            .cf_rules
+            .iter()
+            .map(|rule| {
+                let formulas = rule
+                    .formulas
+                    .iter()
+                    .map(|f| {
+                        let mut context = Context {
+                            sheet_id,
+                            book_name,
+                            id_fetcher: fetcher,
+                        };
+                        (Parser {})
+                            .parse(&f.value, &mut context)
+                            .unwrap_or(ast::Node {
+                                pure: ast::PureNode::Value(ast::Value::Error(ast::Error::Ref)),
+                                bracket: false,
+                            })
+                    })
+                    .collect();
+                CondFormatRule {
+                    rule: rule.clone(),
+                    formulas,
+                }
+            })
+            .collect();
+        manager.add_cond_format(sheet_id, CondFormat { ranges, rules });
+    });
+}
+
+// sqref is a list of ranges separated by spaces, like `A1:B3 D5`.
+fn load_sqref(
+    sheet_id: SheetId,
+    sqref: &str,
+    fetcher: &mut Fetcher,
+) -> Vec<(NormalCellId, NormalCellId)> {
+    sqref
+        .split_whitespace()
+        .filter_map(|r| {
+            let (start, end) = match r.contains(':') {
+                true => parse_range(r)?,
+                false => {
+                    let cell = parse_cell(r)?;
+                    (cell, cell)
+                }
+            };
+            let navigator = &mut fetcher.navigator;
+            let start = navigator.fetch_norm_cell_id(sheet_id, start.0, start.1)?;
+            let end = navigator.fetch_norm_cell_id(sheet_id, end.0, end.1)?;
+            Some((start, end))
+        })
+        .collect()
+}
@@ -40,6 +40,10 @@ impl<'a> IdFetcherTrait for Fetcher<'a> {
         self.sheet_id_manager.get_id(sheet_name)
     }
 
+    fn find_sheet_id(&mut self, sheet_name: &str) -> Option<SheetId> {
+        self.sheet_id_manager.has(sheet_name)
+    }
+
     fn fetch_name_id(&mut self, workbook: &Option<&str>, name: &str) -> logisheets_base::NameId {
         let book_id = match workbook {
             Some(book) => self.fetch_ext_book_id(book),
@@ -48,8 +52,21 @@ impl<'a> IdFetcherTrait for Fetcher<'a> {
         self.name_id_manager.get_id(&(book_id, name.to_owned()))
     }
 
+    fn fetch_local_name_id(
+        &mut self,
+        sheet_id: SheetId,
+        name: &str,
+    ) -> Option<logisheets_base::NameId> {
+        self.name_id_manager.has_local(sheet_id, name)
+    }
+
+    // The books are referred to by their 1-based positions in the external
+    // references, like `[1]Sheet1!A1`.
     fn fetch_ext_book_id(&mut self, book: &str) -> ExtBookId {
-        let idx = book.parse::<usize>().unwrap_or(0);
+        let idx = match book.parse::<usize>() {
+            Ok(i) if i > 0 => i - 1,
+            _ => return 0,
+        };
         if let Some(link) = self
             .workbook
             .xl
@@ -1,24 +1,31 @@
+mod cond_format;
 mod external_links;
 mod fetcher;
+mod names;
 mod sheet;
 mod sst;
 mod styles;
 mod utils;
 mod vertex;
 
-use logisheets_base::id_fetcher::SheetIdFetcherTrait;
+use logisheets_base::{datetime::DateSystem, id_fetcher::SheetIdFetcherTrait, SheetId};
 use logisheets_workbook::prelude::*;
-use sheet::load_comments;
+use sheet::{load_comments, load_persons, load_threaded_comments};
+use std::collections::HashMap;
 
 use crate::{
+    calc_engine::calculator::is_volatile,
     controller::{status::Status, Controller},
     file_loader2::{
+        cond_format::load_cond_formats,
         external_links::load_external_link,
+        fetcher::Fetcher,
+        names::load_defined_names,
         sheet::{load_cols, load_merge_cells, load_sheet_data, load_sheet_format_pr},
         styles::StyleLoader,
     },
     id_manager::SheetIdManager,
-    settings::Settings,
+    settings::{CalcMode, Settings},
     theme_manager::ThemeManager,
 };
 pub struct SheetIdFetcher<'a> {
@@ -44,6 +51,7 @@ pub fn load(wb: Workbook, book_name: String) -> Controller {
         mut sheet_pos_manager,
         mut style_manager,
         mut cell_attachment_manager,
+        mut cond_format_manager,
     } = Status::default();
     let mut sheet_id_fetcher = SheetIdFetcher {
         sheet_id_manager: &mut sheet_id_manager,
@@ -57,10 +65,51 @@ pub fn load(wb: Workbook, book_name: String) -> Controller {
         })
     }
     let mut settings = Settings::default();
+    if let Some(workbook_pr) = &wb.xl.workbook_part.workbook_pr {
+        if workbook_pr.date1904 {
+            settings.date_system = DateSystem::Date1904;
+        }
+    }
     if let Some(calc_pr) = &wb.xl.workbook_part.calc_pr {
+        settings.calc_config.mode = match calc_pr.calc_mode {
+            StCalcMode::Auto => CalcMode::Auto,
+            StCalcMode::AutoNoTable => CalcMode::AutoNoTable,
+            StCalcMode::Manual => CalcMode::Manual,
+        };
+        settings.calc_config.iterative = calc_pr.iterate;
         settings.calc_config.iter_limit = calc_pr.iterate_count as u16;
         settings.calc_config.error = calc_pr.iterate_delta as f32;
     }
+    let sheet_ids = wb
+        .xl
+        .workbook_part
+        .sheets
+        .sheets
+        .iter()
+        .map(|ct_sheet| sheet_id_manager.get_id(&ct_sheet.name))
+        .collect::<Vec<SheetId>>();
+    if let Some(defined_names) = &wb.xl.workbook_part.defined_names {
+        let mut fetcher = Fetcher {
+            sheet_id_manager: &mut sheet_id_manager,
+            text_id_manager: &mut text_id_manager,
+            func_id_manager: &mut func_id_manager,
+            name_id_manager: &mut name_id_manager,
+            navigator: &mut navigator,
+            ext_books_manager: &mut external_links_manager,
+            workbook: &wb,
+        };
+        load_defined_names(
+            defined_names,
+            &sheet_ids,
+            &book_name,
+            &mut vertex_manager,
+            &mut fetcher,
+        );
+    }
+    let persons = match &wb.xl.persons {
+        Some((_, persons)) => load_persons(persons, &mut cell_attachment_manager),
+        None => HashMap::new(),
+    };
     let mut style_loader = StyleLoader::new(&mut style_manager, &wb.xl.styles.1);
     wb.xl
         .workbook_part
@@ -84,6 +133,15 @@ pub fn load(wb: Workbook, book_name: String) -> Controller {
                         &mut cell_attachment_manager,
                     );
                 }
+                if let Some(threaded_comments) = &ws.threaded_comments {
+                    load_threaded_comments(
+                        sheet_id,
+                        threaded_comments,
+                        &persons,
+                        &mut navigator,
+                        &mut cell_attachment_manager,
+                    );
+                }
                 if let Some(cols) = &ws.worksheet_part.cols {
                     load_cols(
                         sheet_id,
@@ -118,9 +176,39 @@ pub fn load(wb: Workbook, book_name: String) -> Controller {
                     &mut vertex_manager,
                     &mut style_loader,
                     &wb,
-                )
+                );
+                let mut fetcher = Fetcher {
+                    sheet_id_manager: &mut sheet_id_manager,
+                    text_id_manager: &mut text_id_manager,
+                    func_id_manager: &mut func_id_manager,
+                    name_id_manager: &mut name_id_manager,
+                    navigator: &mut navigator,
+                    ext_books_manager: &mut external_links_manager,
+                    workbook: &wb,
+                };
+                load_cond_formats(
+                    sheet_id,
+                    &book_name,
+                    &ws.worksheet_part.conditional_formatting,
+                    &mut cond_format_manager,
+                    &mut fetcher,
+                );
             }
         });
+    if let Some(dxfs) = &wb.xl.styles.1.dxfs {
+        style_manager.dxfs = dxfs.dxfs.clone();
+    }
+    let formulas = vertex_manager
+        .status
+        .formulas
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    vertex_manager.update_volatiles(formulas, |id| {
+        func_id_manager
+            .get_string(id)
+            .is_some_and(|name| is_volatile(&name))
+    });
     let status = Status {
         navigator,
         vertex_manager,
@@ -133,6 +221,7 @@ pub fn load(wb: Workbook, book_name: String) -> Controller {
         sheet_pos_manager,
         style_manager,
         cell_attachment_manager,
+        cond_format_manager,
     };
     if let Some(theme) = wb.xl.theme {
         settings.theme = ThemeManager::from(theme.1);
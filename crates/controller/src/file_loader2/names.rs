@@ -0,0 +1,123 @@
+use logisheets_base::{NameId, SheetId, CURR_BOOK};
+use logisheets_workbook::prelude::*;
+
+use crate::vertex_manager::VertexManager;
+
+use super::{fetcher::Fetcher, vertex::load_name_formula};
+
+// All the names are registered before any formula is parsed, because the
+// formulas of the names and cells may refer to the names defined later.
+pub fn load_defined_names(
+    defined_names: &CtDefinedNames,
+    sheet_ids: &[SheetId],
+    book_name: &str,
+    vertex_manager: &mut VertexManager,
+    fetcher: &mut Fetcher,
+) {
+    let names = defined_names
+        .names
+        .iter()
+        .filter_map(|defined_name| {
+            let (name_id, scope) = match defined_name.local_sheet_id {
+                Some(idx) => {
+                    let sheet_id = *sheet_ids.get(idx as usize)?;
+                    let name_id = fetcher
+                        .name_id_manager
+                        .get_local_id(sheet_id, &defined_name.name);
+                    (name_id, Some(sheet_id))
+                }
+                None => {
+                    let key = (CURR_BOOK, defined_name.name.clone());
+                    (fetcher.name_id_manager.get_id(&key), None)
+                }
+            };
+            if defined_name.hidden {
+                fetcher.name_id_manager.hiddens.insert(name_id);
+            }
+            Some((name_id, scope, &defined_name.formula))
+        })
+        .collect::<Vec<(NameId, Option<SheetId>, &String)>>();
+    names.into_iter().for_each(|(name_id, scope, f)| {
+        let sheet_id = scope.or(sheet_ids.first().cloned()).unwrap_or(0);
+        load_name_formula(vertex_manager, book_name, sheet_id, name_id, f, fetcher);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file_loader2::load;
+    use crate::test_utils::{edit, input, value};
+    use crate::Workbook;
+    use logisheets_workbook::prelude::{read, CtDefinedName, CtDefinedNames};
+
+    fn defined_name(
+        name: &str,
+        formula: &str,
+        local_sheet_id: Option<u32>,
+        hidden: bool,
+    ) -> CtDefinedName {
+        CtDefinedName {
+            formula: formula.to_string(),
+            name: name.to_string(),
+            comment: None,
+            comment_menu: None,
+            description: None,
+            help: None,
+            status_bar: None,
+            local_sheet_id,
+            hidden,
+            function: false,
+            vb_procedure: false,
+            xlm: false,
+            function_group_id: None,
+            shortcut_key: None,
+            publish_to_server: false,
+            workbook_parameter: false,
+        }
+    }
+
+    #[test]
+    fn defined_names_test() {
+        let mut wb = Workbook::default();
+        edit(&mut wb, vec![input(0, 0, 0, "10")]);
+        let mut book = read(&wb.controller.save().unwrap()).unwrap();
+        book.xl.workbook_part.defined_names = Some(CtDefinedNames {
+            names: vec![
+                defined_name("TaxRate", "0.2", None, false),
+                defined_name("Base", "Sheet1!$A$1", None, true),
+                defined_name("Total", "Base*(1+TaxRate)", None, false),
+                defined_name("Rate", "0.1", None, false),
+                defined_name("Rate", "0.5", Some(0), false),
+                defined_name("_xlnm.Print_Area", "Sheet1!$A$1:$B$2", Some(0), false),
+            ],
+        });
+        let mut wb = Workbook {
+            controller: load(book, String::from("Book1")),
+        };
+        edit(
+            &mut wb,
+            vec![input(0, 0, 1, "=Total"), input(0, 1, 1, "=Rate")],
+        );
+        assert_eq!(value(&mut wb, 0, 0, 1), "12");
+        assert_eq!(value(&mut wb, 0, 1, 1), "0.5");
+        edit(&mut wb, vec![input(0, 0, 0, "20")]);
+        assert_eq!(value(&mut wb, 0, 0, 1), "24");
+
+        let book = read(&wb.controller.save().unwrap()).unwrap();
+        let names = book.xl.workbook_part.defined_names.unwrap().names;
+        assert_eq!(names.len(), 6);
+        let find = |name: &str, local_sheet_id: Option<u32>| {
+            names
+                .iter()
+                .find(|n| n.name == name && n.local_sheet_id == local_sheet_id)
+                .unwrap()
+        };
+        assert!(find("Base", None).hidden);
+        assert_eq!(find("Base", None).formula, "Sheet1!$A$1");
+        assert_eq!(find("Rate", Some(0)).formula, "0.5");
+        assert_eq!(
+            find("_xlnm.Print_Area", Some(0)).formula,
+            "Sheet1!$A$1:$B$2"
+        );
+    }
+}
@@ -1,10 +1,14 @@
-use logisheets_base::{CellId, CellValue, SheetId};
+use logisheets_base::{AuthorId, CellId, CellValue, SheetId};
 use logisheets_workbook::prelude::*;
+use std::collections::HashMap;
 
 use crate::{
     cell::Cell,
-    cell_attachments::{comment::Comment, CellAttachmentsManager},
-    container::{col_info_manager::ColInfo, row_info_manager::RowInfo, DataContainer},
+    cell_attachments::{
+        comment::{Comment, CommentThread, Mention, Person, ThreadedComment},
+        CellAttachmentsManager,
+    },
+    container::{col_info_manager::ColInfo, row_info_manager::RowInfo, DataContainer, SpillRange},
     ext_book_manager::ExtBooksManager,
     id_manager::{FuncIdManager, NameIdManager, SheetIdManager, TextIdManager},
     navigator::Navigator,
@@ -109,6 +113,96 @@ pub fn load_comments(
         })
 }
 
+// Return the authors of the person ids.
+pub fn load_persons(
+    persons: &PersonListPart,
+    cell_attachment_manager: &mut CellAttachmentsManager,
+) -> HashMap<String, AuthorId> {
+    let comments = &mut cell_attachment_manager.comments;
+    persons
+        .persons
+        .iter()
+        .map(|p| {
+            let author_id = comments.authors.get_id(&p.display_name);
+            let person = Person {
+                id: p.id.clone(),
+                user_id: p.user_id.clone(),
+                provider_id: p.provider_id.clone(),
+            };
+            comments.persons.insert(author_id, person);
+            (p.id.clone(), author_id)
+        })
+        .collect()
+}
+
+pub fn load_threaded_comments(
+    sheet_id: SheetId,
+    threaded_comments: &ThreadedCommentsPart,
+    persons: &HashMap<String, AuthorId>,
+    navigator: &mut Navigator,
+    cell_attachment_manager: &mut CellAttachmentsManager,
+) {
+    let mut threads = Vec::<(CellId, CommentThread)>::new();
+    threaded_comments.threaded_comments.iter().for_each(|tc| {
+        let author = match persons.get(&tc.person_id) {
+            Some(author) => *author,
+            None => return,
+        };
+        let mentions = tc.mentions.as_ref().map_or(vec![], |m| {
+            m.mentions
+                .iter()
+                .filter_map(|m| {
+                    Some(Mention {
+                        id: m.mention_id.clone(),
+                        person: *persons.get(&m.mention_person_id)?,
+                        start: m.start_index,
+                        length: m.length,
+                    })
+                })
+                .collect()
+        });
+        let comment = ThreadedComment {
+            id: tc.id.clone(),
+            author,
+            text: tc
+                .text
+                .as_ref()
+                .map_or(String::from(""), |t| t.value.clone()),
+            date_time: tc.date_time.clone(),
+            mentions,
+        };
+        match &tc.parent_id {
+            Some(parent) => {
+                let thread = threads
+                    .iter_mut()
+                    .find(|(_, t)| t.comments.head().is_some_and(|c| &c.id == parent));
+                if let Some((_, thread)) = thread {
+                    thread.comments.push_back(comment);
+                }
+            }
+            None => {
+                let cell_id = tc
+                    .reference
+                    .as_ref()
+                    .and_then(|r| parse_cell(r))
+                    .and_then(|(row, col)| navigator.fetch_cell_id(sheet_id, row, col));
+                if let Some(cell_id) = cell_id {
+                    let thread = CommentThread {
+                        comments: im::vector![comment],
+                        done: tc.done.unwrap_or(false),
+                    };
+                    threads.push((cell_id, thread));
+                }
+            }
+        }
+    });
+    threads.into_iter().for_each(|(cell_id, thread)| {
+        cell_attachment_manager
+            .comments
+            .add_thread(sheet_id, cell_id, thread)
+    });
+}
+
 pub fn load_sheet_data(
     sheet_id: SheetId,
     book_name: &str,
@@ -166,7 +260,36 @@ pub fn load_sheet_data(
                             workbook,
                         };
                         if let Some(f) = &formula.formula {
-                            if let Some(reference) = &formula.reference {
+                            let array = match (&formula.t, &formula.reference) {
+                                (StCellFormulaType::Array, Some(reference)) => {
+                                    parse_range(reference).or_else(|| {
+                                        parse_cell(reference).map(|start| (start, start))
+                                    })
+                                }
+                                _ => None,
+                            };
+                            if let Some(((row_start, col_start), (row_end, col_end))) = array {
+                                // The spilled values are loaded as the other
+                                // cells and the anchor takes the area back.
+                                load_normal_formula(
+                                    vertex_manager,
+                                    book_name,
+                                    sheet_id,
+                                    row,
+                                    col,
+                                    f,
+                                    &mut fetcher,
+                                );
+                                let spill = SpillRange {
+                                    rows: row_end - row_start + 1,
+                                    cols: col_end - col_start + 1,
+                                    blocked: false,
+                                };
+                                container
+                                    .get_sheet_container(sheet_id)
+                                    .spills
+                                    .insert(id, spill);
+                            } else if let Some(reference) = &formula.reference {
                                 if let Some(((row_start, col_start), (row_end, col_end))) =
                                     parse_range(reference)
                                 {
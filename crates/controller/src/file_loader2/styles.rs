@@ -1,4 +1,6 @@
-use crate::style_manager::{xf_manager::CtrlXf, StyleManager};
+use crate::style_manager::{
+    num_fmt_manager::get_builtin_num_fmt, xf_manager::CtrlXf, StyleManager,
+};
 use logisheets_base::StyleId;
 use logisheets_workbook::prelude::*;
 use std::collections::HashMap;
@@ -60,18 +62,21 @@ impl<'a> StyleLoader<'a> {
             } else {
                 0
             };
-            let num_fmt_id = if let Some(idx) = xf.num_fmt_id {
-                if let Some(num_fmts) = &self.part.num_fmts {
-                    if let Some(f) = num_fmts.num_fmts.get(idx as usize) {
-                        self.manager.num_fmt_manager.get_id(&f.format_code)
-                    } else {
-                        0
+            let num_fmt_id = match xf.num_fmt_id {
+                Some(0) | None => 0,
+                Some(id) => {
+                    let custom =
+                        self.part.num_fmts.as_ref().and_then(|num_fmts| {
+                            num_fmts.num_fmts.iter().find(|f| f.num_fmt_id == id)
+                        });
+                    match custom {
+                        Some(f) => self.manager.num_fmt_manager.get_id(&f.format_code),
+                        None => match get_builtin_num_fmt(id) {
+                            Some(code) => self.manager.num_fmt_manager.get_id(&code.to_string()),
+                            None => 0,
+                        },
                     }
-                } else {
-                    0
                 }
-            } else {
-                0
             };
             let border_id = if let Some(idx) = xf.border_id {
                 if let Some(borders) = &self.part.borders {
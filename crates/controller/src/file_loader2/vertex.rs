@@ -1,7 +1,12 @@
-use logisheets_base::{id_fetcher::IdFetcherTrait, index_fetcher::IndexFetcherTrait, SheetId};
+use logisheets_base::{
+    id_fetcher::IdFetcherTrait, index_fetcher::IndexFetcherTrait, NameId, SheetId,
+};
 use logisheets_parser::{ast, context::Context, Parser};
 
-use crate::vertex_manager::{executors::input_formula::add_ast_node, VertexManager};
+use crate::vertex_manager::{
+    executors::input_formula::{add_ast_node, add_name_node},
+    VertexManager,
+};
 
 pub fn load_normal_formula<T>(
     vertex_manager: &mut VertexManager,
@@ -30,6 +35,30 @@ pub fn load_normal_formula<T>(
     vertex_manager.status = status;
 }
 
+pub fn load_name_formula<T>(
+    vertex_manager: &mut VertexManager,
+    book_name: &str,
+    sheet_id: SheetId,
+    name_id: NameId,
+    f: &str,
+    id_fetcher: &mut T,
+) where
+    T: IdFetcherTrait,
+{
+    let mut context = Context {
+        sheet_id,
+        book_name,
+        id_fetcher,
+    };
+    let parser = Parser {};
+    let ast = parser.parse(f, &mut context).unwrap_or(ast::Node {
+        pure: ast::PureNode::Value(ast::Value::Error(ast::Error::Ref)),
+        bracket: false,
+    });
+    let status = add_name_node(vertex_manager.status.clone(), name_id, ast);
+    vertex_manager.status = status;
+}
+
 pub fn load_shared_formulas<T>(
     vertex_manager: &mut VertexManager,
     book_name: &str,
@@ -0,0 +1,67 @@
+use logisheets_base::SheetId;
+use logisheets_parser::unparse;
+use logisheets_workbook::prelude::*;
+
+use crate::{connectors::NameFetcher, controller::status::Status, navigator::Navigator};
+
+use super::utils::{unparse_cell, unparse_range};
+
+pub fn save_cond_formats(
+    sheet_id: SheetId,
+    status: &Status,
+    navigator: &mut Navigator,
+) -> Vec<CtConditionalFormatting> {
+    status
+        .cond_format_manager
+        .get_cond_formats(sheet_id)
+        .iter()
+        .filter_map(|cf| {
+            let sqref = cf
+                .ranges
+                .iter()
+                .filter_map(|(start, end)| {
+                    let start = navigator.fetch_normal_cell_idx(sheet_id, start)?;
+                    let end = navigator.fetch_normal_cell_idx(sheet_id, end)?;
+                    match start == end {
+                        true => Some(unparse_cell(start.0, start.1)),
+                        false => Some(unparse_range(start, end)),
+                    }
+                })
+                .collect::<Vec<_>>();
+            if sqref.is_empty() {
+                return None;
+            }
+            let cf_rules = cf
+                .rules
+                .iter()
+                .map(|r| {
+                    let mut rule = r.rule.clone();
+                    rule.formulas = r
+                        .formulas
+                        .iter()
+                        .map(|node| {
+                            let mut name_fetcher = NameFetcher {
+                                func_manager: &status.func_id_manager,
+                                sheet_id_manager: &status.sheet_id_manager,
+                                external_links_manager: &status.external_links_manager,
+                                text_id_manager: &status.text_id_manager,
+                                name_id_manager: &status.name_id_manager,
+                                navigator,
+                            };
+                            PlainTextString {
+                                value: unparse::unparse(node, &mut name_fetcher, sheet_id),
+                                space: None,
+                            }
+                        })
+                        .collect();
+                    rule
+                })
+                .collect();
+            Some(CtConditionalFormatting {
+                cf_rules,
+                pviot: false,
+                sqref: sqref.join(" "),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,143 @@
+use logisheets_workbook::prelude::*;
+use logisheets_workbook::workbook::ExternalLink;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    calc_engine::calculator::calc_vertex::Value, controller::status::Status,
+    ext_book_manager::ExtBook,
+};
+
+use super::utils::unparse_cell;
+
+// The formulas in the file refer to the external books by their 1-based
+// positions in the external references, like `[1]Sheet1!A1`. So the books
+// are renamed to these positions in the status which the formulas are
+// unparsed from.
+pub fn save_external_links(
+    status: &mut Status,
+    first_rid: usize,
+) -> (Option<CtExternalReferences>, HashMap<String, ExternalLink>) {
+    let mut books = status
+        .external_links_manager
+        .book_id_manager
+        .ids
+        .iter()
+        .map(|(name, id)| (name.clone(), *id))
+        .collect::<Vec<_>>();
+    if books.is_empty() {
+        return (None, HashMap::new());
+    }
+    books.sort_by_key(|(_, id)| *id);
+    let mut external_references = vec![];
+    let mut external_links = HashMap::new();
+    books.iter().enumerate().for_each(|(idx, (name, id))| {
+        let rid = format!("rId{}", first_rid + idx);
+        let book = status.external_links_manager.books.get(id);
+        external_references.push(CtExternalReference { id: rid.clone() });
+        external_links.insert(
+            rid,
+            ExternalLink {
+                external_link_part: ExternalLinkPart {
+                    external_book: Some(save_external_book(status, book)),
+                },
+                target: name.clone(),
+            },
+        );
+    });
+    status.external_links_manager.book_id_manager.ids = books
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (_, id))| ((idx + 1).to_string(), id))
+        .collect();
+    (
+        Some(CtExternalReferences {
+            external_references,
+        }),
+        external_links,
+    )
+}
+
+// The books that are only referred to by names have no sheets.
+fn save_external_book(status: &Status, book: Option<&ExtBook>) -> CtExternalBook {
+    let book = match book {
+        Some(b) => b,
+        None => {
+            return CtExternalBook {
+                sheet_names: None,
+                defined_names: None,
+                sheet_data_set: None,
+                id: String::from("rId1"),
+            }
+        }
+    };
+    let sheet_names = book
+        .sheets
+        .iter()
+        .map(|sheet_id| CtExternalSheetName {
+            val: status
+                .sheet_id_manager
+                .get_string(sheet_id)
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    // The cached values are listed in the order of the sheet names.
+    let data = book
+        .sheets
+        .iter()
+        .enumerate()
+        .map(|(idx, sheet_id)| {
+            let mut rows = BTreeMap::<usize, BTreeMap<usize, CtExternalCell>>::new();
+            book.data_set
+                .iter()
+                .filter(|((s, _), _)| s == sheet_id)
+                .for_each(|((_, addr), value)| {
+                    if let Some(cell) = save_external_cell(addr.row, addr.col, value) {
+                        rows.entry(addr.row).or_default().insert(addr.col, cell);
+                    }
+                });
+            CtExternalSheetData {
+                rows: rows
+                    .into_iter()
+                    .map(|(row, cells)| CtExternalRow {
+                        cells: cells.into_values().collect(),
+                        r: row as u32 + 1,
+                    })
+                    .collect(),
+                sheet_id: idx as u32,
+                refresh_error: false,
+            }
+        })
+        .collect::<Vec<_>>();
+    CtExternalBook {
+        sheet_names: match sheet_names.is_empty() {
+            true => None,
+            false => Some(CtExternalSheetNames { names: sheet_names }),
+        },
+        defined_names: None,
+        sheet_data_set: match data.is_empty() {
+            true => None,
+            false => Some(CtExternalSheetDataSet { data }),
+        },
+        id: String::from("rId1"),
+    }
+}
+
+fn save_external_cell(row: usize, col: usize, value: &Value) -> Option<CtExternalCell> {
+    let (v, t) = match value {
+        Value::Blank => return None,
+        Value::Number(n) => (n.to_string(), StCellType::N),
+        Value::Text(s) => (s.clone(), StCellType::Str),
+        Value::Boolean(b) => (String::from(if *b { "1" } else { "0" }), StCellType::B),
+        Value::Error(e) => (e.get_err_str().to_string(), StCellType::E),
+        Value::Date(d) => (d.to_rfc3339(), StCellType::D),
+    };
+    Some(CtExternalCell {
+        v: Some(PlainTextString {
+            value: v,
+            space: None,
+        }),
+        r: Some(unparse_cell(row, col)),
+        t,
+        vm: 0,
+    })
+}
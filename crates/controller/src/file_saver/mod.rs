@@ -0,0 +1,215 @@
+mod cond_format;
+mod external_links;
+mod names;
+mod sheet;
+mod sst;
+mod styles;
+mod utils;
+
+use logisheets_base::datetime::DateSystem;
+use logisheets_workbook::prelude::*;
+use logisheets_workbook::workbook::{DocProps, Worksheet, Xl};
+use logisheets_workbook::writer::write;
+use std::collections::HashMap;
+
+use crate::{
+    controller::Controller,
+    file_saver::{
+        cond_format::save_cond_formats,
+        external_links::save_external_links,
+        names::save_defined_names,
+        sheet::{
+            save_cols, save_comments, save_merge_cells, save_persons, save_sheet_data,
+            save_threaded_comments,
+        },
+        sst::SstSaver,
+        styles::save_style_manager,
+    },
+    settings::CalcMode,
+};
+
+pub fn save(controller: &Controller) -> Result<Vec<u8>, SerdeErr> {
+    let wb = save_workbook(controller);
+    let buf = write(wb)?;
+    Ok(buf)
+}
+
+fn save_workbook(controller: &Controller) -> Workbook {
+    let mut status = controller.status.clone();
+    // The relationship ids after the sheets, the styles, the shared strings,
+    // the theme and the persons.
+    let first_ext_rid = status.sheet_pos_manager.pos.len() + 5;
+    let (external_references, external_links) = save_external_links(&mut status, first_ext_rid);
+    let status = &status;
+    let settings = &controller.settings;
+    let mut navigator = status.navigator.clone();
+    // Every threaded comment should refer to a person.
+    let mut cell_attachment_manager = status.cell_attachment_manager.clone();
+    cell_attachment_manager.comments.assign_persons();
+    let mut sst_saver = SstSaver::new(&status.text_id_manager);
+    let mut sheets = Vec::<CtSheet>::new();
+    let mut worksheets = HashMap::<String, Worksheet>::new();
+    status
+        .sheet_pos_manager
+        .pos
+        .iter()
+        .enumerate()
+        .for_each(|(idx, sheet_id)| {
+            let sheet_id = *sheet_id;
+            let rid = format!("rId{}", idx + 1);
+            let name = status
+                .sheet_id_manager
+                .get_string(&sheet_id)
+                .unwrap_or(format!("Sheet{}", idx + 1));
+            let state = if status.sheet_pos_manager.hiddens.contains(&sheet_id) {
+                StSheetState::Hidden
+            } else {
+                StSheetState::Visible
+            };
+            sheets.push(CtSheet {
+                name,
+                sheet_id: idx as u32 + 1,
+                state,
+                id: rid.clone(),
+            });
+            let sheet_data = save_sheet_data(sheet_id, status, &mut navigator, &mut sst_saver);
+            let worksheet_part = WorksheetPart {
+                sheet_pr: None,
+                dimension: None,
+                sheet_views: None,
+                sheet_format_pr: settings.sheet_format_pr.get(&sheet_id).cloned(),
+                cols: save_cols(sheet_id, &status.container, &mut navigator),
+                sheet_data,
+                sheet_calc_pr: None,
+                sheet_protection: None,
+                protected_ranges: None,
+                scenarios: None,
+                auto_filter: None,
+                sort_state: None,
+                data_consolidate: None,
+                custom_sheet_views: None,
+                merge_cells: save_merge_cells(
+                    sheet_id,
+                    &mut navigator,
+                    &status.cell_attachment_manager,
+                ),
+                phonetic_pr: None,
+                conditional_formatting: save_cond_formats(sheet_id, status, &mut navigator),
+                data_validations: None,
+                hyperlinks: None,
+                print_options: None,
+                page_margins: None,
+                page_setup: None,
+                header_footer: None,
+                row_breaks: None,
+                col_breaks: None,
+                custom_properties: None,
+                cell_watches: None,
+                ignored_errors: None,
+                smart_tags: None,
+                drawing: None,
+                drawing_hf: None,
+                picture: None,
+                controls: None,
+                web_publish_items: None,
+                table_parts: None,
+            };
+            let comments = save_comments(sheet_id, &mut navigator, &status.cell_attachment_manager);
+            let threaded_comments =
+                save_threaded_comments(sheet_id, &mut navigator, &cell_attachment_manager);
+            worksheets.insert(
+                rid,
+                Worksheet {
+                    worksheet_part,
+                    comments,
+                    threaded_comments,
+                },
+            );
+        });
+    let sheet_cnt = sheets.len();
+    let calc_pr = CtCalcPr {
+        calc_id: 0,
+        calc_mode: match settings.calc_config.mode {
+            CalcMode::Auto => StCalcMode::Auto,
+            CalcMode::AutoNoTable => StCalcMode::AutoNoTable,
+            CalcMode::Manual => StCalcMode::Manual,
+        },
+        full_calc_on_load: false,
+        ref_mode: StRefMode::A1,
+        iterate: settings.calc_config.iterative,
+        iterate_count: settings.calc_config.iter_limit as u32,
+        iterate_delta: settings.calc_config.error as f64,
+        full_precision: true,
+        calc_completed: true,
+        calc_on_save: true,
+        concurrent_calc: true,
+        concurrent_manual_calc: true,
+        force_full_calc: None,
+    };
+    let workbook_pr = match settings.date_system {
+        DateSystem::Date1900 => None,
+        DateSystem::Date1904 => Some(CtWorkbookPr {
+            date1904: true,
+            show_objects: StObjects::All,
+            show_border_unselected_tables: true,
+            filter_privacy: false,
+            prompted_solutions: false,
+            show_ink_annotation: true,
+            backup_file: false,
+            save_external_link_values: true,
+            update_links: StUpdateLinks::UserSet,
+            code_name: None,
+            hide_pivot_field_list: false,
+            show_pivot_chart_filter: false,
+            allow_refresh_query: false,
+            publish_items: false,
+            check_compatibility: false,
+            auto_compress_pictures: true,
+            refresh_all_connections: true,
+            default_theme_version: None,
+        }),
+    };
+    let workbook_part = WorkbookPart {
+        file_version: None,
+        file_sharing: None,
+        workbook_pr,
+        workbook_protection: None,
+        book_views: None,
+        sheets: CtSheets { sheets },
+        function_groups: None,
+        external_references,
+        defined_names: save_defined_names(status, &mut navigator),
+        calc_pr: Some(calc_pr),
+        ole_size: None,
+        custom_workbook_views: None,
+        pivot_caches: None,
+        smart_tag_pr: None,
+        smart_tag_types: None,
+        web_publishing: None,
+        file_recovery_pr: None,
+        web_publish_objects: None,
+        conformance: None,
+    };
+    let styles = save_style_manager(&status.style_manager);
+    let sst = sst_saver
+        .save()
+        .map(|sst| (format!("rId{}", sheet_cnt + 2), sst));
+    let theme = settings
+        .theme
+        .get_theme_part()
+        .map(|theme| (format!("rId{}", sheet_cnt + 3), theme.clone()));
+    let persons = save_persons(&cell_attachment_manager)
+        .map(|persons| (format!("rId{}", sheet_cnt + 4), persons));
+    Workbook {
+        xl: Xl {
+            workbook_part,
+            styles: (format!("rId{}", sheet_cnt + 1), styles),
+            sst,
+            worksheets,
+            external_links,
+            theme,
+            persons,
+        },
+        doc_props: DocProps::default(),
+    }
+}
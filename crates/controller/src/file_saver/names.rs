@@ -0,0 +1,61 @@
+use logisheets_base::{SheetId, CURR_BOOK};
+use logisheets_parser::unparse;
+use logisheets_workbook::prelude::*;
+
+use crate::{connectors::NameFetcher, controller::status::Status, navigator::Navigator};
+
+// The references in defined names are always written with their sheet names,
+// so the formulas are unparsed in a sheet that does not exist.
+const NO_SHEET: SheetId = SheetId::MAX;
+
+pub fn save_defined_names(status: &Status, navigator: &mut Navigator) -> Option<CtDefinedNames> {
+    let name_id_manager = &status.name_id_manager;
+    let mut names = status
+        .vertex_manager
+        .status
+        .names
+        .iter()
+        .filter_map(|(name_id, node)| {
+            let (book, name) = name_id_manager.get_string(name_id)?;
+            if book != CURR_BOOK {
+                return None;
+            }
+            let local_sheet_id = match name_id_manager.get_scope(name_id) {
+                Some(sheet_id) => Some(status.sheet_pos_manager.get_sheet_idx(sheet_id)? as u32),
+                None => None,
+            };
+            let mut name_fetcher = NameFetcher {
+                func_manager: &status.func_id_manager,
+                sheet_id_manager: &status.sheet_id_manager,
+                external_links_manager: &status.external_links_manager,
+                text_id_manager: &status.text_id_manager,
+                name_id_manager,
+                navigator,
+            };
+            let formula = unparse::unparse(node, &mut name_fetcher, NO_SHEET);
+            Some(CtDefinedName {
+                formula,
+                name,
+                comment: None,
+                comment_menu: None,
+                description: None,
+                help: None,
+                status_bar: None,
+                local_sheet_id,
+                hidden: name_id_manager.hiddens.contains(name_id),
+                function: false,
+                vb_procedure: false,
+                xlm: false,
+                function_group_id: None,
+                shortcut_key: None,
+                publish_to_server: false,
+                workbook_parameter: false,
+            })
+        })
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return None;
+    }
+    names.sort_by(|a, b| (&a.name, a.local_sheet_id).cmp(&(&b.name, b.local_sheet_id)));
+    Some(CtDefinedNames { names })
+}
@@ -0,0 +1,368 @@
+use logisheets_base::{CellValue, SheetId};
+use logisheets_parser::unparse;
+use logisheets_workbook::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    cell::Cell,
+    cell_attachments::{comment::Person, CellAttachmentsManager},
+    connectors::NameFetcher,
+    container::{row_info_manager::RowInfo, DataContainer},
+    controller::status::Status,
+    navigator::Navigator,
+};
+
+use super::{
+    sst::{plain_text_rst, SstSaver},
+    utils::{unparse_cell, unparse_range},
+};
+
+pub fn save_cols(
+    sheet_id: SheetId,
+    container: &DataContainer,
+    navigator: &mut Navigator,
+) -> Option<CtCols> {
+    let sheet_container = container.data.get(&sheet_id)?;
+    let mut cols = sheet_container
+        .col_info
+        .get_all_col_info()
+        .into_iter()
+        .filter_map(|(col_id, info)| {
+            let idx = navigator.fetch_col_idx(sheet_id, col_id)? as u32;
+            Some(CtCol {
+                min: idx + 1,
+                max: idx + 1,
+                width: info.width,
+                style: info.style,
+                hidden: info.hidden,
+                best_fit: info.best_fit,
+                custom_width: info.custom_width,
+                phonetic: false,
+                outline_level: info.outline_level as u32,
+                collapsed: info.collapsed,
+            })
+        })
+        .collect::<Vec<_>>();
+    if cols.is_empty() {
+        return None;
+    }
+    cols.sort_by_key(|c| c.min);
+    Some(CtCols { cols })
+}
+
+pub fn save_merge_cells(
+    sheet_id: SheetId,
+    navigator: &mut Navigator,
+    cell_attachment_manager: &CellAttachmentsManager,
+) -> Option<CtMergeCells> {
+    let merge_cells = cell_attachment_manager.merge_cells.data.get(&sheet_id)?;
+    let mut ranges = merge_cells
+        .iter()
+        .filter_map(|(start, end)| {
+            let start = navigator.fetch_normal_cell_idx(sheet_id, start)?;
+            let end = navigator.fetch_normal_cell_idx(sheet_id, end)?;
+            Some((start, end))
+        })
+        .collect::<Vec<_>>();
+    if ranges.is_empty() {
+        return None;
+    }
+    ranges.sort();
+    let merge_cells = ranges
+        .into_iter()
+        .map(|(start, end)| CtMergeCell {
+            reference: unparse_range(start, end),
+        })
+        .collect::<Vec<_>>();
+    Some(CtMergeCells {
+        count: merge_cells.len() as u32,
+        merge_cells,
+    })
+}
+
+pub fn save_comments(
+    sheet_id: SheetId,
+    navigator: &mut Navigator,
+    cell_attachment_manager: &CellAttachmentsManager,
+) -> Option<Comments> {
+    let comments = &cell_attachment_manager.comments;
+    let sheet_comments = comments.data.get(&sheet_id)?;
+    let mut authors = Vec::<PlainTextString>::new();
+    let mut author_indices = HashMap::new();
+    let mut list = sheet_comments
+        .comments
+        .iter()
+        .filter_map(|(cell_id, comment)| {
+            let (row, col) = navigator.fetch_cell_idx(sheet_id, cell_id)?;
+            let author_id = *author_indices.entry(comment.author).or_insert_with(|| {
+                let name = comments
+                    .get_author_name(&comment.author)
+                    .unwrap_or(String::from(""));
+                authors.push(PlainTextString {
+                    value: name,
+                    space: None,
+                });
+                authors.len() as u32 - 1
+            });
+            let c = CtComment {
+                text: plain_text_rst(comment.text.clone()),
+                comment_pr: None,
+                reference: unparse_cell(row, col),
+                author_id,
+                shape_id: None,
+                guid: None,
+            };
+            Some(((row, col), c))
+        })
+        .collect::<Vec<_>>();
+    if list.is_empty() {
+        return None;
+    }
+    list.sort_by_key(|(pos, _)| *pos);
+    Some(Comments {
+        authors: CtAuthors { authors },
+        comment_list: CtCommentList {
+            comments: list.into_iter().map(|(_, c)| c).collect(),
+        },
+    })
+}
+
+pub fn save_threaded_comments(
+    sheet_id: SheetId,
+    navigator: &mut Navigator,
+    cell_attachment_manager: &CellAttachmentsManager,
+) -> Option<ThreadedCommentsPart> {
+    let comments = &cell_attachment_manager.comments;
+    let sheet_comments = comments.data.get(&sheet_id)?;
+    let person_id = |author| comments.persons.get(author).map(|p: &Person| p.id.clone());
+    let mut threads = sheet_comments
+        .threads
+        .iter()
+        .filter_map(|(cell_id, thread)| {
+            let (row, col) = navigator.fetch_cell_idx(sheet_id, cell_id)?;
+            Some(((row, col), thread))
+        })
+        .collect::<Vec<_>>();
+    if threads.is_empty() {
+        return None;
+    }
+    threads.sort_by_key(|(pos, _)| *pos);
+    let mut threaded_comments = Vec::<CtThreadedComment>::new();
+    threads.into_iter().for_each(|((row, col), thread)| {
+        let root_id = match thread.comments.head() {
+            Some(c) => c.id.clone(),
+            None => return,
+        };
+        thread.comments.iter().enumerate().for_each(|(idx, c)| {
+            let is_root = idx == 0;
+            let author_id = match person_id(&c.author) {
+                Some(id) => id,
+                None => return,
+            };
+            let mentions = if !c.mentions.is_empty() {
+                Some(CtThreadedCommentMentions {
+                    mentions: c
+                        .mentions
+                        .iter()
+                        .filter_map(|m| {
+                            Some(CtMention {
+                                mention_person_id: person_id(&m.person)?,
+                                mention_id: m.id.clone(),
+                                start_index: m.start,
+                                length: m.length,
+                            })
+                        })
+                        .collect(),
+                })
+            } else {
+                None
+            };
+            threaded_comments.push(CtThreadedComment {
+                text: Some(PlainTextString {
+                    value: c.text.clone(),
+                    space: None,
+                }),
+                mentions,
+                reference: Some(unparse_cell(row, col)),
+                date_time: c.date_time.clone(),
+                person_id: author_id,
+                id: c.id.clone(),
+                parent_id: if is_root { None } else { Some(root_id.clone()) },
+                done: if is_root && thread.done {
+                    Some(true)
+                } else {
+                    None
+                },
+            });
+        });
+    });
+    Some(ThreadedCommentsPart { threaded_comments })
+}
+
+pub fn save_persons(cell_attachment_manager: &CellAttachmentsManager) -> Option<PersonListPart> {
+    let comments = &cell_attachment_manager.comments;
+    if comments.persons.is_empty() {
+        return None;
+    }
+    let mut persons = comments.persons.iter().collect::<Vec<_>>();
+    persons.sort_by_key(|(author, _)| **author);
+    let persons = persons
+        .into_iter()
+        .map(|(author, p)| CtPerson {
+            display_name: comments.get_author_name(author).unwrap_or(String::from("")),
+            id: p.id.clone(),
+            user_id: p.user_id.clone(),
+            provider_id: p.provider_id.clone(),
+        })
+        .collect();
+    Some(PersonListPart { persons })
+}
+
+pub fn save_sheet_data(
+    sheet_id: SheetId,
+    status: &Status,
+    navigator: &mut Navigator,
+    sst_saver: &mut SstSaver,
+) -> CtSheetData {
+    let mut rows = BTreeMap::<usize, (Option<&RowInfo>, BTreeMap<usize, CtCell>)>::new();
+    if let Some(sheet_container) = status.container.data.get(&sheet_id) {
+        sheet_container
+            .row_info
+            .get_all_row_info()
+            .into_iter()
+            .for_each(|(row_id, info)| {
+                if let Some(idx) = navigator.fetch_row_idx(sheet_id, row_id) {
+                    rows.entry(idx).or_insert((None, BTreeMap::new())).0 = Some(info);
+                }
+            });
+        sheet_container.cells.iter().for_each(|(cell_id, cell)| {
+            if let Some((row, col)) = navigator.fetch_cell_idx(sheet_id, cell_id) {
+                let ct_cell = save_cell(row, col, cell, sst_saver);
+                rows.entry(row)
+                    .or_insert((None, BTreeMap::new()))
+                    .1
+                    .insert(col, ct_cell);
+            }
+        });
+    }
+    let formulas = status
+        .vertex_manager
+        .status
+        .formulas
+        .iter()
+        .filter(|((s, _), _)| *s == sheet_id)
+        .collect::<Vec<_>>();
+    formulas.into_iter().for_each(|((_, cell_id), node)| {
+        if let Some((row, col)) = navigator.fetch_cell_idx(sheet_id, cell_id) {
+            let mut name_fetcher = NameFetcher {
+                func_manager: &status.func_id_manager,
+                sheet_id_manager: &status.sheet_id_manager,
+                external_links_manager: &status.external_links_manager,
+                text_id_manager: &status.text_id_manager,
+                name_id_manager: &status.name_id_manager,
+                navigator,
+            };
+            let f = unparse::unparse(node, &mut name_fetcher, sheet_id);
+            // The spilled cells are saved as values and the anchor keeps
+            // the area of the array result. The cell metadata marks the
+            // anchor as a dynamic array rather than a CSE array formula.
+            let spill = status
+                .container
+                .data
+                .get(&sheet_id)
+                .and_then(|c| c.spills.get(cell_id))
+                .filter(|s| !s.blocked);
+            let (t, reference, cm) = match spill {
+                Some(s) => (
+                    StCellFormulaType::Array,
+                    Some(unparse_range(
+                        (row, col),
+                        (row + s.rows - 1, col + s.cols - 1),
+                    )),
+                    1,
+                ),
+                None => (StCellFormulaType::Normal, None, 0),
+            };
+            let ct_cell = rows
+                .entry(row)
+                .or_insert((None, BTreeMap::new()))
+                .1
+                .entry(col)
+                .or_insert_with(|| {
+                    let blank = Cell {
+                        value: CellValue::Blank,
+                        style: 0,
+                    };
+                    save_cell(row, col, &blank, sst_saver)
+                });
+            ct_cell.cm = cm;
+            ct_cell.f = Some(CtFormula {
+                formula: Some(f),
+                t,
+                aca: false,
+                reference,
+                dt_2d: false,
+                del1: false,
+                del2: false,
+                r1: None,
+                r2: None,
+                ca: false,
+                si: None,
+                bx: false,
+            });
+        }
+    });
+    let rows = rows
+        .into_iter()
+        .map(|(idx, (info, cells))| {
+            let info = info.cloned().unwrap_or_default();
+            CtRow {
+                cells: cells.into_values().collect(),
+                r: Some(idx as u32 + 1),
+                spans: None,
+                s: info.style,
+                custom_format: info.custom_format,
+                ht: info.ht,
+                hidden: info.hidden,
+                custom_height: info.ht.is_some(),
+                outline_level: info.outline_level,
+                collapsed: info.collapsed,
+                thick_top: false,
+                thick_bot: false,
+                ph: false,
+            }
+        })
+        .collect();
+    CtSheetData { rows }
+}
+
+fn save_cell(row: usize, col: usize, cell: &Cell, sst_saver: &mut SstSaver) -> CtCell {
+    let plain_text = |value: String| Some(PlainTextString { value, space: None });
+    let (v, is, t) = match &cell.value {
+        CellValue::Blank => (None, None, StCellType::N),
+        CellValue::Boolean(b) => {
+            let v = if *b { "1" } else { "0" };
+            (plain_text(v.to_string()), None, StCellType::B)
+        }
+        CellValue::Date(d) => (plain_text(d.to_rfc3339()), None, StCellType::D),
+        CellValue::Error(e) => (plain_text(e.to_string()), None, StCellType::E),
+        CellValue::String(text_id) => {
+            let idx = sst_saver.get_idx(*text_id);
+            (plain_text(idx.to_string()), None, StCellType::S)
+        }
+        CellValue::Number(n) => (plain_text(n.to_string()), None, StCellType::N),
+        CellValue::InlineStr(rst) => (None, Some(rst.clone()), StCellType::InlineStr),
+        CellValue::FormulaStr(s) => (plain_text(s.clone()), None, StCellType::Str),
+    };
+    CtCell {
+        f: None,
+        v,
+        is,
+        r: Some(unparse_cell(row, col)),
+        s: cell.style,
+        t,
+        cm: 0,
+        vm: 0,
+        ph: false,
+    }
+}
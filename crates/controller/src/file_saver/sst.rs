@@ -0,0 +1,68 @@
+use logisheets_base::TextId;
+use logisheets_workbook::prelude::*;
+use std::collections::HashMap;
+
+use crate::id_manager::TextIdManager;
+
+// Collect the strings referred by the cells and give each of them an index in
+// the shared string table.
+pub struct SstSaver<'a> {
+    texts: HashMap<TextId, &'a String>,
+    indices: HashMap<TextId, usize>,
+    si: Vec<CtRst>,
+    count: u32,
+}
+
+impl<'a> SstSaver<'a> {
+    pub fn new(text_id_manager: &'a TextIdManager) -> Self {
+        let texts = text_id_manager
+            .ids
+            .iter()
+            .map(|(s, id)| (*id, s))
+            .collect::<HashMap<_, _>>();
+        SstSaver {
+            texts,
+            indices: HashMap::new(),
+            si: vec![],
+            count: 0,
+        }
+    }
+
+    pub fn get_idx(&mut self, text_id: TextId) -> usize {
+        self.count += 1;
+        if let Some(idx) = self.indices.get(&text_id) {
+            return *idx;
+        }
+        let text = self
+            .texts
+            .get(&text_id)
+            .map_or(String::from(""), |s| s.to_string());
+        let idx = self.si.len();
+        self.si.push(plain_text_rst(text));
+        self.indices.insert(text_id, idx);
+        idx
+    }
+
+    pub fn save(self) -> Option<SstPart> {
+        if self.si.is_empty() {
+            return None;
+        }
+        Some(SstPart {
+            count: Some(self.count),
+            unique_count: Some(self.si.len() as u32),
+            si: self.si,
+        })
+    }
+}
+
+pub fn plain_text_rst(text: String) -> CtRst {
+    let space = if text.starts_with(' ') || text.ends_with(' ') || text.contains('\n') {
+        Some(String::from("preserve"))
+    } else {
+        None
+    };
+    CtRst {
+        t: Some(PlainTextString { value: text, space }),
+        ..Default::default()
+    }
+}
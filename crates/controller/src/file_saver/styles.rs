@@ -0,0 +1,137 @@
+use logisheets_workbook::prelude::*;
+
+use crate::style_manager::{
+    num_fmt_manager::{get_builtin_num_fmt_id, NumFmtId},
+    xf_manager::{CtrlXf, XfManager},
+    StyleManager,
+};
+
+// The ids of the custom number formats start from 164.
+const CUSTOM_NUM_FMT_START: NumFmtId = 164;
+
+// Since the ids in the StyleManager are allocated from 0 continuously, the
+// style id of a cell can be directly used as the index of the cellXfs.
+pub fn save_style_manager(style_manager: &StyleManager) -> StylesheetPart {
+    let mut num_fmts = Vec::<CtNumFmt>::new();
+    let num_fmt_ids = style_manager
+        .num_fmt_manager
+        .get_all_data()
+        .into_iter()
+        .map(|code| {
+            if code.is_empty() {
+                return 0;
+            }
+            if let Some(id) = get_builtin_num_fmt_id(code) {
+                return id;
+            }
+            let id = CUSTOM_NUM_FMT_START + num_fmts.len() as NumFmtId;
+            num_fmts.push(CtNumFmt {
+                num_fmt_id: id,
+                format_code: code.to_string(),
+            });
+            id
+        })
+        .collect::<Vec<_>>();
+    let fonts = style_manager
+        .font_manager
+        .get_all_data()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    let fills = style_manager
+        .fill_manager
+        .get_all_data()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    let borders = style_manager
+        .border_manager
+        .get_all_data()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    let cell_style_xfs = save_xfs(&style_manager.cell_style_xfs_manager, &num_fmt_ids, false);
+    let cell_xfs = save_xfs(&style_manager.cell_xfs_manager, &num_fmt_ids, true);
+    StylesheetPart {
+        num_fmts: if !num_fmts.is_empty() {
+            Some(CtNumFmts {
+                count: num_fmts.len() as u32,
+                num_fmts,
+            })
+        } else {
+            None
+        },
+        fonts: Some(CtFonts {
+            count: fonts.len() as u32,
+            fonts,
+        }),
+        fills: Some(CtFills {
+            count: fills.len() as u32,
+            fills,
+        }),
+        borders: Some(CtBorders {
+            count: borders.len() as u32,
+            borders,
+        }),
+        cell_style_xfs: Some(CtCellStyleXfs {
+            count: cell_style_xfs.len() as u32,
+            xfs: cell_style_xfs,
+        }),
+        cell_xfs: Some(CtCellXfs {
+            count: cell_xfs.len() as u32,
+            xfs: cell_xfs,
+        }),
+        cell_styles: Some(CtCellStyles {
+            count: 1,
+            cell_styles: vec![CtCellStyle {
+                name: Some(String::from("Normal")),
+                xf_id: 0,
+                builtin_id: Some(0),
+                i_level: None,
+                custom_builtin: None,
+            }],
+        }),
+        dxfs: if !style_manager.dxfs.is_empty() {
+            Some(CtDxfs {
+                count: style_manager.dxfs.len() as u32,
+                dxfs: style_manager.dxfs.clone(),
+            })
+        } else {
+            None
+        },
+        table_styles: None,
+        colors: None,
+        ext_lst: None,
+    }
+}
+
+fn save_xfs(manager: &XfManager, num_fmt_ids: &[NumFmtId], with_xf_id: bool) -> Vec<CtXf> {
+    manager
+        .get_all_data()
+        .into_iter()
+        .map(|xf| save_xf(xf, num_fmt_ids, with_xf_id))
+        .collect()
+}
+
+fn save_xf(xf: &CtrlXf, num_fmt_ids: &[NumFmtId], with_xf_id: bool) -> CtXf {
+    let num_fmt_id = xf
+        .num_fmt_id
+        .map(|id| num_fmt_ids.get(id as usize).cloned().unwrap_or(0));
+    CtXf {
+        alignment: xf.alignment.clone(),
+        protction: xf.protection.clone(),
+        num_fmt_id,
+        font_id: xf.font_id,
+        fill_id: xf.fill_id,
+        border_id: xf.border_id,
+        xf_id: if with_xf_id { Some(0) } else { None },
+        quote_prefix: false,
+        pivot_button: false,
+        apply_number_format: xf.apply_number_format,
+        apply_font: xf.apply_font,
+        apply_fill: xf.apply_fill,
+        apply_border: xf.apply_border,
+        apply_alignment: xf.apply_alignment,
+        apply_protection: xf.apply_protection,
+    }
+}
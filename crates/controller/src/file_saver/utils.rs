@@ -0,0 +1,31 @@
+use logisheets_base::index_to_column_label;
+
+// (0, 0) => A1
+pub fn unparse_cell(row: usize, col: usize) -> String {
+    format!("{}{}", index_to_column_label(col), row + 1)
+}
+
+pub fn unparse_range(start: (usize, usize), end: (usize, usize)) -> String {
+    format!(
+        "{}:{}",
+        unparse_cell(start.0, start.1),
+        unparse_cell(end.0, end.1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unparse_cell, unparse_range};
+
+    #[test]
+    fn unparse_cell_test() {
+        assert_eq!(unparse_cell(0, 0), "A1");
+        assert_eq!(unparse_cell(1, 26), "AA2");
+        assert_eq!(unparse_cell(19, 26), "AA20");
+    }
+
+    #[test]
+    fn unparse_range_test() {
+        assert_eq!(unparse_range((1, 0), (3, 1)), "A2:B4");
+    }
+}
@@ -0,0 +1,152 @@
+use logisheets_base::datetime::{get_date_by_serial_num, Date, DateSystem, DATE_1904_OFFSET};
+
+use super::number::render_literal;
+use super::parser::{ElapsedUnit, Token};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+// Render a serial number in the given date system. Return None if the number
+// can not be displayed as a date.
+pub fn render_datetime(tokens: &[Token], value: f64, system: DateSystem) -> Option<String> {
+    let max = match system {
+        DateSystem::Date1900 => 2958466.,
+        DateSystem::Date1904 => (2958466 - DATE_1904_OFFSET) as f64,
+    };
+    if value < 0. || value >= max {
+        return None;
+    }
+    let sub_digits = tokens
+        .iter()
+        .find_map(|t| match t {
+            Token::SubSecond(n) => Some(*n as i32),
+            _ => None,
+        })
+        .unwrap_or(0);
+    // Round to the smallest displayed unit first so that 23:59:59.9 does not
+    // show as 23:59:60.
+    let unit = 10_f64.powi(sub_digits);
+    let total = (value * 86400. * unit).round() / unit;
+    let days = (total / 86400.).floor();
+    let secs = total - days * 86400.;
+    let days = days as u32;
+    let date = if days == 0 && system == DateSystem::Date1900 {
+        // Excel shows the serial number 0 as 1900-01-00.
+        Date {
+            year: 1900,
+            month: 1,
+            day: 0,
+        }
+    } else {
+        get_date_by_serial_num(days, system)
+    };
+    let whole_secs = secs.floor() as u32;
+    let hour = whole_secs / 3600;
+    let minute = (whole_secs / 60) % 60;
+    let second = whole_secs % 60;
+    let has_ampm = tokens.iter().any(|t| matches!(t, Token::AmPm(_)));
+    let result = tokens.iter().fold(String::new(), |mut prev, t| {
+        let s = match t {
+            Token::Year(n) => {
+                if *n <= 2 {
+                    format!("{:02}", date.year % 100)
+                } else {
+                    format!("{:04}", date.year)
+                }
+            }
+            Token::Month(n) => {
+                let name = MONTHS[(date.month as usize + 11) % 12];
+                match n {
+                    1 => date.month.to_string(),
+                    2 => format!("{:02}", date.month),
+                    3 => name[..3].to_string(),
+                    5 => name[..1].to_string(),
+                    _ => name.to_string(),
+                }
+            }
+            Token::Day(n) => {
+                // The serial number 1 is a Sunday in the 1900 date system
+                // and the serial number 0 is a Friday in the 1904 one.
+                let shift = match system {
+                    DateSystem::Date1900 => 6,
+                    DateSystem::Date1904 => 5,
+                };
+                let weekday = WEEKDAYS[(days as usize + shift) % 7];
+                match n {
+                    1 => date.day.to_string(),
+                    2 => format!("{:02}", date.day),
+                    3 => weekday[..3].to_string(),
+                    _ => weekday.to_string(),
+                }
+            }
+            Token::Hour(n) => {
+                let h = if has_ampm {
+                    match hour % 12 {
+                        0 => 12,
+                        h => h,
+                    }
+                } else {
+                    hour
+                };
+                pad(h as u64, *n)
+            }
+            Token::Minute(n) => pad(minute as u64, *n),
+            Token::Second(n) => pad(second as u64, *n),
+            Token::Elapsed(unit, n) => {
+                let total_secs = days as u64 * 86400 + whole_secs as u64;
+                let v = match unit {
+                    ElapsedUnit::Hour => total_secs / 3600,
+                    ElapsedUnit::Minute => total_secs / 60,
+                    ElapsedUnit::Second => total_secs,
+                };
+                pad(v, *n)
+            }
+            Token::SubSecond(n) => {
+                let frac = secs - secs.floor();
+                let digits = format!("{:.*}", *n, frac);
+                // `digits` is like `0.25`.
+                format!(".{}", &digits[2..])
+            }
+            Token::AmPm(full) => match (hour < 12, full) {
+                (true, true) => String::from("AM"),
+                (false, true) => String::from("PM"),
+                (true, false) => String::from("A"),
+                (false, false) => String::from("P"),
+            },
+            _ => render_literal(t),
+        };
+        prev.push_str(&s);
+        prev
+    });
+    Some(result)
+}
+
+fn pad(v: u64, width: usize) -> String {
+    if width >= 2 {
+        format!("{:0width$}", v, width = width)
+    } else {
+        v.to_string()
+    }
+}
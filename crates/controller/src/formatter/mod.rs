@@ -0,0 +1,293 @@
+mod datetime;
+mod number;
+mod parser;
+
+use logisheets_base::datetime::DateSystem;
+
+use self::datetime::render_datetime;
+use self::number::{render_general, render_literal, render_number};
+use self::parser::{parse_sections, Section, SectionKind, Token};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormattedValue {
+    pub text: String,
+    // An index of the legacy color palette, from [Red] or [Color3] etc.
+    pub color: Option<u8>,
+}
+
+// A compiled number format code.
+#[derive(Debug, Clone)]
+pub struct NumFormat {
+    sections: Vec<Section>,
+    date_system: DateSystem,
+}
+
+impl NumFormat {
+    // An empty code is regarded as `General`.
+    pub fn new(code: &str) -> Self {
+        let code = if code.trim() == "" { "General" } else { code };
+        NumFormat {
+            sections: parse_sections(code),
+            date_system: DateSystem::default(),
+        }
+    }
+
+    pub fn with_date_system(mut self, system: DateSystem) -> Self {
+        self.date_system = system;
+        self
+    }
+
+    // Whether the code displays the numbers as dates.
+    pub fn has_date(&self) -> bool {
+        self.sections.iter().any(|s| {
+            s.tokens
+                .iter()
+                .any(|t| matches!(t, Token::Year(_) | Token::Month(_) | Token::Day(_)))
+        })
+    }
+
+    pub fn format_number(&self, value: f64) -> FormattedValue {
+        let (section, value, negative) = match self.select_section(value) {
+            Some(r) => r,
+            None => {
+                return FormattedValue {
+                    text: render_general(value),
+                    color: None,
+                }
+            }
+        };
+        let sign = if negative { "-" } else { "" };
+        let text = match section.kind {
+            SectionKind::General => {
+                let general = render_general(value);
+                render_with(&section.tokens, |t| match t {
+                    Token::General => Some(format!("{}{}", sign, general)),
+                    _ => None,
+                })
+            }
+            SectionKind::Number => format!("{}{}", sign, render_number(&section.tokens, value)),
+            SectionKind::DateTime => {
+                match render_datetime(&section.tokens, value, self.date_system) {
+                    Some(s) if !negative => s,
+                    // Negative dates are displayed as hashes in Excel.
+                    _ => String::from("#"),
+                }
+            }
+            SectionKind::Text => format!("{}{}", sign, render_general(value)),
+            SectionKind::Literal => {
+                let s = render_with(&section.tokens, |_| None);
+                format!("{}{}", sign, s)
+            }
+        };
+        FormattedValue {
+            text,
+            color: section.color,
+        }
+    }
+
+    // Only the 4th section and a single section containing `@` apply to the
+    // texts. Otherwise the texts are displayed as they are.
+    pub fn format_text(&self, text: &str) -> FormattedValue {
+        let section = match self.sections.len() {
+            1 if self.sections[0].kind == SectionKind::Text => Some(&self.sections[0]),
+            n if n >= 4 => Some(&self.sections[3]),
+            _ => None,
+        };
+        match section {
+            Some(section) => FormattedValue {
+                text: render_with(&section.tokens, |t| match t {
+                    Token::Text => Some(text.to_string()),
+                    _ => None,
+                }),
+                color: section.color,
+            },
+            None => FormattedValue {
+                text: text.to_string(),
+                color: None,
+            },
+        }
+    }
+
+    // Choose the section for the number and return the value to render and
+    // whether a minus sign is needed.
+    fn select_section(&self, value: f64) -> Option<(&Section, f64, bool)> {
+        let sections = self.sections.iter().take(3).collect::<Vec<_>>();
+        let first = *sections.first()?;
+        if sections.iter().any(|s| s.condition.is_some()) {
+            let section = sections
+                .iter()
+                .enumerate()
+                .find(|(i, s)| match &s.condition {
+                    Some(c) => c.test(value),
+                    // The section without conditions is for the numbers that
+                    // do not match the others. The 2nd section of a code
+                    // like `[>0]0;[<0]0` has an implicit condition.
+                    None => *i > 0 || sections.len() == 1,
+                })
+                .map(|(_, s)| *s)?;
+            return Some((
+                section,
+                value.abs(),
+                value < 0. && section.condition.is_none(),
+            ));
+        }
+        match sections.len() {
+            1 => Some((first, value.abs(), value < 0.)),
+            2 if value < 0. => Some((sections[1], value.abs(), false)),
+            2 => Some((first, value, false)),
+            _ if value < 0. => Some((sections[1], value.abs(), false)),
+            _ if value == 0. => Some((sections[2], value, false)),
+            _ => Some((first, value, false)),
+        }
+    }
+}
+
+// Render the tokens of a section with the literals and the values given by
+// the closure.
+fn render_with<F>(tokens: &[Token], f: F) -> String
+where
+    F: Fn(&Token) -> Option<String>,
+{
+    tokens.iter().fold(String::new(), |mut prev, t| {
+        match f(t) {
+            Some(s) => prev.push_str(&s),
+            None => prev.push_str(&render_literal(t)),
+        }
+        prev
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumFormat;
+    use logisheets_base::datetime::DateSystem;
+
+    fn fmt(code: &str, value: f64) -> String {
+        NumFormat::new(code).format_number(value).text
+    }
+
+    #[test]
+    fn general_test() {
+        assert_eq!(fmt("General", 12.5), "12.5");
+        assert_eq!(fmt("", -3.0), "-3");
+        assert_eq!(fmt("General", 1.0 / 3.0), "0.333333333");
+        assert_eq!(fmt("General", 123456789012.0), "1.23457E+11");
+        assert_eq!(fmt("General", 0.1 + 0.2), "0.3");
+    }
+
+    #[test]
+    fn decimal_test() {
+        assert_eq!(fmt("0", 2.5), "3");
+        assert_eq!(fmt("0.00", 1.005), "1.01");
+        assert_eq!(fmt("#,##0", 1234567.0), "1,234,567");
+        assert_eq!(fmt("#,##0.00", -1234.5), "-1,234.50");
+        assert_eq!(fmt("0%", 0.256), "26%");
+        assert_eq!(fmt("0.00%", 0.5), "50.00%");
+        assert_eq!(fmt("#.##", 5.0), "5.");
+        assert_eq!(fmt("0.0#", 1.5), "1.5");
+        assert_eq!(fmt("0.??", 1.5), "1.5 ");
+        assert_eq!(fmt("000-0000", 5551234.0), "555-1234");
+        assert_eq!(fmt("#,##0,\"K\"", 12345.0), "12K");
+        assert_eq!(fmt("$#,##0.00", 1000.0), "$1,000.00");
+        assert_eq!(fmt("0.00\\ \"USD\"", 3.0), "3.00 USD");
+    }
+
+    #[test]
+    fn sections_test() {
+        let code = "[Red]-0.00;0.00";
+        let f = NumFormat::new(code);
+        let r = f.format_number(-2.0);
+        assert_eq!(r.text, "2.00");
+        assert_eq!(r.color, None);
+        let r = f.format_number(2.0);
+        assert_eq!(r.text, "-2.00");
+        assert_eq!(r.color, Some(10));
+        assert_eq!(fmt("$#,##0_);($#,##0)", -1234.0), "($1,234)");
+        assert_eq!(fmt("$#,##0_);($#,##0)", 1234.0), "$1,234 ");
+        assert_eq!(fmt("0;-0;\"zero\"", 0.0), "zero");
+        assert_eq!(fmt("0;;", 0.0), "");
+        let f = NumFormat::new("[Blue][>=100]0;[Color3][<0]0;0.0");
+        assert_eq!(f.format_number(150.0).color, Some(12));
+        assert_eq!(f.format_number(-5.0).text, "5");
+        assert_eq!(f.format_number(-5.0).color, Some(10));
+        assert_eq!(f.format_number(5.0).text, "5.0");
+        assert_eq!(
+            fmt("[<=9999999]###-####;(###) ###-####", 5551234.0),
+            "555-1234"
+        );
+        assert_eq!(
+            fmt("[<=9999999]###-####;(###) ###-####", 8005551234.0),
+            "(800) 555-1234"
+        );
+    }
+
+    #[test]
+    fn scientific_test() {
+        assert_eq!(fmt("0.00E+00", 12345.0), "1.23E+04");
+        assert_eq!(fmt("0.00E+00", 0.00012), "1.20E-04");
+        assert_eq!(fmt("##0.0E+0", 12345.0), "12.3E+3");
+        assert_eq!(fmt("0.0E+0", 0.0), "0.0E+0");
+        assert_eq!(fmt("0.00E-00", 12345.0), "1.23E04");
+    }
+
+    #[test]
+    fn fraction_test() {
+        assert_eq!(fmt("# ?/?", 1.5), "1 1/2");
+        assert_eq!(fmt("# ??/??", 3.14159), "3 14/99");
+        assert_eq!(fmt("# ??/??", 0.25), "  1/4 ");
+        assert_eq!(fmt("?/?", 0.75), "3/4");
+        assert_eq!(fmt("# ?/8", 2.3), "2 2/8");
+        assert_eq!(fmt("# ?/?", 0.5), " 1/2");
+        assert_eq!(fmt("# ?/?", 2.0), "2    ");
+    }
+
+    #[test]
+    fn datetime_test() {
+        // 2012-11-10 is a Saturday.
+        assert_eq!(fmt("yyyy-mm-dd", 41223.0), "2012-11-10");
+        assert_eq!(fmt("mm-dd-yy", 41223.0), "11-10-12");
+        assert_eq!(fmt("d-mmm-yy", 41223.0), "10-Nov-12");
+        assert_eq!(fmt("dddd, mmmm d", 41223.0), "Saturday, November 10");
+        assert_eq!(fmt("ddd mmmmm", 41223.0), "Sat N");
+        assert_eq!(fmt("h:mm AM/PM", 41223.75), "6:00 PM");
+        assert_eq!(fmt("h:mm:ss A/P", 0.25), "6:00:00 A");
+        assert_eq!(fmt("hh:mm:ss", 0.5 + 1.0 / 86400.0), "12:00:01");
+        assert_eq!(fmt("[h]:mm:ss", 1.5), "36:00:00");
+        assert_eq!(fmt("mm:ss.0", 1.5 / 86400.0), "00:01.5");
+        assert_eq!(fmt("m/d/yy h:mm", 41223.999999), "11/11/12 0:00");
+        let f = NumFormat::new("dddd yyyy-mm-dd").with_date_system(DateSystem::Date1904);
+        assert_eq!(f.format_number(0.0).text, "Friday 1904-01-01");
+        assert_eq!(
+            f.format_number(41223.0 - 1462.0).text,
+            "Saturday 2012-11-10"
+        );
+        assert!(f.has_date());
+        assert!(!NumFormat::new("[h]:mm:ss").has_date());
+        assert_eq!(fmt("yyyy-mm-dd", -1.0), "#");
+    }
+
+    #[test]
+    fn text_test() {
+        let f = NumFormat::new("@\" units\"");
+        assert_eq!(f.format_text("10").text, "10 units");
+        let f = NumFormat::new("0;-0;0;[Green]\"<\"@\">\"");
+        let r = f.format_text("abc");
+        assert_eq!(r.text, "<abc>");
+        assert_eq!(r.color, Some(11));
+        assert_eq!(NumFormat::new("0.00").format_text("abc").text, "abc");
+        assert_eq!(fmt("@", 3.5), "3.5");
+    }
+
+    #[test]
+    fn builtin_test() {
+        use crate::style_manager::num_fmt_manager::get_builtin_num_fmt;
+        let builtin = |id| NumFormat::new(get_builtin_num_fmt(id).unwrap());
+        (0..50).for_each(|id| {
+            assert!(get_builtin_num_fmt(id).is_some());
+        });
+        assert_eq!(builtin(4).format_number(1234.567).text, "1,234.57");
+        assert_eq!(builtin(10).format_number(0.1234).text, "12.34%");
+        assert_eq!(builtin(44).format_number(0.0).text, " $-   ");
+        assert_eq!(builtin(49).format_text("x").text, "x");
+    }
+}
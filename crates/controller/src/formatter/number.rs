@@ -0,0 +1,387 @@
+use super::parser::{Placeholder, Token};
+
+// Render a number section. The value is non-negative and the sign is handled
+// by the caller.
+pub fn render_number(tokens: &[Token], value: f64) -> String {
+    let (tokens, grouping, scale) = analyze_commas(tokens);
+    let percents = tokens
+        .iter()
+        .filter(|t| matches!(t, Token::Percent))
+        .count();
+    let value = value * 100_f64.powi(percents as i32) / 1000_f64.powi(scale as i32);
+    if let Some(idx) = tokens.iter().position(|t| matches!(t, Token::Exponent(_))) {
+        render_scientific(&tokens, idx, value, grouping)
+    } else if let Some(idx) = tokens.iter().position(|t| matches!(t, Token::Slash)) {
+        render_fraction(&tokens, idx, value)
+    } else {
+        render_decimal(&tokens, value, grouping)
+    }
+}
+
+// Excel shows at most 11 characters in the General format.
+pub fn render_general(value: f64) -> String {
+    if value == 0. {
+        return String::from("0");
+    }
+    let abs = value.abs();
+    let sign = if value < 0. { "-" } else { "" };
+    if !(1e-9..1e11).contains(&abs) {
+        let s = format!("{:.5E}", abs);
+        let (mantissa, exp) = s.split_once('E').unwrap();
+        let mantissa = trim_zeros(mantissa);
+        let exp = exp.parse::<i32>().unwrap_or(0);
+        let exp_sign = if exp < 0 { '-' } else { '+' };
+        return format!("{}{}E{}{:02}", sign, mantissa, exp_sign, exp.abs());
+    }
+    let int_len = if abs < 1. {
+        1
+    } else {
+        abs.log10().floor() as usize + 1
+    };
+    let decimals = 10_usize.saturating_sub(int_len);
+    let s = format!("{:.*}", decimals, round_to(abs, decimals as i32));
+    format!("{}{}", sign, trim_zeros(&s))
+}
+
+fn trim_zeros(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+// Round half away from zero. Only 15 significant digits are kept before
+// rounding because a number like 1.005 is stored as 1.00499999999999989...
+pub fn round_to(value: f64, decimals: i32) -> f64 {
+    let significant = |v: f64| format!("{:.14e}", v).parse::<f64>().unwrap_or(v);
+    let factor = 10_f64.powi(decimals);
+    let r = significant(value * factor).round() / factor;
+    if r.is_finite() {
+        r
+    } else {
+        value
+    }
+}
+
+// A comma between the digit placeholders turns on the thousands separator,
+// and a comma after the last digit placeholder scales the number by 1000.
+// The other commas are displayed as they are.
+fn analyze_commas(tokens: &[Token]) -> (Vec<Token>, bool, usize) {
+    let mut grouping = false;
+    let mut scale = 0;
+    let mut result = vec![];
+    tokens.iter().enumerate().for_each(|(i, t)| {
+        if !matches!(t, Token::Comma) {
+            result.push(t.clone());
+            return;
+        }
+        let digit_before = tokens[..i].iter().any(|t| matches!(t, Token::Digit(_)));
+        let digit_after = tokens[i + 1..]
+            .iter()
+            .find(|t| !matches!(t, Token::Comma))
+            .is_some_and(|t| matches!(t, Token::Digit(_)));
+        if digit_before && digit_after {
+            grouping = true;
+        } else if digit_before {
+            scale += 1;
+        } else {
+            result.push(Token::Literal(String::from(",")));
+        }
+    });
+    (result, grouping, scale)
+}
+
+fn render_decimal(tokens: &[Token], value: f64, grouping: bool) -> String {
+    let point = tokens.iter().position(|t| matches!(t, Token::Point));
+    let frac_cnt = match point {
+        Some(p) => tokens[p..]
+            .iter()
+            .filter(|t| matches!(t, Token::Digit(_)))
+            .count(),
+        None => 0,
+    };
+    let s = format!("{:.*}", frac_cnt, round_to(value, frac_cnt as i32));
+    let (int_digits, frac_digits) = match s.split_once('.') {
+        Some((i, f)) => (i.to_string(), f.to_string()),
+        None => (s, String::new()),
+    };
+    let int_digits = if int_digits == "0" {
+        String::new()
+    } else {
+        int_digits
+    };
+    let int_end = point.unwrap_or(tokens.len());
+    let mut int_part = render_integer(&tokens[..int_end], &int_digits, grouping);
+    if point.is_some()
+        && !tokens[..int_end]
+            .iter()
+            .any(|t| matches!(t, Token::Digit(_)))
+    {
+        // `.00` still shows the integer part if it is not zero.
+        int_part.push_str(&int_digits);
+    }
+    match point {
+        Some(p) => {
+            let frac_part = render_fraction_digits(&tokens[p + 1..], &frac_digits);
+            format!("{}.{}", int_part, frac_part)
+        }
+        None => int_part,
+    }
+}
+
+// Put the digits into the placeholders from the right to the left. The digits
+// left are all put into the first placeholder.
+fn render_integer(tokens: &[Token], digits: &str, grouping: bool) -> String {
+    let placeholders = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Digit(p) => Some(*p),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let digits = digits.chars().collect::<Vec<_>>();
+    let mut filled = vec![String::new(); placeholders.len()];
+    let mut rest = digits.len();
+    placeholders.iter().enumerate().rev().for_each(|(i, p)| {
+        if rest > 0 {
+            rest -= 1;
+            filled[i].push(digits[rest]);
+        } else {
+            match p {
+                Placeholder::Zero => filled[i].push('0'),
+                Placeholder::Question => filled[i].push(' '),
+                Placeholder::Hash => {}
+            }
+        }
+    });
+    if let Some(first) = filled.first_mut() {
+        let leading = digits[..rest].iter().collect::<String>();
+        first.insert_str(0, &leading);
+    }
+    if grouping {
+        let joined = filled.concat();
+        let grouped = group_thousands(&joined);
+        filled = vec![String::new(); placeholders.len()];
+        if let Some(first) = filled.first_mut() {
+            *first = grouped;
+        }
+    }
+    let mut filled = filled.into_iter();
+    tokens.iter().fold(String::new(), |mut prev, t| {
+        match t {
+            Token::Digit(_) => prev.push_str(&filled.next().unwrap_or_default()),
+            _ => prev.push_str(&render_literal(t)),
+        }
+        prev
+    })
+}
+
+fn group_thousands(s: &str) -> String {
+    let pad = s.chars().take_while(|c| *c == ' ').count();
+    let digits = s[pad..].chars().collect::<Vec<_>>();
+    let mut result = " ".repeat(pad);
+    digits.iter().enumerate().for_each(|(i, c)| {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(*c);
+    });
+    result
+}
+
+// The trailing zeros are hidden by `#` and replaced with spaces by `?`.
+fn render_fraction_digits(tokens: &[Token], digits: &str) -> String {
+    let placeholders = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Digit(p) => Some(*p),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let digits = digits.chars().collect::<Vec<_>>();
+    let mut filled = placeholders
+        .iter()
+        .enumerate()
+        .map(|(i, _)| digits.get(i).map_or(String::from("0"), |c| c.to_string()))
+        .collect::<Vec<_>>();
+    for i in (0..placeholders.len()).rev() {
+        if filled[i] != "0" {
+            break;
+        }
+        match placeholders[i] {
+            Placeholder::Zero => break,
+            Placeholder::Hash => filled[i] = String::new(),
+            Placeholder::Question => filled[i] = String::from(" "),
+        }
+    }
+    let mut filled = filled.into_iter();
+    tokens.iter().fold(String::new(), |mut prev, t| {
+        match t {
+            Token::Digit(_) => prev.push_str(&filled.next().unwrap_or_default()),
+            _ => prev.push_str(&render_literal(t)),
+        }
+        prev
+    })
+}
+
+fn render_scientific(tokens: &[Token], idx: usize, value: f64, grouping: bool) -> String {
+    let (mantissa_tokens, exp_tokens) = (&tokens[..idx], &tokens[idx + 1..]);
+    let show_plus = matches!(tokens[idx], Token::Exponent(true));
+    let point = mantissa_tokens
+        .iter()
+        .position(|t| matches!(t, Token::Point))
+        .unwrap_or(mantissa_tokens.len());
+    let int_placeholders = mantissa_tokens[..point]
+        .iter()
+        .filter_map(|t| match t {
+            Token::Digit(p) => Some(*p),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let frac_cnt = mantissa_tokens[point..]
+        .iter()
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count() as i32;
+    let int_cnt = int_placeholders.len().max(1) as i32;
+    // `##0.0E+0` shows the exponents in multiples of 3.
+    let engineering = int_cnt > 1 && int_placeholders[0] == Placeholder::Hash;
+    let (mantissa, exp) = if value == 0. {
+        (0., 0)
+    } else {
+        let e = value.log10().floor() as i32;
+        let mut exp = if engineering {
+            e.div_euclid(int_cnt) * int_cnt
+        } else {
+            e - (int_cnt - 1)
+        };
+        let mut mantissa = round_to(value / 10_f64.powi(exp), frac_cnt);
+        if mantissa >= 10_f64.powi(int_cnt) {
+            let step = if engineering { int_cnt } else { 1 };
+            exp += step;
+            mantissa = round_to(value / 10_f64.powi(exp), frac_cnt);
+        }
+        (mantissa, exp)
+    };
+    let mantissa = render_decimal(mantissa_tokens, mantissa, grouping);
+    let sign = if exp < 0 {
+        "-"
+    } else if show_plus {
+        "+"
+    } else {
+        ""
+    };
+    let exp_digits = exp.abs().to_string();
+    let exp_digits = if exp == 0 { "" } else { &exp_digits };
+    let exp = render_integer(exp_tokens, exp_digits, false);
+    let exp = if exp.is_empty() {
+        String::from("0")
+    } else {
+        exp
+    };
+    format!("{}E{}{}", mantissa, sign, exp)
+}
+
+fn render_fraction(tokens: &[Token], slash: usize, value: f64) -> String {
+    let num_start = tokens[..slash]
+        .iter()
+        .rposition(|t| !matches!(t, Token::Digit(_)))
+        .map_or(0, |p| p + 1);
+    let den_end = tokens[slash + 1..]
+        .iter()
+        .position(|t| !matches!(t, Token::Digit(_)))
+        .map_or(tokens.len(), |p| slash + 1 + p);
+    let (den_end, fixed_den) = match tokens.get(slash + 1) {
+        Some(Token::Denominator(d)) => (slash + 2, Some(*d)),
+        _ => (den_end, None),
+    };
+    let mixed = tokens[..num_start]
+        .iter()
+        .any(|t| matches!(t, Token::Digit(_)));
+    let (int, frac) = if mixed {
+        (value.trunc(), value.fract())
+    } else {
+        (0., value)
+    };
+    let den_tokens = &tokens[slash + 1..den_end];
+    let (mut num, den) = match fixed_den {
+        Some(d) => ((frac * d as f64).round() as u64, d as u64),
+        None => {
+            let max_den = 10_u64.pow(den_tokens.len().max(1) as u32) - 1;
+            best_fraction(frac, max_den)
+        }
+    };
+    let mut int = int as u64;
+    if mixed && num == den {
+        int += 1;
+        num = 0;
+    }
+    let int_part = if mixed {
+        let digits = if int == 0 && num == 0 {
+            String::from("0")
+        } else if int == 0 {
+            String::new()
+        } else {
+            int.to_string()
+        };
+        render_integer(&tokens[..num_start], &digits, false)
+    } else {
+        render_integer(&tokens[..num_start], "", false)
+    };
+    let fraction_part = {
+        let num_str = render_integer(&tokens[num_start..slash], &num.to_string(), false);
+        let den_str = match fixed_den {
+            Some(d) => d.to_string(),
+            None => render_denominator(den_tokens, &den.to_string()),
+        };
+        format!("{}/{}", num_str, den_str)
+    };
+    let fraction_part = if mixed && num == 0 {
+        " ".repeat(fraction_part.chars().count())
+    } else {
+        fraction_part
+    };
+    let rest = tokens[den_end..]
+        .iter()
+        .map(render_literal)
+        .collect::<String>();
+    format!("{}{}{}", int_part, fraction_part, rest)
+}
+
+// The denominators are aligned to the left.
+fn render_denominator(tokens: &[Token], digits: &str) -> String {
+    let mut result = digits.to_string();
+    tokens.iter().skip(digits.len()).for_each(|t| match t {
+        Token::Digit(Placeholder::Question) => result.push(' '),
+        Token::Digit(Placeholder::Zero) => result.push('0'),
+        _ => {}
+    });
+    result
+}
+
+// Find the closest fraction whose denominator is not larger than `max_den`.
+fn best_fraction(value: f64, max_den: u64) -> (u64, u64) {
+    let mut best = (value.round() as u64, 1);
+    let mut best_err = (value - value.round()).abs();
+    for den in 2..=max_den {
+        if best_err == 0. {
+            break;
+        }
+        let num = (value * den as f64).round();
+        let err = (value - num / den as f64).abs();
+        if err < best_err {
+            best = (num as u64, den);
+            best_err = err;
+        }
+    }
+    best
+}
+
+pub fn render_literal(t: &Token) -> String {
+    match t {
+        Token::Literal(s) => s.clone(),
+        Token::Skip => String::from(" "),
+        Token::Percent => String::from("%"),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,451 @@
+// Parse the number format codes described in ECMA-376 18.8.31.
+//
+// A format code has at most 4 sections separated by semicolons. Each section
+// can start with a color and a condition in brackets and the rest of it is
+// compiled into a list of tokens.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    // `0`: Show the digit or a zero.
+    Zero,
+    // `#`: Show the digit only if it is significant.
+    Hash,
+    // `?`: Show the digit or a space.
+    Question,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElapsedUnit {
+    Hour,
+    Minute,
+    Second,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Literal(String),
+    // `_x` leaves a space as wide as the character x.
+    Skip,
+    // `*x` repeats x to fill the cell. The width of the cell is not known
+    // here, so it renders nothing.
+    Fill,
+    Text,
+    General,
+    Digit(Placeholder),
+    Point,
+    Comma,
+    Percent,
+    // `E+` or `E-`. The sign of the exponent is always shown for `E+`.
+    Exponent(bool),
+    Slash,
+    Denominator(u32),
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    Elapsed(ElapsedUnit, usize),
+    SubSecond(usize),
+    // `AM/PM` if true, `A/P` otherwise.
+    AmPm(bool),
+}
+
+impl Token {
+    pub fn is_datetime(&self) -> bool {
+        matches!(
+            self,
+            Token::Year(_)
+                | Token::Month(_)
+                | Token::Day(_)
+                | Token::Hour(_)
+                | Token::Minute(_)
+                | Token::Second(_)
+                | Token::Elapsed(_, _)
+                | Token::SubSecond(_)
+                | Token::AmPm(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub op: Comparison,
+    pub value: f64,
+}
+
+impl Condition {
+    pub fn test(&self, v: f64) -> bool {
+        match self.op {
+            Comparison::Eq => v == self.value,
+            Comparison::Ne => v != self.value,
+            Comparison::Lt => v < self.value,
+            Comparison::Le => v <= self.value,
+            Comparison::Gt => v > self.value,
+            Comparison::Ge => v >= self.value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    General,
+    Number,
+    DateTime,
+    Text,
+    // A section with literals only.
+    Literal,
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub kind: SectionKind,
+    // An index of the legacy color palette.
+    pub color: Option<u8>,
+    pub condition: Option<Condition>,
+    pub tokens: Vec<Token>,
+}
+
+pub fn parse_sections(code: &str) -> Vec<Section> {
+    split_sections(code)
+        .into_iter()
+        .map(|s| parse_section(&s))
+        .collect()
+}
+
+// Split the code by the semicolons which are not quoted or escaped.
+fn split_sections(code: &str) -> Vec<String> {
+    let mut result = vec![];
+    let mut curr = String::new();
+    let mut chars = code.chars();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                curr.push(c);
+            }
+            '\\' if !quoted => {
+                curr.push(c);
+                if let Some(next) = chars.next() {
+                    curr.push(next);
+                }
+            }
+            ';' if !quoted => result.push(std::mem::take(&mut curr)),
+            _ => curr.push(c),
+        }
+    }
+    result.push(curr);
+    result
+}
+
+fn parse_section(s: &str) -> Section {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut color = None;
+    let mut condition = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .map_or(chars.len(), |p| i + 1 + p);
+                push_literal(&mut tokens, &chars[i + 1..end].iter().collect::<String>());
+                i = end + 1;
+            }
+            '\\' => {
+                if let Some(next) = chars.get(i + 1) {
+                    push_literal(&mut tokens, &next.to_string());
+                }
+                i += 2;
+            }
+            '_' => {
+                tokens.push(Token::Skip);
+                i += 2;
+            }
+            '*' => {
+                tokens.push(Token::Fill);
+                i += 2;
+            }
+            '[' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map_or(chars.len(), |p| i + 1 + p);
+                let content = chars[i + 1..end].iter().collect::<String>();
+                parse_bracket(&content, &mut tokens, &mut color, &mut condition);
+                i = end + 1;
+            }
+            '0' => {
+                tokens.push(Token::Digit(Placeholder::Zero));
+                i += 1;
+            }
+            '#' => {
+                tokens.push(Token::Digit(Placeholder::Hash));
+                i += 1;
+            }
+            '?' => {
+                tokens.push(Token::Digit(Placeholder::Question));
+                i += 1;
+            }
+            '.' => {
+                tokens.push(Token::Point);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '%' => {
+                tokens.push(Token::Percent);
+                i += 1;
+            }
+            '@' => {
+                tokens.push(Token::Text);
+                i += 1;
+            }
+            '/' => {
+                let digits = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>();
+                tokens.push(Token::Slash);
+                // A fixed denominator like `# ?/8`.
+                match digits.starts_with('0') {
+                    false if !digits.is_empty() => {
+                        tokens.push(Token::Denominator(digits.parse().unwrap_or(1)));
+                        i += digits.len();
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            'E' | 'e' if matches!(chars.get(i + 1), Some('+') | Some('-')) => {
+                tokens.push(Token::Exponent(chars[i + 1] == '+'));
+                i += 2;
+            }
+            'G' | 'g' if starts_with_ignore_case(&chars[i..], "general") => {
+                tokens.push(Token::General);
+                i += 7;
+            }
+            'A' | 'a' if starts_with_ignore_case(&chars[i..], "am/pm") => {
+                tokens.push(Token::AmPm(true));
+                i += 5;
+            }
+            'A' | 'a' if starts_with_ignore_case(&chars[i..], "a/p") => {
+                tokens.push(Token::AmPm(false));
+                i += 3;
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                let lower = c.to_ascii_lowercase();
+                let cnt = chars[i..]
+                    .iter()
+                    .take_while(|c| c.to_ascii_lowercase() == lower)
+                    .count();
+                let token = match lower {
+                    'y' => Token::Year(cnt),
+                    'm' => Token::Month(cnt),
+                    'd' => Token::Day(cnt),
+                    'h' => Token::Hour(cnt),
+                    _ => Token::Second(cnt),
+                };
+                tokens.push(token);
+                i += cnt;
+            }
+            _ => {
+                push_literal(&mut tokens, &c.to_string());
+                i += 1;
+            }
+        }
+    }
+    let kind = get_kind(&tokens);
+    if kind == SectionKind::DateTime {
+        tokens = resolve_datetime_tokens(tokens);
+    }
+    Section {
+        kind,
+        color,
+        condition,
+        tokens,
+    }
+}
+
+fn starts_with_ignore_case(chars: &[char], s: &str) -> bool {
+    chars.len() >= s.len()
+        && chars
+            .iter()
+            .zip(s.chars())
+            .all(|(a, b)| a.to_ascii_lowercase() == b)
+}
+
+fn push_literal(tokens: &mut Vec<Token>, s: &str) {
+    if let Some(Token::Literal(last)) = tokens.last_mut() {
+        last.push_str(s);
+    } else {
+        tokens.push(Token::Literal(s.to_string()));
+    }
+}
+
+fn parse_bracket(
+    content: &str,
+    tokens: &mut Vec<Token>,
+    color: &mut Option<u8>,
+    condition: &mut Option<Condition>,
+) {
+    let lower = content.to_ascii_lowercase();
+    if let Some(c) = get_named_color(&lower) {
+        *color = Some(c);
+        return;
+    }
+    if let Some(n) = lower.strip_prefix("color") {
+        if let Ok(n) = n.parse::<u8>() {
+            if (1..=56).contains(&n) {
+                // [Color1] is the 8th color in the palette.
+                *color = Some(n + 7);
+            }
+        }
+        return;
+    }
+    if let Some(c) = parse_condition(content) {
+        *condition = Some(c);
+        return;
+    }
+    if let Some(currency) = content.strip_prefix('$') {
+        // [$€-407]: a currency symbol followed by a locale id.
+        let symbol = currency.split('-').next().unwrap_or("");
+        if !symbol.is_empty() {
+            push_literal(tokens, symbol);
+        }
+        return;
+    }
+    let unit = match lower.chars().next() {
+        Some('h') => ElapsedUnit::Hour,
+        Some('m') => ElapsedUnit::Minute,
+        Some('s') => ElapsedUnit::Second,
+        _ => return,
+    };
+    let first = lower.chars().next().unwrap();
+    if lower.chars().all(|c| c == first) {
+        tokens.push(Token::Elapsed(unit, lower.len()));
+    }
+}
+
+fn get_named_color(s: &str) -> Option<u8> {
+    let idx = match s {
+        "black" => 8,
+        "white" => 9,
+        "red" => 10,
+        "green" => 11,
+        "blue" => 12,
+        "yellow" => 13,
+        "magenta" => 14,
+        "cyan" => 15,
+        _ => return None,
+    };
+    Some(idx)
+}
+
+fn parse_condition(s: &str) -> Option<Condition> {
+    let (op, rest) = if let Some(r) = s.strip_prefix("<=") {
+        (Comparison::Le, r)
+    } else if let Some(r) = s.strip_prefix(">=") {
+        (Comparison::Ge, r)
+    } else if let Some(r) = s.strip_prefix("<>") {
+        (Comparison::Ne, r)
+    } else if let Some(r) = s.strip_prefix('<') {
+        (Comparison::Lt, r)
+    } else if let Some(r) = s.strip_prefix('>') {
+        (Comparison::Gt, r)
+    } else if let Some(r) = s.strip_prefix('=') {
+        (Comparison::Eq, r)
+    } else {
+        return None;
+    };
+    let value = rest.trim().parse::<f64>().ok()?;
+    Some(Condition { op, value })
+}
+
+fn get_kind(tokens: &[Token]) -> SectionKind {
+    if tokens.iter().any(|t| t.is_datetime()) {
+        SectionKind::DateTime
+    } else if tokens.iter().any(|t| matches!(t, Token::General)) {
+        SectionKind::General
+    } else if tokens.iter().any(|t| matches!(t, Token::Digit(_))) {
+        SectionKind::Number
+    } else if tokens.iter().any(|t| matches!(t, Token::Text)) {
+        SectionKind::Text
+    } else {
+        SectionKind::Literal
+    }
+}
+
+// In a date or time section, `m` means minutes if it follows hours or comes
+// before seconds, and `.0` after the seconds means the fractions of a second.
+// The other number tokens are displayed as they are.
+fn resolve_datetime_tokens(tokens: Vec<Token>) -> Vec<Token> {
+    let mut tokens = tokens;
+    let datetime_indices = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_datetime())
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    datetime_indices.iter().enumerate().for_each(|(pos, idx)| {
+        if let Token::Month(cnt) = tokens[*idx] {
+            let after_hour = pos > 0
+                && matches!(
+                    tokens[datetime_indices[pos - 1]],
+                    Token::Hour(_) | Token::Elapsed(ElapsedUnit::Hour, _)
+                );
+            let before_second = datetime_indices.get(pos + 1).is_some_and(|i| {
+                matches!(
+                    tokens[*i],
+                    Token::Second(_) | Token::Elapsed(ElapsedUnit::Second, _)
+                )
+            });
+            if cnt <= 2 && (after_hour || before_second) {
+                tokens[*idx] = Token::Minute(cnt);
+            }
+        }
+    });
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(t) = iter.next() {
+        match t {
+            Token::Point if matches!(iter.peek(), Some(Token::Digit(Placeholder::Zero))) => {
+                let mut cnt = 0;
+                while let Some(Token::Digit(Placeholder::Zero)) = iter.peek() {
+                    iter.next();
+                    cnt += 1;
+                }
+                result.push(Token::SubSecond(cnt));
+            }
+            Token::Point => push_literal(&mut result, "."),
+            Token::Comma => push_literal(&mut result, ","),
+            Token::Percent => push_literal(&mut result, "%"),
+            Token::Slash => push_literal(&mut result, "/"),
+            Token::Denominator(n) => push_literal(&mut result, &n.to_string()),
+            Token::Digit(Placeholder::Zero) => push_literal(&mut result, "0"),
+            Token::Digit(Placeholder::Hash) => push_literal(&mut result, "#"),
+            Token::Digit(Placeholder::Question) => push_literal(&mut result, "?"),
+            Token::Exponent(true) => push_literal(&mut result, "E+"),
+            Token::Exponent(false) => push_literal(&mut result, "E-"),
+            Token::Literal(s) => push_literal(&mut result, &s),
+            _ => result.push(t),
+        }
+    }
+    result
+}
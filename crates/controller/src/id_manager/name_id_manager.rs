@@ -1,10 +1,15 @@
 use im::hashmap::HashMap;
-use logisheets_base::{ExtBookId, NameId};
+use im::hashset::HashSet;
+use logisheets_base::{ExtBookId, NameId, SheetId, CURR_BOOK};
 
 #[derive(Debug, Clone)]
 pub struct NameIdManager {
     pub next_available: NameId,
     pub ids: HashMap<(ExtBookId, String), NameId>,
+    // Names defined in the scope of a sheet. They share the id space with
+    // the workbook names above.
+    pub local_ids: HashMap<(SheetId, String), NameId>,
+    pub hiddens: HashSet<NameId>,
 }
 
 impl NameIdManager {
@@ -12,6 +17,8 @@ impl NameIdManager {
         NameIdManager {
             next_available: start,
             ids: HashMap::new(),
+            local_ids: HashMap::new(),
+            hiddens: HashSet::new(),
         }
     }
 
@@ -29,10 +36,111 @@ impl NameIdManager {
         }
     }
 
+    pub fn get_local_id(&mut self, sheet_id: SheetId, name: &str) -> NameId {
+        let key = (sheet_id, name.to_owned());
+        match self.local_ids.get(&key) {
+            Some(r) => *r,
+            None => {
+                let r = self.next_available;
+                self.local_ids.insert(key, r);
+                self.next_available += 1;
+                r
+            }
+        }
+    }
+
+    pub fn has_local(&self, sheet_id: SheetId, name: &str) -> Option<NameId> {
+        self.local_ids.get(&(sheet_id, name.to_owned())).cloned()
+    }
+
+    // Remove the names in the scope of the sheet and return their ids.
+    pub fn remove_local_names(&mut self, sheet_id: SheetId) -> Vec<NameId> {
+        let keys = self
+            .local_ids
+            .keys()
+            .filter(|(s, _)| *s == sheet_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.into_iter()
+            .filter_map(|k| self.local_ids.remove(&k))
+            .collect()
+    }
+
+    // Register the names in the scope of `from` in the scope of `to`, and
+    // return the pairs of the old and new ids. Hidden names stay hidden.
+    pub fn copy_local_names(&mut self, from: SheetId, to: SheetId) -> Vec<(NameId, NameId)> {
+        let names = self
+            .local_ids
+            .iter()
+            .filter(|((s, _), _)| *s == from)
+            .map(|((_, name), nid)| (name.clone(), *nid))
+            .collect::<Vec<_>>();
+        names
+            .into_iter()
+            .map(|(name, old)| {
+                let new = self.get_local_id(to, &name);
+                if self.hiddens.contains(&old) {
+                    self.hiddens.insert(new);
+                }
+                (old, new)
+            })
+            .collect()
+    }
+
+    // Find the name defined in the given scope.
+    pub fn find(&self, scope: Option<SheetId>, name: &str) -> Option<NameId> {
+        match scope {
+            Some(sheet_id) => self.has_local(sheet_id, name),
+            None => self.ids.get(&(CURR_BOOK, name.to_owned())).cloned(),
+        }
+    }
+
+    // Get the id of a name in the given scope, registering it if necessary.
+    pub fn get_scoped_id(&mut self, scope: Option<SheetId>, name: &str) -> NameId {
+        match scope {
+            Some(sheet_id) => self.get_local_id(sheet_id, name),
+            None => self.get_id(&(CURR_BOOK, name.to_owned())),
+        }
+    }
+
+    // Give the name a new string and keep its id and scope, so that the
+    // formulas referring to it do not need to change.
+    pub fn rename(&mut self, name_id: NameId, new_name: String) {
+        let global_key = self
+            .ids
+            .iter()
+            .find(|&(_, v)| *v == name_id)
+            .map(|(k, _)| k.clone());
+        if let Some((book, old_name)) = global_key {
+            self.ids.remove(&(book, old_name));
+            self.ids.insert((book, new_name), name_id);
+            return;
+        }
+        let local_key = self
+            .local_ids
+            .iter()
+            .find(|&(_, v)| *v == name_id)
+            .map(|(k, _)| k.clone());
+        if let Some((sheet_id, old_name)) = local_key {
+            self.local_ids.remove(&(sheet_id, old_name));
+            self.local_ids.insert((sheet_id, new_name), name_id);
+        }
+    }
+
     pub fn get_string(&self, key: &NameId) -> Option<(ExtBookId, String)> {
-        match self.ids.iter().find(|&(_, v)| v == key) {
-            Some(r) => Some(r.0.clone()),
-            None => None,
+        if let Some(r) = self.ids.iter().find(|&(_, v)| v == key) {
+            return Some(r.0.clone());
         }
+        self.local_ids
+            .iter()
+            .find(|&(_, v)| v == key)
+            .map(|((_, name), _)| (CURR_BOOK, name.clone()))
+    }
+
+    pub fn get_scope(&self, key: &NameId) -> Option<SheetId> {
+        self.local_ids
+            .iter()
+            .find(|&(_, v)| v == key)
+            .map(|((sheet_id, _), _)| *sheet_id)
     }
 }
@@ -13,28 +13,37 @@ mod async_func_manager;
 mod calc_engine;
 mod cell;
 mod cell_attachments;
+mod cond_format_manager;
 mod connectors;
 mod container;
 pub mod controller;
 mod data_executor;
+mod diagnostics;
 mod ext_book_manager;
 mod file_loader2;
+mod file_saver;
+mod formatter;
 mod id_manager;
 mod navigator;
 mod payloads;
 mod settings;
 mod style_manager;
+#[cfg(test)]
+mod test_utils;
 mod theme_manager;
 mod vertex_manager;
 mod workbook;
 
 use connectors::NameFetcher;
+use controller::edit_action::{ActionEffect, EditAction};
 use controller::style::StyleConverter;
 pub use controller::{
-    display::{Comment, MergeCell, Value},
+    display::{Comment, CommentReply, MergeCell, Value},
     style::{Border, BorderPr, Fill, Font, Style},
     Controller,
 };
+pub use diagnostics::{diagnose_formula, DiagnosticKind, FormulaDiagnostic};
+pub use logisheets_astchecker::{get_all_func_signatures, FuncSignature};
 use logisheets_parser::unparse;
 pub use logisheets_workbook::prelude::SerdeErr;
 
@@ -60,11 +69,17 @@ pub enum Err {
     NotFound,
 }
 
+// The default one has an empty sheet.
+#[derive(Default)]
 pub struct Workbook {
     controller: Controller,
 }
 
 impl Workbook {
+    pub fn handle_action(&mut self, action: EditAction) -> Option<ActionEffect> {
+        self.controller.handle_action(action)
+    }
+
     pub fn from_file(buf: &[u8], book_name: String) -> Result<Self, Err> {
         match Controller::from_file(book_name, buf) {
             Ok(controller) => Ok(Workbook { controller }),
@@ -302,11 +317,20 @@ impl<'a> Worksheet<'a> {
                             .get_author_name(&author_id)
                             .unwrap();
                         let content = c.text.to_string();
+                        let comments = &self.controller.status.cell_attachment_manager.comments;
+                        let replies = comments
+                            .get_replies(&self.sheet_id, id)
+                            .into_iter()
+                            .map(|(author, content)| CommentReply { author, content })
+                            .collect();
+                        let resolved = comments.is_resolved(&self.sheet_id, id);
                         let comment = Comment {
                             row,
                             col,
                             author,
                             content,
+                            replies,
+                            resolved,
                         };
                         prev.push(comment);
                         prev
@@ -13,6 +13,13 @@ mod fetcher;
 mod id_manager;
 mod sheet_nav;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockError {
+    IdExists,
+    // The id of the block overlapped.
+    Overlap(BlockId),
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Navigator {
     pub sheet_navs: HashMap<SheetId, SheetNav>,
@@ -83,18 +90,46 @@ impl Navigator {
         fetcher.get_norm_cell_idx(cell_id)
     }
 
+    // A block can not take the id of another block in the sheet or overlap
+    // it. Nothing is changed then.
     pub fn create_block(
         &mut self,
         sheet_id: SheetId,
+        block_id: BlockId,
         master: NormalCellId,
         row_cnt: usize,
         col_cnt: usize,
-    ) {
+    ) -> Result<(), BlockError> {
+        if self.get_block_place(sheet_id, block_id).is_some() {
+            return Err(BlockError::IdExists);
+        }
+        if let Some((row, col)) = self.fetch_normal_cell_idx(sheet_id, &master) {
+            let end_row = row + row_cnt.max(1) - 1;
+            let end_col = col + col_cnt.max(1) - 1;
+            let others = self
+                .get_sheet_nav(sheet_id)
+                .data
+                .blocks
+                .iter()
+                .map(|(id, bp)| (*id, bp.master, bp.get_block_size()))
+                .collect::<Vec<_>>();
+            for (id, other_master, (rows, cols)) in others {
+                let (r, c) = match self.fetch_normal_cell_idx(sheet_id, &other_master) {
+                    Some(idx) => idx,
+                    None => continue,
+                };
+                if intersect(row, end_row, r, r + rows.max(1) - 1)
+                    && intersect(col, end_col, c, c + cols.max(1) - 1)
+                {
+                    return Err(BlockError::Overlap(id));
+                }
+            }
+        }
         let sheet_nav = self.get_sheet_nav(sheet_id);
         let block_place = BlockPlace::new(master, row_cnt as u32, col_cnt as u32);
-        let block_id = sheet_nav.id_manager.get_block_id();
         sheet_nav.data.blocks.insert(block_id, block_place);
         sheet_nav.cache = Cache::default();
+        Ok(())
     }
 
     pub fn remove_block(&mut self, sheet_id: SheetId, block_id: BlockId) {
@@ -18,7 +18,7 @@ impl SheetNav {
     pub fn init(row_max: u32, col_max: u32) -> Self {
         SheetNav {
             data: Data::init(row_max, col_max),
-            id_manager: IdManager::new(row_max, col_max, 0),
+            id_manager: IdManager::new(row_max, col_max),
             cache: Cache::default(),
             version: 1,
         }
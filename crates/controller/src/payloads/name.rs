@@ -1,3 +1,5 @@
+use logisheets_base::SheetId;
+
 #[derive(Debug, Clone)]
 pub enum NamePayload {
     Rename(RenameName),
@@ -5,19 +7,23 @@ pub enum NamePayload {
     Add(AddName),
 }
 
+// `scope` is None if the name is defined in the workbook scope.
 #[derive(Debug, Clone)]
 pub struct AddName {
     pub name: String,
+    pub scope: Option<SheetId>,
     pub refer_to: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct RemoveName {
     pub name: String,
+    pub scope: Option<SheetId>,
 }
 
 #[derive(Debug, Clone)]
 pub struct RenameName {
     pub old_name: String,
     pub new_name: String,
+    pub scope: Option<SheetId>,
 }
@@ -0,0 +1,47 @@
+// Comments are attached to the cells. `author` is the display name of the
+// author and the new authors are registered automatically.
+#[derive(Debug, Clone)]
+pub enum CommentPayload {
+    Add(AddCommentPayload),
+    Update(UpdateCommentPayload),
+    Delete(DeleteCommentPayload),
+    Reply(ReplyCommentPayload),
+    Resolve(ResolveCommentPayload),
+}
+
+#[derive(Debug, Clone)]
+pub struct AddCommentPayload {
+    pub row: usize,
+    pub col: usize,
+    pub author: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateCommentPayload {
+    pub row: usize,
+    pub col: usize,
+    pub author: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeleteCommentPayload {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplyCommentPayload {
+    pub row: usize,
+    pub col: usize,
+    pub author: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolveCommentPayload {
+    pub row: usize,
+    pub col: usize,
+    pub resolved: bool,
+}
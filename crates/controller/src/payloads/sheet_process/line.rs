@@ -1,8 +1,10 @@
 use super::style::CellStylePayload;
 
+// The change is applied to `cnt` lines starting at `idx`.
 #[derive(Debug, Clone)]
 pub struct LinePayload {
     pub idx: usize,
+    pub cnt: usize,
     pub change: LineInfoUpdate,
 }
 
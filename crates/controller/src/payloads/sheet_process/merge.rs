@@ -0,0 +1,20 @@
+#[derive(Debug, Clone)]
+pub enum MergePayload {
+    Merge(MergeCellsPayload),
+    Split(SplitMergedCellsPayload),
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeCellsPayload {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+}
+
+// Split the merged cells covering this cell.
+#[derive(Debug, Clone)]
+pub struct SplitMergedCellsPayload {
+    pub row: usize,
+    pub col: usize,
+}
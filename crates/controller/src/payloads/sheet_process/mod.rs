@@ -2,7 +2,9 @@ use logisheets_base::SheetId;
 
 pub mod block;
 pub mod cell;
+pub mod comment;
 pub mod line;
+pub mod merge;
 pub mod property;
 pub mod shift;
 pub mod style;
@@ -25,6 +27,15 @@ pub type BlockInsertColsPayload = block::InsertColsPayload;
 pub type BlockInsertRowsPayload = block::InsertRowsPayload;
 pub type BlockDeleteColsPayload = block::DeleteColsPayload;
 pub type BlockDeleteRowsPayload = block::DeleteRowsPayload;
+pub type MergePayload = merge::MergePayload;
+pub type MergeCellsPayload = merge::MergeCellsPayload;
+pub type SplitMergedCellsPayload = merge::SplitMergedCellsPayload;
+pub type CommentPayload = comment::CommentPayload;
+pub type AddCommentPayload = comment::AddCommentPayload;
+pub type UpdateCommentPayload = comment::UpdateCommentPayload;
+pub type DeleteCommentPayload = comment::DeleteCommentPayload;
+pub type ReplyCommentPayload = comment::ReplyCommentPayload;
+pub type ResolveCommentPayload = comment::ResolveCommentPayload;
 
 #[derive(Debug, Clone)]
 pub struct SheetProcess {
@@ -40,6 +51,8 @@ pub enum SheetPayload {
     Line(LinePayload),
     Property(PropertyPayload),
     Block(BlockPayload),
+    Merge(MergePayload),
+    Comment(CommentPayload),
 }
 
 #[derive(Debug, Clone)]
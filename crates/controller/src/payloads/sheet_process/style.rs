@@ -51,4 +51,5 @@ pub enum CellStylePayload {
     Font(FontPayloadType),
     Border(BorderPayloadType),
     Fill(FillPayloadType),
+    NumFmt(String),
 }
@@ -8,6 +8,10 @@ pub struct SheetShiftPayload {
 pub enum SheetShiftType {
     Insert,
     Delete,
+    // Move the sheet to the given index.
+    Move(usize),
+    // Duplicate the sheet and put the copy at the given index.
+    Copy(usize),
 }
 
 #[derive(Debug, Clone)]
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use logisheets_base::SheetId;
+use logisheets_base::{datetime::DateSystem, input::Locale, SheetId};
 use logisheets_workbook::prelude::CtSheetFormatPr;
 
 use crate::theme_manager::ThemeManager;
@@ -10,6 +10,9 @@ pub struct Settings {
     pub calc_config: CalcConfig,
     pub async_funcs: HashSet<String>, // function names in upper case.
     pub theme: ThemeManager,
+    pub date_system: DateSystem,
+    // Used to recognize the numbers and dates in the user inputs.
+    pub locale: Locale,
 }
 
 impl Default for Settings {
@@ -22,12 +25,18 @@ impl Default for Settings {
             calc_config,
             async_funcs: afuncs.into_iter().collect(),
             theme: ThemeManager::default(),
+            date_system: DateSystem::default(),
+            locale: Locale::default(),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct CalcConfig {
+    pub mode: CalcMode,
+    // Calculate the circular references iteratively. Otherwise the cells in
+    // cycles get 0.
+    pub iterative: bool,
     pub iter_limit: u16,
     pub error: f32,
 }
@@ -35,8 +44,20 @@ pub struct CalcConfig {
 impl Default for CalcConfig {
     fn default() -> Self {
         CalcConfig {
+            mode: CalcMode::Auto,
+            iterative: false,
             iter_limit: 1000,
             error: 0.01,
         }
     }
 }
+
+// Data tables are not supported yet so that `AutoNoTable` works just like
+// `Auto`. In `Manual` mode, nothing is calculated until a recalculation is
+// requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcMode {
+    Auto,
+    AutoNoTable,
+    Manual,
+}
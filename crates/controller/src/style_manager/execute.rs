@@ -16,7 +16,8 @@ pub fn execute_style_payload(
         mut cell_xfs_manager,
         cell_style_xfs_manager,
         mut fill_manager,
-        num_fmt_manager,
+        mut num_fmt_manager,
+        dxfs,
     } = sm;
     let mut xf = cell_xfs_manager.get_data(id)?.clone();
     match payload {
@@ -50,6 +51,10 @@ pub fn execute_style_payload(
             xf.apply_fill = Some(true);
             fill_manager = new_manager;
         }
+        CellStylePayload::NumFmt(code) => {
+            xf.num_fmt_id = Some(num_fmt_manager.get_id(code));
+            xf.apply_number_format = Some(true);
+        }
     };
     let new_id = cell_xfs_manager.get_id(&xf);
     let manager = StyleManager {
@@ -59,6 +64,7 @@ pub fn execute_style_payload(
         cell_style_xfs_manager,
         fill_manager,
         num_fmt_manager,
+        dxfs,
     };
     Some((manager, new_id))
 }
@@ -38,6 +38,17 @@ where
         self.id_to_data.get(&id)
     }
 
+    // Get all the data ordered by their ids.
+    pub fn get_all_data(&self) -> Vec<&T> {
+        let cnt: usize = NumCast::from(self.next_available).unwrap_or(0);
+        (0..cnt)
+            .filter_map(|idx| {
+                let id: I = NumCast::from(idx)?;
+                self.id_to_data.get(&id)
+            })
+            .collect()
+    }
+
     fn registry(&mut self, data: T) -> I {
         let r = self.next_available;
         self.data_to_id.insert(data.clone(), self.next_available);
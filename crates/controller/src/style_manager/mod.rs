@@ -7,7 +7,7 @@ mod execute;
 mod fill_manager;
 mod font_manager;
 mod manager;
-mod num_fmt_manager;
+pub mod num_fmt_manager;
 pub mod xf_manager;
 
 use border_manager::BorderManager;
@@ -18,7 +18,9 @@ use num_fmt_manager::NumFmtManager;
 use xf_manager::XfManager;
 
 use crate::payloads::sheet_process::style::CellStylePayload;
-use logisheets_workbook::prelude::{CtBorder, CtCellAlignment, CtCellProtection, CtFill, CtFont};
+use logisheets_workbook::prelude::{
+    CtBorder, CtCellAlignment, CtCellProtection, CtDxf, CtFill, CtFont,
+};
 
 use self::execute::execute_style_payload;
 
@@ -39,6 +41,8 @@ pub struct StyleManager {
     pub cell_xfs_manager: XfManager,
     pub cell_style_xfs_manager: XfManager,
     pub num_fmt_manager: NumFmtManager,
+    // The differential formats used by the conditional formats.
+    pub dxfs: Vec<CtDxf>,
 }
 
 impl StyleManager {
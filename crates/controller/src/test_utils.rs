@@ -0,0 +1,51 @@
+use crate::controller::edit_action::{
+    ActionEffect, CellInput, EditAction, EditPayload, PayloadsAction,
+};
+use crate::style_manager::xf_manager::CtrlXf;
+use crate::{Value, Workbook};
+
+pub fn edit(wb: &mut Workbook, payloads: Vec<EditPayload>) -> ActionEffect {
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads,
+        undoable: true,
+    }))
+    .unwrap()
+}
+
+pub fn input(sheet_idx: usize, row: usize, col: usize, content: &str) -> EditPayload {
+    EditPayload::CellInput(CellInput {
+        sheet_idx,
+        row,
+        col,
+        content: content.to_string(),
+    })
+}
+
+pub fn value(wb: &mut Workbook, sheet_idx: usize, row: usize, col: usize) -> String {
+    match wb
+        .get_sheet_by_idx(sheet_idx)
+        .unwrap()
+        .get_value(row, col)
+        .unwrap()
+    {
+        Value::Number(n) => n.to_string(),
+        Value::Str(s) => s,
+        Value::Bool(b) => b.to_string().to_uppercase(),
+        Value::Error(e) => e,
+        Value::Empty => String::new(),
+    }
+}
+
+pub fn set_num_fmt(wb: &mut Workbook, sheet_idx: usize, row: usize, col: usize, code: &str) {
+    let controller = &mut wb.controller;
+    let style_manager = &mut controller.status.style_manager;
+    let num_fmt_id = style_manager.num_fmt_manager.get_id(&code.to_string());
+    let style_id = style_manager.cell_xfs_manager.get_id(&CtrlXf {
+        num_fmt_id: Some(num_fmt_id),
+        ..CtrlXf::init()
+    });
+    let sheet_id = controller.get_sheet_id_by_idx(sheet_idx).unwrap();
+    let status = &mut controller.status;
+    let cell_id = status.navigator.fetch_cell_id(sheet_id, row, col).unwrap();
+    status.container.get_cell(sheet_id, &cell_id).unwrap().style = style_id;
+}
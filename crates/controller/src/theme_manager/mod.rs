@@ -10,6 +10,10 @@ impl ThemeManager {
         ThemeManager { theme: Some(part) }
     }
 
+    pub fn get_theme_part(&self) -> Option<&ThemePart> {
+        self.theme.as_ref()
+    }
+
     pub fn get_color(&self, idx: u32) -> String {
         if self.theme.is_none() {
             return String::from("");
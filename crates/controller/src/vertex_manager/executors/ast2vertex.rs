@@ -1,9 +1,10 @@
 use im::HashSet;
 
 use crate::vertex_manager::vertex::{
-    MutAddrRange, MutColRange, MutReferenceVertex, MutRowRange, SheetRangeVertex, Vertex,
+    MutAddrRange, MutColRange, MutReferenceVertex, MutRowRange, SheetRangeVertex, StsRangeVertex,
+    UnMutRefVertex, Vertex,
 };
-use logisheets_base::{CellId, SheetId};
+use logisheets_base::{CellId, NameId, SheetId};
 use logisheets_parser::ast::{self, MutRefWithPrefix};
 
 pub fn find_vertices(node: &ast::Node) -> HashSet<Vertex> {
@@ -15,7 +16,23 @@ pub fn find_vertices(node: &ast::Node) -> HashSet<Vertex> {
         ast::PureNode::Value(_) => HashSet::new(),
         ast::PureNode::Reference(cr) => match cr {
             ast::CellReference::Mut(mut_ref) => find_vertices_from_mut_ref(mut_ref),
-            ast::CellReference::UnMut(_) => todo!(),
+            ast::CellReference::UnMut(unmut_ref) => {
+                let mut res = HashSet::new();
+                match &unmut_ref.prefix {
+                    ast::UnMutRefPrefix::Local(ast::LocalUnMutRefPrefix::SheetToSheet(sts)) => {
+                        let v = StsRangeVertex {
+                            start: sts.from_sheet,
+                            end: sts.to_sheet,
+                            reference: UnMutRefVertex::from_unmut_ref(unmut_ref.reference.clone()),
+                        };
+                        res.insert(Vertex::StsRange(v));
+                    }
+                    ast::UnMutRefPrefix::External(_) => {
+                        res.insert(Vertex::External);
+                    }
+                }
+                res
+            }
             ast::CellReference::Name(nid) => {
                 let mut res = HashSet::new();
                 res.insert(Vertex::Name(*nid));
@@ -50,6 +67,105 @@ pub fn delete_sheet_range_in_ast(node: ast::Node, srv: &SheetRangeVertex) -> ast
     }
 }
 
+pub fn replace_name_in_ast(node: ast::Node, old: NameId, new: NameId) -> ast::Node {
+    let pure = match node.pure {
+        ast::PureNode::Func(func) => {
+            let args = func
+                .args
+                .into_iter()
+                .map(|n| replace_name_in_ast(n, old, new))
+                .collect::<Vec<_>>();
+            ast::PureNode::Func(ast::Func { op: func.op, args })
+        }
+        ast::PureNode::Reference(ast::CellReference::Name(nid)) if nid == old => {
+            ast::PureNode::Reference(ast::CellReference::Name(new))
+        }
+        _ => node.pure,
+    };
+    ast::Node {
+        pure,
+        bracket: node.bracket,
+    }
+}
+
+// Whether the node refers to any cell of the sheet, including the 3D
+// references starting or ending at it, or to any of the names in its scope.
+pub fn refers_to_sheet(node: &ast::Node, sheet_id: SheetId, names: &[NameId]) -> bool {
+    match &node.pure {
+        ast::PureNode::Func(func) => func
+            .args
+            .iter()
+            .any(|n| refers_to_sheet(n, sheet_id, names)),
+        ast::PureNode::Value(_) => false,
+        ast::PureNode::Reference(cr) => match cr {
+            ast::CellReference::Mut(mr) => mr.sheet_id == sheet_id,
+            ast::CellReference::UnMut(ur) => match &ur.prefix {
+                ast::UnMutRefPrefix::Local(ast::LocalUnMutRefPrefix::SheetToSheet(sts)) => {
+                    sts.from_sheet == sheet_id || sts.to_sheet == sheet_id
+                }
+                ast::UnMutRefPrefix::External(_) => false,
+            },
+            ast::CellReference::Name(nid) => names.contains(nid),
+        },
+    }
+}
+
+pub fn delete_sheet_in_ast(node: ast::Node, sheet_id: SheetId, names: &[NameId]) -> ast::Node {
+    map_reference_in_ast(node, &|cr| {
+        let node = ast::Node {
+            pure: ast::PureNode::Reference(cr),
+            bracket: false,
+        };
+        if refers_to_sheet(&node, sheet_id, names) {
+            ast::PureNode::Value(ast::Value::Error(ast::Error::Ref))
+        } else {
+            node.pure
+        }
+    })
+}
+
+pub fn replace_sheet_in_ast(node: ast::Node, old: SheetId, new: SheetId) -> ast::Node {
+    let replace = |s: SheetId| if s == old { new } else { s };
+    map_reference_in_ast(node, &|cr| match cr {
+        ast::CellReference::Mut(mut mr) => {
+            mr.sheet_id = replace(mr.sheet_id);
+            ast::PureNode::Reference(ast::CellReference::Mut(mr))
+        }
+        ast::CellReference::UnMut(mut ur) => {
+            if let ast::UnMutRefPrefix::Local(ast::LocalUnMutRefPrefix::SheetToSheet(sts)) =
+                &mut ur.prefix
+            {
+                sts.from_sheet = replace(sts.from_sheet);
+                sts.to_sheet = replace(sts.to_sheet);
+            }
+            ast::PureNode::Reference(ast::CellReference::UnMut(ur))
+        }
+        ast::CellReference::Name(_) => ast::PureNode::Reference(cr),
+    })
+}
+
+fn map_reference_in_ast<F>(node: ast::Node, f: &F) -> ast::Node
+where
+    F: Fn(ast::CellReference) -> ast::PureNode,
+{
+    let pure = match node.pure {
+        ast::PureNode::Func(func) => {
+            let args = func
+                .args
+                .into_iter()
+                .map(|n| map_reference_in_ast(n, f))
+                .collect::<Vec<_>>();
+            ast::PureNode::Func(ast::Func { op: func.op, args })
+        }
+        ast::PureNode::Value(_) => node.pure,
+        ast::PureNode::Reference(cr) => f(cr),
+    };
+    ast::Node {
+        pure,
+        bracket: node.bracket,
+    }
+}
+
 pub fn delete_cell_in_ast(node: ast::Node, sheet_id: SheetId, cell_id: CellId) -> ast::Node {
     let pure = match node.pure {
         ast::PureNode::Func(func) => {
@@ -53,6 +53,8 @@ where
         }
         SheetPayload::Line(_) => prev,
         SheetPayload::Property(_) => prev,
+        SheetPayload::Merge(_) => prev,
+        SheetPayload::Comment(_) => prev,
         SheetPayload::Block(b) => handle_block_payload(prev, b, sheet_id, ctx),
     }
 }
@@ -1,13 +1,15 @@
 use crate::vertex_manager::executors::utils::handle_sheet_range_affect_result;
 use crate::vertex_manager::status::Status;
-use crate::vertex_manager::vertex::{MutReferenceVertex, SheetRangeVertex, StsRangeVertex, Vertex};
+use crate::vertex_manager::vertex::{
+    FormulaId, MutReferenceVertex, SheetRangeVertex, StsRangeVertex, Vertex,
+};
 
 use super::super::context::ContextTrait;
 use super::ast2vertex::find_vertices;
 use super::base::{AffectResult, ExecuteResult, SubPayload};
 use super::utils::{erase_deps, handle_sts_affect_result};
 use im::HashSet;
-use logisheets_base::{CellId, SheetId};
+use logisheets_base::{CellId, NameId, SheetId};
 use logisheets_parser::{ast, Parser};
 
 #[derive(Debug)]
@@ -141,6 +143,68 @@ pub fn add_ast_node(status: Status, sheet_id: SheetId, cell_id: CellId, node: as
     status
 }
 
+pub fn remove_ast_node(status: Status, sheet_id: SheetId, cell_id: CellId) -> Status {
+    erase_deps(status, sheet_id, cell_id)
+}
+
+// Rebuild the dependencies of a formula with the references only known after
+// calculating it, like the results of OFFSET and INDIRECT.
+pub fn add_dynamic_deps(status: Status, formula_id: FormulaId, dynamic: HashSet<Vertex>) -> Status {
+    let (sheet_id, cell_id) = formula_id;
+    let node = match status.formulas.get(&formula_id) {
+        Some(node) => node.clone(),
+        None => return status,
+    };
+    let deps = find_vertices(&node).union(dynamic);
+    let status = erase_deps(status, sheet_id, cell_id);
+    let status = add_deps_and_ranges(status, sheet_id, cell_id, deps);
+    add_formula(status, sheet_id, cell_id, node)
+}
+
+// Set the formula of a defined name. The range vertices are registered in the
+// sheets they belong to so that changes in these ranges can be noticed.
+pub fn add_name_node(status: Status, name_id: NameId, node: ast::Node) -> Status {
+    let v = Vertex::Name(name_id);
+    let deps = find_vertices(&node);
+    let Status {
+        graph,
+        formulas,
+        mut names,
+        mut range_vertices,
+        mut sts_vertices,
+        volatiles,
+    } = status;
+    let graph = graph.erase_vertex(&v);
+    let graph = deps.into_iter().fold(graph, |g, dep| {
+        match &dep {
+            Vertex::SheetRange(srv) => {
+                let set = range_vertices.get_mut(&srv.sheet_id);
+                if let Some(set) = set {
+                    set.insert(srv.clone());
+                } else {
+                    let mut set = HashSet::new();
+                    set.insert(srv.clone());
+                    range_vertices.insert(srv.sheet_id, set);
+                }
+            }
+            Vertex::StsRange(sts) => {
+                sts_vertices.insert(sts.clone());
+            }
+            _ => {}
+        }
+        g.add_dep(v.clone(), dep)
+    });
+    names.insert(name_id, node);
+    Status {
+        graph,
+        formulas,
+        names,
+        range_vertices,
+        sts_vertices,
+        volatiles,
+    }
+}
+
 fn add_deps_and_ranges(
     status: Status,
     sheet_id: SheetId,
@@ -155,18 +219,20 @@ fn add_deps_and_ranges(
             names,
             mut range_vertices,
             mut sts_vertices,
+            volatiles,
         } = p;
         let graph = graph.add_dep(v.clone(), dep.clone());
         match dep {
             Vertex::Cell(_) => {}
             Vertex::SheetRange(srv) => {
-                let set = range_vertices.get_mut(&sheet_id);
+                let set = range_vertices.get_mut(&srv.sheet_id);
                 if let Some(set) = set {
                     set.insert(srv);
                 } else {
+                    let range_sheet = srv.sheet_id;
                     let mut set = HashSet::new();
                     set.insert(srv);
-                    range_vertices.insert(sheet_id, set);
+                    range_vertices.insert(range_sheet, set);
                 }
             }
             Vertex::StsRange(sts) => {
@@ -181,6 +247,7 @@ fn add_deps_and_ranges(
             names,
             range_vertices,
             sts_vertices,
+            volatiles,
         }
     });
     status
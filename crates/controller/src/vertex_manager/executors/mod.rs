@@ -7,9 +7,11 @@ mod input_value;
 mod insert_block_line;
 mod insert_line;
 mod insert_range;
+pub mod name;
 mod remove_block;
 mod remove_block_line;
 mod remove_range;
+pub mod sheet;
 mod utils;
 
 pub type ExecuteResult = base::ExecuteResult;
@@ -0,0 +1,58 @@
+use im::HashSet;
+use logisheets_base::NameId;
+
+use crate::vertex_manager::status::Status;
+use crate::vertex_manager::vertex::{FormulaId, Vertex};
+
+use super::ast2vertex::replace_name_in_ast;
+use super::input_formula::{add_ast_node, add_name_node};
+
+// Remove the definition of a name. The formulas referring to it are kept and
+// they still depend on the name vertex.
+pub fn remove_name_node(status: Status, name_id: NameId) -> Status {
+    let Status {
+        graph,
+        formulas,
+        names,
+        range_vertices,
+        sts_vertices,
+        volatiles,
+    } = status;
+    Status {
+        graph: graph.erase_vertex(&Vertex::Name(name_id)),
+        formulas,
+        names: names.without(&name_id),
+        range_vertices,
+        sts_vertices,
+        volatiles,
+    }
+}
+
+// Make the formulas and the names referring to `old` refer to `new`. Return
+// the new status and the cells whose formulas are changed.
+pub fn replace_name_node(status: Status, old: NameId, new: NameId) -> (Status, HashSet<FormulaId>) {
+    let rdeps = status
+        .graph
+        .get_rdeps(&Vertex::Name(old))
+        .map_or(HashSet::new(), |r| r.clone());
+    let mut cells = HashSet::new();
+    let status = rdeps.into_iter().fold(status, |prev, v| match v {
+        Vertex::Cell((sheet_id, cell_id)) => match prev.formulas.get(&(sheet_id, cell_id)) {
+            Some(node) => {
+                cells.insert((sheet_id, cell_id));
+                let node = replace_name_in_ast(node.clone(), old, new);
+                add_ast_node(prev, sheet_id, cell_id, node)
+            }
+            None => prev,
+        },
+        Vertex::Name(nid) => match prev.names.get(&nid) {
+            Some(node) => {
+                let node = replace_name_in_ast(node.clone(), old, new);
+                add_name_node(prev, nid, node)
+            }
+            None => prev,
+        },
+        _ => prev,
+    });
+    (status, cells)
+}
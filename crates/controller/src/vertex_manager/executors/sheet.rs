@@ -0,0 +1,98 @@
+use im::HashSet;
+use logisheets_base::{NameId, SheetId};
+use logisheets_parser::ast;
+
+use crate::vertex_manager::status::Status;
+use crate::vertex_manager::vertex::{FormulaId, Vertex};
+
+use super::ast2vertex::{
+    delete_sheet_in_ast, refers_to_sheet, replace_name_in_ast, replace_sheet_in_ast,
+};
+use super::input_formula::{add_ast_node, add_name_node};
+use super::name::remove_name_node;
+use super::utils::erase_deps;
+
+// Remove the formulas in the sheet and the names in its scope, and turn the
+// references to them into #REF!. Return the new status and the cells whose
+// formulas are changed.
+pub fn delete_sheet(
+    status: Status,
+    sheet_id: SheetId,
+    names: &[NameId],
+) -> (Status, HashSet<FormulaId>) {
+    let in_sheet = status
+        .formulas
+        .keys()
+        .filter(|(s, _)| *s == sheet_id)
+        .cloned()
+        .collect::<Vec<_>>();
+    let status = in_sheet
+        .into_iter()
+        .fold(status, |prev, (s, c)| erase_deps(prev, s, c));
+    let referring = status
+        .formulas
+        .iter()
+        .filter(|(_, node)| refers_to_sheet(node, sheet_id, names))
+        .map(|(fid, node)| (*fid, node.clone()))
+        .collect::<Vec<_>>();
+    let mut cells = HashSet::new();
+    let status = referring.into_iter().fold(status, |prev, ((s, c), node)| {
+        cells.insert((s, c));
+        add_ast_node(prev, s, c, delete_sheet_in_ast(node, sheet_id, names))
+    });
+    let referring_names = status
+        .names
+        .iter()
+        .filter(|(nid, node)| !names.contains(nid) && refers_to_sheet(node, sheet_id, names))
+        .map(|(nid, node)| (*nid, node.clone()))
+        .collect::<Vec<_>>();
+    let status = referring_names
+        .into_iter()
+        .fold(status, |prev, (nid, node)| {
+            cells.extend(prev.graph.get_cell_rdeps(&Vertex::Name(nid)));
+            add_name_node(prev, nid, delete_sheet_in_ast(node, sheet_id, names))
+        });
+    let mut status = names
+        .iter()
+        .fold(status, |prev, nid| remove_name_node(prev, *nid));
+    status.range_vertices.remove(&sheet_id);
+    (status, cells)
+}
+
+// Add the formulas of the sheet `from` to the sheet `to`, and the names in
+// the scope of `from` to `to` with the new ids in `names`. The references to
+// `from` and its names in these formulas are re-anchored to `to`, just like
+// what a user would get by copying the whole sheet.
+pub fn copy_sheet(
+    status: Status,
+    from: SheetId,
+    to: SheetId,
+    names: &[(NameId, NameId)],
+) -> (Status, HashSet<FormulaId>) {
+    let reanchor = |node: ast::Node| {
+        names
+            .iter()
+            .fold(replace_sheet_in_ast(node, from, to), |prev, (old, new)| {
+                replace_name_in_ast(prev, *old, *new)
+            })
+    };
+    let copied_names = names
+        .iter()
+        .filter_map(|(old, new)| Some((*new, status.names.get(old)?.clone())))
+        .collect::<Vec<_>>();
+    let status = copied_names.into_iter().fold(status, |prev, (nid, node)| {
+        add_name_node(prev, nid, reanchor(node))
+    });
+    let formulas = status
+        .formulas
+        .iter()
+        .filter(|((s, _), _)| *s == from)
+        .map(|((_, c), node)| (*c, node.clone()))
+        .collect::<Vec<_>>();
+    let mut cells = HashSet::new();
+    let status = formulas.into_iter().fold(status, |prev, (cell_id, node)| {
+        cells.insert((to, cell_id));
+        add_ast_node(prev, to, cell_id, reanchor(node))
+    });
+    (status, cells)
+}
@@ -74,6 +74,7 @@ pub fn delete_rdeps_cell(status: Status, sheet_id: SheetId, cell_id: CellId) ->
         names: status.names,
         range_vertices: status.range_vertices,
         sts_vertices: status.sts_vertices,
+        volatiles: status.volatiles,
     }
 }
 
@@ -101,9 +102,9 @@ pub fn erase_deps(status: Status, sheet_id: SheetId, cell_id: CellId) -> Status
         (status.range_vertices.clone(), status.sts_vertices.clone()),
         |(sr, sts), v| match v {
             Vertex::SheetRange(s) => {
-                if let Some(curr) = sr.get(&sheet_id) {
+                if let Some(curr) = sr.get(&s.sheet_id) {
                     let new_sr = curr.clone().without(s);
-                    (sr.update(sheet_id, new_sr), sts)
+                    (sr.update(s.sheet_id, new_sr), sts)
                 } else {
                     (sr, sts)
                 }
@@ -118,6 +119,7 @@ pub fn erase_deps(status: Status, sheet_id: SheetId, cell_id: CellId) -> Status
         names: status.names,
         range_vertices: new_sheet_ranges,
         sts_vertices: new_sts_ranges,
+        volatiles: status.volatiles.without(&formula_id),
     }
 }
 
@@ -130,21 +132,11 @@ pub fn handle_sts_affect_result(
     match affect_result {
         AffectResult::DirtyOnly => {
             let vertex = Vertex::StsRange(v.clone());
-            let rdeps = prev.status.graph.get_rdeps(&vertex);
-            if rdeps.is_none() {
+            let nodes = prev.status.graph.get_cell_rdeps(&vertex);
+            if nodes.is_empty() {
                 return prev;
             }
-            let rdeps = rdeps.unwrap().clone();
             let (dirty_nodes, calc_rdeps) = {
-                let nodes = rdeps
-                    .into_iter()
-                    .fold(HashSet::new(), |p, node| match node {
-                        Vertex::Cell(f) => p.update(f),
-                        Vertex::SheetRange(_) => p,
-                        Vertex::StsRange(_) => p,
-                        Vertex::External => p,
-                        Vertex::Name(_) => p,
-                    });
                 if let Some(fid) = formula {
                     let init = prev.calc_rdeps;
                     let calc_rdeps = nodes.into_iter().fold(init, |p, d| {
@@ -177,21 +169,11 @@ pub fn handle_sheet_range_affect_result(
     match affect_result {
         AffectResult::DirtyOnly => {
             let vertex = Vertex::SheetRange(v.clone());
-            let rdeps = prev.status.graph.get_rdeps(&vertex);
-            if rdeps.is_none() {
+            let nodes = prev.status.graph.get_cell_rdeps(&vertex);
+            if nodes.is_empty() {
                 return prev;
             }
-            let rdeps = rdeps.unwrap().clone();
             let (dirty_nodes, calc_rdeps) = {
-                let nodes = rdeps
-                    .into_iter()
-                    .fold(HashSet::new(), |p, node| match node {
-                        Vertex::Cell(f) => p.update(f),
-                        Vertex::SheetRange(_) => p,
-                        Vertex::StsRange(_) => p,
-                        Vertex::External => p,
-                        Vertex::Name(_) => p,
-                    });
                 if let Some(fid) = formula {
                     let init = prev.calc_rdeps;
                     let calc_rdeps = nodes.into_iter().fold(init, |p, d| {
@@ -251,6 +233,7 @@ pub fn handle_sheet_range_affect_result(
                 names: prev.status.names,
                 range_vertices: new_sheet_ranges,
                 sts_vertices: prev.status.sts_vertices,
+                volatiles: prev.status.volatiles,
             };
             ExecuteResult {
                 status: new_status,
@@ -300,6 +283,7 @@ pub fn handle_sheet_range_affect_result(
                 names: prev.status.names,
                 range_vertices: new_sheet_ranges,
                 sts_vertices: prev.status.sts_vertices,
+                volatiles: prev.status.volatiles,
             };
             ExecuteResult {
                 status: new_status,
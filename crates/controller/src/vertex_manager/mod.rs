@@ -10,8 +10,10 @@ use std::hash::Hash;
 use self::context::ContextTrait;
 use self::executors::{exec::exec, ExecuteResult};
 use self::status::Status;
-use self::vertex::FormulaId;
+use self::vertex::{FormulaId, Vertex};
 use im::{HashMap, HashSet};
+use logisheets_base::{FuncId, NameId, SheetId};
+use logisheets_parser::ast;
 
 #[derive(Debug, Clone, Default)]
 pub struct VertexManager {
@@ -38,6 +40,68 @@ impl VertexManager {
         }
     }
 
+    // Take the dirty nodes in this sheet for calculation and leave the others
+    // queued.
+    pub fn clone_for_calc_sheet(&mut self, sheet_id: SheetId) -> Self {
+        let (dirty_nodes, others) = self
+            .dirty_nodes
+            .clone()
+            .into_iter()
+            .partition::<HashSet<_>, _>(|(s, _)| *s == sheet_id);
+        self.dirty_nodes = others;
+        VertexManager {
+            status: self.status.clone(),
+            dirty_nodes,
+            calc_rdeps: self.calc_rdeps.clone(),
+        }
+    }
+
+    // Get the formulas which need calculating, including the ones that
+    // depend on the dirty nodes.
+    pub fn get_stale_formulas(&self) -> HashSet<FormulaId> {
+        let mut visited = HashSet::<FormulaId>::new();
+        let mut stack = self.dirty_nodes.iter().cloned().collect::<Vec<_>>();
+        while let Some(fid) = stack.pop() {
+            if visited.contains(&fid) {
+                continue;
+            }
+            visited.insert(fid);
+            let rdeps = self.status.graph.get_cell_rdeps(&Vertex::Cell(fid));
+            stack.extend(rdeps);
+            if let Some(rdeps) = self.calc_rdeps.get(&fid) {
+                stack.extend(rdeps.iter().cloned());
+            }
+        }
+        visited
+            .into_iter()
+            .filter(|fid| self.status.formulas.contains_key(fid))
+            .collect()
+    }
+
+    // Check whether these formulas call volatile functions. The removed
+    // formulas are forgotten, too.
+    pub fn update_volatiles<I, F>(&mut self, fids: I, is_volatile_func: F)
+    where
+        I: IntoIterator<Item = FormulaId>,
+        F: Fn(&FuncId) -> bool,
+    {
+        let Status {
+            formulas,
+            names,
+            volatiles,
+            ..
+        } = &mut self.status;
+        volatiles.retain(|fid| formulas.contains_key(fid));
+        fids.into_iter().for_each(|fid| match formulas.get(&fid) {
+            Some(node) if has_volatile_func(node, names, &is_volatile_func, &mut vec![]) => {
+                volatiles.insert(fid);
+            }
+            _ => {
+                volatiles.remove(&fid);
+            }
+        });
+    }
+
     pub fn clone_for_calc(&mut self) -> Self {
         let mut dirty_nodes = HashSet::new();
         let mut calc_rdeps = HashMap::new();
@@ -51,6 +115,40 @@ impl VertexManager {
     }
 }
 
+// A formula referring to a name is volatile if the definition of the name is.
+fn has_volatile_func<F>(
+    node: &ast::Node,
+    names: &HashMap<NameId, ast::Node>,
+    is_volatile_func: &F,
+    visited_names: &mut Vec<NameId>,
+) -> bool
+where
+    F: Fn(&FuncId) -> bool,
+{
+    match &node.pure {
+        ast::PureNode::Func(f) => {
+            let volatile = match &f.op {
+                ast::Operator::Function(id) => is_volatile_func(id),
+                _ => false,
+            };
+            volatile
+                || f.args
+                    .iter()
+                    .any(|arg| has_volatile_func(arg, names, is_volatile_func, visited_names))
+        }
+        ast::PureNode::Reference(ast::CellReference::Name(nid)) => {
+            if visited_names.contains(nid) {
+                return false;
+            }
+            visited_names.push(*nid);
+            names
+                .get(nid)
+                .is_some_and(|n| has_volatile_func(n, names, is_volatile_func, visited_names))
+        }
+        _ => false,
+    }
+}
+
 fn merge_calc_rdeps<T, V>(
     rdeps1: HashMap<T, HashSet<V>>,
     rdeps2: HashMap<T, HashSet<V>>,
@@ -15,6 +15,8 @@ pub struct Status {
     pub names: HashMap<NameId, ast::Node>,
     pub range_vertices: HashMap<SheetId, HashSet<SheetRangeVertex>>,
     pub sts_vertices: HashSet<StsRangeVertex>,
+    // The formulas calling volatile functions.
+    pub volatiles: HashSet<FormulaId>,
 }
 
 impl Default for Status {
@@ -28,6 +30,7 @@ impl Default for Status {
             names: HashMap::new(),
             range_vertices: HashMap::new(),
             sts_vertices: HashSet::new(),
+            volatiles: HashSet::new(),
         }
     }
 }
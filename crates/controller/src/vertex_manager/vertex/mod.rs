@@ -2,6 +2,7 @@ mod cell;
 mod sheet_range;
 mod sts_range;
 
+use im::HashSet;
 use logisheets_base::NameId;
 
 use super::graph::Graph;
@@ -10,6 +11,7 @@ pub type FormulaId = cell::FormulaId;
 pub type SheetRangeVertex = sheet_range::SheetRangeVertex;
 pub type MutReferenceVertex = sheet_range::MutReferenceVertex;
 pub type StsRangeVertex = sts_range::StsRangeVertex;
+pub type UnMutRefVertex = sts_range::UnMutRefVertex;
 pub type MutRowRange = sheet_range::MutRowRange;
 pub type MutColRange = sheet_range::MutColRange;
 pub type MutAddrRange = sheet_range::AddrRange;
@@ -56,6 +58,28 @@ impl Ord for Vertex {
 }
 
 impl Graph<Vertex> {
+    // Get the cells depending on this vertex. A cell that depends on a defined
+    // name depends on what the name refers to, too.
+    pub fn get_cell_rdeps(&self, v: &Vertex) -> HashSet<FormulaId> {
+        let mut result = HashSet::new();
+        let mut visited_names = HashSet::new();
+        let mut stack = vec![v.clone()];
+        while let Some(curr) = stack.pop() {
+            if let Some(rdeps) = self.get_rdeps(&curr) {
+                rdeps.iter().for_each(|rdep| match rdep {
+                    Vertex::Cell(fid) => {
+                        result.insert(*fid);
+                    }
+                    Vertex::Name(nid) if visited_names.insert(*nid).is_none() => {
+                        stack.push(rdep.clone());
+                    }
+                    _ => {}
+                });
+            }
+        }
+        result
+    }
+
     pub fn merge_vertex(self, arg1: &SheetRangeVertex, arg2: &SheetRangeVertex) -> Self {
         let v1 = Vertex::SheetRange(arg1.clone());
         let v2 = Vertex::SheetRange(arg2.clone());
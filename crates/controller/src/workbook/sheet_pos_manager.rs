@@ -41,7 +41,43 @@ impl SheetPosManager {
             }
             SheetShiftType::Delete => {
                 let mut pos = self.pos;
-                pos.remove(payload.idx);
+                let id = pos.remove(payload.idx);
+                SheetPosManager {
+                    pos,
+                    hiddens: self.hiddens.without(&id),
+                }
+            }
+            SheetShiftType::Move(to) => {
+                let mut pos = self.pos;
+                let id = pos.remove(payload.idx);
+                pos.insert(to, id);
+                SheetPosManager {
+                    pos,
+                    hiddens: self.hiddens,
+                }
+            }
+            SheetShiftType::Copy(to) => {
+                let id = self.pos[payload.idx];
+                let name = sheet_id_manager.get_string(&id).unwrap_or_default();
+                // The copy of `Sheet1` is named `Sheet1 (2)`, `Sheet1 (3)` and so on.
+                let base = match name.rfind(" (") {
+                    Some(i)
+                        if name.ends_with(')')
+                            && name[i + 2..name.len() - 1].parse::<u32>().is_ok() =>
+                    {
+                        name[..i].to_string()
+                    }
+                    _ => name,
+                };
+                let mut x = 2;
+                let mut new_name = format!("{} ({})", base, x);
+                while sheet_id_manager.has(&new_name).is_some() {
+                    x += 1;
+                    new_name = format!("{} ({})", base, x);
+                }
+                let new_id = sheet_id_manager.get_id(&new_name);
+                let mut pos = self.pos;
+                pos.insert(to, new_id);
                 SheetPosManager {
                     pos,
                     hiddens: self.hiddens,
@@ -0,0 +1,199 @@
+// Run the cases in `ast_checker/funcs/*.json` through a headless workbook.
+// See `ast_checker/funcs/README.md` for the format of the cases.
+extern crate logisheets_controller;
+extern crate serde;
+extern crate serde_json;
+
+use logisheets_controller::controller::edit_action::{
+    CellInput, EditAction, EditPayload, PayloadsAction,
+};
+use logisheets_controller::{get_all_func_signatures, FuncSignature, Value, Workbook};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+
+const SPEC_DIR: &str = "ast_checker/funcs";
+// The cell where the formula of a case is put.
+const FORMULA_CELL: &str = "Z1";
+
+#[derive(Deserialize)]
+struct Spec {
+    name: String,
+    #[serde(default)]
+    cases: Vec<Case>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    #[serde(default)]
+    cells: BTreeMap<String, String>,
+    formula: String,
+    expected: Option<serde_json::Value>,
+    error: Option<String>,
+    #[serde(default)]
+    note: String,
+}
+
+fn load_specs() -> Vec<(String, String)> {
+    let mut paths = fs::read_dir(SPEC_DIR)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|p| {
+            let file = p.file_name().unwrap().to_string_lossy().to_string();
+            (file, fs::read_to_string(&p).unwrap())
+        })
+        .collect()
+}
+
+// Parse the addresses like `B3` into the row and column indices.
+fn parse_addr(addr: &str) -> (usize, usize) {
+    let split = addr.find(|c: char| c.is_ascii_digit()).unwrap();
+    let (letters, digits) = addr.split_at(split);
+    let col = letters
+        .chars()
+        .fold(0, |prev, c| prev * 26 + (c as usize - 'A' as usize + 1));
+    (digits.parse::<usize>().unwrap() - 1, col - 1)
+}
+
+fn run_case(case: &Case) -> Result<(), String> {
+    let mut wb = Workbook::default();
+    let input = |addr: &str, content: &str| {
+        let (row, col) = parse_addr(addr);
+        EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row,
+            col,
+            content: content.to_string(),
+        })
+    };
+    let mut payloads = case
+        .cells
+        .iter()
+        .map(|(addr, content)| input(addr, content))
+        .collect::<Vec<_>>();
+    payloads.push(input(FORMULA_CELL, &case.formula));
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads,
+        undoable: false,
+    }));
+    let (row, col) = parse_addr(FORMULA_CELL);
+    let actual = wb
+        .get_sheet_by_idx(0)
+        .unwrap()
+        .get_value(row, col)
+        .map_err(|e| format!("{:?}", e))?;
+    let matched = match (&case.expected, &case.error, &actual) {
+        (_, Some(e), Value::Error(a)) => e == a,
+        (Some(serde_json::Value::Number(e)), None, Value::Number(a)) => {
+            let e = e.as_f64().unwrap();
+            (e - a).abs() <= 1e-9 * e.abs().max(1.)
+        }
+        (Some(serde_json::Value::String(e)), None, Value::Str(a)) => e == a,
+        (Some(serde_json::Value::Bool(e)), None, Value::Bool(a)) => e == a,
+        _ => false,
+    };
+    match matched {
+        true => Ok(()),
+        false => Err(format!(
+            "expected {:?}, got {:?}",
+            case.error
+                .clone()
+                .map(serde_json::Value::String)
+                .or(case.expected.clone()),
+            actual
+        )),
+    }
+}
+
+#[test]
+fn func_conformance_test() {
+    let failures = load_specs()
+        .into_iter()
+        .flat_map(|(file, content)| {
+            let spec = serde_json::from_str::<Spec>(&content)
+                .unwrap_or_else(|e| panic!("{}: {}", file, e));
+            let name = spec.name;
+            spec.cases
+                .into_iter()
+                .filter_map(move |case| {
+                    run_case(&case).err().map(|e| {
+                        let note = match case.note.as_str() {
+                            "" => String::new(),
+                            n => format!(" ({})", n),
+                        };
+                        format!("{} {}{}: {}", name, case.formula, note, e)
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+// The registry is fed from the same files.
+#[test]
+fn func_spec_registry_test() {
+    let registry = get_all_func_signatures();
+    load_specs().into_iter().for_each(|(file, content)| {
+        let spec = serde_json::from_str::<FuncSignature>(&content)
+            .unwrap_or_else(|e| panic!("{}: {}", file, e));
+        let sign = registry
+            .iter()
+            .find(|s| s.name == spec.name)
+            .unwrap_or_else(|| panic!("{} is not registered", spec.name));
+        assert_eq!(sign.arg_count, spec.arg_count, "{}", file);
+        assert_eq!(sign.args.len(), spec.args.len(), "{}", file);
+        assert_eq!(sign.volatile, spec.volatile, "{}", file);
+        assert_eq!(sign.return_kind, spec.return_kind, "{}", file);
+    });
+}
+
+// The checker accepts a call by its argument count, so every accepted count
+// must be calculated into a value or an error instead of a panic.
+#[test]
+fn func_accepted_arg_count_test() {
+    let cells = [("A1", "1"), ("B2", "2"), ("A3", "text")]
+        .iter()
+        .map(|(addr, content)| (addr.to_string(), content.to_string()))
+        .collect::<BTreeMap<_, _>>();
+    let samples = ["1", "0", "-1", "", "TRUE", "\"a\"", "A1:B3", "{1,2;3,4}"];
+    let failures = get_all_func_signatures()
+        .into_iter()
+        .flat_map(|sign| {
+            (0..=6)
+                .filter(|count| sign.arg_count.check(*count))
+                .flat_map(|count| {
+                    samples
+                        .iter()
+                        .map(|sample| {
+                            let args = (0..count)
+                                .map(|i| match sign.args.get(i).and_then(|a| a.ref_only) {
+                                    Some(true) => "A1:B3",
+                                    _ => sample,
+                                })
+                                .collect::<Vec<_>>();
+                            format!("={}({})", sign.name, args.join(","))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|formula| {
+            let case = Case {
+                cells: cells.clone(),
+                formula: formula.clone(),
+                expected: None,
+                error: None,
+                note: String::new(),
+            };
+            panic::catch_unwind(AssertUnwindSafe(|| run_case(&case))).is_err()
+        })
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
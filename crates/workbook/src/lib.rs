@@ -15,10 +15,13 @@ use xmlserde::*;
 pub mod prelude {
     pub use super::ooxml::comments::*;
     pub use super::ooxml::complex_types::*;
+    pub use super::ooxml::external_links::*;
+    pub use super::ooxml::persons::PersonListPart;
     pub use super::ooxml::simple_types::*;
     pub use super::ooxml::sst::SstPart;
     pub use super::ooxml::style_sheet::StylesheetPart;
     pub use super::ooxml::theme::*;
+    pub use super::ooxml::threaded_comments::ThreadedCommentsPart;
     pub use super::ooxml::workbook::*;
     pub use super::ooxml::worksheet::*;
     pub use super::reader::*;
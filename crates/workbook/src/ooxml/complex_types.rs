@@ -504,7 +504,7 @@ pub struct CtDxfs {
     pub dxfs: Vec<CtDxf>,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtDxf {
     #[xmlserde(name = b"font", ty = "child")]
     pub font: Option<CtFont>,
@@ -520,7 +520,7 @@ pub struct CtDxf {
     pub protection: Option<CtCellProtection>,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtNumFmt {
     #[xmlserde(name = b"numFmtId", ty = "attr")]
     pub num_fmt_id: StNumFmtId,
@@ -690,6 +690,8 @@ pub struct CtDefinedNames {
 
 #[derive(Debug, XmlSerialize, XmlDeserialize)]
 pub struct CtDefinedName {
+    #[xmlserde(ty = "text", default = "empty_string")]
+    pub formula: String,
     #[xmlserde(name = b"name", ty = "attr")]
     pub name: String,
     #[xmlserde(name = b"comment", ty = "attr")]
@@ -2224,7 +2226,7 @@ pub struct CtDataValidation {
     pub sqref: String,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtConditionalFormatting {
     #[xmlserde(name = b"cfRule", ty = "child")]
     pub cf_rules: Vec<CtCfRule>,
@@ -2234,7 +2236,7 @@ pub struct CtConditionalFormatting {
     pub sqref: String,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtCfRule {
     #[xmlserde(name = b"formula", ty = "child")]
     pub formulas: Vec<PlainTextString>,
@@ -2272,7 +2274,7 @@ pub struct CtCfRule {
     pub equal_average: bool,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtDataBar {
     #[xmlserde(name = b"cfvo", ty = "child")]
     pub cfvos: Vec<CtCfvo>, // must has 2 elements
@@ -2286,7 +2288,7 @@ pub struct CtDataBar {
     pub show_value: bool,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtIconSet {
     #[xmlserde(name = b"cfvo", ty = "child")]
     pub cfvos: Vec<CtCfvo>, // at least 2 elements
@@ -2304,7 +2306,7 @@ pub struct CtIconSet {
     pub reverse: bool,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtCfvo {
     #[xmlserde(name = b"type", ty = "attr")]
     pub ty: StCfvoType,
@@ -2314,7 +2316,7 @@ pub struct CtCfvo {
     pub gte: bool,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtColorScale {
     #[xmlserde(name = b"cfvo", ty = "child")]
     pub cfvos: Vec<CtCfvo>, // at least 2
@@ -2468,6 +2470,56 @@ pub struct CtCell {
     pub ph: bool,
 }
 
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CtThreadedComment {
+    #[xmlserde(name = b"text", ty = "child")]
+    pub text: Option<PlainTextString>,
+    #[xmlserde(name = b"mentions", ty = "child")]
+    pub mentions: Option<CtThreadedCommentMentions>,
+    #[xmlserde(name = b"ref", ty = "attr")]
+    pub reference: Option<StRef>,
+    #[xmlserde(name = b"dT", ty = "attr")]
+    pub date_time: Option<String>,
+    #[xmlserde(name = b"personId", ty = "attr")]
+    pub person_id: String,
+    #[xmlserde(name = b"id", ty = "attr")]
+    pub id: String,
+    #[xmlserde(name = b"parentId", ty = "attr")]
+    pub parent_id: Option<String>,
+    #[xmlserde(name = b"done", ty = "attr")]
+    pub done: Option<bool>,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CtThreadedCommentMentions {
+    #[xmlserde(name = b"mention", ty = "child")]
+    pub mentions: Vec<CtMention>,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CtMention {
+    #[xmlserde(name = b"mentionpersonId", ty = "attr")]
+    pub mention_person_id: String,
+    #[xmlserde(name = b"mentionId", ty = "attr")]
+    pub mention_id: String,
+    #[xmlserde(name = b"startIndex", ty = "attr")]
+    pub start_index: u32,
+    #[xmlserde(name = b"length", ty = "attr")]
+    pub length: u32,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CtPerson {
+    #[xmlserde(name = b"displayName", ty = "attr")]
+    pub display_name: String,
+    #[xmlserde(name = b"id", ty = "attr")]
+    pub id: String,
+    #[xmlserde(name = b"userId", ty = "attr")]
+    pub user_id: Option<String>,
+    #[xmlserde(name = b"providerId", ty = "attr")]
+    pub provider_id: Option<String>,
+}
+
 mod tests {
     use super::*;
 
@@ -7,6 +7,10 @@ use super::{
 #[derive(Debug, XmlSerialize, XmlDeserialize)]
 #[xmlserde(root = b"externalLink")]
 #[xmlserde(with_ns = b"http://schemas.openxmlformats.org/spreadsheetml/2006/main")]
+#[xmlserde(with_custom_ns(
+    b"r",
+    b"http://schemas.openxmlformats.org/officeDocument/2006/relationships"
+))]
 pub struct ExternalLinkPart {
     #[xmlserde(name = b"externalBook", ty = "child")]
     pub external_book: Option<CtExternalBook>,
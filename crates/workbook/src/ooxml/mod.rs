@@ -5,6 +5,7 @@ pub mod core_properties;
 pub mod defaults;
 pub mod doc_props;
 pub mod external_links;
+pub mod persons;
 pub mod relationships;
 pub mod simple_types;
 pub mod sst;
@@ -12,5 +13,6 @@ pub mod style_sheet;
 #[cfg(test)]
 pub mod test_utils;
 pub mod theme;
+pub mod threaded_comments;
 pub mod workbook;
 pub mod worksheet;
@@ -0,0 +1,28 @@
+use super::complex_types::*;
+use xmlserde::{XmlDeserialize, XmlSerialize};
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[xmlserde(with_ns = b"http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments")]
+#[xmlserde(root = b"personList")]
+pub struct PersonListPart {
+    #[xmlserde(name = b"person", ty = "child")]
+    pub persons: Vec<CtPerson>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PersonListPart;
+    use crate::ooxml::test_utils::in_one_line;
+    use crate::{xml_deserialize_from_str, xml_serialize_with_decl};
+
+    #[test]
+    fn test1() {
+        let xml = include_str!("../../examples/persons.xml");
+        let r = xml_deserialize_from_str::<PersonListPart>(xml).unwrap();
+        assert_eq!(r.persons.len(), 2);
+        assert_eq!(r.persons[0].display_name, "Alice");
+        assert_eq!(r.persons[1].user_id, None);
+        let actual = xml_serialize_with_decl(r);
+        assert_eq!(in_one_line(xml), actual);
+    }
+}
@@ -84,7 +84,7 @@ xml_serde_enum! {
 
 xml_serde_enum! {
     /// 18.18.12
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     StCfType {
         Expression => "expression",
         CellIs => "cellIs",
@@ -95,19 +95,20 @@ xml_serde_enum! {
         UniqueValues => "uniqueValues",
         DuplicateValues => "duplicateValues",
         ContainsText => "containsText",
+        NotContainsText => "notContainsText",
         BeginsWith => "beginsWith",
         EndsWith => "endsWith",
-        ContainsBlank => "containsBlank",
+        ContainsBlanks => "containsBlanks",
         NotContainsBlanks => "notContainsBlanks",
-        ContainErrors => "containErrors",
-        NotContainErrors => "notContainErrors",
+        ContainsErrors => "containsErrors",
+        NotContainsErrors => "notContainsErrors",
         TimePeriod => "timePeriod",
         AboveAverage => "aboveAverage",
     }
 }
 
 xml_serde_enum! {
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     StCfvoType {
         Num => "num",
         Percent => "percent",
@@ -129,7 +130,7 @@ xml_serde_enum! {
 
 xml_serde_enum! {
     /// 18.18.15
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     StConditionalFormattingOperator {
         LessThan => "lessThan",
         LessThanOrEqual => "lessThanOrEqual",
@@ -449,7 +450,8 @@ xml_serde_enum! {
 
 xml_serde_enum! {
     /// 18.18.42
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, TS)]
+    #[ts(export, export_to = "../../../src/bindings/st_icon_set_type.ts")]
     StIconSetType {
         ThreeArrows => "3Arrows",
         ThreeArrowsGray => "3ArrowsGray",
@@ -985,11 +987,11 @@ xml_serde_enum! {
 
 xml_serde_enum! {
     /// 18.18.82
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     StTimePeriod {
        Today => "today",
        Yesterday => "yesterday",
-       Tommorow => "tommorow",
+       Tomorrow => "tomorrow",
        Last7Days => "last7Days",
        ThisMonth => "thisMonth",
        LastMonth => "lastMonth",
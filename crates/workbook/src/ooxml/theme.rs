@@ -2,7 +2,7 @@ use super::defaults::default_zero_u8;
 use xmlserde::{Unparsed, XmlDeserialize, XmlSerialize};
 
 // Ct_OfficeStyleSheet 20.1.6.2
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 #[xmlserde(root = b"a:theme")]
 #[xmlserde(with_custom_ns(b"a", b"http://schemas.openxmlformats.org/drawingml/2006/main"))]
 pub struct ThemePart {
@@ -16,7 +16,7 @@ pub struct ThemePart {
     // pub ext_lst: Option<CtOfficeArtExtensionList>,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtBaseStyles {
     #[xmlserde(name = b"a:clrScheme", ty = "child")]
     pub clr_scheme: CtColorScheme,
@@ -26,7 +26,7 @@ pub struct CtBaseStyles {
     pub fmt_scheme: Unparsed,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtColorScheme {
     #[xmlserde(name = b"name", ty = "attr")]
     pub name: String,
@@ -56,7 +56,7 @@ pub struct CtColorScheme {
     pub fol_hlink: EgColorChoice,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub enum EgColorChoice {
     #[xmlserde(name = b"a:sysClr")]
     SysClr(CtSystemColor),
@@ -86,7 +86,7 @@ impl EgColorChoice {
     }
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtSystemColor {
     #[xmlserde(name = b"val", ty = "attr")]
     pub val: String,
@@ -94,13 +94,13 @@ pub struct CtSystemColor {
     pub last_clr: Option<String>,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtSrgbColor {
     #[xmlserde(name = b"val", ty = "attr")]
     pub val: String,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct ThemeCtFontScheme {
     #[xmlserde(name = b"name", ty = "attr")]
     pub name: String,
@@ -110,7 +110,7 @@ pub struct ThemeCtFontScheme {
     pub minor_font: CtFontCollection,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtFontCollection {
     #[xmlserde(name = b"a:latin", ty = "child")]
     pub latin: CtTextFont,
@@ -122,7 +122,7 @@ pub struct CtFontCollection {
     pub fonts: Vec<CtSupplementalFont>,
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtTextFont {
     #[xmlserde(name = b"typeface", ty = "attr")]
     pub typeface: String,
@@ -138,7 +138,7 @@ fn default_charset() -> u8 {
     1
 }
 
-#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtSupplementalFont {
     #[xmlserde(name = b"script", ty = "attr")]
     pub script: String,
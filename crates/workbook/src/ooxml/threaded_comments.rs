@@ -0,0 +1,35 @@
+use super::complex_types::*;
+use xmlserde::{XmlDeserialize, XmlSerialize};
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+#[xmlserde(with_ns = b"http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments")]
+#[xmlserde(root = b"ThreadedComments")]
+pub struct ThreadedCommentsPart {
+    #[xmlserde(name = b"threadedComment", ty = "child")]
+    pub threaded_comments: Vec<CtThreadedComment>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThreadedCommentsPart;
+    use crate::ooxml::test_utils::in_one_line;
+    use crate::{xml_deserialize_from_str, xml_serialize_with_decl};
+
+    #[test]
+    fn test1() {
+        let xml = include_str!("../../examples/threaded_comments.xml");
+        let r = xml_deserialize_from_str::<ThreadedCommentsPart>(xml).unwrap();
+        assert_eq!(r.threaded_comments.len(), 3);
+        let root = &r.threaded_comments[0];
+        assert_eq!(root.reference.as_deref(), Some("B2"));
+        assert_eq!(root.done, Some(true));
+        let reply = &r.threaded_comments[1];
+        assert_eq!(reply.parent_id.as_deref(), Some(root.id.as_str()));
+        let mentions = &reply.mentions.as_ref().unwrap().mentions;
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].start_index, 0);
+        assert_eq!(mentions[0].length, 4);
+        let actual = xml_serialize_with_decl(r);
+        assert_eq!(in_one_line(xml), actual);
+    }
+}
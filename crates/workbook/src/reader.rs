@@ -5,8 +5,9 @@ use crate::ooxml::doc_props::DocPropCore;
 use crate::ooxml::doc_props::DocPropCustom;
 use crate::ooxml::theme::ThemePart;
 use crate::ooxml::{
-    comments::Comments, external_links::ExternalLinkPart, relationships::Relationships,
-    sst::SstPart, style_sheet::StylesheetPart, workbook::WorkbookPart, worksheet::WorksheetPart,
+    comments::Comments, external_links::ExternalLinkPart, persons::PersonListPart,
+    relationships::Relationships, sst::SstPart, style_sheet::StylesheetPart,
+    threaded_comments::ThreadedCommentsPart, workbook::WorkbookPart, worksheet::WorksheetPart,
 };
 use crate::workbook::Id;
 use crate::workbook::Xl;
@@ -123,6 +124,7 @@ fn de_xl<R: Read + Seek>(path: &str, archive: &mut ZipArchive<R>) -> Result<Xl,
     let mut worksheets = HashMap::<Id, Worksheet>::new();
     let mut external_links = HashMap::<Id, ExternalLink>::new();
     let mut theme = Option::<(Id, ThemePart)>::None;
+    let mut persons = Option::<(Id, PersonListPart)>::None;
     let path_buf = get_rels(path)?;
     let rels = path_buf.to_str();
     if rels.is_none() {
@@ -209,6 +211,21 @@ fn de_xl<R: Read + Seek>(path: &str, archive: &mut ZipArchive<R>) -> Result<Xl,
                     }
                 }
             }
+            PERSON => {
+                let target = &r.target;
+                let id = r.id;
+                let path = get_target_abs_path(rels, target);
+                if let Some(s) = path.to_str() {
+                    match de_persons(s, archive) {
+                        Ok(w) => {
+                            persons = Some((id, w));
+                        }
+                        Err(e) => {
+                            println!("parsing file: {:?} but meet error:{:?}", s, e)
+                        }
+                    }
+                }
+            }
             _ => {}
         });
     Ok(Xl {
@@ -218,6 +235,7 @@ fn de_xl<R: Read + Seek>(path: &str, archive: &mut ZipArchive<R>) -> Result<Xl,
         worksheets,
         external_links,
         theme,
+        persons,
     })
 }
 
@@ -227,6 +245,7 @@ fn de_worksheet<R: Read + Seek>(
 ) -> Result<Worksheet, SerdeErr> {
     let worksheet_part = de_worksheet_part(path, archive)?;
     let mut comments = Option::<Comments>::None;
+    let mut threaded_comments = Option::<ThreadedCommentsPart>::None;
     let path_buf = get_rels(path)?;
     let rels = path_buf.to_str();
     if rels.is_none() {
@@ -238,6 +257,7 @@ fn de_worksheet<R: Read + Seek>(
         return Ok(Worksheet {
             worksheet_part,
             comments,
+            threaded_comments,
         });
     }
     let relationships = result.unwrap();
@@ -257,11 +277,21 @@ fn de_worksheet<R: Read + Seek>(
                     }
                 }
             }
+            THREADED_COMMENT => {
+                let target = &r.target;
+                let path = get_target_abs_path(rels, target);
+                if let Some(p) = path.to_str() {
+                    if let Ok(c) = de_threaded_comments(p, archive) {
+                        threaded_comments = Some(c);
+                    }
+                }
+            }
             _ => {}
         });
     Ok(Worksheet {
         worksheet_part,
         comments,
+        threaded_comments,
     })
 }
 
@@ -284,6 +314,8 @@ define_de_func!(de_external_link_part, ExternalLinkPart);
 define_de_func!(de_workbook_part, WorkbookPart);
 define_de_func!(de_worksheet_part, WorksheetPart);
 define_de_func!(de_comments, Comments);
+define_de_func!(de_threaded_comments, ThreadedCommentsPart);
+define_de_func!(de_persons, PersonListPart);
 define_de_func!(de_sst, SstPart);
 define_de_func!(de_style_part, StylesheetPart);
 define_de_func!(de_theme, ThemePart);
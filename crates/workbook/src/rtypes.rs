@@ -9,10 +9,17 @@ pub const SST: RType =
     RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings");
 pub const EXT_LINK: RType =
     RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLink");
+pub const EXT_LINK_PATH: RType =
+    RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLinkPath");
+pub const SHEET_METADATA: RType =
+    RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/sheetMetadata");
 pub const STYLE: RType =
     RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles");
 pub const COMMENTS: RType =
     RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments");
+pub const THREADED_COMMENT: RType =
+    RType("http://schemas.microsoft.com/office/2017/10/relationships/threadedComment");
+pub const PERSON: RType = RType("http://schemas.microsoft.com/office/2017/10/relationships/person");
 pub const THEME: RType =
     RType("http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme");
 pub const DOC_PROP_APP: RType = RType(
@@ -1,9 +1,11 @@
 use crate::ooxml::comments::Comments;
 use crate::ooxml::doc_props::{DocPropApp, DocPropCore, DocPropCustom};
 use crate::ooxml::external_links::*;
+use crate::ooxml::persons::PersonListPart;
 use crate::ooxml::sst::SstPart;
 use crate::ooxml::style_sheet::StylesheetPart;
 use crate::ooxml::theme::ThemePart;
+use crate::ooxml::threaded_comments::ThreadedCommentsPart;
 use crate::ooxml::workbook::WorkbookPart;
 use crate::ooxml::worksheet::WorksheetPart;
 use std::collections::HashMap;
@@ -26,12 +28,14 @@ pub struct Xl {
     pub worksheets: HashMap<Id, Worksheet>,
     pub external_links: HashMap<Id, ExternalLink>,
     pub theme: Option<(Id, ThemePart)>,
+    pub persons: Option<(Id, PersonListPart)>,
 }
 
 #[derive(Debug)]
 pub struct Worksheet {
     pub worksheet_part: WorksheetPart,
     pub comments: Option<Comments>,
+    pub threaded_comments: Option<ThreadedCommentsPart>,
 }
 
 #[derive(Debug)]
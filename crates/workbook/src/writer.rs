@@ -2,17 +2,20 @@ use crate::ooxml::content_types::{ContentTypes, CtDefault, CtOverride};
 use crate::ooxml::doc_props::{DocPropApp, DocPropCore, DocPropCustom};
 use crate::ooxml::relationships::{CtRelationship, Relationships};
 use crate::prelude::StTargetMode;
-use crate::prelude::{Comments, SstPart, StylesheetPart, ThemePart, WorkbookPart, WorksheetPart};
+use crate::prelude::{
+    Comments, ExternalLinkPart, PersonListPart, SstPart, StylesheetPart, ThemePart,
+    ThreadedCommentsPart, WorkbookPart, WorksheetPart,
+};
 use crate::rtypes::{
-    RType, COMMENTS, DOC_PROP_APP, DOC_PROP_CORE, DOC_PROP_CUSTOM, EXT_LINK, SST, STYLE, THEME,
-    WORKBOOK, WORKSHEET,
+    RType, COMMENTS, DOC_PROP_APP, DOC_PROP_CORE, DOC_PROP_CUSTOM, EXT_LINK, EXT_LINK_PATH, PERSON,
+    SHEET_METADATA, SST, STYLE, THEME, THREADED_COMMENT, WORKBOOK, WORKSHEET,
 };
 use std::io::{Cursor, Write};
 use xmlserde::xml_serialize_with_decl;
 use zip::write::{FileOptions, ZipWriter};
 use zip::CompressionMethod;
 
-use crate::workbook::{DocProps, Workbook, Worksheet, Xl};
+use crate::workbook::{DocProps, ExternalLink, Workbook, Worksheet, Xl};
 use zip::result::ZipResult;
 
 macro_rules! define_se_func {
@@ -62,6 +65,11 @@ pub struct WriteProof {
     pub rtype: RType<'static>,
 }
 
+// The only cell metadata, which `cm="1"` refers to, marks the formulas as
+// dynamic arrays. Otherwise Excel reads the array formulas as legacy CSE ones.
+const DYNAMIC_ARRAY_METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<metadata xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:xda="http://schemas.microsoft.com/office/spreadsheetml/2017/dynamicarray"><metadataTypes count="1"><metadataType name="XLDAPR" minSupportedVersion="120000" copy="1" pasteAll="1" pasteValues="1" merge="1" splitFirst="1" rowColShift="1" clearFormats="1" clearComments="1" assign="1" coerce="1" cellMeta="1"/></metadataTypes><futureMetadata name="XLDAPR" count="1"><bk><extLst><ext uri="{bdbb8cdc-fa1e-496e-a857-3c3f30c029c3}"><xda:dynamicArrayProperties fDynamic="1" fCollapsed="0"/></ext></extLst></bk></futureMetadata><cellMetadata count="1"><bk><rc t="1" v="0"/></bk></cellMetadata></metadata>"#;
+
 type Writer<'a> = ZipWriter<Cursor<&'a mut Vec<u8>>>;
 
 pub fn write(wb: Workbook) -> ZipResult<Vec<u8>> {
@@ -137,6 +145,13 @@ fn write_xl(xl: Xl, writer: &mut Writer) -> ZipResult<Vec<WriteProof>> {
     let mut relationships = Vec::<CtRelationship>::new();
 
     let mut worksheets = xl.worksheets;
+    let has_cell_metadata = worksheets.values().any(|ws| {
+        ws.worksheet_part
+            .sheet_data
+            .rows
+            .iter()
+            .any(|row| row.cells.iter().any(|c| c.cm > 0))
+    });
     let mut sheet_ids = xl
         .workbook_part
         .sheets
@@ -150,6 +165,9 @@ fn write_xl(xl: Xl, writer: &mut Writer) -> ZipResult<Vec<WriteProof>> {
 
     writer.add_directory("xl/worksheets", options())?;
     writer.add_directory("xl/worksheets/_rels", options())?;
+    if worksheets.values().any(|ws| ws.threaded_comments.is_some()) {
+        writer.add_directory("xl/threadedComments", options())?;
+    }
 
     while let Some(sheet_id) = sheet_ids.pop() {
         if let Some(ws) = worksheets.remove(&sheet_id) {
@@ -197,6 +215,66 @@ fn write_xl(xl: Xl, writer: &mut Writer) -> ZipResult<Vec<WriteProof>> {
         });
     }
 
+    if let Some(persons) = xl.persons {
+        writer.add_directory("xl/persons", options())?;
+        let p = write_persons(
+            persons.1,
+            writer,
+            FileLocation::from("xl/persons/person.xml"),
+        )?;
+        result.push(p);
+        relationships.push(CtRelationship {
+            id: persons.0,
+            ty: PERSON.0.to_string(),
+            target: String::from("persons/person.xml"),
+            target_mode: StTargetMode::Internal,
+        });
+    }
+
+    // The external links are numbered in the order of the external references.
+    let mut external_links = xl.external_links;
+    let ext_ids = xl
+        .workbook_part
+        .external_references
+        .iter()
+        .flat_map(|ers| ers.external_references.iter().map(|er| er.id.clone()))
+        .collect::<Vec<_>>();
+    if !external_links.is_empty() {
+        writer.add_directory("xl/externalLinks", options())?;
+        writer.add_directory("xl/externalLinks/_rels", options())?;
+    }
+    for (idx, id) in ext_ids.into_iter().enumerate() {
+        if let Some(link) = external_links.remove(&id) {
+            let p = write_external_link(link, writer, idx + 1)?;
+            result.push(p);
+            relationships.push(CtRelationship {
+                id,
+                ty: EXT_LINK.0.to_string(),
+                target: format!("externalLinks/externalLink{}.xml", idx + 1),
+                target_mode: StTargetMode::Internal,
+            });
+        }
+    }
+
+    if has_cell_metadata {
+        writer.start_file("xl/metadata.xml", options())?;
+        writer.write(DYNAMIC_ARRAY_METADATA.as_bytes())?;
+        result.push(WriteProof {
+            path: FileLocation::from("xl/metadata.xml"),
+            rtype: SHEET_METADATA,
+        });
+        let id = (relationships.len() + 1..)
+            .map(|i| format!("rId{}", i))
+            .find(|id| relationships.iter().all(|r| &r.id != id))
+            .unwrap();
+        relationships.push(CtRelationship {
+            id,
+            ty: SHEET_METADATA.0.to_string(),
+            target: String::from("metadata.xml"),
+            target_mode: StTargetMode::Internal,
+        });
+    }
+
     let p = write_workbook_part(
         xl.workbook_part,
         writer,
@@ -222,7 +300,7 @@ fn write_worksheet<'a>(
 ) -> ZipResult<Vec<WriteProof>> {
     let mut result = Vec::<WriteProof>::new();
     let mut relationships = Vec::<CtRelationship>::new();
-    let rid = 1_usize;
+    let mut rid = 1_usize;
 
     if let Some(comments) = wb.comments {
         let p = write_comment(
@@ -237,6 +315,22 @@ fn write_worksheet<'a>(
             target_mode: StTargetMode::Internal,
         });
         result.push(p);
+        rid += 1;
+    }
+
+    if let Some(threaded_comments) = wb.threaded_comments {
+        let p = write_threaded_comments(
+            threaded_comments,
+            writer,
+            FileLocation::from(format!("xl/threadedComments/threadedComment{}.xml", idx)),
+        )?;
+        relationships.push(CtRelationship {
+            id: format!("rId{}", rid),
+            target: format!("../threadedComments/threadedComment{}.xml", idx),
+            ty: THREADED_COMMENT.0.to_string(),
+            target_mode: StTargetMode::Internal,
+        });
+        result.push(p);
     }
 
     let proof = write_sheet_part(
@@ -255,11 +349,48 @@ fn write_worksheet<'a>(
     Ok(result)
 }
 
+fn write_external_link(
+    link: ExternalLink,
+    writer: &mut Writer,
+    idx: usize,
+) -> ZipResult<WriteProof> {
+    let rid = link
+        .external_link_part
+        .external_book
+        .as_ref()
+        .map(|b| b.id.clone())
+        .unwrap_or_default();
+    let proof = write_external_link_part(
+        link.external_link_part,
+        writer,
+        FileLocation::from(format!("xl/externalLinks/externalLink{}.xml", idx)),
+    )?;
+    let relationships = vec![CtRelationship {
+        id: rid,
+        ty: EXT_LINK_PATH.0.to_string(),
+        target: link.target,
+        target_mode: StTargetMode::External,
+    }];
+    write_relationships(
+        Relationships { relationships },
+        writer,
+        &format!("xl/externalLinks/_rels/externalLink{}.xml.rels", idx),
+    )?;
+    Ok(proof)
+}
+
 define_se_func!(write_sst, SstPart, SST);
+define_se_func!(write_external_link_part, ExternalLinkPart, EXT_LINK);
 define_se_func!(write_stylesheet, StylesheetPart, STYLE);
 define_se_func!(write_theme, ThemePart, THEME);
 
 define_se_func!(write_comment, Comments, COMMENTS);
+define_se_func!(
+    write_threaded_comments,
+    ThreadedCommentsPart,
+    THREADED_COMMENT
+);
+define_se_func!(write_persons, PersonListPart, PERSON);
 define_se_func!(write_sheet_part, WorksheetPart, WORKSHEET);
 define_se_func!(write_workbook_part, WorkbookPart, WORKBOOK);
 
@@ -368,6 +499,11 @@ fn get_content_type(rtype: RType) -> &'static str {
         STYLE => "application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml",
         EXT_LINK => "application/vnd.openxmlformats-officedocument.spreadsheetml.externalLink+xml",
         THEME => "application/vnd.openxmlformats-officedocument.theme+xml",
+        THREADED_COMMENT => "application/vnd.ms-excel.threadedcomments+xml",
+        PERSON => "application/vnd.ms-excel.person+xml",
+        SHEET_METADATA => {
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheetMetadata+xml"
+        }
         _ => unreachable!(),
     }
 }
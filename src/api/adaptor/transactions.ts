@@ -1,4 +1,4 @@
-import { Transaction, PayloadsTransaction, UndoTransaction, RedoTransaction } from '../transactions'
+import { Transaction, PayloadsTransaction, UndoTransaction, RedoTransaction, RecalculateTransaction, SetDateSystemTransaction, SetLocaleTransaction } from '../transactions'
 import { EditAction, EditPayload, StyleUpdateType } from '@/bindings'
 import { Payload, SetFont, SetBorder } from '../payloads'
 
@@ -8,6 +8,15 @@ export function adaptTransaction(t: Transaction): EditAction {
     if (t instanceof RedoTransaction) {
         return 'Redo'
     }
+    if (t instanceof RecalculateTransaction) {
+        if (t.sheetIdx === undefined)
+            return 'Recalculate'
+        return {RecalculateSheet: t.sheetIdx}
+    }
+    if (t instanceof SetDateSystemTransaction)
+        return {SetDateSystem: {date1904: t.date1904, shiftDates: t.shiftDates}}
+    if (t instanceof SetLocaleTransaction)
+        return {SetLocale: t.locale}
     if (t instanceof PayloadsTransaction) {
         const transaction = t
         const payloads: EditPayload[] = []
@@ -80,6 +89,7 @@ export function adaptPayload(p: Payload): EditPayload {
                 sheetIdx: p.sheetIdx,
                 visible: p.visible,
                 start: p.row,
+                count: p.count,
             } 
         }
     case 'setColVisible':
@@ -89,6 +99,7 @@ export function adaptPayload(p: Payload): EditPayload {
                 sheetIdx: p.sheetIdx,
                 visible: p.visible,
                 start: p.col,
+                count: p.count,
             } 
         }
     case 'insertBlockRows':
@@ -139,8 +150,83 @@ export function adaptPayload(p: Payload): EditPayload {
         return adapatSetFont(p)
     case 'setBorder':
         return adaptSetBorder(p)
-    case 'deleteSheet':
     case 'insertSheet':
+        return {
+            InsertSheet: {sheetIdx: p.sheetIdx}
+        }
+    case 'deleteSheet':
+        return {
+            DeleteSheet: {sheetIdx: p.sheetIdx}
+        }
+    case 'moveSheet':
+        return {
+            MoveSheet: {sheetIdx: p.sheetIdx, newIdx: p.newIdx}
+        }
+    case 'copySheet':
+        return {
+            CopySheet: {sheetIdx: p.sheetIdx, newIdx: p.newIdx}
+        }
+    case 'mergeCells':
+        return {
+            MergeCells: {
+                sheetIdx: p.sheetIdx,
+                startRow: p.startRow,
+                startCol: p.startCol,
+                endRow: p.endRow,
+                endCol: p.endCol,
+            }
+        }
+    case 'splitMergedCells':
+        return {
+            SplitMergedCells: {sheetIdx: p.sheetIdx, row: p.row, col: p.col}
+        }
+    case 'addComment':
+        return {
+            AddComment: {
+                sheetIdx: p.sheetIdx,
+                row: p.row,
+                col: p.col,
+                author: p.author,
+                text: p.text,
+            }
+        }
+    case 'updateComment':
+        return {
+            UpdateComment: {
+                sheetIdx: p.sheetIdx,
+                row: p.row,
+                col: p.col,
+                author: p.author,
+                text: p.text,
+            }
+        }
+    case 'deleteComment':
+        return {
+            DeleteComment: {
+                sheetIdx: p.sheetIdx,
+                row: p.row,
+                col: p.col,
+            }
+        }
+    case 'replyComment':
+        return {
+            ReplyComment: {
+                sheetIdx: p.sheetIdx,
+                row: p.row,
+                col: p.col,
+                author: p.author,
+                text: p.text,
+            }
+        }
+    case 'resolveComment':
+        return {
+            ResolveComment: {
+                sheetIdx: p.sheetIdx,
+                row: p.row,
+                col: p.col,
+                resolved: p.resolved,
+            }
+        }
     default:
         throw Error(`unimplemented: ${p}`)
     }
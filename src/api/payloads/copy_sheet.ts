@@ -0,0 +1,27 @@
+export interface CopySheet {
+    type: 'copySheet'
+    sheetIdx: number
+    newIdx: number
+}
+
+export class CopySheetBuilder {
+    private _sheetIdx?: number
+    private _newIdx?: number
+    public sheetIdx(sheetIdx: number): this {
+        this._sheetIdx = sheetIdx
+        return this
+    }
+    public newIdx(newIdx: number): this {
+        this._newIdx = newIdx
+        return this
+    }
+    public build(): CopySheet {
+        if (this._sheetIdx === undefined) throw Error('sheetIdx is undefined!')
+        if (this._newIdx === undefined) throw Error('newIdx is undefined!')
+        return {
+            type: 'copySheet',
+            sheetIdx: this._sheetIdx,
+            newIdx: this._newIdx,
+        }
+    }
+}
\ No newline at end of file
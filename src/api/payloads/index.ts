@@ -18,13 +18,25 @@ import {SetRowVisible} from './set_row_visible'
 import {SheetRename} from './sheet_rename'
 import {DeleteSheet} from './delete_sheet'
 import {InsertSheet} from './insert_sheet'
+import {MoveSheet} from './move_sheet'
+import {CopySheet} from './copy_sheet'
+import {MergeCells} from './merge_cells'
+import {SplitMergedCells} from './split_merged_cells'
+import {AddComment} from './add_comment'
+import {UpdateComment} from './update_comment'
+import {DeleteComment} from './delete_comment'
+import {ReplyComment} from './reply_comment'
+import {ResolveComment} from './resolve_comment'
 
 
+export * from './add_comment'
 export * from './cell_input'
+export * from './copy_sheet'
 export * from './create_block'
 export * from './delete_block_cols'
 export * from './delete_block_rows'
 export * from './delete_cols'
+export * from './delete_comment'
 export * from './delete_rows'
 export * from './delete_sheet'
 export * from './insert_block_cols'
@@ -32,7 +44,11 @@ export * from './insert_block_rows'
 export * from './insert_cols'
 export * from './insert_rows'
 export * from './insert_sheet'
+export * from './merge_cells'
 export * from './move_block'
+export * from './move_sheet'
+export * from './reply_comment'
+export * from './resolve_comment'
 export * from './set_border'
 export * from './set_col_visible'
 export * from './set_col_width'
@@ -40,6 +56,8 @@ export * from './set_font'
 export * from './set_row_height'
 export * from './set_row_visible'
 export * from './sheet_rename'
+export * from './split_merged_cells'
+export * from './update_comment'
 export type Payload =
     | CreateBlock
     | DeleteBlockCols
@@ -60,4 +78,13 @@ export type Payload =
     | SheetRename
     | CellInput
     | InsertSheet
-    | DeleteSheet
\ No newline at end of file
+    | DeleteSheet
+    | MoveSheet
+    | CopySheet
+    | MergeCells
+    | SplitMergedCells
+    | AddComment
+    | UpdateComment
+    | DeleteComment
+    | ReplyComment
+    | ResolveComment
\ No newline at end of file
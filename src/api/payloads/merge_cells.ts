@@ -0,0 +1,51 @@
+export interface MergeCells {
+    type: 'mergeCells'
+    sheetIdx: number
+    startRow: number
+    startCol: number
+    endRow: number
+    endCol: number
+}
+
+export class MergeCellsBuilder {
+    private _sheetIdx?: number
+    private _startRow?: number
+    private _startCol?: number
+    private _endRow?: number
+    private _endCol?: number
+    public sheetIdx(sheetIdx: number): this {
+        this._sheetIdx = sheetIdx
+        return this
+    }
+    public startRow(startRow: number): this {
+        this._startRow = startRow
+        return this
+    }
+    public startCol(startCol: number): this {
+        this._startCol = startCol
+        return this
+    }
+    public endRow(endRow: number): this {
+        this._endRow = endRow
+        return this
+    }
+    public endCol(endCol: number): this {
+        this._endCol = endCol
+        return this
+    }
+    public build(): MergeCells {
+        if (this._sheetIdx === undefined) throw Error('sheetIdx is undefined!')
+        if (this._startRow === undefined) throw Error('startRow is undefined!')
+        if (this._startCol === undefined) throw Error('startCol is undefined!')
+        if (this._endRow === undefined) throw Error('endRow is undefined!')
+        if (this._endCol === undefined) throw Error('endCol is undefined!')
+        return {
+            type: 'mergeCells',
+            sheetIdx: this._sheetIdx,
+            startRow: this._startRow,
+            startCol: this._startCol,
+            endRow: this._endRow,
+            endCol: this._endCol,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+export interface ResolveComment {
+    type: 'resolveComment'
+    sheetIdx: number
+    row: number
+    col: number
+    resolved: boolean
+}
+
+export class ResolveCommentBuilder {
+    private _sheetIdx?: number
+    private _row?: number
+    private _col?: number
+    private _resolved?: boolean
+    public sheetIdx(sheetIdx: number): this {
+        this._sheetIdx = sheetIdx
+        return this
+    }
+    public row(row: number): this {
+        this._row = row
+        return this
+    }
+    public col(col: number): this {
+        this._col = col
+        return this
+    }
+    public resolved(resolved: boolean): this {
+        this._resolved = resolved
+        return this
+    }
+    public build(): ResolveComment {
+        if (this._sheetIdx === undefined) throw Error('sheetIdx is undefined!')
+        if (this._row === undefined) throw Error('row is undefined!')
+        if (this._col === undefined) throw Error('col is undefined!')
+        if (this._resolved === undefined) throw Error('resolved is undefined!')
+        return {
+            type: 'resolveComment',
+            sheetIdx: this._sheetIdx,
+            row: this._row,
+            col: this._col,
+            resolved: this._resolved,
+        }
+    }
+}
\ No newline at end of file
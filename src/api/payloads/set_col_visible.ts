@@ -2,12 +2,14 @@ export interface SetColVisible {
     readonly type: 'setColVisible'
     readonly sheetIdx: number
     readonly col: number
+    readonly count: number
     readonly visible: boolean
 }
 
 export class SetColVisibleBuilder {
     private  _sheetIdx?: number
     private  _col?: number
+    private  _count = 1
     private  _visible?: boolean
     public sheetIdx(sheetIdx: number): this {
         this._sheetIdx = sheetIdx
@@ -17,6 +19,10 @@ export class SetColVisibleBuilder {
         this._col = col
         return this
     }
+    public count(count: number): this {
+        this._count = count
+        return this
+    }
     public visible(visible: boolean): this {
         this._visible = visible
         return this
@@ -29,6 +35,7 @@ export class SetColVisibleBuilder {
             type: 'setColVisible',
             sheetIdx: this._sheetIdx,
             col: this._col,
+            count: this._count,
             visible: this._visible,
         }
     }
@@ -3,12 +3,14 @@ export interface SetRowVisible {
     readonly type: 'setRowVisible'
     readonly sheetIdx: number
     readonly row: number
+    readonly count: number
     readonly visible: boolean
 }
 
 export class SetRowVisibleBuilder {
     private  _sheetIdx?: number
     private  _row?: number
+    private  _count = 1
     private  _visible?: boolean
     public sheetIdx(sheetIdx: number): this {
         this._sheetIdx = sheetIdx
@@ -18,6 +20,10 @@ export class SetRowVisibleBuilder {
         this._row = row
         return this
     }
+    public count(count: number): this {
+        this._count = count
+        return this
+    }
     public visible(visible: boolean): this {
         this._visible = visible
         return this
@@ -30,6 +36,7 @@ export class SetRowVisibleBuilder {
             type: 'setRowVisible',
             sheetIdx: this._sheetIdx,
             row: this._row,
+            count: this._count,
             visible: this._visible,
         }
     }
@@ -0,0 +1,51 @@
+export interface UpdateComment {
+    type: 'updateComment'
+    sheetIdx: number
+    row: number
+    col: number
+    author: string
+    text: string
+}
+
+export class UpdateCommentBuilder {
+    private _sheetIdx?: number
+    private _row?: number
+    private _col?: number
+    private _author?: string
+    private _text?: string
+    public sheetIdx(sheetIdx: number): this {
+        this._sheetIdx = sheetIdx
+        return this
+    }
+    public row(row: number): this {
+        this._row = row
+        return this
+    }
+    public col(col: number): this {
+        this._col = col
+        return this
+    }
+    public author(author: string): this {
+        this._author = author
+        return this
+    }
+    public text(text: string): this {
+        this._text = text
+        return this
+    }
+    public build(): UpdateComment {
+        if (this._sheetIdx === undefined) throw Error('sheetIdx is undefined!')
+        if (this._row === undefined) throw Error('row is undefined!')
+        if (this._col === undefined) throw Error('col is undefined!')
+        if (this._author === undefined) throw Error('author is undefined!')
+        if (this._text === undefined) throw Error('text is undefined!')
+        return {
+            type: 'updateComment',
+            sheetIdx: this._sheetIdx,
+            row: this._row,
+            col: this._col,
+            author: this._author,
+            text: this._text,
+        }
+    }
+}
\ No newline at end of file
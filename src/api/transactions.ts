@@ -1,3 +1,4 @@
+import { Locale } from '@/bindings'
 import { Payload } from './payloads'
 
 export class UndoTransaction {
@@ -10,6 +11,33 @@ export class RedoTransaction {
 }
 
 
+// Calculate the stale cells in the manual calculation mode. Only the sheet
+// at `sheetIdx` is calculated if it is given.
+export class RecalculateTransaction {
+    public constructor(public readonly sheetIdx?: number) {}
+
+    public static type = 'recalculate'
+}
+
+// Switch to the 1904 date system or back. The numbers displayed as dates are
+// shifted to keep their dates if `shiftDates` is true.
+export class SetDateSystemTransaction {
+    public constructor(
+        public readonly date1904: boolean,
+        public readonly shiftDates: boolean,
+    ) {}
+
+    public static type = 'setDateSystem'
+}
+
+// Change the separators, the date order and the currency symbols used to
+// recognize the numbers and dates typed into cells.
+export class SetLocaleTransaction {
+    public constructor(public readonly locale: Locale) {}
+
+    public static type = 'setLocale'
+}
+
 export class PayloadsTransaction {
     public constructor(
         payloads: Payload[],
@@ -30,4 +58,7 @@ export class PayloadsTransaction {
 export type Transaction =
     | UndoTransaction
     | RedoTransaction
+    | RecalculateTransaction
+    | SetDateSystemTransaction
+    | SetLocaleTransaction
     | PayloadsTransaction
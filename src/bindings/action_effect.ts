@@ -1,4 +1,9 @@
 import type { CellId } from "./cell_id"
+import type { CycleCell } from "./cycle_cell"
+import type { FormulaRejection } from "./formula_rejection"
+import type { InvalidBlock } from "./invalid_block"
+import type { InvalidMerge } from "./invalid_merge"
+import type { InvalidName } from "./invalid_name"
 import type { Task } from "./task"
 
-export interface ActionEffect { sheets: Array<number>, async_tasks: Array<Task>, dirtys: Array<[number, CellId]>, }
\ No newline at end of file
+export interface ActionEffect { sheets: Array<number>, async_tasks: Array<Task>, dirtys: Array<[number, CellId]>, cycles: Array<Array<CycleCell>>, rejections: Array<FormulaRejection>, invalid_names: Array<InvalidName>, invalid_blocks: Array<InvalidBlock>, invalid_merges: Array<InvalidMerge>, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface ArgCount { le: number | null, ge: number | null, eq: number | null, odd: boolean | null, even: boolean | null, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArgCount } from "../../../src/bindings/arg_count";
+
+export interface ArgCountError { name: string, count: number, expected: ArgCount, }
\ No newline at end of file
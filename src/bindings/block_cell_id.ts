@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface BlockCellId { blockId: number, row: number, col: number, }
\ No newline at end of file
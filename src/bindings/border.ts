@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { BorderPr } from "./border_pr";
 
 export interface Border { left: BorderPr | null, right: BorderPr | null, top: BorderPr | null, bottom: BorderPr | null, diagonal: BorderPr | null, vertical: BorderPr | null, horizontal: BorderPr | null, diagonalUp: boolean | null, diagonalDown: boolean | null, outline: boolean, }
\ No newline at end of file
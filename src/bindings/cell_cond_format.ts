@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Color } from "./color";
+import type { DataBar } from "./data_bar";
+import type { DiffStyle } from "./diff_style";
+import type { Icon } from "./icon";
+
+export interface CellCondFormat { row: number, col: number, style: DiffStyle | null, color: Color | null, dataBar: DataBar | null, icon: Icon | null, }
\ No newline at end of file
@@ -1,3 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Color } from "./color";
 import type { Value } from "./value";
 
-export interface CellFormulaValue { row: number, col: number, formula: string, value: Value, }
\ No newline at end of file
+export interface CellFormulaValue { row: number, col: number, formula: string, value: Value, formattedText: string, color: Color | null, }
\ No newline at end of file
@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { BlockCellId } from "./block_cell_id";
 import type { NormalCellId } from "./normal_cell_id";
 
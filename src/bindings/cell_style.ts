@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Style } from "./style";
 
 export interface CellStyle { row: number, col: number, style: Style, }
\ No newline at end of file
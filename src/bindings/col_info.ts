@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface ColInfo { idx: number, width: number, hidden: boolean, }
\ No newline at end of file
@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface Color { red: number | null, green: number | null, blue: number | null, alpha: number | null, }
\ No newline at end of file
@@ -1,2 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommentReply } from "./comment_reply";
 
-export interface Comment { row: number, col: number, author: string, content: string, }
\ No newline at end of file
+export interface Comment { row: number, col: number, author: string, content: string, replies: Array<CommentReply>, resolved: boolean, }
\ No newline at end of file
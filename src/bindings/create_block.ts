@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface CreateBlock { sheetIdx: number, id: number, masterRow: number, masterCol: number, rowCnt: number, colCnt: number, }
\ No newline at end of file
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArgCountError } from "./arg_count_error";
+import type { ArgError } from "./arg_error";
+import type { InvalidSheetName } from "./invalid_sheet_name";
+import type { SyntaxError } from "./syntax_error";
+import type { UnknownFunction } from "./unknown_function";
+
+export type DiagnosticKind = { syntax: SyntaxError } | "unmatchedParenthesis" | { unknownFunction: UnknownFunction } | { argCount: ArgCountError } | { arg: ArgError } | { invalidSheetName: InvalidSheetName };
\ No newline at end of file
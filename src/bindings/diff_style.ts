@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Border } from "./border";
+import type { Fill } from "./fill";
+import type { Font } from "./font";
+
+export interface DiffStyle { font: Font | null, fill: Fill | null, border: Border | null, formatter: string | null, }
\ No newline at end of file
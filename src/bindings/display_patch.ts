@@ -1,10 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DefinedNames } from "./defined_names";
 import type { SheetBlocks } from "./sheet_blocks";
 import type { SheetColInfo } from "./sheet_col_info";
 import type { SheetComments } from "./sheet_comments";
+import type { SheetCondFormats } from "./sheet_cond_formats";
 import type { SheetMergeCells } from "./sheet_merge_cells";
 import type { SheetNames } from "./sheet_names";
 import type { SheetRowInfo } from "./sheet_row_info";
+import type { SheetStaleCells } from "./sheet_stale_cells";
 import type { SheetStyles } from "./sheet_styles";
 import type { SheetValues } from "./sheet_values";
 
-export type DisplayPatch = { values: SheetValues } | { styles: SheetStyles } | { rowInfo: SheetRowInfo } | { colInfo: SheetColInfo } | { mergeCells: SheetMergeCells } | { comments: SheetComments } | { blocks: SheetBlocks } | { sheetNames: SheetNames };
\ No newline at end of file
+export type DisplayPatch = { values: SheetValues } | { styles: SheetStyles } | { rowInfo: SheetRowInfo } | { colInfo: SheetColInfo } | { mergeCells: SheetMergeCells } | { comments: SheetComments } | { blocks: SheetBlocks } | { sheetNames: SheetNames } | { names: DefinedNames } | { staleCells: SheetStaleCells } | { condFormats: SheetCondFormats };
\ No newline at end of file
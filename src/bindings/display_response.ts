@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { DisplayPatch } from "./display_patch";
 
 export interface DisplayResponse { patches: Array<DisplayPatch>, }
\ No newline at end of file
@@ -1,3 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Locale } from "../../../src/bindings/locale";
 import type { PayloadsAction } from "./payloads_action";
+import type { SetDateSystem } from "./set_date_system";
 
-export type EditAction = "Undo" | "Redo" | { Payloads: PayloadsAction };
\ No newline at end of file
+export type EditAction = "Undo" | "Redo" | { Payloads: PayloadsAction } | "Recalculate" | { RecalculateSheet: number } | { SetDateSystem: SetDateSystem } | { SetLocale: Locale };
\ No newline at end of file
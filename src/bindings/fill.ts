@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { GradientFill } from "./gradient_fill";
 import type { PatternFill } from "./pattern_fill";
 
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RejectReason } from "./reject_reason";
+
+export interface FormulaRejection { sheetIdx: number, row: number, col: number, name: string | null, funcName: string, reason: RejectReason, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArgCount } from "./arg_count";
+import type { ArgDef } from "./arg_def";
+import type { ReturnKind } from "./return_kind";
+
+export interface FuncSignature { name: string, argCount: ArgCount, args: Array<ArgDef>, volatile: boolean, returnKind: ReturnKind, description: string, }
\ No newline at end of file
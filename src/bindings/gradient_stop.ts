@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Color } from "./color";
 
 export interface GradientStop { color: Color, position: number, }
\ No newline at end of file
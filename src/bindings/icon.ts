@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StIconSetType } from "../../../src/bindings/st_icon_set_type";
+
+export interface Icon { iconSet: StIconSetType, idx: number, showValue: boolean, }
\ No newline at end of file
@@ -1,4 +1,8 @@
 export * from './action_effect'
+export * from './arg_count'
+export * from './arg_count_error'
+export * from './arg_error'
+export * from './arg_def'
 export * from './block_cell_id'
 export * from './block_info'
 export * from './block_input'
@@ -6,6 +10,7 @@ export * from './block_style_update'
 export * from './border'
 export * from './border_pr'
 export * from './cell_alignment'
+export * from './cell_cond_format'
 export * from './cell_formula_value'
 export * from './cell_id'
 export * from './cell_input'
@@ -16,6 +21,10 @@ export * from './col_shift'
 export * from './color'
 export * from './comment'
 export * from './create_block'
+export * from './cycle_cell'
+export * from './data_bar'
+export * from './diagnostic_kind'
+export * from './diff_style'
 export * from './display_patch'
 export * from './display_request'
 export * from './display_response'
@@ -26,10 +35,17 @@ export * from './font_family'
 export * from './font_name'
 export * from './font_scheme'
 export * from './font_size'
+export * from './formula_diagnostic'
+export * from './formula_rejection'
+export * from './func_signature'
 export * from './gradient_fill'
 export * from './gradient_stop'
+export * from './icon'
 export * from './index'
 export * from './int_property'
+export * from './invalid_name'
+export * from './name_conflict'
+export * from './invalid_sheet_name'
 export * from './line_shift_in_block'
 export * from './merge_cell'
 export * from './move_block'
@@ -37,13 +53,17 @@ export * from './normal_cell_id'
 export * from './pattern_fill'
 export * from './payload'
 export * from './payloads_action'
+export * from './reject_reason'
+export * from './return_kind'
 export * from './row_info'
 export * from './row_shift'
 export * from './set_col_width'
+export * from './set_date_system'
 export * from './set_row_height'
 export * from './sheet_blocks'
 export * from './sheet_col_info'
 export * from './sheet_comments'
+export * from './sheet_cond_formats'
 export * from './sheet_merge_cells'
 export * from './sheet_row_info'
 export * from './sheet_styles'
@@ -52,6 +72,7 @@ export * from './st_border_style'
 export * from './st_font_scheme'
 export * from './st_gradient_type'
 export * from './st_horizontal_alignment'
+export * from './st_icon_set_type'
 export * from './st_pattern_type'
 export * from './st_underline_values'
 export * from './st_vertical_align_run'
@@ -59,7 +80,19 @@ export * from './st_vertical_alignment'
 export * from './style'
 export * from './style_update'
 export * from './style_update_type'
+export * from './syntax_error'
 export * from './task'
+export * from './trace_external'
+export * from './trace_name'
+export * from './trace_node'
+export * from './trace_range'
+export * from './trace_vertex'
 export * from './underline_property'
+export * from './unknown_function'
 export * from './value'
 export * from './vertical_align_font_property'
+export * from './date_order'
+export * from './locale'
+export * from './block_conflict'
+export * from './invalid_block'
+export * from './invalid_merge'
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockConflict } from "./block_conflict";
+
+export interface InvalidBlock { sheetIdx: number, id: number, reason: BlockConflict, }
\ No newline at end of file
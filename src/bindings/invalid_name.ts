@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NameConflict } from "./name_conflict";
+
+export interface InvalidName { name: string, sheetIdx: number | null, reason: NameConflict, }
\ No newline at end of file
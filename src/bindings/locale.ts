@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DateOrder } from "./date_order";
+
+export interface Locale { decimalSeparator: string, groupSeparator: string, dateOrder: DateOrder, currencySymbols: Array<string>, }
\ No newline at end of file
@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface MoveBlock { sheetIdx: number, id: number, newMasterRow: number, newMasterCol: number, }
\ No newline at end of file
@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface NormalCellId { row: number, col: number, followRow: number | null, followCol: number | null, }
\ No newline at end of file
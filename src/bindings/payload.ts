@@ -1,15 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AddComment } from "./add_comment";
 import type { BlockInput } from "./block_input";
 import type { BlockStyleUpdate } from "./block_style_update";
 import type { CellInput } from "./cell_input";
 import type { ColShift } from "./col_shift";
+import type { CopySheet } from "./copy_sheet";
 import type { CreateBlock } from "./create_block";
+import type { DefineName } from "./define_name";
+import type { DeleteComment } from "./delete_comment";
+import type { DeleteName } from "./delete_name";
+import type { DeleteSheet } from "./delete_sheet";
+import type { InsertSheet } from "./insert_sheet";
 import type { LineShiftInBlock } from "./line_shift_in_block";
+import type { MergeCells } from "./merge_cells";
 import type { MoveBlock } from "./move_block";
+import type { MoveSheet } from "./move_sheet";
+import type { RenameName } from "./rename_name";
+import type { ReplyComment } from "./reply_comment";
+import type { ResolveComment } from "./resolve_comment";
 import type { RowShift } from "./row_shift";
 import type { SetColWidth } from "./set_col_width";
 import type { SetRowHeight } from "./set_row_height";
 import type { SetVisible } from "./set_visible";
 import type { SheetRename } from "./sheet_rename";
+import type { SplitMergedCells } from "./split_merged_cells";
 import type { StyleUpdate } from "./style_update";
+import type { UpdateComment } from "./update_comment";
 
-export type EditPayload = { BlockInput: BlockInput } | { BlockStyleUpdate: BlockStyleUpdate } | { CellInput: CellInput } | { ColShift: ColShift } | { CreateBlock: CreateBlock } | { LineShiftInBlock: LineShiftInBlock } | { MoveBlock: MoveBlock } | { RowShift: RowShift } | { SetColWidth: SetColWidth } | { SetRowHeight: SetRowHeight } | { StyleUpdate: StyleUpdate } | { SheetRename: SheetRename } | { SetVisible: SetVisible };
\ No newline at end of file
+export type EditPayload = { BlockInput: BlockInput } | { BlockStyleUpdate: BlockStyleUpdate } | { CellInput: CellInput } | { ColShift: ColShift } | { CreateBlock: CreateBlock } | { LineShiftInBlock: LineShiftInBlock } | { MoveBlock: MoveBlock } | { RowShift: RowShift } | { SetColWidth: SetColWidth } | { SetRowHeight: SetRowHeight } | { StyleUpdate: StyleUpdate } | { SheetRename: SheetRename } | { InsertSheet: InsertSheet } | { DeleteSheet: DeleteSheet } | { MoveSheet: MoveSheet } | { CopySheet: CopySheet } | { SetVisible: SetVisible } | { MergeCells: MergeCells } | { SplitMergedCells: SplitMergedCells } | { AddComment: AddComment } | { UpdateComment: UpdateComment } | { DeleteComment: DeleteComment } | { ReplyComment: ReplyComment } | { ResolveComment: ResolveComment } | { DefineName: DefineName } | { DeleteName: DeleteName } | { RenameName: RenameName };
\ No newline at end of file
@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { EditPayload } from "./payload";
 
 export interface PayloadsAction { payloads: Array<EditPayload>, undoable: boolean, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArgCount } from "../../../src/bindings/arg_count";
+
+export type RejectReason = { argCount: ArgCount } | { arg: number };
\ No newline at end of file
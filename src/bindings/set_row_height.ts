@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export interface SetRowHeight { sheetIdx: number, row: number, height: number, }
\ No newline at end of file
@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export interface SetVisible { isRow: boolean, sheetIdx: number, start: number, visible: boolean, }
\ No newline at end of file
+export interface SetVisible { isRow: boolean, sheetIdx: number, start: number, count: number, visible: boolean, }
\ No newline at end of file
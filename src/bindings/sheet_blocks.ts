@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { BlockInfo } from "./block_info";
 
 export interface SheetBlocks { sheetIdx: number, blocks: Array<BlockInfo>, }
\ No newline at end of file
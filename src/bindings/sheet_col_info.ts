@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ColInfo } from "./col_info";
 
 export interface SheetColInfo { sheetIdx: number, info: Array<ColInfo>, defaultWidth: number, }
\ No newline at end of file
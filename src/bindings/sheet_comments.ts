@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Comment } from "./comment";
 
 export interface SheetComments { sheetIdx: number, comments: Array<Comment>, }
\ No newline at end of file
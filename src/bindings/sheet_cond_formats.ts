@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CellCondFormat } from "./cell_cond_format";
+
+export interface SheetCondFormats { sheetIdx: number, cells: Array<CellCondFormat>, }
\ No newline at end of file
@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { MergeCell } from "./merge_cell";
 
 export interface SheetMergeCells { sheetIdx: number, mergeCells: Array<MergeCell>, }
\ No newline at end of file
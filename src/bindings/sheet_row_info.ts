@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { RowInfo } from "./row_info";
 
 export interface SheetRowInfo { sheetIdx: number, info: Array<RowInfo>, defaultHeight: number, }
\ No newline at end of file
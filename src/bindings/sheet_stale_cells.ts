@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StaleCell } from "./stale_cell";
+
+export interface SheetStaleCells { sheetIdx: number, cells: Array<StaleCell>, }
\ No newline at end of file
@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CellStyle } from "./cell_style";
 
 export interface SheetStyles { sheetIdx: number, styles: Array<CellStyle>, }
\ No newline at end of file
@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CellFormulaValue } from "./cell_formula_value";
 
 export interface SheetValues { sheetIdx: number, values: Array<CellFormulaValue>, }
\ No newline at end of file
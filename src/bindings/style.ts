@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Border } from "./border";
 import type { CtCellAlignment } from "../../../src/bindings/cell_alignment";
 import type { CtCellProtection } from "../../../src/bindings/cell_protection";
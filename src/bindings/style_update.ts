@@ -1,3 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { StyleUpdateType } from "./style_update_type";
 
 export interface StyleUpdate { sheetIdx: number, row: number, col: number, ty: StyleUpdateType, }
\ No newline at end of file
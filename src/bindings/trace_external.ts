@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface TraceExternal { workbook: string, fromSheet: string, toSheet: string, rowStart: number, colStart: number, rowEnd: number, colEnd: number, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TraceVertex } from "./trace_vertex";
+
+export interface TraceNode { depth: number, vertex: TraceVertex, }
\ No newline at end of file
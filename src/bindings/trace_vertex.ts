@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TraceExternal } from "./trace_external";
+import type { TraceName } from "./trace_name";
+import type { TraceRange } from "./trace_range";
+
+export type TraceVertex = { range: TraceRange } | { name: TraceName } | { external: TraceExternal };
\ No newline at end of file
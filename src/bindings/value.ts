@@ -1,2 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export type Value = { str: string } | { bool: boolean } | { number: number } | { error: string } | "empty";
\ No newline at end of file
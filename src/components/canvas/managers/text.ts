@@ -4,17 +4,19 @@ import { StandardKeyboardEvent } from '@/common/events'
 import { useRef, useState } from 'react'
 import { shallowCopy } from '@/common'
 import { StartCellEvent } from './start-cell'
-import initFc, { formula_check } from '../../../wasms/fc/pkg'
+import initFc, { formula_diagnose } from '../../../wasms/fc/pkg'
 import { isFormula } from '@/core/snippet'
 import { SheetService } from '@/core/data'
 import { useInjection } from '@/core/ioc/provider'
 import { TYPES } from '@/core/ioc/types'
+import { FormulaDiagnostic } from '@/bindings'
 
 export const useText = () => {
     const SHEET_SERVICE = useInjection<SheetService>(TYPES.Sheet)
     const [editing, setEditing] = useState(false)
     const [context, setContext] = useState<Context<Cell>>()
     const [validFormulaOpen, setValidFormulaOpen] = useState(false)
+    const [diagnostics, setDiagnostics] = useState<readonly FormulaDiagnostic[]>([])
     const currText = useRef('')
 
     const _lastMouseDownTime = useRef(0)
@@ -38,7 +40,10 @@ export const useText = () => {
         if (!isFormula(formula))
             return true
         await initFc()
-        const checked = formula_check(formula)
+        const sheetNames = SHEET_SERVICE.getSheets().map(s => s.name)
+        const result = formula_diagnose(formula, sheetNames, []) as FormulaDiagnostic[]
+        setDiagnostics(result)
+        const checked = result.length === 0
         setValidFormulaOpen(!checked)
         return checked
     }
@@ -112,6 +117,7 @@ export const useText = () => {
         currText,
         setValidFormulaOpen,
         validFormulaOpen,
+        diagnostics,
         checkFormula,
         init,
         blur,
@@ -1,5 +1,5 @@
 import { Observable, ReplaySubject, Subject } from 'rxjs'
-import { DisplayPatch } from '@/bindings'
+import { DisplayPatch, FuncSignature, TraceNode } from '@/bindings'
 import { ClientSend, ServerSend } from '@/message'
 import { Payload, PayloadsTransaction, adaptTransaction } from '@/api'
 import { hasOwnProperty } from '@/common'
@@ -26,6 +26,13 @@ export class Backend {
     get sheetUpdated$(): Observable<number[]> {
         return this._sheetUpdated$
     }
+
+    get funcSignatures$(): Observable<readonly FuncSignature[]> {
+        return this._funcSignatures$
+    }
+    get traceNodes$(): Observable<readonly TraceNode[]> {
+        return this._traceNodes$
+    }
     send$ = new ReplaySubject<Blob>(5)
     handleResponse(msg: ServerSend) {
         console.log(`standalone: ${STAND_ALONE}, response`, msg)
@@ -47,6 +54,10 @@ export class Backend {
     private _render$ = new Subject<void>()
     // Sever tolds the client that these sheets are dirty.
     private _sheetUpdated$ = new Subject<number[]>()
+    // The signatures of the builtin functions, used in the formula editor.
+    private _funcSignatures$ = new ReplaySubject<readonly FuncSignature[]>(1)
+    // The precedents or dependents of the cell asked by the last trace request.
+    private _traceNodes$ = new Subject<readonly TraceNode[]>()
     private _wasmSvc = new StandAloneService([])
     private _handleServerSend(serverSend: ServerSend) {
         if (serverSend.$case === 'displayResponse') {
@@ -61,6 +72,10 @@ export class Backend {
             const sheetUpdated = serverSend.actionEffect.sheets
             console.log('ws: sheet updated', sheetUpdated)
             this._sheetUpdated$.next(sheetUpdated)
+        } else if (serverSend.$case === 'funcSignatures') {
+            this._funcSignatures$.next(serverSend.funcSignatures)
+        } else if (serverSend.$case === 'traceNodes') {
+            this._traceNodes$.next(serverSend.traceNodes)
         }
     }
 
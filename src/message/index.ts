@@ -1,17 +1,30 @@
-import {ActionEffect, DisplayRequest, DisplayResponse, EditAction} from '../bindings'
+import {ActionEffect, DisplayRequest, DisplayResponse, EditAction, FuncSignature, TraceNode} from '../bindings'
 export type ClientSend = 
     | {$case: 'transaction'; transaction: EditAction}
     | {$case: 'displayRequest'; displayRequest: DisplayRequest}
     | {$case: 'openFile'; openFile: OpenFile}
+    | {$case: 'funcSignaturesRequest'}
+    | {$case: 'traceRequest'; traceRequest: TraceRequest}
 
 export interface OpenFile {
     name: string,
     content: Uint8Array,
 }
 
+// Trace the precedents of the cell, or its dependents if `dependents` is true.
+export interface TraceRequest {
+    sheetIdx: number,
+    row: number,
+    col: number,
+    depth: number,
+    dependents: boolean,
+}
+
 export type ServerSend =
     | {$case: 'displayResponse'; displayResponse: DisplayResponse}
     | {$case: 'actionEffect'; actionEffect: ActionEffect}
+    | {$case: 'funcSignatures'; funcSignatures: readonly FuncSignature[]}
+    | {$case: 'traceNodes'; traceNodes: readonly TraceNode[]}
 
 export interface OpenFile {
     fileId: string;
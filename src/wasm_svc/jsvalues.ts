@@ -1,9 +1,15 @@
+import {CycleCell, FormulaRejection, InvalidBlock, InvalidMerge, InvalidName} from '@/bindings'
 
 export interface TransactionEndResult {
     readonly code: TransactionCode
     readonly tasks: Task[]
     readonly asyncId: number
     readonly sheetIdx: number[]
+    readonly cycles: CycleCell[][]
+    readonly rejections: FormulaRejection[]
+    readonly invalidNames: InvalidName[]
+    readonly invalidBlocks: InvalidBlock[]
+    readonly invalidMerges: InvalidMerge[]
 }
 
 export const enum TransactionCode {
@@ -1,22 +1,37 @@
 import { Subject, ReplaySubject } from 'rxjs'
 // import { ClientSend, DisplayRequest, DisplayResponse, OpenFile, Payload, ServerSend, ShiftType, Transaction } from '../proto/message'
-import { DisplayRequest, DisplayResponse, EditAction as Transaction, EditPayload as Payload } from '@/bindings'
-import { ClientSend, ServerSend, OpenFile } from '@/message'
+import { DisplayRequest, DisplayResponse, EditAction as Transaction, EditPayload as Payload, FuncSignature, TraceNode } from '@/bindings'
+import { ClientSend, ServerSend, OpenFile, TraceRequest } from '@/message'
 import initWasm, {
     read_file,
+    recalculate,
+    recalculate_sheet,
+    set_date_system,
+    set_locale,
+    add_comment,
     block_input,
     cell_input,
     col_delete,
     create_block,
+    delete_comment,
+    get_all_func_signatures,
     get_patches,
     input_async_result,
+    merge_cells,
     move_block,
     redo,
+    reply_comment,
+    resolve_comment,
     row_delete,
     row_insert,
+    set_visible,
+    split_merged_cells,
+    trace_dependents,
+    trace_precedents,
     transaction_end,
     transaction_start,
     undo,
+    update_comment,
     ReadFileResult,
     col_insert,
 } from '../wasms/server/pkg'
@@ -45,7 +60,7 @@ export class Service {
             const r = input_async_result(res) as TransactionEndResult
             const serverSend: ServerSend = {
                 $case: 'actionEffect',
-                actionEffect: {sheets: r.sheetIdx, async_tasks: [], dirtys: []} // todo!()
+                actionEffect: {sheets: r.sheetIdx, async_tasks: [], dirtys: [], cycles: r.cycles, rejections: r.rejections, invalid_names: r.invalidNames, invalid_blocks: r.invalidBlocks, invalid_merges: r.invalidMerges} // todo!()
             }
             this.output$.next(serverSend)
         })
@@ -59,6 +74,10 @@ export class Service {
             return this._execDisplayReq(clientSend.displayRequest)
         else if (clientSend.$case === 'openFile')
             return this._execOpenFile(clientSend.openFile)
+        else if (clientSend.$case === 'funcSignaturesRequest')
+            return this._execFuncSignaturesReq()
+        else if (clientSend.$case === 'traceRequest')
+            return this._execTraceReq(clientSend.traceRequest)
         else
             throw Error(`Not support ${clientSend}`)
     }
@@ -68,12 +87,25 @@ export class Service {
         if (r === ReadFileResult.Ok) {
             return {
                 $case: 'actionEffect',
-                actionEffect: {sheets: [], async_tasks: [], dirtys: []},
+                actionEffect: {sheets: [], async_tasks: [], dirtys: [], cycles: [], rejections: [], invalid_names: [], invalid_blocks: [], invalid_merges: []},
             }
         }
         throw Error('read file Error!')
     }
 
+    private _execFuncSignaturesReq(): ServerSend {
+        const funcSignatures = get_all_func_signatures() as FuncSignature[]
+        return { $case: 'funcSignatures', funcSignatures }
+    }
+
+    private _execTraceReq(req: TraceRequest): ServerSend {
+        const {sheetIdx, row, col, depth} = req
+        const traceNodes = (req.dependents
+            ? trace_dependents(sheetIdx, row, col, depth)
+            : trace_precedents(sheetIdx, row, col, depth)) as TraceNode[]
+        return { $case: 'traceNodes', traceNodes }
+    }
+
     private _execDisplayReq(req: DisplayRequest): ServerSend {
         const displayResponse = get_patches(req.sheetIdx, req.version) as DisplayResponse
         return { $case: 'displayResponse', displayResponse}
@@ -84,6 +116,16 @@ export class Service {
             return this._execUndo()
         if (transaction === 'Redo')
             return this._execRedo()
+        if (transaction === 'Recalculate')
+            return this._execRecalculate(recalculate())
+        if (hasOwnProperty(transaction, 'RecalculateSheet'))
+            return this._execRecalculate(recalculate_sheet(transaction.RecalculateSheet))
+        if (hasOwnProperty(transaction, 'SetDateSystem')) {
+            const {date1904, shiftDates} = transaction.SetDateSystem
+            return this._execRecalculate(set_date_system(date1904, shiftDates))
+        }
+        if (hasOwnProperty(transaction, 'SetLocale'))
+            return this._execRecalculate(set_locale(transaction.SetLocale))
         transaction_start()
         transaction.Payloads.payloads.forEach(p => {
             this._addPayload(p)
@@ -95,7 +137,7 @@ export class Service {
         }
         return {
             $case: 'actionEffect',
-            actionEffect: {sheets: [], dirtys: [], async_tasks:[]}
+            actionEffect: {sheets: [], dirtys: [], async_tasks:[], cycles: result.cycles, rejections: result.rejections, invalid_names: result.invalidNames, invalid_blocks: result.invalidBlocks, invalid_merges: result.invalidMerges}
         }
     }
 
@@ -151,16 +193,94 @@ export class Service {
                 blockInput.input,
             )
         }
+        if (hasOwnProperty(p, 'SetVisible')) {
+            const setVisible = p.SetVisible
+            return set_visible(
+                setVisible.isRow,
+                setVisible.sheetIdx,
+                setVisible.start,
+                setVisible.count,
+                setVisible.visible,
+            )
+        }
+        if (hasOwnProperty(p, 'MergeCells')) {
+            const mergeCells = p.MergeCells
+            return merge_cells(
+                mergeCells.sheetIdx,
+                mergeCells.startRow,
+                mergeCells.startCol,
+                mergeCells.endRow,
+                mergeCells.endCol,
+            )
+        }
+        if (hasOwnProperty(p, 'SplitMergedCells')) {
+            const split = p.SplitMergedCells
+            return split_merged_cells(split.sheetIdx, split.row, split.col)
+        }
+        if (hasOwnProperty(p, 'AddComment')) {
+            const addComment = p.AddComment
+            return add_comment(
+                addComment.sheetIdx,
+                addComment.row,
+                addComment.col,
+                addComment.author,
+                addComment.text,
+            )
+        }
+        if (hasOwnProperty(p, 'UpdateComment')) {
+            const updateComment = p.UpdateComment
+            return update_comment(
+                updateComment.sheetIdx,
+                updateComment.row,
+                updateComment.col,
+                updateComment.author,
+                updateComment.text,
+            )
+        }
+        if (hasOwnProperty(p, 'DeleteComment')) {
+            const deleteComment = p.DeleteComment
+            return delete_comment(
+                deleteComment.sheetIdx,
+                deleteComment.row,
+                deleteComment.col,
+            )
+        }
+        if (hasOwnProperty(p, 'ReplyComment')) {
+            const replyComment = p.ReplyComment
+            return reply_comment(
+                replyComment.sheetIdx,
+                replyComment.row,
+                replyComment.col,
+                replyComment.author,
+                replyComment.text,
+            )
+        }
+        if (hasOwnProperty(p, 'ResolveComment')) {
+            const resolveComment = p.ResolveComment
+            return resolve_comment(
+                resolveComment.sheetIdx,
+                resolveComment.row,
+                resolveComment.col,
+                resolveComment.resolved,
+            )
+        }
         console.log('Unimplemented!')
     }
 
+    private _execRecalculate(result: TransactionEndResult): ServerSend {
+        return {
+            $case: 'actionEffect',
+            actionEffect: {sheets: [], dirtys: [], async_tasks:[], cycles: result.cycles, rejections: result.rejections, invalid_names: result.invalidNames, invalid_blocks: result.invalidBlocks, invalid_merges: result.invalidMerges}
+        }
+    }
+
     private _execUndo(): ServerSend {
         const r = undo()
         if (!r)
             console.log('undo failed')
         return {
             $case: 'actionEffect',
-            actionEffect: {sheets: [], dirtys: [], async_tasks:[]}
+            actionEffect: {sheets: [], dirtys: [], async_tasks:[], cycles: [], rejections: [], invalid_names: [], invalid_blocks: [], invalid_merges: []}
         }
     }
 
@@ -170,7 +290,7 @@ export class Service {
             console.log('redo failed')
         return {
             $case: 'actionEffect',
-            actionEffect: {sheets: [], dirtys: [], async_tasks:[]}
+            actionEffect: {sheets: [], dirtys: [], async_tasks:[], cycles: [], rejections: [], invalid_names: [], invalid_blocks: [], invalid_merges: []}
         }
     }
 
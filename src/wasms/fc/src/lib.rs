@@ -10,3 +10,15 @@ pub fn formula_check(f: &str) -> bool {
     // web_sys::console::log_1(&r.to_string().into());
     r
 }
+
+/// Input: the formula, the sheet names and the custom function names.
+/// The last two are `Array<string>`.
+/// Output: Array<FormulaDiagnostic>
+#[wasm_bindgen]
+pub fn formula_diagnose(f: &str, sheet_names: JsValue, custom_funcs: JsValue) -> JsValue {
+    use logisheets_controller::diagnose_formula;
+    let sheet_names: Vec<String> = sheet_names.into_serde().unwrap_or_default();
+    let custom_funcs: Vec<String> = custom_funcs.into_serde().unwrap_or_default();
+    let result = diagnose_formula(f, &sheet_names, &custom_funcs);
+    JsValue::from_serde(&result).unwrap()
+}
@@ -3,8 +3,11 @@ use crate::async_helper::PendingTask;
 use super::async_helper::AsyncHelper;
 use lazy_static::lazy_static;
 use logisheets_controller::controller::edit_action::{
-    BlockInput, CellInput, ColShift, CreateBlock, EditAction, EditPayload, MoveBlock,
-    PayloadsAction, RowShift,
+    ActionEffect, AddComment, BlockInput, CellInput, ColShift, CopySheet, CreateBlock, CycleCell,
+    DeleteComment, DeleteSheet, EditAction, EditPayload, FormulaRejection, InsertSheet,
+    InvalidBlock, InvalidMerge, InvalidName, Locale, MergeCells, MoveBlock, MoveSheet, PayloadsAction,
+    ReplyComment, ResolveComment, RowShift, SetDateSystem, SetVisible, SplitMergedCells,
+    UpdateComment,
 };
 use logisheets_controller::controller::{display::DisplayRequest, Controller};
 use logisheets_controller::{AsyncCalcResult, AsyncErr, Task};
@@ -31,6 +34,14 @@ pub fn read_file(name: String, buf: &[u8]) -> ReadFileResult {
     }
 }
 
+#[wasm_bindgen]
+/// The xlsx file of the current workbook. It is empty if the workbook fails
+/// to be saved.
+pub fn save() -> Vec<u8> {
+    let ctrl = CONTROLLER.lock().unwrap();
+    ctrl.save().unwrap_or_default()
+}
+
 #[wasm_bindgen]
 pub fn undo() -> bool {
     let mut ctrl = CONTROLLER.lock().unwrap();
@@ -59,7 +70,72 @@ pub fn transaction_end(undoable: bool) -> JsValue {
         payloads: empty,
         undoable,
     });
-    let result = match ctrl.handle_action(action) {
+    let result = get_transaction_end_result(ctrl.handle_action(action));
+    JsValue::from_serde(&result).unwrap()
+}
+
+/// Calculate the stale cells in the manual calculation mode.
+/// Output: TransactionEndResult
+#[wasm_bindgen]
+pub fn recalculate() -> JsValue {
+    let mut ctrl = CONTROLLER.lock().unwrap();
+    let result = get_transaction_end_result(ctrl.handle_action(EditAction::Recalculate));
+    JsValue::from_serde(&result).unwrap()
+}
+
+/// Output: TransactionEndResult
+#[wasm_bindgen]
+pub fn recalculate_sheet(sheet_idx: usize) -> JsValue {
+    let mut ctrl = CONTROLLER.lock().unwrap();
+    let action = EditAction::RecalculateSheet(sheet_idx);
+    let result = get_transaction_end_result(ctrl.handle_action(action));
+    JsValue::from_serde(&result).unwrap()
+}
+
+/// Output: TransactionEndResult
+#[wasm_bindgen]
+pub fn set_date_system(date1904: bool, shift_dates: bool) -> JsValue {
+    let mut ctrl = CONTROLLER.lock().unwrap();
+    let action = EditAction::SetDateSystem(SetDateSystem {
+        date1904,
+        shift_dates,
+    });
+    let result = get_transaction_end_result(ctrl.handle_action(action));
+    JsValue::from_serde(&result).unwrap()
+}
+
+/// Input: Locale
+/// Output: TransactionEndResult
+#[wasm_bindgen]
+pub fn set_locale(locale: &JsValue) -> JsValue {
+    let locale: Locale = locale.into_serde().unwrap();
+    let mut ctrl = CONTROLLER.lock().unwrap();
+    let result = get_transaction_end_result(ctrl.handle_action(EditAction::SetLocale(locale)));
+    JsValue::from_serde(&result).unwrap()
+}
+
+/// Output: Array<FuncSignature>
+#[wasm_bindgen]
+pub fn get_all_func_signatures() -> JsValue {
+    JsValue::from_serde(&logisheets_controller::get_all_func_signatures()).unwrap()
+}
+
+/// Output: Array<TraceNode>
+#[wasm_bindgen]
+pub fn trace_precedents(sheet_idx: usize, row: usize, col: usize, depth: usize) -> JsValue {
+    let mut ctrl = CONTROLLER.lock().unwrap();
+    JsValue::from_serde(&ctrl.trace_precedents(sheet_idx, row, col, depth)).unwrap()
+}
+
+/// Output: Array<TraceNode>
+#[wasm_bindgen]
+pub fn trace_dependents(sheet_idx: usize, row: usize, col: usize, depth: usize) -> JsValue {
+    let mut ctrl = CONTROLLER.lock().unwrap();
+    JsValue::from_serde(&ctrl.trace_dependents(sheet_idx, row, col, depth)).unwrap()
+}
+
+fn get_transaction_end_result(effect: Option<ActionEffect>) -> TransactionEndResult {
+    match effect {
         Some(effect) => {
             let async_id = if effect.async_tasks.len() > 0 {
                 let t = PendingTask {
@@ -70,17 +146,20 @@ pub fn transaction_end(undoable: bool) -> JsValue {
             } else {
                 0
             };
-            let r = TransactionEndResult {
+            TransactionEndResult {
                 sheet_idx: effect.sheets,
                 tasks: effect.async_tasks,
                 async_id,
                 code: TransactionCode::Ok,
-            };
-            r
+                cycles: effect.cycles,
+                rejections: effect.rejections,
+                invalid_names: effect.invalid_names,
+                invalid_blocks: effect.invalid_blocks,
+                invalid_merges: effect.invalid_merges,
+            }
         }
         None => TransactionEndResult::from_err_code(TransactionCode::Err),
-    };
-    JsValue::from_serde(&result).unwrap()
+    }
 }
 
 /// Input: AsyncFuncResult
@@ -117,6 +196,11 @@ pub fn input_async_result(result: &JsValue) -> JsValue {
                         tasks: effect.async_tasks,
                         async_id,
                         code: TransactionCode::Ok,
+                        cycles: effect.cycles,
+                        rejections: effect.rejections,
+                        invalid_names: effect.invalid_names,
+                        invalid_blocks: effect.invalid_blocks,
+                        invalid_merges: effect.invalid_merges,
                     }
                 }
                 None => TransactionEndResult::from_err_code(TransactionCode::Err),
@@ -246,6 +330,73 @@ pub fn block_input(sheet_idx: usize, block_id: usize, row: usize, col: usize, in
     payloads.push(EditPayload::BlockInput(bi));
 }
 
+#[wasm_bindgen]
+pub fn set_visible(is_row: bool, sheet_idx: usize, start: usize, count: usize, visible: bool) {
+    let sv = SetVisible {
+        is_row,
+        sheet_idx,
+        start,
+        count,
+        visible,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::SetVisible(sv));
+}
+
+#[wasm_bindgen]
+pub fn merge_cells(
+    sheet_idx: usize,
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+) {
+    let mc = MergeCells {
+        sheet_idx,
+        start_row,
+        start_col,
+        end_row,
+        end_col,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::MergeCells(mc));
+}
+
+#[wasm_bindgen]
+pub fn split_merged_cells(sheet_idx: usize, row: usize, col: usize) {
+    let smc = SplitMergedCells {
+        sheet_idx,
+        row,
+        col,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::SplitMergedCells(smc));
+}
+
+#[wasm_bindgen]
+pub fn sheet_insert(sheet_idx: usize) {
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::InsertSheet(InsertSheet { sheet_idx }));
+}
+
+#[wasm_bindgen]
+pub fn sheet_delete(sheet_idx: usize) {
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::DeleteSheet(DeleteSheet { sheet_idx }));
+}
+
+#[wasm_bindgen]
+pub fn sheet_move(sheet_idx: usize, new_idx: usize) {
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::MoveSheet(MoveSheet { sheet_idx, new_idx }));
+}
+
+#[wasm_bindgen]
+pub fn sheet_copy(sheet_idx: usize, new_idx: usize) {
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::CopySheet(CopySheet { sheet_idx, new_idx }));
+}
+
 #[wasm_bindgen]
 pub enum ReadFileResult {
     Ok,
@@ -265,6 +416,11 @@ pub struct TransactionEndResult {
     tasks: Vec<Task>,
     async_id: u32,
     code: TransactionCode,
+    cycles: Vec<Vec<CycleCell>>,
+    rejections: Vec<FormulaRejection>,
+    invalid_names: Vec<InvalidName>,
+    invalid_blocks: Vec<InvalidBlock>,
+    invalid_merges: Vec<InvalidMerge>,
 }
 
 #[derive(Deserialize)]
@@ -280,6 +436,11 @@ impl TransactionEndResult {
             tasks: vec![],
             async_id: 0,
             code,
+            cycles: vec![],
+            rejections: vec![],
+            invalid_names: vec![],
+            invalid_blocks: vec![],
+            invalid_merges: vec![],
         }
     }
 }
@@ -298,3 +459,65 @@ fn parse_async_value(s: String) -> AsyncCalcResult {
         _ => Ok(s),
     }
 }
+
+#[wasm_bindgen]
+pub fn add_comment(sheet_idx: usize, row: usize, col: usize, author: String, text: String) {
+    let p = AddComment {
+        sheet_idx,
+        row,
+        col,
+        author,
+        text,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::AddComment(p));
+}
+
+#[wasm_bindgen]
+pub fn update_comment(sheet_idx: usize, row: usize, col: usize, author: String, text: String) {
+    let p = UpdateComment {
+        sheet_idx,
+        row,
+        col,
+        author,
+        text,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::UpdateComment(p));
+}
+
+#[wasm_bindgen]
+pub fn delete_comment(sheet_idx: usize, row: usize, col: usize) {
+    let p = DeleteComment {
+        sheet_idx,
+        row,
+        col,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::DeleteComment(p));
+}
+
+#[wasm_bindgen]
+pub fn reply_comment(sheet_idx: usize, row: usize, col: usize, author: String, text: String) {
+    let p = ReplyComment {
+        sheet_idx,
+        row,
+        col,
+        author,
+        text,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::ReplyComment(p));
+}
+
+#[wasm_bindgen]
+pub fn resolve_comment(sheet_idx: usize, row: usize, col: usize, resolved: bool) {
+    let p = ResolveComment {
+        sheet_idx,
+        row,
+        col,
+        resolved,
+    };
+    let mut payloads = PAYLOADS.lock().unwrap();
+    payloads.push(EditPayload::ResolveComment(p));
+}